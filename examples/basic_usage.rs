@@ -97,6 +97,11 @@ fn main() -> Result<()> {
         as_of_frame: None,
         as_of_ts: None,
         no_sketch: false,
+        filter: None,
+        facets: Vec::new(),
+        hybrid: None,
+        reranker: None,
+        fragments: None,
     };
     let response = mem.search(request)?;
     println!("   Query: 'memvid'");
@@ -126,6 +131,11 @@ fn main() -> Result<()> {
         as_of_frame: None,
         as_of_ts: None,
         no_sketch: false,
+        filter: None,
+        facets: Vec::new(),
+        hybrid: None,
+        reranker: None,
+        fragments: None,
     };
     let response = mem.search(request)?;
     println!("   Query: 'documentation' (scope: mv2://docs/)");
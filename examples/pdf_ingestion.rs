@@ -112,6 +112,11 @@ fn main() -> Result<()> {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         };
 
         let response = mem.search(request)?;
@@ -35,6 +35,8 @@ pub const WAL_SIZE_XLARGE: u64 = 64 * 1024 * 1024;
 pub const WAL_CHECKPOINT_THRESHOLD: f64 = 0.75;
 /// Additional checkpoint every N transactions (PRD default).
 pub const WAL_CHECKPOINT_PERIOD: u64 = 1_000;
+/// `.mv2e` format version of live-encrypted memories (v1 is the sealed capsule).
+pub const MV2E_LIVE_VERSION: u16 = 2;
 
 /// Memvid's Ed25519 public key for verifying signed tickets.
 /// This key is used to verify that tickets were issued by the official Memvid control plane.
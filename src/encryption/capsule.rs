@@ -8,7 +8,7 @@ use zeroize::Zeroize;
 use crate::encryption::capsule_stream::{lock_file_stream, unlock_file_stream};
 use crate::encryption::crypto::{decrypt, derive_key};
 use crate::encryption::error::EncryptionError;
-use crate::encryption::types::Mv2eHeader;
+use crate::encryption::types::{KdfAlgorithm, Mv2eHeader};
 
 /// Lock (encrypt) an `.mv2` file into a `.mv2e` capsule.
 pub fn lock_file(
@@ -39,6 +39,9 @@ pub fn unlock_file(
             path: Some(input.to_path_buf()),
         })?;
     let header = Mv2eHeader::decode(&header_bytes)?;
+    if header.kdf_algorithm != KdfAlgorithm::Argon2id {
+        return Err(EncryptionError::RecipientCapsule);
+    }
 
     if header.reserved[0] == 0x01 {
         unlock_file_stream(input, output, password)
@@ -6,7 +6,7 @@ use std::path::{Path, PathBuf};
 use zeroize::Zeroize;
 
 use crate::encryption::capsule::{validate_mv2_file, write_atomic};
-use crate::encryption::constants::{MV2E_MAGIC, MV2E_VERSION, NONCE_SIZE, SALT_SIZE};
+use crate::encryption::constants::{KEY_SIZE, MV2E_MAGIC, MV2E_VERSION, NONCE_SIZE, SALT_SIZE};
 use crate::encryption::crypto::{decrypt, derive_key, encrypt};
 use crate::encryption::error::EncryptionError;
 use crate::encryption::types::{CipherAlgorithm, KdfAlgorithm, Mv2eHeader};
@@ -54,28 +54,7 @@ pub fn lock_file_stream(
     write_atomic(&output_path, |file| -> Result<(), EncryptionError> {
         let mut writer = BufWriter::new(file);
         writer.write_all(&header.encode())?;
-
-        let mut buffer = vec![0u8; CHUNK_SIZE];
-        let mut chunk_index: u64 = 0;
-
-        loop {
-            let n = reader.read(&mut buffer)?;
-            if n == 0 {
-                break;
-            }
-
-            let mut nonce = base_nonce;
-            nonce[NONCE_SIZE - 8..].copy_from_slice(&chunk_index.to_be_bytes());
-
-            let ciphertext = encrypt(&buffer[..n], &key, &nonce)?;
-
-            let chunk_len = ciphertext.len() as u32;
-            writer.write_all(&chunk_len.to_le_bytes())?;
-            writer.write_all(&ciphertext)?;
-
-            chunk_index += 1;
-        }
-
+        encrypt_chunks(&mut reader, &mut writer, &key, &base_nonce)?;
         writer.flush()?;
         Ok(())
     })?;
@@ -98,6 +77,9 @@ pub fn unlock_file_stream(
     reader.read_exact(&mut header_bytes)?;
 
     let header = Mv2eHeader::decode(&header_bytes)?;
+    if header.kdf_algorithm != KdfAlgorithm::Argon2id {
+        return Err(EncryptionError::RecipientCapsule);
+    }
     let mut key = derive_key(password, &header.salt)?;
 
     let output_path = output
@@ -106,33 +88,73 @@ pub fn unlock_file_stream(
 
     write_atomic(&output_path, |file| -> Result<(), EncryptionError> {
         let mut writer = BufWriter::new(file);
-        let mut chunk_index: u64 = 0;
+        decrypt_chunks(&mut reader, &mut writer, &key, &header.nonce)?;
+        writer.flush()?;
+        Ok(())
+    })?;
 
-        loop {
-            let mut len_bytes = [0u8; 4];
-            match reader.read_exact(&mut len_bytes) {
-                Ok(()) => {}
-                Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
-                Err(e) => return Err(e.into()),
-            }
-            let chunk_len = u32::from_le_bytes(len_bytes) as usize;
+    key.zeroize();
+    Ok(output_path)
+}
+
+/// Encrypt everything `reader` yields as `[len][chunk]` frames, one nonce per chunk index.
+pub(crate) fn encrypt_chunks(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    key: &[u8; KEY_SIZE],
+    base_nonce: &[u8; NONCE_SIZE],
+) -> Result<(), EncryptionError> {
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut chunk_index: u64 = 0;
+
+    loop {
+        let n = reader.read(&mut buffer)?;
+        if n == 0 {
+            break;
+        }
 
-            let mut ciphertext = vec![0u8; chunk_len];
-            reader.read_exact(&mut ciphertext)?;
+        let mut nonce = *base_nonce;
+        nonce[NONCE_SIZE - 8..].copy_from_slice(&chunk_index.to_be_bytes());
 
-            let mut nonce = header.nonce;
-            nonce[NONCE_SIZE - 8..].copy_from_slice(&chunk_index.to_be_bytes());
+        let ciphertext = encrypt(&buffer[..n], key, &nonce)?;
 
-            let plaintext = decrypt(&ciphertext, &key, &nonce)?;
-            writer.write_all(&plaintext)?;
+        let chunk_len = ciphertext.len() as u32;
+        writer.write_all(&chunk_len.to_le_bytes())?;
+        writer.write_all(&ciphertext)?;
 
-            chunk_index += 1;
+        chunk_index += 1;
+    }
+    Ok(())
+}
+
+/// Decrypt `[len][chunk]` frames until the end of `reader`.
+pub(crate) fn decrypt_chunks(
+    reader: &mut impl Read,
+    writer: &mut impl Write,
+    key: &[u8; KEY_SIZE],
+    base_nonce: &[u8; NONCE_SIZE],
+) -> Result<(), EncryptionError> {
+    let mut chunk_index: u64 = 0;
+
+    loop {
+        let mut len_bytes = [0u8; 4];
+        match reader.read_exact(&mut len_bytes) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
         }
+        let chunk_len = u32::from_le_bytes(len_bytes) as usize;
 
-        writer.flush()?;
-        Ok(())
-    })?;
+        let mut ciphertext = vec![0u8; chunk_len];
+        reader.read_exact(&mut ciphertext)?;
 
-    key.zeroize();
-    Ok(output_path)
+        let mut nonce = *base_nonce;
+        nonce[NONCE_SIZE - 8..].copy_from_slice(&chunk_index.to_be_bytes());
+
+        let plaintext = decrypt(&ciphertext, key, &nonce)?;
+        writer.write_all(&plaintext)?;
+
+        chunk_index += 1;
+    }
+    Ok(())
 }
@@ -11,6 +11,8 @@ pub const MV2E_HEADER_SIZE: usize = 64;
 
 /// KDF algorithm identifiers.
 pub const KDF_ARGON2ID: u8 = 1;
+/// The data key is wrapped per X25519 recipient in an extended header.
+pub const KDF_X25519: u8 = 2;
 
 /// Cipher algorithm identifiers.
 pub const CIPHER_AES_256_GCM: u8 = 1;
@@ -21,6 +23,11 @@ pub const NONCE_SIZE: usize = 12;
 pub const TAG_SIZE: usize = 16;
 pub const KEY_SIZE: usize = 32;
 
+/// Header flag (`reserved[1]`) marking a capsule signed with Ed25519.
+pub const FLAG_SIGNED: u8 = 0x01;
+/// Maximum number of recipients a capsule can be sealed to.
+pub const MAX_RECIPIENTS: usize = 1024;
+
 /// Argon2id parameters (OWASP 2024 recommendations).
 pub const ARGON2_MEMORY_KIB: u32 = 64 * 1024; // 64 MiB
 pub const ARGON2_ITERATIONS: u32 = 3;
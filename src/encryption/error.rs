@@ -42,6 +42,24 @@ pub enum EncryptionError {
 
     #[error("Corrupted decryption - output is not a valid MV2 file")]
     CorruptedDecryption,
+
+    #[error("Capsule is sealed to X25519 recipients - unlock it with a recipient key")]
+    RecipientCapsule,
+
+    #[error("Capsule is password-protected - unlock it with its password")]
+    PasswordCapsule,
+
+    #[error("Capsule has no recipients")]
+    NoRecipients,
+
+    #[error("Too many recipients: {count} (maximum {max})")]
+    TooManyRecipients { count: usize, max: usize },
+
+    #[error("Key is not a recipient of this capsule")]
+    NotARecipient,
+
+    #[error("Capsule signature invalid: {reason}")]
+    SignatureInvalid { reason: String },
 }
 
 impl From<std::io::Error> for EncryptionError {
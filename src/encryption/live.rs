@@ -0,0 +1,1002 @@
+//! Live-encrypted memories (`.mv2e` version 2).
+//!
+//! Unlike capsules, which wrap a whole `.mv2` file, a live-encrypted memory is read and
+//! written in place: the logical `.mv2` byte stream (header, WAL, frame payloads, indexes,
+//! TOC and footer) is split into pages that are individually sealed with AES-256-GCM.
+//!
+//! Layout:
+//! - `[0, ENVELOPE_SIZE)`: plaintext key envelope. Its header names the format, KDF, cipher
+//!   and page size; two key slots hold the random data key wrapped with a key derived from
+//!   the password via Argon2id. Each slot carries a generation and a checksum, and the
+//!   newest intact slot wins, so a password rotation interrupted at any point leaves either
+//!   the old or the new password working.
+//! - `[ENVELOPE_SIZE, DATA_OFFSET)`: the write journal. Its first page holds two header
+//!   copies, followed by `JOURNAL_SLOTS` undo slots. Before a write touches stored pages,
+//!   their sealed bytes and the physical length are copied into the slots and synced, so
+//!   reopening after a crash puts back whatever a torn write left half-updated.
+//! - then one physical page per `PAGE_DATA_SIZE` logical bytes:
+//!   `[epoch][nonce][ciphertext][tag]`, authenticated with the page index so pages cannot
+//!   be swapped, with a flag marking the final page so a file cut at a page boundary fails
+//!   authentication, and with the epoch of the write that sealed it. Epochs grow with every
+//!   write and the journal records the newest one, so a page newer than the journal (e.g.
+//!   spliced in from a later copy of the memory) is rejected. The last page only stores the
+//!   bytes in use, which keeps the logical length implicit in the physical one.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use aes_gcm::aead::{Aead, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use zeroize::Zeroizing;
+
+use crate::constants::MV2E_LIVE_VERSION;
+use crate::encryption::constants::{
+    CIPHER_AES_256_GCM, KDF_ARGON2ID, KEY_SIZE, MV2E_MAGIC, NONCE_SIZE, SALT_SIZE, TAG_SIZE,
+};
+use crate::encryption::crypto::{decrypt, derive_key, encrypt};
+use crate::encryption::error::EncryptionError;
+
+/// Size of the plaintext key envelope at the start of the file.
+pub(crate) const ENVELOPE_SIZE: u64 = 4096;
+const PAGE_SIZE: u64 = 4096;
+/// `[epoch u64][nonce][tag]` around the ciphertext of each page.
+const PAGE_OVERHEAD: u64 = (8 + NONCE_SIZE + TAG_SIZE) as u64;
+const PAGE_DATA_SIZE: u64 = PAGE_SIZE - PAGE_OVERHEAD;
+const WRAPPED_KEY_SIZE: usize = KEY_SIZE + TAG_SIZE;
+const ZERO_FILL_CHUNK: u64 = 1024 * 1024;
+
+/// Undo slots in the write journal, one stored page each.
+const JOURNAL_SLOTS: u64 = 16;
+/// Pages covered by one journaled write; the extra slot holds a resealed final page.
+const MAX_PAGES_PER_WRITE: u64 = JOURNAL_SLOTS - 1;
+/// Each of the two journal header copies takes half of the journal's first page.
+const JOURNAL_HEADER_SIZE: usize = 2048;
+/// `[epoch u64][active u8][physical length u64][slot count u8]`.
+const JOURNAL_FIXED_SIZE: usize = 18;
+/// `[page u64][stored length u32]` per undo slot.
+const JOURNAL_ENTRY_SIZE: usize = 12;
+const JOURNAL_MAC_SIZE: usize = 32;
+const JOURNAL_SLOT_OFFSET: u64 = ENVELOPE_SIZE + PAGE_SIZE;
+/// Physical offset of the first sealed page.
+const DATA_OFFSET: u64 = JOURNAL_SLOT_OFFSET + JOURNAL_SLOTS * PAGE_SIZE;
+
+/// Offsets of the two key slots inside the envelope.
+const KEY_SLOT_OFFSETS: [usize; 2] = [512, 1024];
+/// `[generation u64][salt][nonce][wrapped key][blake3 of the preceding bytes]`.
+const KEY_SLOT_BODY_SIZE: usize = 8 + SALT_SIZE + NONCE_SIZE + WRAPPED_KEY_SIZE;
+const KEY_SLOT_SIZE: usize = KEY_SLOT_BODY_SIZE + 32;
+
+/// The data key wrapped with one password, as stored in a key slot.
+#[derive(Debug, Clone)]
+struct KeySlot {
+    generation: u64,
+    salt: [u8; SALT_SIZE],
+    nonce: [u8; NONCE_SIZE],
+    wrapped_key: [u8; WRAPPED_KEY_SIZE],
+}
+
+impl KeySlot {
+    fn seal(
+        data_key: &[u8; KEY_SIZE],
+        password: &[u8],
+        generation: u64,
+    ) -> Result<Self, EncryptionError> {
+        let mut salt = [0u8; SALT_SIZE];
+        let mut nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+
+        let wrapping_key = Zeroizing::new(derive_key(password, &salt)?);
+        let wrapped = encrypt(data_key, &wrapping_key, &nonce)?;
+        let wrapped_key =
+            wrapped
+                .try_into()
+                .map_err(|wrapped: Vec<u8>| EncryptionError::SizeMismatch {
+                    expected: WRAPPED_KEY_SIZE as u64,
+                    actual: wrapped.len() as u64,
+                })?;
+        Ok(Self {
+            generation,
+            salt,
+            nonce,
+            wrapped_key,
+        })
+    }
+
+    fn unwrap_key(&self, password: &[u8]) -> Result<Zeroizing<[u8; KEY_SIZE]>, EncryptionError> {
+        let wrapping_key = Zeroizing::new(derive_key(password, &self.salt)?);
+        let plaintext = Zeroizing::new(decrypt(&self.wrapped_key, &wrapping_key, &self.nonce)?);
+        let mut key = Zeroizing::new([0u8; KEY_SIZE]);
+        if plaintext.len() != KEY_SIZE {
+            return Err(EncryptionError::SizeMismatch {
+                expected: KEY_SIZE as u64,
+                actual: plaintext.len() as u64,
+            });
+        }
+        key.copy_from_slice(&plaintext);
+        Ok(key)
+    }
+
+    fn encode(&self) -> [u8; KEY_SLOT_SIZE] {
+        let mut buf = [0u8; KEY_SLOT_SIZE];
+        buf[0..8].copy_from_slice(&self.generation.to_le_bytes());
+        buf[8..40].copy_from_slice(&self.salt);
+        buf[40..52].copy_from_slice(&self.nonce);
+        buf[52..KEY_SLOT_BODY_SIZE].copy_from_slice(&self.wrapped_key);
+        let checksum = blake3::hash(&buf[..KEY_SLOT_BODY_SIZE]);
+        buf[KEY_SLOT_BODY_SIZE..].copy_from_slice(checksum.as_bytes());
+        buf
+    }
+
+    /// Decode a slot, or `None` when it is empty or torn.
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let (body, checksum) = bytes.split_at(KEY_SLOT_BODY_SIZE);
+        if blake3::hash(body).as_bytes() != checksum {
+            return None;
+        }
+        let generation = u64::from_le_bytes(body[0..8].try_into().ok()?);
+        if generation == 0 {
+            return None;
+        }
+        Some(Self {
+            generation,
+            salt: body[8..40].try_into().ok()?,
+            nonce: body[40..52].try_into().ok()?,
+            wrapped_key: body[52..].try_into().ok()?,
+        })
+    }
+}
+
+/// Encode a fresh envelope whose first slot holds `slot`.
+fn encode_envelope(slot: &KeySlot) -> Vec<u8> {
+    let mut buf = vec![0u8; narrow(ENVELOPE_SIZE)];
+    buf[0..4].copy_from_slice(&MV2E_MAGIC);
+    buf[4..6].copy_from_slice(&MV2E_LIVE_VERSION.to_le_bytes());
+    buf[6] = KDF_ARGON2ID;
+    buf[7] = CIPHER_AES_256_GCM;
+    buf[8..12].copy_from_slice(&PAGE_SIZE.to_le_bytes()[..4]);
+    let start = KEY_SLOT_OFFSETS[0];
+    buf[start..start + KEY_SLOT_SIZE].copy_from_slice(&slot.encode());
+    buf
+}
+
+/// Validate the envelope header and return the newest intact key slot with its index.
+fn decode_envelope(bytes: &[u8]) -> Result<(usize, KeySlot), EncryptionError> {
+    let magic = [bytes[0], bytes[1], bytes[2], bytes[3]];
+    if magic != MV2E_MAGIC {
+        return Err(EncryptionError::InvalidMagic {
+            expected: MV2E_MAGIC,
+            found: magic,
+        });
+    }
+    let version = u16::from_le_bytes([bytes[4], bytes[5]]);
+    if version != MV2E_LIVE_VERSION {
+        return Err(EncryptionError::UnsupportedVersion { version });
+    }
+    if bytes[6] != KDF_ARGON2ID {
+        return Err(EncryptionError::UnsupportedKdf { id: bytes[6] });
+    }
+    if bytes[7] != CIPHER_AES_256_GCM {
+        return Err(EncryptionError::UnsupportedCipher { id: bytes[7] });
+    }
+    let page_size = u32::from_le_bytes([bytes[8], bytes[9], bytes[10], bytes[11]]);
+    if u64::from(page_size) != PAGE_SIZE {
+        return Err(EncryptionError::SizeMismatch {
+            expected: PAGE_SIZE,
+            actual: u64::from(page_size),
+        });
+    }
+
+    KEY_SLOT_OFFSETS
+        .iter()
+        .enumerate()
+        .filter_map(|(index, &offset)| {
+            let start = offset;
+            KeySlot::decode(&bytes[start..start + KEY_SLOT_SIZE]).map(|slot| (index, slot))
+        })
+        .max_by_key(|(_, slot)| slot.generation)
+        .ok_or_else(|| EncryptionError::Decryption {
+            reason: "no intact key slot in the envelope".into(),
+        })
+}
+
+fn read_envelope(file: &mut File) -> Result<(usize, KeySlot), EncryptionError> {
+    let mut envelope = vec![0u8; narrow(ENVELOPE_SIZE)];
+    file.seek(SeekFrom::Start(0))?;
+    file.read_exact(&mut envelope)?;
+    decode_envelope(&envelope)
+}
+
+/// One copy of the write journal header.
+///
+/// Copy 0 is written, with the undo slots, before a write touches the file; copy 1 once it
+/// has landed. The newest intact copy wins, and an active one means the write was cut short.
+#[derive(Debug, Clone, Default)]
+struct JournalHeader {
+    epoch: u64,
+    active: bool,
+    /// Physical length before the write, restored when it is undone.
+    physical_len: u64,
+    /// Page index and stored length of each undo slot, in slot order.
+    entries: Vec<(u64, u32)>,
+}
+
+impl JournalHeader {
+    /// Encode the header, keyed-hashed together with the contents of its undo slots.
+    fn encode(&self, key: &PageKey, slots: &[Vec<u8>]) -> Vec<u8> {
+        let mut buf = vec![0u8; JOURNAL_HEADER_SIZE];
+        buf[0..8].copy_from_slice(&self.epoch.to_le_bytes());
+        buf[8] = u8::from(self.active);
+        buf[9..17].copy_from_slice(&self.physical_len.to_le_bytes());
+        buf[17] = u8::try_from(self.entries.len()).unwrap_or(u8::MAX);
+        for (index, (page, stored)) in self.entries.iter().enumerate() {
+            let start = JOURNAL_FIXED_SIZE + index * JOURNAL_ENTRY_SIZE;
+            buf[start..start + 8].copy_from_slice(&page.to_le_bytes());
+            buf[start + 8..start + 12].copy_from_slice(&stored.to_le_bytes());
+        }
+        let body = JOURNAL_HEADER_SIZE - JOURNAL_MAC_SIZE;
+        let mac = key.journal_mac(&buf[..body], slots);
+        buf[body..].copy_from_slice(&mac);
+        buf
+    }
+
+    /// Decode one header copy, or `None` when it is empty, torn or forged.
+    fn decode(
+        bytes: &[u8],
+        key: &PageKey,
+        file: &File,
+    ) -> io::Result<Option<(Self, Vec<Vec<u8>>)>> {
+        let count = usize::from(bytes[17]);
+        if count as u64 > JOURNAL_SLOTS {
+            return Ok(None);
+        }
+        let header = Self {
+            epoch: read_u64(&bytes[0..8]),
+            active: bytes[8] != 0,
+            physical_len: read_u64(&bytes[9..17]),
+            entries: (0..count)
+                .map(|index| {
+                    let start = JOURNAL_FIXED_SIZE + index * JOURNAL_ENTRY_SIZE;
+                    let page = read_u64(&bytes[start..start + 8]);
+                    let mut stored = [0u8; 4];
+                    stored.copy_from_slice(&bytes[start + 8..start + 12]);
+                    (page, u32::from_le_bytes(stored))
+                })
+                .collect(),
+        };
+        let mut slots = Vec::with_capacity(count);
+        for (slot, (_, stored)) in header.entries.iter().enumerate() {
+            if u64::from(*stored) > PAGE_SIZE {
+                return Ok(None);
+            }
+            let mut contents = vec![0u8; *stored as usize];
+            read_exact_at(file, &mut contents, journal_slot_offset(slot))?;
+            slots.push(contents);
+        }
+        let body = JOURNAL_HEADER_SIZE - JOURNAL_MAC_SIZE;
+        if key.journal_mac(&bytes[..body], &slots) != bytes[body..] {
+            return Ok(None);
+        }
+        Ok(Some((header, slots)))
+    }
+}
+
+/// Data key and its cipher, shared by every handle on the same memory.
+struct PageKey {
+    key: Zeroizing<[u8; KEY_SIZE]>,
+    cipher: Aes256Gcm,
+    /// Keys the journal checksums, so a forged journal cannot roll pages back.
+    journal_key: Zeroizing<[u8; 32]>,
+    /// Newest write epoch handed out or recorded by the journal.
+    epoch: AtomicU64,
+}
+
+impl PageKey {
+    fn new(key: Zeroizing<[u8; KEY_SIZE]>) -> Result<Self, EncryptionError> {
+        let cipher =
+            Aes256Gcm::new_from_slice(key.as_ref()).map_err(|e| EncryptionError::CipherInit {
+                reason: e.to_string(),
+            })?;
+        let journal_key = Zeroizing::new(blake3::derive_key(
+            "memvid live-encrypted write journal v1",
+            key.as_ref(),
+        ));
+        Ok(Self {
+            key,
+            cipher,
+            journal_key,
+            epoch: AtomicU64::new(0),
+        })
+    }
+
+    fn journal_mac(&self, header: &[u8], slots: &[Vec<u8>]) -> [u8; JOURNAL_MAC_SIZE] {
+        let mut hasher = blake3::Hasher::new_keyed(&self.journal_key);
+        hasher.update(header);
+        for slot in slots {
+            hasher.update(slot);
+        }
+        *hasher.finalize().as_bytes()
+    }
+
+    fn next_epoch(&self) -> u64 {
+        self.epoch.fetch_add(1, Ordering::SeqCst) + 1
+    }
+}
+
+/// A `.mv2` byte stream stored as AES-256-GCM sealed pages.
+///
+/// Offsets seen through `Read`/`Write`/`Seek` are logical `.mv2` offsets; plaintext only
+/// ever exists in memory.
+pub(crate) struct EncryptedFile {
+    file: File,
+    key: Arc<PageKey>,
+    position: u64,
+    /// Whether writes keep an undo record; off for staging images, which a crash discards.
+    journaled: bool,
+}
+
+impl std::fmt::Debug for EncryptedFile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EncryptedFile")
+            .field("position", &self.position)
+            .field("journaled", &self.journaled)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EncryptedFile {
+    /// Initialise `file` as an empty live-encrypted memory with a fresh data key.
+    pub(crate) fn create(mut file: File, password: &[u8]) -> Result<Self, EncryptionError> {
+        let mut data_key = Zeroizing::new([0u8; KEY_SIZE]);
+        OsRng.fill_bytes(data_key.as_mut());
+        let slot = KeySlot::seal(&data_key, password, 1)?;
+
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&encode_envelope(&slot))?;
+        file.set_len(DATA_OFFSET)?;
+        let created = Self {
+            file,
+            key: Arc::new(PageKey::new(data_key)?),
+            position: 0,
+            journaled: true,
+        };
+        created.write_journal(1, &JournalHeader::default(), &[])?;
+        created.file.sync_all()?;
+        Ok(created)
+    }
+
+    /// Unlock a live-encrypted memory with its password, undoing a write cut short by a crash.
+    pub(crate) fn open(mut file: File, password: &[u8]) -> Result<Self, EncryptionError> {
+        let (_, slot) = read_envelope(&mut file)?;
+        let data_key = slot.unwrap_key(password)?;
+        let opened = Self {
+            file,
+            key: Arc::new(PageKey::new(data_key)?),
+            position: 0,
+            journaled: true,
+        };
+        opened.recover()?;
+        Ok(opened)
+    }
+
+    /// Whether `file` starts with the envelope of a live-encrypted memory.
+    pub(crate) fn is_live_encrypted(file: &mut File) -> io::Result<bool> {
+        let mut prefix = [0u8; 6];
+        file.seek(SeekFrom::Start(0))?;
+        let matches = file.read_exact(&mut prefix).is_ok()
+            && prefix[0..4] == MV2E_MAGIC
+            && u16::from_le_bytes([prefix[4], prefix[5]]) == MV2E_LIVE_VERSION;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(matches)
+    }
+
+    /// Wrap the data key with `new_password`; the pages themselves are not re-encrypted.
+    ///
+    /// The new slot is written and synced before the active one is cleared, so a crash
+    /// leaves one of the two passwords able to open the memory.
+    pub(crate) fn rewrap_key(&mut self, new_password: &[u8]) -> Result<(), EncryptionError> {
+        let (active, current) = read_envelope(&mut self.file)?;
+        let slot = KeySlot::seal(&self.key.key, new_password, current.generation + 1)?;
+        let target = 1 - active;
+        write_all_at(&self.file, &slot.encode(), KEY_SLOT_OFFSETS[target] as u64)?;
+        self.file.sync_all()?;
+        write_all_at(
+            &self.file,
+            &[0u8; KEY_SLOT_SIZE],
+            KEY_SLOT_OFFSETS[active] as u64,
+        )?;
+        self.file.sync_all()?;
+        Ok(())
+    }
+
+    /// A handle on another physical file encrypted with the same data key.
+    pub(crate) fn with_file(&self, file: File) -> Self {
+        Self {
+            file,
+            key: Arc::clone(&self.key),
+            position: 0,
+            journaled: true,
+        }
+    }
+
+    /// Like [`Self::with_file`], for a staging image: its writes skip the undo record.
+    pub(crate) fn with_staging_file(&self, file: File) -> Self {
+        Self {
+            journaled: false,
+            ..self.with_file(file)
+        }
+    }
+
+    pub(crate) fn try_clone(&self) -> io::Result<Self> {
+        Ok(Self {
+            file: self.file.try_clone()?,
+            key: Arc::clone(&self.key),
+            position: self.position,
+            journaled: self.journaled,
+        })
+    }
+
+    pub(crate) fn raw(&self) -> &File {
+        &self.file
+    }
+
+    /// Logical length of the memory.
+    pub(crate) fn len(&self) -> io::Result<u64> {
+        let physical = self.file.metadata()?.len();
+        Ok(logical_len(physical))
+    }
+
+    /// Number of physical bytes holding the first `logical` bytes, rounded up to whole pages.
+    pub(crate) fn physical_prefix_len(&self, logical: u64) -> io::Result<u64> {
+        let pages = logical.div_ceil(PAGE_DATA_SIZE);
+        let physical = self.file.metadata()?.len();
+        Ok((DATA_OFFSET + pages * PAGE_SIZE).min(physical))
+    }
+
+    pub(crate) fn set_len(&self, len: u64) -> io::Result<()> {
+        let current = self.len()?;
+        if len > current {
+            let zeros = vec![0u8; narrow(ZERO_FILL_CHUNK.min(len - current))];
+            let mut offset = current;
+            while offset < len {
+                let chunk = (len - offset).min(zeros.len() as u64);
+                self.write_at(offset, &zeros[..narrow(chunk)])?;
+                offset += chunk;
+            }
+            return Ok(());
+        }
+        if len == 0 {
+            return self.file.set_len(DATA_OFFSET);
+        }
+        // The new last page may have been sealed as an inner page, either because the tail
+        // is being cut off or because a staging image copied only a prefix of the pages.
+        // Its position is authenticated either way; only the final flag is rewritten.
+        let page = (len - 1) / PAGE_DATA_SIZE;
+        let keep = len - page * PAGE_DATA_SIZE;
+        let (mut plaintext, sealed_final) = self.open_page_either(page, current)?;
+        if len == current && sealed_final {
+            return Ok(());
+        }
+        plaintext.truncate(narrow(keep));
+        // The page is resealed before the tail is cut, so the cut is the last step and a
+        // crash before it is undone from the journal.
+        self.journaled(current, &[page], |epoch| {
+            self.write_page(page, &plaintext, true, epoch)?;
+            self.file.set_len(page_offset(page) + keep + PAGE_OVERHEAD)
+        })
+    }
+
+    pub(crate) fn sync_all(&self) -> io::Result<()> {
+        self.file.sync_all()
+    }
+
+    /// Decrypt the whole logical stream into memory.
+    pub(crate) fn read_all(&self) -> io::Result<Vec<u8>> {
+        let len = self.len()?;
+        let mut bytes = Vec::with_capacity(usize::try_from(len).unwrap_or(0));
+        for page in 0..len.div_ceil(PAGE_DATA_SIZE) {
+            bytes.extend_from_slice(&self.read_page(page, len)?);
+        }
+        Ok(bytes)
+    }
+
+    /// Undo a write that a crash cut short and pick up the newest write epoch.
+    fn recover(&self) -> io::Result<()> {
+        let (header, slots) = self.read_journal()?.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidData, "write journal is damaged")
+        })?;
+        self.key.epoch.fetch_max(header.epoch, Ordering::SeqCst);
+        if !header.active {
+            return Ok(());
+        }
+        // Writes never shrink the file and a truncation cuts it as its last step, so a
+        // shorter file means the journaled operation landed.
+        if self.file.metadata()?.len() >= header.physical_len {
+            for ((page, _), contents) in header.entries.iter().zip(&slots) {
+                write_all_at(&self.file, contents, page_offset(*page))?;
+            }
+            self.file.set_len(header.physical_len)?;
+            self.file.sync_all()?;
+        }
+        let settled = JournalHeader {
+            epoch: header.epoch,
+            ..JournalHeader::default()
+        };
+        self.write_journal(1, &settled, &[])?;
+        self.file.sync_all()
+    }
+
+    /// The newest intact journal header copy with its undo slots.
+    fn read_journal(&self) -> io::Result<Option<(JournalHeader, Vec<Vec<u8>>)>> {
+        let mut copies = vec![0u8; narrow(PAGE_SIZE)];
+        read_exact_at(&self.file, &mut copies, ENVELOPE_SIZE)?;
+        let mut newest: Option<(JournalHeader, Vec<Vec<u8>>)> = None;
+        // On an epoch tie the second copy wins: it is written after the first.
+        for copy in copies.chunks_exact(JOURNAL_HEADER_SIZE) {
+            if let Some(decoded) = JournalHeader::decode(copy, &self.key, &self.file)? {
+                if newest
+                    .as_ref()
+                    .is_none_or(|(header, _)| decoded.0.epoch >= header.epoch)
+                {
+                    newest = Some(decoded);
+                }
+            }
+        }
+        Ok(newest)
+    }
+
+    fn write_journal(
+        &self,
+        copy: usize,
+        header: &JournalHeader,
+        slots: &[Vec<u8>],
+    ) -> io::Result<()> {
+        let offset = ENVELOPE_SIZE + (copy * JOURNAL_HEADER_SIZE) as u64;
+        write_all_at(&self.file, &header.encode(&self.key, slots), offset)
+    }
+
+    /// Run `apply` so that a crash at any point leaves the old or the new pages, never a mix
+    /// that fails authentication.
+    ///
+    /// The stored bytes of the `saved` pages and the physical length are copied into the
+    /// journal and synced before `apply` seals pages with the new write epoch; reopening
+    /// after a crash puts them back.
+    fn journaled(
+        &self,
+        len: u64,
+        saved: &[u64],
+        apply: impl FnOnce(u64) -> io::Result<()>,
+    ) -> io::Result<()> {
+        debug_assert!(saved.len() as u64 <= JOURNAL_SLOTS);
+        let epoch = self.key.next_epoch();
+        if self.journaled {
+            let mut header = JournalHeader {
+                epoch,
+                active: true,
+                physical_len: self.file.metadata()?.len(),
+                entries: Vec::with_capacity(saved.len()),
+            };
+            let mut slots = Vec::with_capacity(saved.len());
+            for (slot, &page) in saved.iter().enumerate() {
+                let mut contents = vec![0u8; narrow(stored_len(page, len))];
+                read_exact_at(&self.file, &mut contents, page_offset(page))?;
+                write_all_at(&self.file, &contents, journal_slot_offset(slot))?;
+                header.entries.push((
+                    page,
+                    u32::try_from(contents.len()).map_err(io::Error::other)?,
+                ));
+                slots.push(contents);
+            }
+            self.write_journal(0, &header, &slots)?;
+            self.file.sync_all()?;
+            apply(epoch)?;
+            self.file.sync_all()?;
+        } else {
+            apply(epoch)?;
+        }
+        let landed = JournalHeader {
+            epoch,
+            ..JournalHeader::default()
+        };
+        self.write_journal(1, &landed, &[])
+    }
+
+    fn read_page(&self, page: u64, len: u64) -> io::Result<Vec<u8>> {
+        let start = page * PAGE_DATA_SIZE;
+        if start >= len {
+            return Ok(Vec::new());
+        }
+        self.open_page(page, len, is_final_page(page, len))
+    }
+
+    /// Decrypt `page` whether or not it was sealed as the final page.
+    fn open_page_either(&self, page: u64, len: u64) -> io::Result<(Vec<u8>, bool)> {
+        match self.open_page(page, len, true) {
+            Ok(plaintext) => Ok((plaintext, true)),
+            Err(_) => self
+                .open_page(page, len, false)
+                .map(|plaintext| (plaintext, false)),
+        }
+    }
+
+    fn open_page(&self, page: u64, len: u64, last: bool) -> io::Result<Vec<u8>> {
+        let mut sealed = vec![0u8; narrow(stored_len(page, len))];
+        read_exact_at(&self.file, &mut sealed, page_offset(page))?;
+        let (epoch, sealed) = sealed.split_at(8);
+        let epoch = read_u64(epoch);
+        let (nonce, ciphertext) = sealed.split_at(NONCE_SIZE);
+        let plaintext = self
+            .key
+            .cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &page_aad(page, last, epoch),
+                },
+            )
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("encrypted page {page} failed authentication"),
+                )
+            })?;
+        self.check_epoch(page, epoch)?;
+        Ok(plaintext)
+    }
+
+    /// Reject a page sealed by a write the journal never recorded.
+    fn check_epoch(&self, page: u64, epoch: u64) -> io::Result<()> {
+        if epoch <= self.key.epoch.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        // Another process may have written since the key was loaded.
+        if let Some((header, _)) = self.read_journal()? {
+            self.key.epoch.fetch_max(header.epoch, Ordering::SeqCst);
+        }
+        if epoch <= self.key.epoch.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("encrypted page {page} is newer than the write journal"),
+        ))
+    }
+
+    fn write_page(&self, page: u64, plaintext: &[u8], last: bool, epoch: u64) -> io::Result<()> {
+        let mut nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut nonce);
+        let ciphertext = self
+            .key
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad: &page_aad(page, last, epoch),
+                },
+            )
+            .map_err(|err| io::Error::other(format!("failed to seal page {page}: {err}")))?;
+        let mut sealed = Vec::with_capacity(8 + NONCE_SIZE + ciphertext.len());
+        sealed.extend_from_slice(&epoch.to_le_bytes());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        write_all_at(&self.file, &sealed, page_offset(page))
+    }
+
+    /// Write `buf` at logical `offset`, zero-filling any gap after the current end.
+    ///
+    /// Large writes are split into runs of pages that each fit the journal, so every run
+    /// lands or is undone as a whole.
+    fn write_at(&self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        if buf.is_empty() {
+            return Ok(());
+        }
+        if offset > self.len()? {
+            self.set_len(offset)?;
+        }
+        let mut written = 0;
+        while written < buf.len() {
+            let at = offset + written as u64;
+            let run_end = (at / PAGE_DATA_SIZE + MAX_PAGES_PER_WRITE) * PAGE_DATA_SIZE;
+            let take = (buf.len() - written).min(narrow(run_end - at));
+            self.write_run(at, &buf[written..written + take])?;
+            written += take;
+        }
+        Ok(())
+    }
+
+    /// Write `buf`, spanning at most `MAX_PAGES_PER_WRITE` pages, at `offset` within or at
+    /// the end of the stream.
+    fn write_run(&self, offset: u64, buf: &[u8]) -> io::Result<()> {
+        let len = self.len()?;
+        let end = offset + buf.len() as u64;
+        let new_len = end.max(len);
+        let first_page = offset / PAGE_DATA_SIZE;
+        let last_page = (end - 1) / PAGE_DATA_SIZE;
+        // A full final page that is not rewritten below stops being the final page.
+        let resealed = (new_len > len && len > 0 && len % PAGE_DATA_SIZE == 0)
+            .then(|| len / PAGE_DATA_SIZE - 1)
+            .filter(|&previous| previous < first_page);
+        let stored_pages = len.div_ceil(PAGE_DATA_SIZE);
+        let saved: Vec<u64> = resealed
+            .into_iter()
+            .chain(first_page..stored_pages.min(last_page + 1))
+            .collect();
+
+        self.journaled(len, &saved, |epoch| {
+            if let Some(previous) = resealed {
+                let plaintext = self.read_page(previous, len)?;
+                self.write_page(previous, &plaintext, false, epoch)?;
+            }
+            for page in first_page..=last_page {
+                let page_start = page * PAGE_DATA_SIZE;
+                let page_end = (page_start + PAGE_DATA_SIZE).min(new_len);
+                let copy_start = offset.max(page_start);
+                let copy_end = end.min(page_end);
+                // Pages overwritten entirely do not need their previous contents.
+                let mut plaintext = if copy_start == page_start && copy_end == page_end {
+                    Vec::new()
+                } else {
+                    self.read_page(page, len)?
+                };
+                plaintext.resize(narrow(page_end - page_start), 0);
+
+                let src = narrow(copy_start - offset)..narrow(copy_end - offset);
+                let dst = narrow(copy_start - page_start)..narrow(copy_end - page_start);
+                plaintext[dst].copy_from_slice(&buf[src]);
+                self.write_page(page, &plaintext, is_final_page(page, new_len), epoch)?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl Read for EncryptedFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.len()?;
+        if buf.is_empty() || self.position >= len {
+            return Ok(0);
+        }
+        let page = self.position / PAGE_DATA_SIZE;
+        let plaintext = self.read_page(page, len)?;
+        let within = narrow(self.position - page * PAGE_DATA_SIZE);
+        let count = (plaintext.len() - within).min(buf.len());
+        buf[..count].copy_from_slice(&plaintext[within..within + count]);
+        self.position += count as u64;
+        Ok(count)
+    }
+}
+
+impl Write for EncryptedFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.write_at(self.position, buf)?;
+        self.position += buf.len() as u64;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+impl Seek for EncryptedFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let target = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(delta) => self.len()?.checked_add_signed(delta),
+            SeekFrom::Current(delta) => self.position.checked_add_signed(delta),
+        };
+        let target = target.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )
+        })?;
+        self.position = target;
+        Ok(target)
+    }
+}
+
+/// Narrow a length bounded by a page, a write run or the caller's buffer.
+#[allow(clippy::cast_possible_truncation)]
+const fn narrow(len: u64) -> usize {
+    len as usize
+}
+
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut raw = [0u8; 8];
+    raw.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(raw)
+}
+
+fn page_offset(page: u64) -> u64 {
+    DATA_OFFSET + page * PAGE_SIZE
+}
+
+fn journal_slot_offset(slot: usize) -> u64 {
+    JOURNAL_SLOT_OFFSET + slot as u64 * PAGE_SIZE
+}
+
+/// Physical size of `page` in a stream of logical length `len`.
+fn stored_len(page: u64, len: u64) -> u64 {
+    (len - page * PAGE_DATA_SIZE).min(PAGE_DATA_SIZE) + PAGE_OVERHEAD
+}
+
+fn is_final_page(page: u64, len: u64) -> bool {
+    len > 0 && page == (len - 1) / PAGE_DATA_SIZE
+}
+
+/// Associated data of a page: its index, whether it ends the logical stream, and the epoch
+/// of the write that sealed it.
+fn page_aad(page: u64, last: bool, epoch: u64) -> [u8; 17] {
+    let mut aad = [0u8; 17];
+    aad[..8].copy_from_slice(&page.to_le_bytes());
+    aad[8] = u8::from(last);
+    aad[9..].copy_from_slice(&epoch.to_le_bytes());
+    aad
+}
+
+fn logical_len(physical: u64) -> u64 {
+    let body = physical.saturating_sub(DATA_OFFSET);
+    let full_pages = body / PAGE_SIZE;
+    // A tail too short to hold a sealed byte is a torn page extension.
+    let tail = (body % PAGE_SIZE).saturating_sub(PAGE_OVERHEAD);
+    full_pages * PAGE_DATA_SIZE + tail
+}
+
+#[cfg(unix)]
+fn read_exact_at(file: &File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+#[cfg(unix)]
+fn write_all_at(file: &File, buf: &[u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::write_all_at(file, buf, offset)
+}
+
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset)? {
+            0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+            n => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn write_all_at(file: &File, mut buf: &[u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buf.is_empty() {
+        match file.seek_write(buf, offset)? {
+            0 => return Err(io::ErrorKind::WriteZero.into()),
+            n => {
+                buf = &buf[n..];
+                offset += n as u64;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use tempfile::NamedTempFile;
+
+    const PASSWORD: &[u8] = b"pw";
+
+    fn reopen(temp: &NamedTempFile) -> EncryptedFile {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(temp.path())
+            .expect("open");
+        EncryptedFile::open(file, PASSWORD).expect("unlock")
+    }
+
+    fn created(contents: &[u8]) -> (NamedTempFile, EncryptedFile) {
+        let temp = NamedTempFile::new().expect("temp file");
+        let file = EncryptedFile::create(temp.reopen().expect("reopen"), PASSWORD).expect("create");
+        file.write_at(0, contents).expect("write");
+        (temp, file)
+    }
+
+    fn simulated_crash() -> io::Error {
+        io::Error::other("simulated crash")
+    }
+
+    #[test]
+    fn torn_append_across_a_page_boundary_is_undone_on_open() {
+        let before = vec![b'a'; PAGE_DATA_SIZE as usize - 10];
+        let (temp, file) = created(&before);
+        let len = file.len().expect("len");
+
+        // The new second page lands, then the crash tears the rewrite of the first one.
+        let crashed = file.journaled(len, &[0], |epoch| {
+            file.write_page(1, &[b'b'; 90], true, epoch)?;
+            write_all_at(&file.file, &[0xAA; 100], page_offset(0))?;
+            Err(simulated_crash())
+        });
+        assert!(crashed.is_err());
+        drop(file);
+
+        let recovered = reopen(&temp);
+        assert_eq!(recovered.read_all().expect("read"), before);
+        recovered.write_at(len, &[b'b'; 100]).expect("append");
+        let mut expected = before;
+        expected.extend_from_slice(&[b'b'; 100]);
+        assert_eq!(reopen(&temp).read_all().expect("read"), expected);
+    }
+
+    #[test]
+    fn append_interrupted_before_resealing_a_full_final_page_is_undone_on_open() {
+        let before = vec![b'a'; PAGE_DATA_SIZE as usize];
+        let (temp, file) = created(&before);
+        let len = file.len().expect("len");
+
+        // The first page is still sealed as the final one when the crash hits.
+        let crashed = file.journaled(len, &[0], |epoch| {
+            file.write_page(1, b"tail", true, epoch)?;
+            Err(simulated_crash())
+        });
+        assert!(crashed.is_err());
+        assert!(file.read_all().is_err());
+        drop(file);
+
+        assert_eq!(reopen(&temp).read_all().expect("read"), before);
+    }
+
+    #[test]
+    fn truncation_that_landed_is_kept_on_open() {
+        let before = vec![b'a'; 3 * PAGE_DATA_SIZE as usize];
+        let (temp, file) = created(&before);
+        let current = file.len().expect("len");
+        let keep = PAGE_DATA_SIZE + 7;
+
+        // The tail is cut, but the crash hits before the journal records it.
+        let crashed = file.journaled(current, &[1], |epoch| {
+            file.write_page(1, &[b'a'; 7], true, epoch)?;
+            file.file.set_len(page_offset(1) + 7 + PAGE_OVERHEAD)?;
+            Err(simulated_crash())
+        });
+        assert!(crashed.is_err());
+        drop(file);
+
+        let recovered = reopen(&temp);
+        assert_eq!(recovered.len().expect("len"), keep);
+        assert_eq!(
+            recovered.read_all().expect("read"),
+            &before[..keep as usize]
+        );
+    }
+
+    #[test]
+    fn page_from_a_later_version_is_rejected() {
+        let (temp, file) = created(&[b'a'; 100]);
+        let earlier = std::fs::read(temp.path()).expect("read");
+        file.write_at(0, &[b'b'; 100]).expect("overwrite");
+        let later = std::fs::read(temp.path()).expect("read");
+        drop(file);
+
+        let page = page_offset(0) as usize..later.len();
+        let mut spliced = earlier;
+        spliced[page.clone()].copy_from_slice(&later[page]);
+        std::fs::write(temp.path(), &spliced).expect("write");
+
+        let err = reopen(&temp).read_all().expect_err("spliced page");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,4 +1,5 @@
-//! Password-based encryption capsules for `.mv2` files (`.mv2e`).
+//! Encryption capsules for `.mv2` files (`.mv2e`), sealed with a password or to X25519
+//! recipients.
 //!
 //! This module is feature-gated (`encryption`) to keep the default memvid-core
 //! binary size small and avoid pulling crypto dependencies into users that don't
@@ -9,10 +10,18 @@ mod capsule_stream;
 mod constants;
 mod crypto;
 mod error;
+mod live;
+mod recipients;
 mod types;
 
+pub use crate::constants::MV2E_LIVE_VERSION;
 pub use capsule::{lock_file, unlock_file};
 pub use capsule_stream::{lock_file_stream, unlock_file_stream};
 pub use constants::*;
 pub use error::EncryptionError;
+pub(crate) use live::EncryptedFile;
+pub use recipients::{
+    CapsuleInfo, RecipientPublicKey, RecipientSecretKey, lock_file_for_recipients,
+    unlock_file_for_recipient, verify_capsule,
+};
 pub use types::{CipherAlgorithm, KdfAlgorithm, Mv2eHeader};
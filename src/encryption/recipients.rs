@@ -0,0 +1,548 @@
+//! Public-key `.mv2e` capsules.
+//!
+//! A recipient capsule is encrypted with a random data key that is wrapped once per X25519
+//! recipient, so every listed recipient can unlock it with their own secret key. The capsule
+//! can also be signed with Ed25519, which lets recipients check who produced a memory before
+//! unlocking it.
+//!
+//! Layout, after the 64-byte [`Mv2eHeader`] (`kdf_algorithm = X25519`, streaming framing):
+//! - `[count: u16]` then `count` stanzas `[recipient: 32][ephemeral: 32][nonce: 12][wrapped: 48]`;
+//! - with [`FLAG_SIGNED`] in `reserved[1]`: `[signer: 32][signature: 64]`, an Ed25519
+//!   signature over the BLAKE3 digest of every other byte of the capsule;
+//! - the data chunks, framed like streaming password capsules.
+
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use curve25519_dalek::montgomery::MontgomeryPoint;
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
+use rand::RngCore;
+use rand::rngs::OsRng;
+use zeroize::Zeroizing;
+
+use crate::encryption::capsule::{validate_mv2_file, write_atomic};
+use crate::encryption::capsule_stream::{decrypt_chunks, encrypt_chunks};
+use crate::encryption::constants::{
+    FLAG_SIGNED, KEY_SIZE, MAX_RECIPIENTS, MV2E_MAGIC, MV2E_VERSION, NONCE_SIZE, SALT_SIZE,
+    TAG_SIZE,
+};
+use crate::encryption::crypto::{decrypt, encrypt};
+use crate::encryption::error::EncryptionError;
+use crate::encryption::types::{CipherAlgorithm, KdfAlgorithm, Mv2eHeader};
+
+const POINT_SIZE: usize = 32;
+const WRAPPED_KEY_SIZE: usize = KEY_SIZE + TAG_SIZE;
+const STANZA_SIZE: usize = POINT_SIZE * 2 + NONCE_SIZE + WRAPPED_KEY_SIZE;
+const SIGNATURE_SIZE: usize = 64;
+const WRAP_CONTEXT: &str = "memvid mv2e x25519 key wrap v1";
+const SIGNATURE_CONTEXT: &str = "memvid mv2e capsule signature v1";
+
+/// X25519 public key a capsule can be sealed to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RecipientPublicKey([u8; POINT_SIZE]);
+
+impl RecipientPublicKey {
+    #[must_use]
+    pub fn from_bytes(bytes: [u8; POINT_SIZE]) -> Self {
+        Self(bytes)
+    }
+
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; POINT_SIZE] {
+        self.0
+    }
+}
+
+/// X25519 secret key of a capsule recipient.
+#[derive(Clone)]
+pub struct RecipientSecretKey(Zeroizing<[u8; KEY_SIZE]>);
+
+impl RecipientSecretKey {
+    /// Generate a fresh key from the OS random number generator.
+    #[must_use]
+    pub fn generate() -> Self {
+        let mut bytes = Zeroizing::new([0u8; KEY_SIZE]);
+        OsRng.fill_bytes(bytes.as_mut());
+        Self(bytes)
+    }
+
+    #[must_use]
+    pub fn from_bytes(bytes: [u8; KEY_SIZE]) -> Self {
+        Self(Zeroizing::new(bytes))
+    }
+
+    #[must_use]
+    pub fn to_bytes(&self) -> [u8; KEY_SIZE] {
+        *self.0
+    }
+
+    #[must_use]
+    pub fn public_key(&self) -> RecipientPublicKey {
+        RecipientPublicKey(MontgomeryPoint::mul_base_clamped(*self.0).to_bytes())
+    }
+}
+
+impl std::fmt::Debug for RecipientSecretKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("RecipientSecretKey")
+            .field(&self.public_key())
+            .finish()
+    }
+}
+
+/// What a capsule declares about itself, with its signature already checked.
+#[derive(Debug, Clone)]
+pub struct CapsuleInfo {
+    pub header: Mv2eHeader,
+    /// Recipients the data key is wrapped for (empty for password capsules).
+    pub recipients: Vec<RecipientPublicKey>,
+    /// Key that signed the capsule, if it is signed.
+    pub signer: Option<VerifyingKey>,
+}
+
+/// Data key wrapped for one recipient.
+struct Stanza {
+    recipient: [u8; POINT_SIZE],
+    ephemeral: [u8; POINT_SIZE],
+    nonce: [u8; NONCE_SIZE],
+    wrapped_key: [u8; WRAPPED_KEY_SIZE],
+}
+
+impl Stanza {
+    fn seal(
+        data_key: &[u8; KEY_SIZE],
+        recipient: &RecipientPublicKey,
+        salt: &[u8; SALT_SIZE],
+    ) -> Result<Self, EncryptionError> {
+        let ephemeral_secret = RecipientSecretKey::generate();
+        let ephemeral = ephemeral_secret.public_key().0;
+        let shared = MontgomeryPoint(recipient.0).mul_clamped(*ephemeral_secret.0);
+        let wrapping_key = wrapping_key(&shared, &ephemeral, &recipient.0, salt)?;
+
+        let mut nonce = [0u8; NONCE_SIZE];
+        OsRng.fill_bytes(&mut nonce);
+        let wrapped_key = encrypt(data_key, &wrapping_key, &nonce)?
+            .try_into()
+            .map_err(|wrapped: Vec<u8>| EncryptionError::SizeMismatch {
+                expected: WRAPPED_KEY_SIZE as u64,
+                actual: wrapped.len() as u64,
+            })?;
+        Ok(Self {
+            recipient: recipient.0,
+            ephemeral,
+            nonce,
+            wrapped_key,
+        })
+    }
+
+    fn open(
+        &self,
+        secret: &RecipientSecretKey,
+        salt: &[u8; SALT_SIZE],
+    ) -> Result<Zeroizing<[u8; KEY_SIZE]>, EncryptionError> {
+        let shared = MontgomeryPoint(self.ephemeral).mul_clamped(*secret.0);
+        let wrapping_key = wrapping_key(&shared, &self.ephemeral, &self.recipient, salt)?;
+        let key = Zeroizing::new(decrypt(&self.wrapped_key, &wrapping_key, &self.nonce)?);
+        let mut data_key = Zeroizing::new([0u8; KEY_SIZE]);
+        if key.len() != KEY_SIZE {
+            return Err(EncryptionError::SizeMismatch {
+                expected: KEY_SIZE as u64,
+                actual: key.len() as u64,
+            });
+        }
+        data_key.copy_from_slice(&key);
+        Ok(data_key)
+    }
+
+    fn encode(&self) -> [u8; STANZA_SIZE] {
+        let mut buf = [0u8; STANZA_SIZE];
+        buf[0..32].copy_from_slice(&self.recipient);
+        buf[32..64].copy_from_slice(&self.ephemeral);
+        buf[64..64 + NONCE_SIZE].copy_from_slice(&self.nonce);
+        buf[64 + NONCE_SIZE..].copy_from_slice(&self.wrapped_key);
+        buf
+    }
+
+    fn decode(buf: &[u8; STANZA_SIZE]) -> Self {
+        let mut stanza = Self {
+            recipient: [0u8; POINT_SIZE],
+            ephemeral: [0u8; POINT_SIZE],
+            nonce: [0u8; NONCE_SIZE],
+            wrapped_key: [0u8; WRAPPED_KEY_SIZE],
+        };
+        stanza.recipient.copy_from_slice(&buf[0..32]);
+        stanza.ephemeral.copy_from_slice(&buf[32..64]);
+        stanza.nonce.copy_from_slice(&buf[64..64 + NONCE_SIZE]);
+        stanza.wrapped_key.copy_from_slice(&buf[64 + NONCE_SIZE..]);
+        stanza
+    }
+}
+
+/// Key wrapping the data key for one recipient, bound to both public keys and the capsule salt.
+fn wrapping_key(
+    shared: &MontgomeryPoint,
+    ephemeral: &[u8; POINT_SIZE],
+    recipient: &[u8; POINT_SIZE],
+    salt: &[u8; SALT_SIZE],
+) -> Result<Zeroizing<[u8; KEY_SIZE]>, EncryptionError> {
+    // A low-order point yields an all-zero secret that anyone could compute.
+    if shared.as_bytes() == &[0u8; POINT_SIZE] {
+        return Err(EncryptionError::KeyDerivation {
+            reason: "recipient key is a low-order point".to_string(),
+        });
+    }
+    let mut hasher = blake3::Hasher::new_derive_key(WRAP_CONTEXT);
+    hasher.update(shared.as_bytes());
+    hasher.update(ephemeral);
+    hasher.update(recipient);
+    hasher.update(salt);
+    Ok(Zeroizing::new(*hasher.finalize().as_bytes()))
+}
+
+/// Header and key stanzas of a recipient capsule, as read from its start.
+struct Envelope {
+    header: Mv2eHeader,
+    stanzas: Vec<Stanza>,
+    signature: Option<(VerifyingKey, Signature)>,
+    /// Digest state covering everything read so far except the signature itself.
+    hasher: blake3::Hasher,
+}
+
+impl Envelope {
+    fn read(reader: &mut impl Read) -> Result<Self, EncryptionError> {
+        let mut header_bytes = [0u8; Mv2eHeader::SIZE];
+        reader.read_exact(&mut header_bytes)?;
+        let header = Mv2eHeader::decode(&header_bytes)?;
+        if header.kdf_algorithm != KdfAlgorithm::X25519 {
+            return Err(EncryptionError::PasswordCapsule);
+        }
+
+        let mut hasher = blake3::Hasher::new_derive_key(SIGNATURE_CONTEXT);
+        hasher.update(&header_bytes);
+
+        let mut count_bytes = [0u8; 2];
+        reader.read_exact(&mut count_bytes)?;
+        hasher.update(&count_bytes);
+        let count = usize::from(u16::from_le_bytes(count_bytes));
+        if count > MAX_RECIPIENTS {
+            return Err(EncryptionError::TooManyRecipients {
+                count,
+                max: MAX_RECIPIENTS,
+            });
+        }
+
+        let mut stanzas = Vec::with_capacity(count);
+        for _ in 0..count {
+            let mut buf = [0u8; STANZA_SIZE];
+            reader.read_exact(&mut buf)?;
+            hasher.update(&buf);
+            stanzas.push(Stanza::decode(&buf));
+        }
+
+        let signature = if header.reserved[1] & FLAG_SIGNED != 0 {
+            let mut signer = [0u8; POINT_SIZE];
+            let mut signature = [0u8; SIGNATURE_SIZE];
+            reader.read_exact(&mut signer)?;
+            reader.read_exact(&mut signature)?;
+            hasher.update(&signer);
+            let signer = VerifyingKey::from_bytes(&signer).map_err(|err| {
+                EncryptionError::SignatureInvalid {
+                    reason: format!("invalid signer key: {err}"),
+                }
+            })?;
+            Some((signer, Signature::from_bytes(&signature)))
+        } else {
+            None
+        };
+
+        Ok(Self {
+            header,
+            stanzas,
+            signature,
+            hasher,
+        })
+    }
+
+    fn recipients(&self) -> Vec<RecipientPublicKey> {
+        self.stanzas
+            .iter()
+            .map(|stanza| RecipientPublicKey(stanza.recipient))
+            .collect()
+    }
+
+    fn unwrap_key(
+        &self,
+        secret: &RecipientSecretKey,
+    ) -> Result<Zeroizing<[u8; KEY_SIZE]>, EncryptionError> {
+        let public = secret.public_key();
+        let stanza = self
+            .stanzas
+            .iter()
+            .find(|stanza| stanza.recipient == public.0)
+            .ok_or(EncryptionError::NotARecipient)?;
+        stanza.open(secret, &self.header.salt)
+    }
+
+    /// Check the signature against the digest of the whole capsule, given the bytes after
+    /// the envelope have been fed to `hasher`.
+    fn verify(
+        signature: Option<&(VerifyingKey, Signature)>,
+        hasher: &blake3::Hasher,
+    ) -> Result<Option<VerifyingKey>, EncryptionError> {
+        let Some((signer, signature)) = signature else {
+            return Ok(None);
+        };
+        signer
+            .verify_strict(hasher.finalize().as_bytes(), signature)
+            .map_err(|_| EncryptionError::SignatureInvalid {
+                reason: "signature does not match capsule contents".to_string(),
+            })?;
+        Ok(Some(*signer))
+    }
+}
+
+/// Forwards reads while hashing the bytes that pass through.
+struct HashingReader<'a, R> {
+    inner: R,
+    hasher: &'a mut blake3::Hasher,
+}
+
+impl<R: Read> Read for HashingReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+}
+
+/// Forwards writes while hashing the bytes that pass through.
+struct HashingWriter<'a, W> {
+    inner: W,
+    hasher: &'a mut blake3::Hasher,
+}
+
+impl<W: Write> Write for HashingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.hasher.update(&buf[..n]);
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Lock an `.mv2` file into a `.mv2e` capsule that each of `recipients` can unlock,
+/// optionally signed by `signer`.
+///
+/// # Errors
+///
+/// Fails when `input` is not an `.mv2` file, when there are no or too many recipients, and
+/// when the capsule cannot be written.
+pub fn lock_file_for_recipients(
+    input: impl AsRef<Path>,
+    output: Option<&Path>,
+    recipients: &[RecipientPublicKey],
+    signer: Option<&SigningKey>,
+) -> Result<PathBuf, EncryptionError> {
+    let input = input.as_ref();
+    validate_mv2_file(input)?;
+    if recipients.is_empty() {
+        return Err(EncryptionError::NoRecipients);
+    }
+    if recipients.len() > MAX_RECIPIENTS {
+        return Err(EncryptionError::TooManyRecipients {
+            count: recipients.len(),
+            max: MAX_RECIPIENTS,
+        });
+    }
+
+    let metadata = std::fs::metadata(input)?;
+
+    let mut data_key = Zeroizing::new([0u8; KEY_SIZE]);
+    let mut salt = [0u8; SALT_SIZE];
+    let mut base_nonce = [0u8; NONCE_SIZE];
+    OsRng.fill_bytes(data_key.as_mut());
+    OsRng.fill_bytes(&mut salt);
+    OsRng.fill_bytes(&mut base_nonce);
+
+    let stanzas = recipients
+        .iter()
+        .map(|recipient| Stanza::seal(&data_key, recipient, &salt))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let header = Mv2eHeader {
+        magic: MV2E_MAGIC,
+        version: MV2E_VERSION,
+        kdf_algorithm: KdfAlgorithm::X25519,
+        cipher_algorithm: CipherAlgorithm::Aes256Gcm,
+        salt,
+        nonce: base_nonce,
+        original_size: metadata.len(),
+        reserved: [0x01, if signer.is_some() { FLAG_SIGNED } else { 0 }, 0, 0],
+    };
+
+    let output_path = output.map_or_else(|| input.with_extension("mv2e"), PathBuf::from);
+
+    let mut reader = BufReader::new(File::open(input)?);
+
+    write_atomic(&output_path, |file| -> Result<(), EncryptionError> {
+        let mut hasher = blake3::Hasher::new_derive_key(SIGNATURE_CONTEXT);
+        let signature_offset;
+        {
+            let mut writer = HashingWriter {
+                inner: BufWriter::new(&mut *file),
+                hasher: &mut hasher,
+            };
+            writer.write_all(&header.encode())?;
+            let count =
+                u16::try_from(stanzas.len()).map_err(|_| EncryptionError::TooManyRecipients {
+                    count: stanzas.len(),
+                    max: MAX_RECIPIENTS,
+                })?;
+            writer.write_all(&count.to_le_bytes())?;
+            for stanza in &stanzas {
+                writer.write_all(&stanza.encode())?;
+            }
+            signature_offset = (Mv2eHeader::SIZE + 2 + stanzas.len() * STANZA_SIZE) as u64;
+            if let Some(signer) = signer {
+                writer.write_all(signer.verifying_key().as_bytes())?;
+                // Placeholder, outside the digest; filled in once the chunks are written.
+                writer.inner.write_all(&[0u8; SIGNATURE_SIZE])?;
+            }
+            encrypt_chunks(&mut reader, &mut writer, &data_key, &base_nonce)?;
+            writer.flush()?;
+        }
+
+        if let Some(signer) = signer {
+            let signature = signer.sign(hasher.finalize().as_bytes());
+            file.seek(SeekFrom::Start(signature_offset + POINT_SIZE as u64))?;
+            file.write_all(&signature.to_bytes())?;
+        }
+        Ok(())
+    })?;
+
+    Ok(output_path)
+}
+
+/// Unlock a recipient capsule with one of its recipients' secret key.
+///
+/// With `trusted_signer` set, the capsule must carry a valid signature from that key; this
+/// is checked before anything is decrypted.
+///
+/// # Errors
+///
+/// Fails when `secret` is not one of the recipients, when the signature is missing or does
+/// not match `trusted_signer`, and when the decrypted size does not match the header.
+pub fn unlock_file_for_recipient(
+    input: impl AsRef<Path>,
+    output: Option<&Path>,
+    secret: &RecipientSecretKey,
+    trusted_signer: Option<&VerifyingKey>,
+) -> Result<PathBuf, EncryptionError> {
+    let input = input.as_ref();
+
+    if let Some(trusted) = trusted_signer {
+        let info = verify_capsule(input)?;
+        if info.header.kdf_algorithm != KdfAlgorithm::X25519 {
+            return Err(EncryptionError::PasswordCapsule);
+        }
+        if info.signer.as_ref() != Some(trusted) {
+            return Err(EncryptionError::SignatureInvalid {
+                reason: match info.signer {
+                    Some(_) => "capsule was signed by a different key".to_string(),
+                    None => "capsule is not signed".to_string(),
+                },
+            });
+        }
+    }
+
+    let mut reader = BufReader::new(File::open(input)?);
+    let envelope = Envelope::read(&mut reader)?;
+    let data_key = envelope.unwrap_key(secret)?;
+    let Envelope {
+        header,
+        signature,
+        mut hasher,
+        ..
+    } = envelope;
+
+    let output_path = output.map_or_else(|| input.with_extension("mv2"), PathBuf::from);
+
+    write_atomic(&output_path, |file| -> Result<(), EncryptionError> {
+        let mut writer = CountingWriter {
+            inner: BufWriter::new(file),
+            written: 0,
+        };
+        let mut chunks = HashingReader {
+            inner: &mut reader,
+            hasher: &mut hasher,
+        };
+        decrypt_chunks(&mut chunks, &mut writer, &data_key, &header.nonce)?;
+        writer.flush()?;
+        if writer.written != header.original_size {
+            return Err(EncryptionError::SizeMismatch {
+                expected: header.original_size,
+                actual: writer.written,
+            });
+        }
+        // The file may have changed since it was verified; never publish unverified bytes.
+        if trusted_signer.is_some() {
+            Envelope::verify(signature.as_ref(), &hasher)?;
+        }
+        Ok(())
+    })?;
+
+    Ok(output_path)
+}
+
+/// Read a capsule's recipients and verify its signature, without decrypting it.
+///
+/// # Errors
+///
+/// Fails with [`EncryptionError::SignatureInvalid`] when a signature is present but does
+/// not match the capsule contents.
+pub fn verify_capsule(input: impl AsRef<Path>) -> Result<CapsuleInfo, EncryptionError> {
+    let mut reader = BufReader::new(File::open(input.as_ref())?);
+
+    let mut header_bytes = [0u8; Mv2eHeader::SIZE];
+    reader.read_exact(&mut header_bytes)?;
+    let header = Mv2eHeader::decode(&header_bytes)?;
+    if header.kdf_algorithm != KdfAlgorithm::X25519 {
+        return Ok(CapsuleInfo {
+            header,
+            recipients: Vec::new(),
+            signer: None,
+        });
+    }
+
+    reader.seek(SeekFrom::Start(0))?;
+    let mut envelope = Envelope::read(&mut reader)?;
+    io::copy(&mut reader, &mut envelope.hasher)?;
+    let signer = Envelope::verify(envelope.signature.as_ref(), &envelope.hasher)?;
+    Ok(CapsuleInfo {
+        recipients: envelope.recipients(),
+        header: envelope.header,
+        signer,
+    })
+}
+
+/// Forwards writes while counting the bytes written.
+struct CountingWriter<W> {
+    inner: W,
+    written: u64,
+}
+
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
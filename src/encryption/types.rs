@@ -1,6 +1,6 @@
 use crate::encryption::constants::{
-    CIPHER_AES_256_GCM, KDF_ARGON2ID, MV2E_HEADER_SIZE, MV2E_MAGIC, MV2E_VERSION, NONCE_SIZE,
-    SALT_SIZE,
+    CIPHER_AES_256_GCM, KDF_ARGON2ID, KDF_X25519, MV2E_HEADER_SIZE, MV2E_MAGIC, MV2E_VERSION,
+    NONCE_SIZE, SALT_SIZE,
 };
 use crate::encryption::error::EncryptionError;
 
@@ -21,6 +21,7 @@ pub struct Mv2eHeader {
 #[repr(u8)]
 pub enum KdfAlgorithm {
     Argon2id = KDF_ARGON2ID,
+    X25519 = KDF_X25519,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -62,6 +63,7 @@ impl Mv2eHeader {
 
         let kdf_algorithm = match bytes[6] {
             KDF_ARGON2ID => KdfAlgorithm::Argon2id,
+            KDF_X25519 => KdfAlgorithm::X25519,
             other => return Err(EncryptionError::UnsupportedKdf { id: other }),
         };
 
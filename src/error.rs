@@ -75,6 +75,10 @@ pub enum MemvidError {
     #[error("This file is encrypted: {path}\n{hint}")]
     EncryptedFile { path: PathBuf, hint: String },
 
+    #[cfg(feature = "encryption")]
+    #[error(transparent)]
+    Encryption(#[from] crate::encryption::EncryptionError),
+
     #[error("Table of contents validation failed: {reason}")]
     InvalidToc { reason: Cow<'static, str> },
 
@@ -106,6 +110,12 @@ pub enum MemvidError {
     #[error("Vector index is not enabled")]
     VecNotEnabled,
 
+    #[error("Vector space '{name}' does not exist")]
+    VecSpaceNotFound { name: String },
+
+    #[error("Vector space '{name}' already exists")]
+    VecSpaceExists { name: String },
+
     #[error("CLIP index is not enabled")]
     ClipNotEnabled,
 
@@ -127,6 +137,9 @@ pub enum MemvidError {
     #[error("Unable to checkpoint embedded WAL: {reason}")]
     CheckpointFailed { reason: String },
 
+    #[error("A background commit is in progress; wait for it before writing")]
+    CommitInProgress,
+
     #[error("Ticket sequence is out of order (expected > {expected}, got {actual})")]
     TicketSequence { expected: i64, actual: i64 },
 
@@ -160,15 +173,29 @@ pub enum MemvidError {
     #[error("Operation requires an open memory")]
     RequiresOpen,
 
+    #[error("Operation requires a read-only handle")]
+    RequiresReadOnly,
+
     #[error("Doctor command requires at least one operation")]
     DoctorNoOp,
 
     #[error("Doctor operation failed: {reason}")]
     Doctor { reason: String },
 
+    #[error("Merge failed: {reason}")]
+    MergeFailed { reason: String },
+
+    #[error("JSONL import failed at line {line}: {reason}")]
+    JsonlImport { line: usize, reason: String },
+
     #[error("Feature '{feature}' is not available in this build")]
     FeatureUnavailable { feature: &'static str },
 
+    #[error(
+        "Changes after generation {requested} are no longer in the change log; generations up to {pruned_through} were pruned"
+    )]
+    ChangesPruned { requested: u64, pruned_through: u64 },
+
     #[error("Invalid search cursor: {reason}")]
     InvalidCursor { reason: &'static str },
 
@@ -190,6 +217,9 @@ pub enum MemvidError {
     #[error("Model signature verification failed: {reason}")]
     ModelSignatureInvalid { reason: Box<str> },
 
+    #[error("Memory signature verification failed: {reason}")]
+    MemorySignatureInvalid { reason: Box<str> },
+
     #[error("Model manifest invalid: {reason}")]
     ModelManifestInvalid { reason: Box<str> },
 
@@ -211,6 +241,9 @@ pub enum MemvidError {
     #[error("Tantivy error: {reason}")]
     Tantivy { reason: String },
 
+    #[error("Invalid lex analyzer configuration: {reason}")]
+    InvalidLexAnalyzer { reason: Box<str> },
+
     #[error("Table extraction failed: {reason}")]
     TableExtraction { reason: String },
 
@@ -5,11 +5,12 @@
 //! 2. Match patterns against entity state (MemoryCards) or graph (Logic-Mesh)
 //! 3. Combine graph-filtered candidates with vector ranking
 
-use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 
 use crate::types::{
-    GraphMatchResult, GraphPattern, HybridSearchHit, PatternTerm, QueryPlan, SearchRequest,
-    TriplePattern,
+    Cardinality, GraphMatchResult, GraphPattern, HybridSearchHit, MemoryCardId, PatternTerm,
+    QueryPlan, SearchRequest, TriplePattern, ValueType,
 };
 use crate::{FrameId, Memvid, Result};
 
@@ -92,6 +93,13 @@ impl QueryPlanner {
     /// Analyze a query and produce an execution plan.
     #[must_use]
     pub fn plan(&self, query: &str, top_k: usize) -> QueryPlan {
+        // Queries written in the pattern syntax run as graph queries as they are.
+        if query.trim_start().starts_with('?') {
+            if let Ok(pattern) = GraphPattern::parse(query) {
+                return QueryPlan::graph_only(pattern, top_k);
+            }
+        }
+
         let query_lower = query.to_lowercase();
 
         // Try to detect relational patterns
@@ -187,97 +195,236 @@ fn extract_possessive_query(query: &str) -> Option<(String, String)> {
 }
 
 /// Graph matcher that executes patterns against MemoryCards.
+///
+/// Every card is a `(entity, slot, value)` fact; single-valued slots only contribute their
+/// current card. Patterns are joined on shared variables: a variable bound by one pattern must
+/// match the same value (case-insensitively) wherever it appears again. Literal objects match
+/// any value containing them.
 pub struct GraphMatcher<'a> {
     memvid: &'a Memvid,
 }
 
+/// A card viewed as a triple.
+struct Fact<'a> {
+    entity: String,
+    slot: String,
+    value: &'a str,
+    frame_id: FrameId,
+    confidence: f32,
+}
+
+/// Partial solution: variable bindings and the cards that produced them.
+#[derive(Clone)]
+struct Solution {
+    entity: Option<String>,
+    bindings: HashMap<String, String>,
+    frame_ids: Vec<FrameId>,
+    confidence: f32,
+}
+
+impl Solution {
+    fn empty() -> Self {
+        Self {
+            entity: None,
+            bindings: HashMap::new(),
+            frame_ids: Vec::new(),
+            confidence: 1.0,
+        }
+    }
+
+    fn into_result(self) -> GraphMatchResult {
+        let mut frame_ids = self.frame_ids;
+        frame_ids.sort_unstable();
+        frame_ids.dedup();
+        GraphMatchResult {
+            entity: self.entity.unwrap_or_default(),
+            frame_ids,
+            bindings: self.bindings,
+            confidence: self.confidence,
+        }
+    }
+}
+
 impl<'a> GraphMatcher<'a> {
     /// Create a new graph matcher.
     pub fn new(memvid: &'a Memvid) -> Self {
         Self { memvid }
     }
 
-    /// Execute a graph pattern and return matching results.
+    /// Execute a graph pattern and return one result per distinct set of bindings.
+    ///
+    /// Results with the same bindings are merged, keeping the frames of every card behind
+    /// them. The result's entity is the subject of the first triple.
     pub fn execute(&self, pattern: &GraphPattern) -> Vec<GraphMatchResult> {
-        let mut results = Vec::new();
+        if pattern.triples.is_empty() {
+            return Vec::new();
+        }
+        let facts = self.facts();
+        let mut by_slot: HashMap<&str, Vec<&Fact<'_>>> = HashMap::new();
+        for fact in &facts {
+            by_slot.entry(fact.slot.as_str()).or_default().push(fact);
+        }
 
-        for triple in &pattern.triples {
-            let matches = self.match_triple(triple);
-            results.extend(matches);
+        let join = |solutions: Vec<Solution>, triples: &[TriplePattern]| {
+            triples.iter().fold(solutions, |solutions, triple| {
+                let candidates: Vec<&Fact<'_>> = match &triple.predicate {
+                    PatternTerm::Literal(slot) => by_slot
+                        .get(slot.to_lowercase().as_str())
+                        .cloned()
+                        .unwrap_or_default(),
+                    PatternTerm::Variable(_) => facts.iter().collect(),
+                };
+                solutions
+                    .iter()
+                    .flat_map(|solution| {
+                        candidates
+                            .iter()
+                            .filter_map(move |fact| extend(solution, triple, fact))
+                    })
+                    .collect()
+            })
+        };
+
+        let mut solutions = join(vec![Solution::empty()], &pattern.triples);
+        for group in &pattern.optional {
+            solutions = solutions
+                .into_iter()
+                .flat_map(|solution| {
+                    let extended = join(vec![solution.clone()], group);
+                    if extended.is_empty() {
+                        vec![solution]
+                    } else {
+                        extended
+                    }
+                })
+                .collect();
         }
 
-        // Deduplicate by entity
-        let mut seen = HashSet::new();
-        results.retain(|r| seen.insert(r.entity.clone()));
+        let types = self.variable_types(pattern);
+        let value_type = |variable: &str| types.get(variable).unwrap_or(&ValueType::Any);
+        solutions.retain(|solution| {
+            pattern.filters.iter().all(|filter| {
+                let Some(left) = solution.bindings.get(&filter.variable) else {
+                    return false;
+                };
+                let right = match &filter.value {
+                    PatternTerm::Literal(value) => value,
+                    PatternTerm::Variable(name) => match solution.bindings.get(name) {
+                        Some(value) => value,
+                        None => return false,
+                    },
+                };
+                value_type(&filter.variable)
+                    .compare(left, right)
+                    .is_some_and(|ordering| filter.op.holds(ordering))
+            })
+        });
 
-        results
-    }
+        // Merge solutions that bind the same values, keeping the first one's position.
+        let mut merged: Vec<Solution> = Vec::new();
+        let mut index: HashMap<Vec<(String, String)>, usize> = HashMap::new();
+        for solution in solutions {
+            let mut key: Vec<_> = solution
+                .bindings
+                .iter()
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
+            key.sort();
+            key.push((String::new(), solution.entity.clone().unwrap_or_default()));
+            if let Some(&at) = index.get(&key) {
+                let existing = &mut merged[at];
+                existing.frame_ids.extend(solution.frame_ids);
+                existing.confidence = existing.confidence.max(solution.confidence);
+            } else {
+                index.insert(key, merged.len());
+                merged.push(solution);
+            }
+        }
 
-    fn match_triple(&self, triple: &TriplePattern) -> Vec<GraphMatchResult> {
-        let mut results = Vec::new();
-
-        match (&triple.subject, &triple.predicate, &triple.object) {
-            // Pattern: ?entity :slot "value" - find entities with this slot value
-            (
-                PatternTerm::Variable(var),
-                PatternTerm::Literal(slot),
-                PatternTerm::Literal(value),
-            ) => {
-                // Iterate all entities and check for matching slot value
-                for entity in self.memvid.memory_entities() {
-                    let cards = self.memvid.get_entity_memories(&entity);
-                    for card in cards {
-                        if card.slot.to_lowercase() == *slot
-                            && card.value.to_lowercase().contains(&value.to_lowercase())
-                        {
-                            let mut result = GraphMatchResult::new(
-                                entity.clone(),
-                                vec![card.source_frame_id],
-                                1.0,
-                            );
-                            result.bind(var, entity.clone());
-                            results.push(result);
-                            break; // One match per entity
+        if !pattern.order_by.is_empty() {
+            merged.sort_by(|a, b| {
+                pattern
+                    .order_by
+                    .iter()
+                    .map(|key| {
+                        let ordering =
+                            match (a.bindings.get(&key.variable), b.bindings.get(&key.variable)) {
+                                (Some(left), Some(right)) => value_type(&key.variable)
+                                    .compare(left, right)
+                                    .unwrap_or_else(|| left.cmp(right)),
+                                // Unbound values sort first.
+                                (left, right) => left.is_some().cmp(&right.is_some()),
+                            };
+                        if key.descending {
+                            ordering.reverse()
+                        } else {
+                            ordering
                         }
-                    }
-                }
-            }
+                    })
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            });
+        }
+        if let Some(limit) = pattern.limit {
+            merged.truncate(limit);
+        }
 
-            // Pattern: "entity" :slot ?value - get entity's slot value
-            (
-                PatternTerm::Literal(entity),
-                PatternTerm::Literal(slot),
-                PatternTerm::Variable(var),
-            ) => {
-                if let Some(card) = self.memvid.get_current_memory(entity, slot) {
-                    let mut result =
-                        GraphMatchResult::new(entity.clone(), vec![card.source_frame_id], 1.0);
-                    result.bind(var, card.value.clone());
-                    results.push(result);
-                }
-            }
+        merged.into_iter().map(Solution::into_result).collect()
+    }
 
-            // Pattern: "entity" :slot "value" - check if entity has this exact value
-            (
-                PatternTerm::Literal(entity),
-                PatternTerm::Literal(slot),
-                PatternTerm::Literal(value),
-            ) => {
-                if let Some(card) = self.memvid.get_current_memory(entity, slot) {
-                    if card.value.to_lowercase().contains(&value.to_lowercase()) {
-                        let result =
-                            GraphMatchResult::new(entity.clone(), vec![card.source_frame_id], 1.0);
-                        results.push(result);
-                    }
+    /// Cards as facts: every card of multi-valued slots, the current card of the others.
+    fn facts(&self) -> Vec<Fact<'a>> {
+        let track = &self.memvid.memories_track;
+        let schemas = &self.memvid.schema_registry;
+        let mut current: HashMap<(String, String), Option<MemoryCardId>> = HashMap::new();
+        let mut facts = Vec::new();
+        for card in track.cards() {
+            if card.is_retracted() {
+                continue;
+            }
+            let entity = card.entity.to_lowercase();
+            let slot = card.slot.to_lowercase();
+            let multiple = schemas
+                .get(&slot)
+                .is_some_and(|schema| schema.cardinality == Cardinality::Multiple);
+            if !multiple {
+                let current_id = *current
+                    .entry((entity.clone(), slot.clone()))
+                    .or_insert_with(|| track.get_current(&entity, &slot).map(|card| card.id));
+                if current_id != Some(card.id) {
+                    continue;
                 }
             }
+            facts.push(Fact {
+                entity,
+                slot,
+                value: &card.value,
+                frame_id: card.source_frame_id,
+                confidence: card.confidence.unwrap_or(1.0),
+            });
+        }
+        facts
+    }
 
-            _ => {
-                // Other patterns not yet implemented
+    /// Declared value types of variables bound in object position under a known predicate.
+    fn variable_types(&self, pattern: &GraphPattern) -> HashMap<String, ValueType> {
+        let mut types = HashMap::new();
+        for triple in pattern
+            .triples
+            .iter()
+            .chain(pattern.optional.iter().flatten())
+        {
+            if let (PatternTerm::Literal(slot), PatternTerm::Variable(variable)) =
+                (&triple.predicate, &triple.object)
+            {
+                if let Some(schema) = self.memvid.schema_registry.get(&slot.to_lowercase()) {
+                    types
+                        .entry(variable.clone())
+                        .or_insert_with(|| schema.range.clone());
+                }
             }
         }
-
-        results
+        types
     }
 
     /// Get frame IDs from graph matches for use in vector search filtering.
@@ -305,6 +452,46 @@ impl<'a> GraphMatcher<'a> {
     }
 }
 
+/// Extend `solution` with `fact` if it matches `triple` under the solution's bindings.
+fn extend(solution: &Solution, triple: &TriplePattern, fact: &Fact<'_>) -> Option<Solution> {
+    let mut next = solution.clone();
+    bind_term(&mut next, &triple.subject, &fact.entity, false)?;
+    bind_term(&mut next, &triple.predicate, &fact.slot, false)?;
+    bind_term(&mut next, &triple.object, fact.value, true)?;
+    if next.entity.is_none() {
+        next.entity = Some(match &triple.subject {
+            PatternTerm::Literal(entity) => entity.clone(),
+            PatternTerm::Variable(_) => fact.entity.clone(),
+        });
+    }
+    next.frame_ids.push(fact.frame_id);
+    next.confidence = next.confidence.min(fact.confidence);
+    Some(next)
+}
+
+fn bind_term(
+    solution: &mut Solution,
+    term: &PatternTerm,
+    value: &str,
+    partial_literal: bool,
+) -> Option<()> {
+    let matched = match term {
+        PatternTerm::Literal(literal) if partial_literal => {
+            value.to_lowercase().contains(&literal.to_lowercase())
+        }
+        PatternTerm::Literal(literal) => value.to_lowercase() == literal.to_lowercase(),
+        PatternTerm::Variable(name) => {
+            if let Some(bound) = solution.bindings.get(name) {
+                bound.to_lowercase() == value.to_lowercase()
+            } else {
+                solution.bindings.insert(name.clone(), value.to_string());
+                true
+            }
+        }
+    };
+    matched.then_some(())
+}
+
 /// Execute a hybrid search: graph filter + vector ranking.
 pub fn hybrid_search(memvid: &mut Memvid, plan: &QueryPlan) -> Result<Vec<HybridSearchHit>> {
     match plan {
@@ -312,36 +499,7 @@ pub fn hybrid_search(memvid: &mut Memvid, plan: &QueryPlan) -> Result<Vec<Hybrid
             query_text, top_k, ..
         } => {
             // Fall back to regular lexical search
-            let query = query_text.as_deref().unwrap_or("");
-            let request = SearchRequest {
-                query: query.to_string(),
-                top_k: *top_k,
-                snippet_chars: 200,
-                uri: None,
-                scope: None,
-                cursor: None,
-                #[cfg(feature = "temporal_track")]
-                temporal: None,
-                as_of_frame: None,
-                as_of_ts: None,
-                no_sketch: false,
-            };
-            let response = memvid.search(request)?;
-            Ok(response
-                .hits
-                .iter()
-                .map(|h| {
-                    let score = h.score.unwrap_or(0.0);
-                    HybridSearchHit {
-                        frame_id: h.frame_id,
-                        score,
-                        graph_score: 0.0,
-                        vector_score: score,
-                        matched_entity: None,
-                        preview: Some(h.text.clone()),
-                    }
-                })
-                .collect())
+            lexical_hits(memvid, query_text.as_deref(), *top_k)
         }
 
         QueryPlan::GraphOnly { pattern, limit } => {
@@ -376,36 +534,7 @@ pub fn hybrid_search(memvid: &mut Memvid, plan: &QueryPlan) -> Result<Vec<Hybrid
 
             if candidate_frames.is_empty() {
                 // No graph matches - fall back to lexical search
-                let query = query_text.as_deref().unwrap_or("");
-                let request = SearchRequest {
-                    query: query.to_string(),
-                    top_k: *top_k,
-                    snippet_chars: 200,
-                    uri: None,
-                    scope: None,
-                    cursor: None,
-                    #[cfg(feature = "temporal_track")]
-                    temporal: None,
-                    as_of_frame: None,
-                    as_of_ts: None,
-                    no_sketch: false,
-                };
-                let response = memvid.search(request)?;
-                return Ok(response
-                    .hits
-                    .iter()
-                    .map(|h| {
-                        let score = h.score.unwrap_or(0.0);
-                        HybridSearchHit {
-                            frame_id: h.frame_id,
-                            score,
-                            graph_score: 0.0,
-                            vector_score: score,
-                            matched_entity: None,
-                            preview: Some(h.text.clone()),
-                        }
-                    })
-                    .collect());
+                return lexical_hits(memvid, query_text.as_deref(), *top_k);
             }
 
             // Step 2: Return graph matches directly with frame previews
@@ -433,6 +562,48 @@ pub fn hybrid_search(memvid: &mut Memvid, plan: &QueryPlan) -> Result<Vec<Hybrid
     }
 }
 
+/// Plain lexical search, used when a plan has no graph matches to rank.
+fn lexical_hits(
+    memvid: &mut Memvid,
+    query_text: Option<&str>,
+    top_k: usize,
+) -> Result<Vec<HybridSearchHit>> {
+    let request = SearchRequest {
+        query: query_text.unwrap_or("").to_string(),
+        top_k,
+        snippet_chars: 200,
+        uri: None,
+        scope: None,
+        cursor: None,
+        #[cfg(feature = "temporal_track")]
+        temporal: None,
+        as_of_frame: None,
+        as_of_ts: None,
+        no_sketch: false,
+        filter: None,
+        facets: Vec::new(),
+        hybrid: None,
+        reranker: None,
+        fragments: None,
+    };
+    let response = memvid.search(request)?;
+    Ok(response
+        .hits
+        .iter()
+        .map(|h| {
+            let score = h.score.unwrap_or(0.0);
+            HybridSearchHit {
+                frame_id: h.frame_id,
+                score,
+                graph_score: 0.0,
+                vector_score: score,
+                matched_entity: None,
+                preview: Some(h.text.clone()),
+            }
+        })
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -482,6 +653,18 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_query_planner_parses_pattern_syntax() {
+        let planner = QueryPlanner::new();
+        match planner.plan("?p employer acme . ?p location ?city", 10) {
+            QueryPlan::GraphOnly { pattern, limit } => {
+                assert_eq!(pattern.triples.len(), 2);
+                assert_eq!(limit, 10);
+            }
+            _ => panic!("Expected graph-only plan for pattern query"),
+        }
+    }
+
     #[test]
     fn test_extract_value() {
         assert_eq!(extract_value("San Francisco and"), "San Francisco");
@@ -0,0 +1,168 @@
+//! File handle over the bytes of a `.mv2` memory.
+//!
+//! Plain memories are accessed directly; live-encrypted memories (`encryption` feature) go
+//! through page-level AES-256-GCM so that offsets stay logical `.mv2` offsets either way.
+
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::ops::Deref;
+
+use memmap2::Mmap;
+
+#[cfg(feature = "encryption")]
+use crate::encryption::EncryptedFile;
+
+#[derive(Debug)]
+pub(crate) enum DataFile {
+    Plain(File),
+    #[cfg(feature = "encryption")]
+    Encrypted(EncryptedFile),
+}
+
+/// Read-only view of the full contents of a [`DataFile`].
+pub(crate) enum DataFileBytes {
+    Mapped(Mmap),
+    #[cfg(feature = "encryption")]
+    Owned(Vec<u8>),
+}
+
+impl Deref for DataFileBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Self::Mapped(mmap) => mmap,
+            #[cfg(feature = "encryption")]
+            Self::Owned(bytes) => bytes,
+        }
+    }
+}
+
+impl DataFile {
+    /// The underlying physical file, e.g. for locking or copying the stored bytes verbatim.
+    pub(crate) fn raw(&self) -> &File {
+        match self {
+            Self::Plain(file) => file,
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.raw(),
+        }
+    }
+
+    /// A handle of the same kind (and key) over another physical file.
+    pub(crate) fn with_file(&self, file: File) -> Self {
+        match self {
+            Self::Plain(_) => Self::Plain(file),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(existing) => Self::Encrypted(existing.with_file(file)),
+        }
+    }
+
+    /// Like [`Self::with_file`], for a staging image that is discarded if a commit crashes.
+    pub(crate) fn with_staging_file(&self, file: File) -> Self {
+        match self {
+            Self::Plain(_) => Self::Plain(file),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(existing) => Self::Encrypted(existing.with_staging_file(file)),
+        }
+    }
+
+    pub(crate) fn is_encrypted(&self) -> bool {
+        !matches!(self, Self::Plain(_))
+    }
+
+    pub(crate) fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Self::Plain(file) => file.try_clone().map(Self::Plain),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.try_clone().map(Self::Encrypted),
+        }
+    }
+
+    /// Logical length of the memory.
+    pub(crate) fn len(&self) -> io::Result<u64> {
+        match self {
+            Self::Plain(file) => Ok(file.metadata()?.len()),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.len(),
+        }
+    }
+
+    /// Number of physical bytes that hold the first `len` logical bytes.
+    pub(crate) fn physical_prefix_len(&self, len: u64) -> io::Result<u64> {
+        match self {
+            Self::Plain(_) => Ok(len),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.physical_prefix_len(len),
+        }
+    }
+
+    pub(crate) fn set_len(&self, len: u64) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.set_len(len),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.set_len(len),
+        }
+    }
+
+    pub(crate) fn sync_all(&self) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.sync_all(),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.sync_all(),
+        }
+    }
+
+    /// The whole logical contents: memory-mapped when plain, decrypted in memory otherwise.
+    pub(crate) fn map(&self) -> io::Result<DataFileBytes> {
+        match self {
+            // Safety: we only create a read-only mapping over stable file bytes.
+            Self::Plain(file) => Ok(DataFileBytes::Mapped(unsafe { Mmap::map(file)? })),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.read_all().map(DataFileBytes::Owned),
+        }
+    }
+}
+
+impl From<File> for DataFile {
+    fn from(file: File) -> Self {
+        Self::Plain(file)
+    }
+}
+
+impl Read for DataFile {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.read(buf),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.read(buf),
+        }
+    }
+}
+
+impl Write for DataFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(buf),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.flush(),
+        }
+    }
+}
+
+impl Seek for DataFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        match self {
+            Self::Plain(file) => file.seek(pos),
+            #[cfg(feature = "encryption")]
+            Self::Encrypted(file) => file.seek(pos),
+        }
+    }
+}
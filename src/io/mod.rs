@@ -1,5 +1,6 @@
 //! Low-level IO primitives for interacting with `.mv2` files.
 
+pub(crate) mod data_file;
 pub mod header;
 #[cfg(feature = "parallel_segments")]
 pub mod manifest_wal;
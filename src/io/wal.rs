@@ -4,6 +4,7 @@ use std::io::{Read, Seek, SeekFrom, Write};
 use crate::{
     constants::{WAL_CHECKPOINT_PERIOD, WAL_CHECKPOINT_THRESHOLD},
     error::{MemvidError, Result},
+    io::data_file::DataFile,
     types::Header,
 };
 
@@ -26,7 +27,7 @@ pub struct WalRecord {
 
 #[derive(Debug)]
 pub struct EmbeddedWal {
-    file: File,
+    file: DataFile,
     region_offset: u64,
     region_size: u64,
     write_head: u64,
@@ -36,18 +37,29 @@ pub struct EmbeddedWal {
     checkpoint_sequence: u64,
     appends_since_checkpoint: u64,
     read_only: bool,
+    /// Fsync after every appended record (disabled while a batch syncs once at the end).
+    sync_on_append: bool,
 }
 
 impl EmbeddedWal {
     pub fn open(file: &File, header: &Header) -> Result<Self> {
-        Self::open_internal(file, header, false)
+        Self::open_internal(&DataFile::Plain(file.try_clone()?), header, false)
     }
 
     pub fn open_read_only(file: &File, header: &Header) -> Result<Self> {
+        Self::open_internal(&DataFile::Plain(file.try_clone()?), header, true)
+    }
+
+    /// Open the WAL of a memory through its (possibly encrypted) data file.
+    pub(crate) fn open_data_file(file: &DataFile, header: &Header) -> Result<Self> {
+        Self::open_internal(file, header, false)
+    }
+
+    pub(crate) fn open_data_file_read_only(file: &DataFile, header: &Header) -> Result<Self> {
         Self::open_internal(file, header, true)
     }
 
-    fn open_internal(file: &File, header: &Header, read_only: bool) -> Result<Self> {
+    fn open_internal(file: &DataFile, header: &Header, read_only: bool) -> Result<Self> {
         if header.wal_size == 0 {
             return Err(MemvidError::InvalidHeader {
                 reason: "wal_size must be non-zero".into(),
@@ -81,6 +93,7 @@ impl EmbeddedWal {
             checkpoint_sequence,
             appends_since_checkpoint: 0,
             read_only,
+            sync_on_append: true,
         };
 
         if !wal.read_only {
@@ -214,12 +227,32 @@ impl EmbeddedWal {
         }
     }
 
+    /// Smallest region size that fits one more entry of `payload_len` bytes.
+    ///
+    /// Pending records are never wrapped over, so with records pending the entry has to fit
+    /// after the write head; room for the trailing sentinel header is included.
+    #[must_use]
+    pub fn required_region_size(&self, payload_len: usize) -> u64 {
+        let entry_size = ENTRY_HEADER_SIZE as u64 + payload_len as u64;
+        let start = if self.pending_bytes > 0 {
+            self.write_head.max(self.pending_bytes)
+        } else {
+            0
+        };
+        start + entry_size + ENTRY_HEADER_SIZE as u64
+    }
+
+    /// Enable or disable the fsync performed after every appended record.
+    pub fn set_sync_on_append(&mut self, enabled: bool) {
+        self.sync_on_append = enabled;
+    }
+
     pub fn region_offset(&self) -> u64 {
         self.region_offset
     }
 
     pub fn file(&self) -> &File {
-        &self.file
+        self.file.raw()
     }
 
     fn initialise_sentinel(&mut self) -> Result<()> {
@@ -249,7 +282,9 @@ impl EmbeddedWal {
 
         // Force fsync to ensure data is durable before returning
         // Critical for preventing corruption during rapid file operations
-        self.file.sync_all()?;
+        if self.sync_on_append {
+            self.file.sync_all()?;
+        }
 
         Ok(())
     }
@@ -295,7 +330,11 @@ impl EmbeddedWal {
         Ok(())
     }
 
-    fn scan_records(file: &mut File, offset: u64, size: u64) -> Result<(Vec<ScannedRecord>, u64)> {
+    fn scan_records(
+        file: &mut DataFile,
+        offset: u64,
+        size: u64,
+    ) -> Result<(Vec<ScannedRecord>, u64)> {
         let mut records = Vec::new();
         let mut cursor = 0u64;
         while cursor + ENTRY_HEADER_SIZE as u64 <= size {
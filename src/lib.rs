@@ -30,6 +30,7 @@ pub mod text;
 mod toc;
 pub mod types;
 pub mod vec;
+pub mod vec_hnsw;
 pub mod vec_pq;
 
 // Triplet extraction module for automatic SPO extraction during ingestion
@@ -42,6 +43,10 @@ pub mod graph_search;
 // but ClipModel/inference requires the "clip" feature
 pub mod clip;
 
+// Local ONNX text embedder (BGE / nomic) for vector indexing and queries
+#[cfg(feature = "vec")]
+pub mod text_embed;
+
 // Whisper module for audio transcription
 // Model inference requires the "whisper" feature
 pub mod whisper;
@@ -93,8 +98,9 @@ pub use io::wal::{EmbeddedWal, WalRecord, WalStats};
 pub use lex::{LexIndex, LexIndexArtifact, LexIndexBuilder, LexSearchHit};
 pub use lock::FileLock;
 pub use memvid::{
-    BlobReader, EnrichmentHandle, EnrichmentStats, LockSettings, Memvid, OpenReadOptions,
-    SketchCandidate, SketchSearchOptions, SketchSearchStats,
+    BackgroundCommit, BlobReader, EnrichmentHandle, EnrichmentStats, LockSettings, Memvid,
+    MemvidReader, OpenReadOptions, RefreshReport, SketchCandidate, SketchSearchOptions,
+    SketchSearchStats, Transaction,
     mutation::{CommitMode, CommitOptions},
     start_enrichment_worker, start_enrichment_worker_with_embeddings,
 };
@@ -102,14 +108,15 @@ pub use memvid::{
 pub use memvid::{BuildOpts, ParallelInput, ParallelPayload};
 pub use models::{
     ModelManifest, ModelManifestEntry, ModelVerification, ModelVerificationStatus,
-    ModelVerifyOptions, verify_model_dir, verify_models,
+    ModelVerifyOptions, load_model_manifest, verify_model_dir, verify_models,
 };
 pub use reader::{
     DocumentFormat, DocumentReader, PassthroughReader, PdfReader, ReaderDiagnostics, ReaderHint,
     ReaderOutput, ReaderRegistry,
 };
 pub use signature::{
-    parse_ed25519_public_key_base64, verify_model_manifest, verify_ticket_signature,
+    parse_ed25519_public_key_base64, verify_memory_signature, verify_model_manifest,
+    verify_ticket_signature,
 };
 pub use text::{NormalizedText, normalize_text, truncate_at_grapheme_boundary};
 #[cfg(feature = "temporal_track")]
@@ -121,21 +128,30 @@ pub use types::{
 };
 pub use types::{
     AskCitation, AskMode, AskRequest, AskResponse, AskRetriever, AskStats, AudioSegmentMetadata,
-    AuditOptions, AuditReport, CanonicalEncoding, DOCTOR_PLAN_VERSION, DocAudioMetadata,
-    DocExifMetadata, DocGpsMetadata, DocMetadata, DoctorActionDetail, DoctorActionKind,
-    DoctorActionPlan, DoctorActionReport, DoctorActionStatus, DoctorFinding, DoctorFindingCode,
-    DoctorMetrics, DoctorOptions, DoctorPhaseDuration, DoctorPhaseKind, DoctorPhasePlan,
-    DoctorPhaseReport, DoctorPhaseStatus, DoctorPlan, DoctorReport, DoctorSeverity, DoctorStatus,
-    EmbeddingIdentity, EmbeddingIdentityCount, EmbeddingIdentitySummary, Frame, FrameId, FrameRole,
-    FrameStatus, Header, IndexManifests, LexIndexManifest, LexSegmentDescriptor,
-    MEMVID_EMBEDDING_DIMENSION_KEY, MEMVID_EMBEDDING_MODEL_KEY, MEMVID_EMBEDDING_NORMALIZED_KEY,
-    MEMVID_EMBEDDING_PROVIDER_KEY, MediaManifest, MemvidHandle, Open, PutOptions,
-    PutOptionsBuilder, Sealed, SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams,
-    SearchRequest, SearchResponse, SegmentCatalog, SegmentCommon, SegmentCompression, SegmentMeta,
-    SegmentSpan, SourceSpan, Stats, TextChunkManifest, TextChunkRange, Ticket, TicketRef, Tier,
-    TimeIndexManifest, TimeSegmentDescriptor, TimelineEntry, TimelineQuery, TimelineQueryBuilder,
-    Toc, VecEmbedder, VecIndexManifest, VecSegmentDescriptor, VectorCompression, VerificationCheck,
-    VerificationReport, VerificationStatus,
+    AuditOptions, AuditReport, CanonicalEncoding, ChangeEvent, ChangeKind, ChangeLog,
+    DOCTOR_PLAN_VERSION, DocAudioMetadata, DocExifMetadata, DocGpsMetadata, DocMetadata,
+    DoctorActionDetail, DoctorActionKind, DoctorActionPlan, DoctorActionReport, DoctorActionStatus,
+    DoctorFinding, DoctorFindingCode, DoctorMetrics, DoctorOptions, DoctorPhaseDuration,
+    DoctorPhaseKind, DoctorPhasePlan, DoctorPhaseReport, DoctorPhaseStatus, DoctorPlan,
+    DoctorReport, DoctorSeverity, DoctorStatus, EmbeddingIdentity, EmbeddingIdentityCount,
+    EmbeddingIdentitySummary, FacetCounts, FacetField, FacetValueCount, Frame, FrameId, FrameRole,
+    FrameStatus, FusionStrategy, Header, HitHighlights, HitScoreBreakdown, HybridSearch,
+    IndexManifests, LexAnalyzerConfig, LexIndexManifest, LexLanguage, LexSegmentDescriptor,
+    LexTokenizer, MEMVID_EMBEDDING_DIMENSION_KEY, MEMVID_EMBEDDING_MODEL_KEY,
+    MEMVID_EMBEDDING_NORMALIZED_KEY, MEMVID_EMBEDDING_PROVIDER_KEY, MediaManifest, MemorySignature,
+    MemvidHandle, MergeOptions, MergeReport, MetadataFilter, Open, PutManyOpts, PutOptions,
+    PutOptionsBuilder, PutRequest, Sealed, SearchEngineKind, SearchHit, SearchHitMetadata,
+    SearchParams, SearchRequest, SearchResponse, SegmentCatalog, SegmentCommon, SegmentCompression,
+    SegmentMeta, SegmentSpan, SnippetFragment, SnippetFragments, SourceSpan, Stats,
+    TextChunkManifest, TextChunkRange, Ticket, TicketRef, Tier, TimeIndexManifest,
+    TimeSegmentDescriptor, TimelineEntry, TimelineQuery, TimelineQueryBuilder, Toc, TocPart,
+    UriConflictPolicy, VacuumReport, VecEmbedder, VecIndexManifest, VecSegmentDescriptor,
+    VecSpaceManifest, VectorCompression, VerificationCheck, VerificationReport, VerificationStatus,
+};
+// Portable JSONL dump records (see `Memvid::export_jsonl`)
+pub use types::{
+    JSONL_FORMAT, JSONL_FORMAT_VERSION, JsonlClipEmbedding, JsonlFrame, JsonlHeader, JsonlPayload,
+    JsonlRecord, JsonlReport, JsonlVecSpace,
 };
 // Memory card types for structured memory extraction and storage
 pub use types::{
@@ -146,7 +162,7 @@ pub use types::{
 // Logic-Mesh types for entity-relationship graph traversal
 pub use types::{
     EdgeDirection, EntityKind, FollowResult, LOGIC_MESH_MAGIC, LOGIC_MESH_VERSION, LinkType,
-    LogicMesh, LogicMeshManifest, MeshEdge, MeshNode,
+    LogicMesh, LogicMeshManifest, MeshEdge, MeshNode, MeshPath, MeshSubgraph, SubgraphNode,
 };
 // Sketch track types for fast candidate generation
 pub use types::{
@@ -183,11 +199,16 @@ pub use types::{
 };
 // Reranker types for second-stage ranking in RAG pipelines
 pub use types::reranker::{
-    Reranker, RerankerConfig, RerankerDocument, RerankerKind, RerankerResult,
+    Bm25Reranker, Reranker, RerankerConfig, RerankerDocument, RerankerHook, RerankerKind,
+    RerankerResult,
 };
 #[cfg(feature = "parallel_segments")]
 pub use types::{IndexSegmentRef, SegmentKind, SegmentStats};
 pub use vec::{VecIndex, VecIndexArtifact, VecSearchHit};
+pub use vec_hnsw::{
+    HNSW_CHAIN_MAGIC, HNSW_MAGIC, HnswConfig, HnswIndex, HnswSegmentLink, MAX_HNSW_CHAIN_DEPTH,
+    MIN_VECTORS_FOR_HNSW,
+};
 pub use vec_pq::{
     CompressionStats, ProductQuantizer, QuantizedVecIndex, QuantizedVecIndexArtifact,
     QuantizedVecIndexBuilder,
@@ -199,6 +220,8 @@ pub use clip::{
     ImageInfo, MOBILECLIP_DIMS, SIGLIP_DIMS, default_model_info, filter_junk_images,
     get_model_info,
 };
+#[cfg(feature = "vec")]
+pub use text_embed::{EmbeddingPooling, LocalOnnxEmbedder, LocalOnnxEmbedderConfig};
 // CLIP model inference requires the "clip" feature
 #[cfg(feature = "clip")]
 pub use clip::{ClipModel, calculate_color_variance, get_image_info};
@@ -234,11 +257,11 @@ pub use replay::{
 
 #[cfg(test)]
 use once_cell::sync::Lazy;
-use std::fs::File;
-use std::io::Cursor;
+use std::io::{Cursor, Seek, Write};
 use std::path::Path;
 #[cfg(test)]
 use std::sync::Mutex;
+use std::sync::atomic::Ordering;
 
 use bincode::config::{self, Config};
 use io::header::HeaderCodec;
@@ -320,7 +343,15 @@ impl Memvid {
         self.read_only
     }
 
+    /// Whether a background commit started on this handle has yet to publish.
+    pub(crate) fn background_commit_running(&self) -> bool {
+        self.background_commit_active.load(Ordering::Acquire)
+    }
+
     pub(crate) fn ensure_writable(&mut self) -> Result<()> {
+        if self.background_commit_running() {
+            return Err(MemvidError::CommitInProgress);
+        }
         if self.read_only {
             self.lock.upgrade_to_exclusive()?;
             self.read_only = false;
@@ -332,7 +363,7 @@ impl Memvid {
         if self.read_only {
             return Ok(());
         }
-        if self.dirty || self.tantivy_index_pending() {
+        if self.dirty || self.tantivy_index_pending() || self.background_commit_running() {
             return Ok(());
         }
         self.lock.downgrade_to_shared()?;
@@ -346,16 +377,17 @@ impl Drop for Memvid {
         if self.dirty {
             let _ = self.commit();
         }
-        // Clean up temporary manifest.wal file (parallel_segments feature)
+        // Clean up temporary manifest.wal file (parallel_segments feature). Only the handle
+        // that opened it owns it; snapshots and background commit copies leave it alone.
         #[cfg(feature = "parallel_segments")]
-        {
+        if self.manifest_wal.is_some() {
             use crate::memvid::lifecycle::cleanup_manifest_wal_public;
             cleanup_manifest_wal_public(self.path());
         }
     }
 }
 
-pub(crate) fn persist_header(file: &mut File, header: &Header) -> Result<()> {
+pub(crate) fn persist_header<W: Write + Seek>(file: &mut W, header: &Header) -> Result<()> {
     HeaderCodec::write(file, header)
 }
 
@@ -598,6 +630,11 @@ mod tests {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
             };
             let response = mem.search(request).expect("search");
             assert_eq!(response.hits.len(), 1);
@@ -617,6 +654,11 @@ mod tests {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
             };
             let response = reopened.search(request).expect("search reopened");
             assert_eq!(response.hits.len(), 1);
@@ -688,6 +730,11 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    filter: None,
+                    facets: Vec::new(),
+                    hybrid: None,
+                    reranker: None,
+                    fragments: None,
                 })
                 .expect("search");
 
@@ -749,6 +796,11 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    filter: None,
+                    facets: Vec::new(),
+                    hybrid: None,
+                    reranker: None,
+                    fragments: None,
                 })
                 .expect("search");
 
@@ -833,6 +885,11 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    filter: None,
+                    facets: Vec::new(),
+                    hybrid: None,
+                    reranker: None,
+                    fragments: None,
                 })
                 .expect("uri search");
             assert_eq!(uri_response.engine, SearchEngineKind::Tantivy);
@@ -856,6 +913,11 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    filter: None,
+                    facets: Vec::new(),
+                    hybrid: None,
+                    reranker: None,
+                    fragments: None,
                 })
                 .expect("scope search");
             assert_eq!(scope_response.engine, SearchEngineKind::Tantivy);
@@ -909,6 +971,11 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    filter: None,
+                    facets: Vec::new(),
+                    hybrid: None,
+                    reranker: None,
+                    fragments: None,
                 })
                 .expect("page one");
             assert_eq!(first_page.engine, SearchEngineKind::Tantivy);
@@ -932,6 +999,11 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    filter: None,
+                    facets: Vec::new(),
+                    hybrid: None,
+                    reranker: None,
+                    fragments: None,
                 })
                 .expect("page two");
             assert_eq!(second_page.engine, SearchEngineKind::Tantivy);
@@ -973,6 +1045,11 @@ mod tests {
                     as_of_frame: None,
                     as_of_ts: None,
                     no_sketch: false,
+                    filter: None,
+                    facets: Vec::new(),
+                    hybrid: None,
+                    reranker: None,
+                    fragments: None,
                 })
                 .expect("search with tantivy");
 
@@ -4,7 +4,9 @@ use std::num::NonZeroU64;
 use std::time::Instant;
 
 use crate::memvid::lifecycle::Memvid;
-use crate::memvid::search::helpers::{build_context, reorder_hits_by_token_matches};
+use crate::memvid::search::helpers::{
+    build_context, reorder_hits_by_token_matches, rerank_hits, retain_hits_matching_filter,
+};
 #[cfg(feature = "temporal_track")]
 use crate::types::TemporalFilter;
 use crate::types::{
@@ -103,6 +105,11 @@ impl Memvid {
             // Disable sketch pre-filter for ask queries - accuracy is more important than speed
             // SimHash can filter out semantically relevant documents that use different wording
             no_sketch: true,
+            filter: request.filter.clone(),
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         };
 
         // Pre-compute the query embedding once so we can reuse it for vector recall and semantic re-rank
@@ -360,6 +367,13 @@ impl Memvid {
             )?;
         }
 
+        if let Some(hook) = request.reranker.as_ref() {
+            let hits = std::mem::take(&mut retrieval.hits);
+            let (hits, total_hits) = rerank_hits(&request.question, hits, hook, request.top_k)?;
+            retrieval.hits = hits;
+            retrieval.total_hits = total_hits;
+        }
+
         // Apply correction boost AFTER all other reranking - corrections should have final priority
         // This ensures user corrections override all other ranking signals
         promote_corrections(self, &mut retrieval.hits)?;
@@ -492,32 +506,49 @@ impl Memvid {
         }
         let query_embedding = query_embedding_cow.as_ref();
         let expected_dimension = embedder.embedding_dimension();
-        let stored_dimension = self
-            .toc
-            .indexes
-            .vec
-            .as_ref()
-            .map(|manifest| manifest.dimension)
-            .filter(|dim| *dim > 0)
-            .or_else(|| {
-                self.vec_index
+        let hit_embeddings: Vec<(u64, Option<Vec<f32>>)> =
+            if let Some(space) = request.vec_space.as_deref() {
+                let index = self.vec_space_index(space, query_embedding.len())?;
+                hits.iter()
+                    .map(|hit| {
+                        let embedding = index.embedding_for(hit.frame_id).map(<[f32]>::to_vec);
+                        (hit.frame_id, embedding)
+                    })
+                    .collect()
+            } else {
+                let stored_dimension = self
+                    .toc
+                    .indexes
+                    .vec
                     .as_ref()
-                    .and_then(|index| index.entries().next().map(|(_, emb)| emb.len() as u32))
-            })
-            .unwrap_or(0);
-        if stored_dimension > 0 && query_embedding.len() as u32 != stored_dimension {
-            return Err(MemvidError::VecDimensionMismatch {
-                expected: stored_dimension,
-                actual: query_embedding.len(),
-            });
-        }
+                    .map(|manifest| manifest.dimension)
+                    .filter(|dim| *dim > 0)
+                    .or_else(|| {
+                        self.vec_index
+                            .as_ref()
+                            .and_then(|index| index.entries().next())
+                            .and_then(|(_, emb)| u32::try_from(emb.len()).ok())
+                    })
+                    .unwrap_or(0);
+                if stored_dimension > 0
+                    && u32::try_from(query_embedding.len()).ok() != Some(stored_dimension)
+                {
+                    return Err(MemvidError::VecDimensionMismatch {
+                        expected: stored_dimension,
+                        actual: query_embedding.len(),
+                    });
+                }
+                hits.iter()
+                    .map(|hit| Ok((hit.frame_id, self.frame_embedding(hit.frame_id)?)))
+                    .collect::<Result<_>>()?
+            };
 
         let mut semantic_scores: HashMap<u64, f32> = HashMap::new();
-        for hit in hits.iter() {
-            if let Some(embedding) = self.frame_embedding(hit.frame_id)? {
+        for (frame_id, embedding) in hit_embeddings {
+            if let Some(embedding) = embedding {
                 if expected_dimension == 0 || embedding.len() == expected_dimension {
                     let score = cosine_similarity(&query_embedding, &embedding);
-                    semantic_scores.insert(hit.frame_id, score);
+                    semantic_scores.insert(frame_id, score);
                 }
             }
         }
@@ -552,6 +583,7 @@ impl Memvid {
                 context: String::new(),
                 next_cursor: None,
                 engine: SearchEngineKind::LexFallback,
+                facets: Vec::new(),
                 elapsed_ms,
                 params: SearchParams {
                     top_k: request.top_k,
@@ -573,6 +605,15 @@ impl Memvid {
             }
         }
 
+        if let Some(filter) = request.filter.as_ref() {
+            all_frame_ids.retain(|(frame_id, _)| {
+                usize::try_from(*frame_id)
+                    .ok()
+                    .and_then(|index| self.toc.frames.get(index))
+                    .is_some_and(|frame| filter.matches(frame))
+            });
+        }
+
         tracing::debug!(
             "timeline fallback: expanding {} parent entries to {} total frames (including children)",
             entries.len(),
@@ -622,6 +663,8 @@ impl Memvid {
                 text: frame_text.clone(),
                 chunk_text: Some(frame_text.clone()),
                 metadata: None,
+                score_breakdown: None,
+                highlights: None,
             });
         }
 
@@ -635,6 +678,7 @@ impl Memvid {
             context,
             next_cursor: None,
             engine: SearchEngineKind::LexFallback, // Mark as fallback
+            facets: Vec::new(),
             elapsed_ms,
             params: SearchParams {
                 top_k: request.top_k,
@@ -1326,10 +1370,11 @@ fn vector_hits(
     // Use adaptive retrieval if configured
     if let Some(ref adaptive_config) = request.adaptive {
         if adaptive_config.enabled {
-            let result = memvid.search_adaptive(
+            let result = memvid.search_adaptive_in(
+                request.vec_space.as_deref(),
                 &request.question,
                 query_embedding,
-                adaptive_config.clone(),
+                adaptive_config,
                 request.snippet_chars,
                 request.scope.as_deref(),
             )?;
@@ -1339,16 +1384,23 @@ fn vector_hits(
                 result.stats.returned,
                 result.stats.triggered_by
             );
-            return Ok(result.results);
+            let mut hits = result.results;
+            if let Some(filter) = request.filter.as_ref() {
+                retain_hits_matching_filter(&mut hits, memvid, filter);
+            }
+            return Ok(hits);
         }
     }
 
-    let vec_response = memvid.vec_search_with_embedding(
+    let vec_response = memvid.vec_search_in(
+        request.vec_space.as_deref(),
         &request.question,
         query_embedding,
         limit,
         request.snippet_chars,
         request.scope.as_deref(),
+        None,
+        request.filter.as_ref(),
     )?;
 
     Ok(vec_response.hits)
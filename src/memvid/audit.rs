@@ -71,6 +71,9 @@ impl Memvid {
             as_of_frame: None,
             as_of_ts: None,
             adaptive: None,
+            filter: None,
+            vec_space: None,
+            reranker: None,
         };
 
         let response = self.ask(request, embedder)?;
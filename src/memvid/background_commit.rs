@@ -0,0 +1,129 @@
+//! Commits whose files and indexes are written on a worker thread.
+//!
+//! [`Memvid::commit_in_background`] snapshots the pending WAL records and the state they
+//! apply to, then returns. A worker thread copies the file to a staging image, applies the
+//! records there, rebuilds the vector, time and lex indexes and writes them with the TOC and
+//! footer. [`BackgroundCommit::wait`] then swaps the staging image in atomically.
+//!
+//! Until then the handle, and any other handle open on the file, keeps serving the previous
+//! generation. Writes through the handle fail with [`MemvidError::CommitInProgress`].
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+
+use crate::error::{MemvidError, Result};
+use crate::memvid::lifecycle::Memvid;
+use crate::memvid::mutation::{CommitOptions, PendingCommit, StagedCommit};
+
+/// Handle to a commit started by [`Memvid::commit_in_background`].
+///
+/// Dropping it without [`Self::wait`] abandons the commit: its staging copy is discarded and
+/// the WAL records stay pending for the next commit.
+#[derive(Debug)]
+pub struct BackgroundCommit {
+    /// Worker staging the commit, or `None` when nothing is left to publish.
+    worker: Option<JoinHandle<Result<StagedCommit>>>,
+    /// In-progress flag of the handle that started the commit.
+    active: Arc<AtomicBool>,
+}
+
+impl BackgroundCommit {
+    /// Check whether the commit is ready to publish or failed, without blocking.
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.worker.as_ref().is_none_or(JoinHandle::is_finished)
+    }
+
+    /// Block until the commit is staged, then publish it on `mem`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error that staging or publishing the commit failed with, or
+    /// [`MemvidError::CheckpointFailed`] when `mem` is not the handle that started it. A
+    /// failed commit leaves the memory at its previous generation with the WAL records still
+    /// pending, so a later commit retries them.
+    pub fn wait(mut self, mem: &mut Memvid) -> Result<()> {
+        let Some(worker) = self.worker.take() else {
+            return Ok(());
+        };
+        if !Arc::ptr_eq(&self.active, &mem.background_commit_active) {
+            return Err(MemvidError::CheckpointFailed {
+                reason: "background commit was started on another handle".into(),
+            });
+        }
+        let staged = worker.join().unwrap_or_else(|_| {
+            Err(MemvidError::CheckpointFailed {
+                reason: "background commit panicked".into(),
+            })
+        });
+        let outcome = staged.and_then(|staged| mem.publish_background_commit(staged));
+        if let Err(err) = &outcome {
+            tracing::warn!(error = %err, "background commit failed");
+        }
+        outcome
+    }
+
+    /// Publish the commit on `mem` if it finished, or return the handle otherwise.
+    ///
+    /// # Errors
+    ///
+    /// Hands the handle back while the commit is still running; see [`Self::wait`] for the
+    /// outcome.
+    pub fn try_wait(self, mem: &mut Memvid) -> std::result::Result<Result<()>, Self> {
+        if self.is_finished() {
+            Ok(self.wait(mem))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl Drop for BackgroundCommit {
+    fn drop(&mut self) {
+        if self.worker.is_some() {
+            tracing::debug!("background commit abandoned before publishing");
+        }
+        self.active.store(false, Ordering::Release);
+    }
+}
+
+impl Memvid {
+    /// Commit the pending changes, writing them on a worker thread when `options.background`
+    /// is set.
+    ///
+    /// The pending WAL records are snapshotted before this returns. The handle keeps serving
+    /// the previous generation and rejects writes until [`BackgroundCommit::wait`] publishes
+    /// the commit. Without `background`, the commit runs on the caller's thread and the
+    /// returned handle has nothing left to publish.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the handle cannot be written or the records cannot be read, including with
+    /// [`MemvidError::CommitInProgress`] while another background commit runs. Errors
+    /// writing the commit are reported by [`BackgroundCommit::wait`].
+    ///
+    /// # Example
+    /// ```ignore
+    /// let commit = mem.commit_in_background(CommitOptions::default().background(true))?;
+    ///
+    /// // Searches keep answering from the previous generation meanwhile...
+    ///
+    /// commit.wait(&mut mem)?;
+    /// ```
+    pub fn commit_in_background(&mut self, options: CommitOptions) -> Result<BackgroundCommit> {
+        if !options.background {
+            self.commit_with_options(options)?;
+            return Ok(BackgroundCommit {
+                worker: None,
+                active: Arc::clone(&self.background_commit_active),
+            });
+        }
+        let pending = self.begin_background_commit(options.mode)?;
+        let worker = pending.map(|pending| thread::spawn(move || PendingCommit::stage(pending)));
+        Ok(BackgroundCommit {
+            worker,
+            active: Arc::clone(&self.background_commit_active),
+        })
+    }
+}
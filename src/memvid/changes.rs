@@ -0,0 +1,130 @@
+//! Change feed over committed mutations.
+//!
+//! Mutations are buffered as they are applied and stamped with the generation of the commit
+//! that publishes them, then kept in the TOC's change log. Frame changes carry the sequence of
+//! their WAL record; card, mesh and ticket changes that bypass the WAL carry the last sequence
+//! written before them, so the log stays ordered by `(generation, sequence)`. Each commit
+//! prunes the oldest generations beyond the handle's retention.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::error::{MemvidError, Result};
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{ChangeEvent, ChangeKind};
+
+/// Changes kept in the log unless [`Memvid::set_change_log_retention`] says otherwise.
+pub(crate) const DEFAULT_CHANGE_LOG_RETENTION: usize = 10_000;
+
+/// How often [`Memvid::watch`] re-reads the commit footer.
+const WATCH_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+impl Memvid {
+    /// Changes published by commits after `generation`, oldest first.
+    ///
+    /// Pass `0` for the whole log. Only committed changes are listed; on a read-only handle,
+    /// [`Memvid::refresh`] picks up the ones committed since it was opened.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::ChangesPruned`] when some of those changes were pruned, in
+    /// which case the consumer has to resynchronise from the current state.
+    pub fn changes_since(&self, generation: u64) -> Result<impl Iterator<Item = ChangeEvent> + '_> {
+        let log = &self.toc.change_log;
+        if generation < log.pruned_through {
+            return Err(MemvidError::ChangesPruned {
+                requested: generation,
+                pruned_through: log.pruned_through,
+            });
+        }
+        let start = log
+            .events
+            .partition_point(|event| event.generation <= generation);
+        Ok(log.events[start..].iter().cloned())
+    }
+
+    /// Keep at most `max_events` changes in the change log, dropping the oldest commits
+    /// first; the newest commit is kept whole. Applies from the next commit on.
+    pub fn set_change_log_retention(&mut self, max_events: usize) {
+        self.change_log_retention = max_events;
+    }
+
+    /// Block until a commit newer than `generation` is published to the file.
+    ///
+    /// Returns the published generation, or `None` if `timeout` elapsed first. The footer is
+    /// polled, so this also wakes for commits made by other processes.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the file cannot be reopened or its commit footer cannot be read.
+    pub fn watch(&self, generation: u64, timeout: Option<Duration>) -> Result<Option<u64>> {
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            if let Some(published) = self.published_generation()? {
+                if published != generation {
+                    return Ok(Some(published));
+                }
+            }
+            let mut pause = WATCH_POLL_INTERVAL;
+            if let Some(deadline) = deadline {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                if remaining.is_zero() {
+                    return Ok(None);
+                }
+                pause = pause.min(remaining);
+            }
+            thread::sleep(pause);
+        }
+    }
+
+    /// Buffer a change that is not carried by a WAL record.
+    pub(crate) fn record_change(&mut self, kind: ChangeKind) {
+        let sequence = self.wal.stats().sequence;
+        self.pending_changes.push(ChangeEvent {
+            generation: 0,
+            sequence,
+            kind,
+        });
+    }
+
+    /// Buffer the changes carried by the WAL record `sequence`, ahead of the direct changes
+    /// made after that record was written.
+    pub(crate) fn record_wal_changes<I>(&mut self, sequence: u64, kinds: I)
+    where
+        I: IntoIterator<Item = ChangeKind>,
+    {
+        let at = self
+            .pending_changes
+            .partition_point(|event| event.sequence < sequence);
+        let events = kinds.into_iter().map(|kind| ChangeEvent {
+            generation: 0,
+            sequence,
+            kind,
+        });
+        self.pending_changes.splice(at..at, events);
+    }
+
+    /// Log a change written by the commit in progress, leaving buffered changes for the
+    /// commit that persists them.
+    pub(crate) fn publish_change(&mut self, kind: ChangeKind) {
+        let sequence = self.wal.stats().sequence;
+        self.toc.change_log.events.push(ChangeEvent {
+            generation: self.generation,
+            sequence,
+            kind,
+        });
+    }
+
+    /// Move buffered changes into the TOC under the generation being committed.
+    pub(crate) fn publish_changes(&mut self) {
+        let generation = self.generation;
+        self.toc
+            .change_log
+            .events
+            .extend(self.pending_changes.drain(..).map(|mut event| {
+                event.generation = generation;
+                event
+            }));
+        self.toc.change_log.prune(self.change_log_retention);
+    }
+}
@@ -6,6 +6,7 @@ use std::path::{Path, PathBuf};
 use std::time::Instant;
 
 use crate::error::{MemvidError, Result};
+use crate::io::data_file::DataFile;
 use crate::io::header::HeaderCodec;
 use crate::io::time_index::{calculate_checksum as time_index_checksum, read_track};
 use crate::io::wal::EmbeddedWal;
@@ -390,9 +391,10 @@ impl DoctorPlanner {
         );
 
         doctor_log!("doctor: attempting read_toc");
-        let (toc, toc_offset, recovered) = match read_toc(&mut file, header) {
+        let mut data_file = DataFile::Plain(file.try_clone()?);
+        let (toc, toc_offset, recovered) = match read_toc(&mut data_file, header) {
             Ok(toc) => (toc, header.footer_offset, false),
-            Err(_) => match recover_toc(&mut file, Some(header.footer_offset)) {
+            Err(_) => match recover_toc(&mut data_file, Some(header.footer_offset)) {
                 Ok((toc, offset)) => {
                     doctor_log!("doctor: recover_toc succeeded at offset {}", offset);
                     probe.findings.push(DoctorFinding::warning(
@@ -439,6 +441,15 @@ impl DoctorPlanner {
             ));
         }
 
+        if toc.merkle_root != [0u8; 32]
+            && toc.compute_merkle_root().ok() != Some(toc.merkle_root)
+        {
+            probe.findings.push(DoctorFinding::error(
+                DoctorFindingCode::MerkleMismatch,
+                "merkle root does not match frames and index segments".to_string(),
+            ));
+        }
+
         if header.toc_checksum != toc.toc_checksum {
             probe.findings.push(DoctorFinding::warning(
                 DoctorFindingCode::HeaderTocChecksumMismatch,
@@ -577,6 +588,10 @@ impl DoctorPlanner {
             return;
         };
         probe.index.lex_expected_docs = manifest.doc_count;
+        if manifest.bytes_length == 0 {
+            // Placeholder manifest (lex enabled but empty, or only the analyzer is recorded)
+            return;
+        }
 
         #[cfg(feature = "lex")]
         {
@@ -792,7 +807,24 @@ impl DoctorPlanner {
             ));
             return;
         }
-        match VecIndex::decode(&buf) {
+        let decoded = VecIndex::decode_chain(
+            &buf,
+            manifest.bytes_offset,
+            manifest.compression_mode.clone(),
+            |offset, length| {
+                let length = usize::try_from(length)
+                    .ok()
+                    .filter(|_| length <= crate::MAX_INDEX_BYTES)
+                    .ok_or(MemvidError::InvalidToc {
+                        reason: "vector index segment exceeds safety limit".into(),
+                    })?;
+                let mut segment = vec![0u8; length];
+                file.seek(SeekFrom::Start(offset))?;
+                file.read_exact(&mut segment)?;
+                Ok(segment)
+            },
+        );
+        match decoded {
             Ok(index) => {
                 if index.entries().count() as u64 != manifest.vector_count {
                     probe.index.needs_vec = true;
@@ -1342,11 +1374,14 @@ impl DoctorExecutor {
                 })
             }
             DoctorActionKind::VacuumCompaction => {
-                mem.vacuum()?;
+                let report = mem.vacuum()?;
                 Ok(DoctorActionReport {
                     action: action.action,
                     status: DoctorActionStatus::Executed,
-                    detail: Some("vacuum completed".into()),
+                    detail: Some(format!(
+                        "vacuum completed: reclaimed {} bytes ({} -> {})",
+                        report.bytes_reclaimed, report.bytes_before, report.bytes_after
+                    )),
                 })
             }
             DoctorActionKind::RecomputeToc => {
@@ -1401,7 +1436,7 @@ impl DoctorExecutor {
 
         if lex {
             mem.lex_enabled = true;
-            mem.toc.indexes.lex = None;
+            mem.toc.indexes.replace_lex(None);
             mem.lex_index = None;
         }
         if vec {
@@ -1411,7 +1446,7 @@ impl DoctorExecutor {
         } else if mem.vec_enabled {
             // CRITICAL: If we're NOT rebuilding vec index but it exists,
             // we must load it first so rebuild_indexes can preserve it.
-            // Otherwise build_vec_artifact reads from self.vec_index (which is None)
+            // Otherwise update_vec_index reads from self.vec_index (which is None)
             // and the vectors are lost.
             if mem.vec_index.is_none() && mem.toc.indexes.vec.is_some() {
                 doctor_log!("doctor: loading existing vec index to preserve it");
@@ -1488,7 +1523,7 @@ impl DoctorExecutor {
         doctor_log!("doctor: reset_wal - header updated with wal_sequence=0, wal_checkpoint_pos=0");
 
         // Now reopen the WAL with the clean state
-        mem.wal = EmbeddedWal::open(&mem.file, &mem.header)?;
+        mem.wal = EmbeddedWal::open_data_file(&mem.file, &mem.header)?;
         doctor_log!("doctor: reset_wal - WAL reopened successfully");
 
         // CRITICAL: Clear dirty flag to prevent Drop from calling commit()
@@ -0,0 +1,84 @@
+//! Live-encrypted memories.
+//!
+//! A live-encrypted memory is a regular `.mv2` whose bytes are stored as AES-256-GCM pages
+//! behind a password-wrapped data key (see `crate::encryption` for the layout). It is opened
+//! and mutated in place, so no plaintext copy of the memory is ever written; the Tantivy
+//! index is kept in memory for the same reason.
+
+use std::fs::OpenOptions;
+use std::path::Path;
+
+use crate::encryption::EncryptedFile;
+use crate::error::{MemvidError, Result};
+use crate::io::data_file::DataFile;
+use crate::lock::FileLock;
+use crate::memvid::lifecycle::{Memvid, ensure_single_file};
+
+impl Memvid {
+    /// Create a new, empty memory encrypted with a data key wrapped by `password`.
+    ///
+    /// # Errors
+    ///
+    /// Fails when `path` cannot be created or locked, or when the key envelope cannot be
+    /// written.
+    pub fn create_encrypted<P: AsRef<Path>>(path: P, password: &[u8]) -> Result<Self> {
+        let path_ref = path.as_ref();
+        ensure_single_file(path_ref)?;
+
+        OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path_ref)?;
+        let (file, lock) = FileLock::open_and_lock(path_ref)?;
+        let file = EncryptedFile::create(file, password)?;
+        Self::create_locked(DataFile::Encrypted(file), lock, path_ref)
+    }
+
+    /// Open a live-encrypted memory with exclusive access, performing recovery if needed.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`EncryptionError::Decryption`](crate::encryption::EncryptionError) when the
+    /// password does not unwrap the data key, and with [`MemvidError::InvalidHeader`] when the
+    /// file is not live-encrypted.
+    pub fn open_encrypted<P: AsRef<Path>>(path: P, password: &[u8]) -> Result<Self> {
+        let path_ref = path.as_ref();
+        ensure_single_file(path_ref)?;
+
+        let (mut file, lock) = FileLock::open_and_lock(path_ref)?;
+        if !EncryptedFile::is_live_encrypted(&mut file)? {
+            return Err(MemvidError::InvalidHeader {
+                reason: "not a live-encrypted memory; use Memvid::open or memvid unlock".into(),
+            });
+        }
+        let file = EncryptedFile::open(file, password)?;
+        Self::open_locked(DataFile::Encrypted(file), lock, path_ref)
+    }
+
+    /// Whether this memory is stored encrypted.
+    #[must_use]
+    pub fn is_encrypted(&self) -> bool {
+        self.file.is_encrypted()
+    }
+
+    /// Rotate the password of a live-encrypted memory.
+    ///
+    /// Only the wrapped data key is rewritten; pages stay encrypted with the same data key,
+    /// so rotation is cheap regardless of the memory size.
+    ///
+    /// # Errors
+    ///
+    /// Fails on a read-only handle, on a memory that is not encrypted, or when the new key
+    /// slot cannot be written.
+    pub fn rotate_encryption_password(&mut self, new_password: &[u8]) -> Result<()> {
+        self.ensure_writable()?;
+        match &mut self.file {
+            DataFile::Encrypted(file) => Ok(file.rewrap_key(new_password)?),
+            DataFile::Plain(_) => Err(MemvidError::InvalidHeader {
+                reason: "memory is not encrypted".into(),
+            }),
+        }
+    }
+}
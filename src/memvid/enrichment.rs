@@ -467,33 +467,42 @@ impl Memvid {
 
         let count = embeddings.len();
 
-        // Build new vector index with existing + new embeddings
-        let mut builder = VecIndexBuilder::new();
-
-        // Add existing embeddings from current index
-        if let Some(ref vec_index) = self.vec_index {
-            for (frame_id, embedding) in vec_index.entries() {
-                // Skip if we're replacing this frame's embedding
-                if !embeddings.iter().any(|(id, _)| *id == frame_id) {
-                    builder.add_document(frame_id, embedding.to_vec());
+        let artifact = if let Some(crate::vec::VecIndex::Hnsw(graph)) = self.vec_index.as_mut() {
+            // HNSW graphs take the new vectors in place; replaced frames are tombstoned.
+            for (frame_id, embedding) in embeddings {
+                graph.insert(frame_id, embedding)?;
+            }
+            graph.encode()?
+        } else {
+            // Build new vector index with existing + new embeddings
+            let mut builder = VecIndexBuilder::new();
+
+            // Add existing embeddings from current index
+            if let Some(ref vec_index) = self.vec_index {
+                for (frame_id, embedding) in vec_index.entries() {
+                    // Skip if we're replacing this frame's embedding
+                    if !embeddings.iter().any(|(id, _)| *id == frame_id) {
+                        builder.add_document(frame_id, embedding.to_vec());
+                    }
                 }
             }
-        }
 
-        // Add new embeddings
-        for (frame_id, embedding) in embeddings {
-            builder.add_document(frame_id, embedding);
-        }
+            // Add new embeddings
+            for (frame_id, embedding) in embeddings {
+                builder.add_document(frame_id, embedding);
+            }
 
-        // Finish building the index
-        let artifact = builder.finish()?;
-        if artifact.vector_count == 0 {
-            return Ok(0);
-        }
+            // Finish building the index
+            let artifact = builder.finish()?;
+            if artifact.vector_count == 0 {
+                return Ok(0);
+            }
 
-        // Decode and store the new index
-        let new_index = crate::vec::VecIndex::decode(&artifact.bytes)?;
-        self.vec_index = Some(new_index);
+            // Decode and store the new index
+            let new_index = crate::vec::VecIndex::decode(&artifact.bytes)?;
+            self.vec_index = Some(new_index);
+            artifact
+        };
 
         // Update TOC with new manifest
         self.toc.indexes.vec = Some(crate::types::VecIndexManifest {
@@ -1,9 +1,9 @@
 //! Frame payload and preview helpers for `Memvid`.
 
-use std::fs::File;
 use std::io::{self, Cursor, Read, Seek, SeekFrom};
 
 use crate::error::{MemvidError, Result};
+use crate::io::data_file::DataFile;
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{CanonicalEncoding, Frame, FrameId, FrameRole, FrameStatus, MediaManifest};
 
@@ -22,7 +22,7 @@ pub struct BlobReader {
 
 enum BlobReaderInner {
     File {
-        file: File,
+        file: DataFile,
         start: u64,
         len: u64,
         pos: u64,
@@ -31,7 +31,7 @@ enum BlobReaderInner {
 }
 
 impl BlobReader {
-    fn from_file(file: File, start: u64, len: u64) -> Self {
+    fn from_file(file: DataFile, start: u64, len: u64) -> Self {
         Self {
             inner: BlobReaderInner::File {
                 file,
@@ -637,7 +637,7 @@ impl Memvid {
             });
         }
 
-        let file_len = self.file.metadata()?.len();
+        let file_len = self.file.len()?;
         if frame_end > file_len {
             return Err(MemvidError::InvalidFrame {
                 frame_id: frame.id,
@@ -0,0 +1,505 @@
+//! Portable JSONL export/import of a whole memory.
+//!
+//! The dump is independent of the on-disk `.mv2` layout: payloads are written in canonical
+//! (decoded) form and every derived structure is expressed through its serde representation.
+//! Importing replays frames through the embedded WAL in frame ID order, so the rebuilt memory
+//! keeps the original frame IDs and memory cards, Logic-Mesh references, CLIP entries and
+//! vector space embeddings need no remapping.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, BufWriter, Write};
+
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+
+use crate::clip::ClipIndex;
+use crate::error::{MemvidError, Result};
+use crate::memvid::lifecycle::Memvid;
+use crate::memvid::mutation::WalEntryData;
+use crate::types::{
+    CanonicalEncoding, ChangeKind, Frame, FrameId, FrameStatus, JSONL_FORMAT, JSONL_FORMAT_VERSION,
+    JsonlClipEmbedding, JsonlFrame, JsonlHeader, JsonlPayload, JsonlRecord, JsonlReport,
+    JsonlVecSpace, MemoryCard, MeshEdge, MeshNode,
+};
+use crate::vec::VecIndex;
+
+fn write_record<W: Write>(writer: &mut W, record: &JsonlRecord) -> Result<()> {
+    serde_json::to_writer(&mut *writer, record).map_err(std::io::Error::from)?;
+    writer.write_all(b"\n")?;
+    Ok(())
+}
+
+fn clip_embeddings_by_frame(index: &ClipIndex) -> HashMap<FrameId, Vec<JsonlClipEmbedding>> {
+    let mut by_frame: HashMap<FrameId, Vec<JsonlClipEmbedding>> = HashMap::new();
+    for (frame_id, page, embedding) in index.entries() {
+        by_frame
+            .entry(frame_id)
+            .or_default()
+            .push(JsonlClipEmbedding {
+                page,
+                embedding: embedding.to_vec(),
+            });
+    }
+    by_frame
+}
+
+fn import_error(line: usize, reason: impl Into<String>) -> MemvidError {
+    MemvidError::JsonlImport {
+        line,
+        reason: reason.into(),
+    }
+}
+
+impl JsonlPayload {
+    fn from_bytes(bytes: Vec<u8>) -> Self {
+        match String::from_utf8(bytes) {
+            Ok(text) => Self::Text(text),
+            Err(err) => Self::Base64(BASE64_STANDARD.encode(err.into_bytes())),
+        }
+    }
+
+    fn into_bytes(self) -> std::result::Result<Vec<u8>, base64::DecodeError> {
+        match self {
+            Self::Text(text) => Ok(text.into_bytes()),
+            Self::Base64(encoded) => BASE64_STANDARD.decode(encoded),
+        }
+    }
+}
+
+/// Records that reference frames and are applied once every frame has been committed.
+#[derive(Default)]
+struct PendingImport {
+    deleted: Vec<Frame>,
+    /// (child, parent) links whose parent was committed by an earlier checkpoint.
+    parents: Vec<(FrameId, FrameId)>,
+    clip: Vec<(FrameId, JsonlClipEmbedding)>,
+    spaces: Vec<JsonlVecSpace>,
+    space_embeddings: Vec<(FrameId, String, Vec<f32>)>,
+    cards: Vec<MemoryCard>,
+    nodes: Vec<MeshNode>,
+    edges: Vec<MeshEdge>,
+    #[cfg(feature = "replay")]
+    sessions: Vec<crate::replay::ReplaySession>,
+}
+
+impl Memvid {
+    /// Stream the whole memory as JSON lines.
+    ///
+    /// Writes a header followed by predicate schemas, vector space declarations, every frame
+    /// (including superseded and deleted ones) with its canonical payload and embeddings, memory
+    /// cards, Logic-Mesh nodes and edges, and replay sessions. Only committed state is exported;
+    /// pending WAL entries are not included. Product-quantized indexes no longer hold the
+    /// original vectors, so their embeddings are not exported.
+    ///
+    /// # Errors
+    ///
+    /// Fails when an index or payload cannot be read, or when writing to `writer` fails.
+    pub fn export_jsonl<W: Write>(&mut self, writer: W) -> Result<JsonlReport> {
+        if self.vec_enabled {
+            self.ensure_vec_index()?;
+        }
+        if self.clip_enabled {
+            self.ensure_clip_index()?;
+        }
+
+        self.ensure_vec_spaces_loaded()?;
+
+        // Payload reads need `&mut self`, so hold the indexes aside while streaming frames.
+        let vec_index = self.vec_index.take();
+        let clip_index = self.clip_index.take();
+        let space_indexes = std::mem::take(&mut self.vec_space_indexes);
+        let result = self.write_jsonl(
+            writer,
+            vec_index.as_ref(),
+            clip_index.as_ref(),
+            &space_indexes,
+        );
+        self.vec_index = vec_index;
+        self.clip_index = clip_index;
+        self.vec_space_indexes = space_indexes;
+        result
+    }
+
+    fn write_jsonl<W: Write>(
+        &mut self,
+        writer: W,
+        vec_index: Option<&VecIndex>,
+        clip_index: Option<&ClipIndex>,
+        space_indexes: &BTreeMap<String, VecIndex>,
+    ) -> Result<JsonlReport> {
+        let mut writer = BufWriter::new(writer);
+        let mut report = JsonlReport::default();
+
+        let header = JsonlHeader {
+            format: JSONL_FORMAT.to_string(),
+            version: JSONL_FORMAT_VERSION,
+            producer: env!("CARGO_PKG_VERSION").to_string(),
+            frame_count: self.toc.frames.len() as u64,
+            lex_enabled: self.lex_enabled,
+            vec_enabled: self.vec_enabled,
+            clip_enabled: self.clip_enabled,
+            schema_strict: self.schema_strict,
+        };
+        write_record(&mut writer, &JsonlRecord::Header(header))?;
+
+        let mut schemas: Vec<_> = self.schema_registry.all().cloned().collect();
+        schemas.sort_by(|a, b| a.id.cmp(&b.id));
+        for schema in schemas {
+            write_record(&mut writer, &JsonlRecord::Schema(schema))?;
+            report.schemas += 1;
+        }
+
+        let mut space_embeddings: HashMap<FrameId, BTreeMap<String, Vec<f32>>> = HashMap::new();
+        for space in &self.toc.indexes.vec_spaces {
+            let record = JsonlVecSpace {
+                name: space.name.clone(),
+                identity: space.identity.clone(),
+                compression: space.index.compression_mode.clone(),
+            };
+            write_record(&mut writer, &JsonlRecord::VecSpace(record))?;
+            report.vec_spaces += 1;
+            let Some(index) = space_indexes.get(&space.name) else {
+                continue;
+            };
+            if matches!(index, VecIndex::Compressed(_)) {
+                tracing::warn!(space = %space.name, "skipping quantized vector space embeddings");
+            }
+            for (frame_id, embedding) in index.entries() {
+                space_embeddings
+                    .entry(frame_id)
+                    .or_default()
+                    .insert(space.name.clone(), embedding.to_vec());
+            }
+        }
+
+        let embeddings: HashMap<FrameId, &[f32]> = vec_index
+            .map(|index| index.entries().collect())
+            .unwrap_or_default();
+        let mut clip_embeddings = clip_index.map(clip_embeddings_by_frame).unwrap_or_default();
+
+        for index in 0..self.toc.frames.len() {
+            let frame = self.toc.frames[index].clone();
+            let payload = self.export_frame_payload(&frame)?;
+            let embedding = embeddings.get(&frame.id).map(|emb| emb.to_vec());
+            let clip = clip_embeddings.remove(&frame.id).unwrap_or_default();
+            let spaces = space_embeddings.remove(&frame.id).unwrap_or_default();
+            report.frames += 1;
+            report.embeddings += u64::from(embedding.is_some());
+            report.clip_embeddings += clip.len() as u64;
+            report.space_embeddings += spaces.len() as u64;
+            let record = JsonlFrame {
+                frame,
+                payload,
+                embedding,
+                clip_embeddings: clip,
+                space_embeddings: spaces,
+            };
+            write_record(&mut writer, &JsonlRecord::Frame(Box::new(record)))?;
+        }
+
+        for card in self.memories_track.cards() {
+            write_record(&mut writer, &JsonlRecord::MemoryCard(card.clone()))?;
+            report.memory_cards += 1;
+        }
+        for node in &self.logic_mesh.nodes {
+            write_record(&mut writer, &JsonlRecord::MeshNode(node.clone()))?;
+            report.mesh_nodes += 1;
+        }
+        for edge in &self.logic_mesh.edges {
+            write_record(&mut writer, &JsonlRecord::MeshEdge(edge.clone()))?;
+            report.mesh_edges += 1;
+        }
+        #[cfg(feature = "replay")]
+        for session in &self.completed_sessions {
+            write_record(
+                &mut writer,
+                &JsonlRecord::ReplaySession(Box::new(session.clone())),
+            )?;
+            report.replay_sessions += 1;
+        }
+
+        writer.flush()?;
+        tracing::info!(
+            frames = report.frames,
+            embeddings = report.embeddings,
+            cards = report.memory_cards,
+            "jsonl export completed"
+        );
+        Ok(report)
+    }
+
+    /// Decoded payload of a single frame (not the reassembled document text).
+    fn export_frame_payload(&mut self, frame: &Frame) -> Result<Option<JsonlPayload>> {
+        let raw = match self.read_frame_payload_bytes(frame) {
+            Ok(raw) => raw,
+            // Vacuum releases the payloads of retired frames but keeps their metadata.
+            Err(err) if frame.status != FrameStatus::Active => {
+                tracing::debug!(frame_id = frame.id, %err, "skipping released payload");
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+        if raw.is_empty() {
+            return Ok(Some(JsonlPayload::Text(String::new())));
+        }
+        let decoded = crate::decode_canonical_bytes(&raw, frame.canonical_encoding, frame.id)?;
+        Ok(Some(JsonlPayload::from_bytes(decoded)))
+    }
+
+    /// Rebuild this (empty) memory from a dump written by [`Memvid::export_jsonl`].
+    ///
+    /// Frames are re-inserted in order so they keep their original IDs, then deleted frames
+    /// are tombstoned and embeddings (including vector spaces), memory cards, Logic-Mesh,
+    /// replay sessions and schemas are restored. The import is committed before returning.
+    ///
+    /// Schemas and the strict-validation flag go into this handle's registry, like those set
+    /// with [`Memvid::register_schema`]. The file does not store them, so a reopened memory
+    /// starts from the built-in schemas; register the dump's [`JsonlRecord::Schema`] records
+    /// again if it needs them.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::JsonlImport`] when the memory is not empty or a line is not
+    /// a valid record, and on any error writing or committing the restored state.
+    pub fn import_jsonl<R: BufRead>(&mut self, reader: R) -> Result<JsonlReport> {
+        self.ensure_mutation_allowed()?;
+        if self.next_frame_id() != 0 {
+            return Err(import_error(0, "target memory must be empty"));
+        }
+
+        let mut report = JsonlReport::default();
+        let mut pending = PendingImport::default();
+        let mut header_seen = false;
+        // Source frame ID -> WAL sequence of its insert in the current batch, for linking
+        // children to parents.
+        let mut sequences: HashMap<FrameId, u64> = HashMap::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line_no = index + 1;
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let record: JsonlRecord = serde_json::from_str(&line)
+                .map_err(|err| import_error(line_no, err.to_string()))?;
+
+            match record {
+                JsonlRecord::Header(header) => {
+                    if header_seen {
+                        return Err(import_error(line_no, "duplicate header record"));
+                    }
+                    self.apply_jsonl_header(&header, line_no)?;
+                    header_seen = true;
+                }
+                _ if !header_seen => {
+                    return Err(import_error(
+                        line_no,
+                        "dump must start with a header record",
+                    ));
+                }
+                JsonlRecord::Schema(schema) => {
+                    self.schema_registry.register(schema);
+                    report.schemas += 1;
+                }
+                JsonlRecord::VecSpace(space) => pending.spaces.push(space),
+                JsonlRecord::Frame(record) => {
+                    report.embeddings += u64::from(record.embedding.is_some());
+                    self.import_jsonl_frame(*record, line_no, &mut sequences, &mut pending)?;
+                    report.frames += 1;
+                }
+                JsonlRecord::MemoryCard(card) => pending.cards.push(card),
+                JsonlRecord::MeshNode(node) => pending.nodes.push(node),
+                JsonlRecord::MeshEdge(edge) => pending.edges.push(edge),
+                JsonlRecord::ReplaySession(session) => {
+                    #[cfg(feature = "replay")]
+                    pending.sessions.push(*session);
+                    #[cfg(not(feature = "replay"))]
+                    tracing::warn!(
+                        session_id = %session.session_id,
+                        "replay feature disabled; dropping replay session"
+                    );
+                }
+            }
+        }
+        if !header_seen {
+            return Err(import_error(0, "dump is empty"));
+        }
+
+        self.commit()?;
+        self.finish_jsonl_import(pending, &mut report)?;
+        self.commit()?;
+        tracing::info!(
+            frames = report.frames,
+            embeddings = report.embeddings,
+            cards = report.memory_cards,
+            "jsonl import completed"
+        );
+        Ok(report)
+    }
+
+    fn apply_jsonl_header(&mut self, header: &JsonlHeader, line: usize) -> Result<()> {
+        if header.format != JSONL_FORMAT {
+            return Err(import_error(
+                line,
+                format!("unknown dump format '{}'", header.format),
+            ));
+        }
+        if header.version > JSONL_FORMAT_VERSION {
+            return Err(import_error(
+                line,
+                format!(
+                    "dump version {} is newer than supported version {}",
+                    header.version, JSONL_FORMAT_VERSION
+                ),
+            ));
+        }
+        if header.lex_enabled && !self.lex_enabled {
+            self.enable_lex()?;
+        }
+        if header.vec_enabled && !self.vec_enabled {
+            self.enable_vec()?;
+        }
+        if header.clip_enabled && !self.clip_enabled {
+            self.enable_clip()?;
+        }
+        self.schema_strict = header.schema_strict;
+        Ok(())
+    }
+
+    fn import_jsonl_frame(
+        &mut self,
+        record: JsonlFrame,
+        line: usize,
+        sequences: &mut HashMap<FrameId, u64>,
+        pending: &mut PendingImport,
+    ) -> Result<()> {
+        let JsonlFrame {
+            frame,
+            payload,
+            embedding,
+            clip_embeddings,
+            space_embeddings,
+        } = record;
+        let expected = self.next_frame_id();
+        if frame.id != expected {
+            return Err(import_error(
+                line,
+                format!("expected frame {expected}, found frame {}", frame.id),
+            ));
+        }
+
+        let canonical = payload
+            .map(JsonlPayload::into_bytes)
+            .transpose()
+            .map_err(|err| import_error(line, format!("invalid base64 payload: {err}")))?;
+        let stored = match (canonical, frame.canonical_encoding) {
+            (None, _) => Vec::new(),
+            (Some(bytes), _) if bytes.is_empty() => bytes,
+            (Some(bytes), CanonicalEncoding::Zstd) => {
+                zstd::encode_all(std::io::Cursor::new(bytes), 3)?
+            }
+            (Some(bytes), CanonicalEncoding::Plain) => bytes,
+        };
+
+        // Children are linked through the WAL sequence of their parent's insert, which only
+        // resolves within one batch, so prefer to checkpoint before a top-level frame.
+        if frame.parent_id.is_none() && self.wal.should_checkpoint() {
+            self.commit()?;
+            sequences.clear();
+        }
+
+        let id = frame.id;
+        let parent_sequence = frame.parent_id.and_then(|p| sequences.get(&p).copied());
+        if let (Some(parent), None) = (frame.parent_id, parent_sequence) {
+            // The parent was committed by an earlier checkpoint; link it once the child is.
+            pending.parents.push((id, parent));
+        }
+        let supersedes = frame.supersedes;
+        if frame.status == FrameStatus::Deleted {
+            pending.deleted.push(frame.clone());
+        }
+        let mut entry = WalEntryData::insert_from_frame(frame, stored);
+        entry.embedding = embedding;
+        entry.parent_sequence = parent_sequence;
+        entry.supersedes_frame_id = supersedes;
+        let sequence = self.append_frame_entry(entry)?;
+        sequences.insert(id, sequence);
+        pending
+            .clip
+            .extend(clip_embeddings.into_iter().map(|clip| (id, clip)));
+        pending.space_embeddings.extend(
+            space_embeddings
+                .into_iter()
+                .map(|(space, embedding)| (id, space, embedding)),
+        );
+        Ok(())
+    }
+
+    fn finish_jsonl_import(
+        &mut self,
+        pending: PendingImport,
+        report: &mut JsonlReport,
+    ) -> Result<()> {
+        if !pending.deleted.is_empty() {
+            for frame in &pending.deleted {
+                self.append_frame_entry(WalEntryData::tombstone_for(frame))?;
+            }
+            self.commit()?;
+        }
+
+        for (child, parent) in pending.parents {
+            if let Some(frame) = usize::try_from(child)
+                .ok()
+                .and_then(|index| self.toc.frames.get_mut(index))
+            {
+                frame.parent_id = Some(parent);
+            }
+        }
+
+        if !pending.clip.is_empty() && !self.clip_enabled {
+            self.enable_clip()?;
+        }
+        for (frame_id, clip) in pending.clip {
+            self.add_clip_embedding_with_page(frame_id, clip.page, clip.embedding)?;
+            report.clip_embeddings += 1;
+        }
+
+        report.vec_spaces = pending.spaces.len() as u64;
+        for space in pending.spaces {
+            self.create_vec_space(&space.name, space.identity, space.compression)?;
+        }
+        // Inserted directly rather than through `add_embeddings_to_space`, which only takes
+        // active frames: the dump also carries the embeddings of retired frames.
+        for (frame_id, space, embedding) in pending.space_embeddings {
+            self.ensure_vec_space_dimension(&space, embedding.len())?;
+            self.insert_space_embedding(&space, frame_id, embedding)?;
+            report.space_embeddings += 1;
+        }
+
+        report.memory_cards = pending.cards.len() as u64;
+        for card in pending.cards {
+            let card_id = self.memories_track.add_card(card);
+            self.record_change(ChangeKind::MemoryCardAdded { card_id });
+        }
+
+        report.mesh_nodes = pending.nodes.len() as u64;
+        report.mesh_edges = pending.edges.len() as u64;
+        if !pending.nodes.is_empty() || !pending.edges.is_empty() {
+            self.add_mesh_nodes(pending.nodes);
+            self.add_mesh_edges(pending.edges);
+            self.logic_mesh.finalize();
+        }
+
+        #[cfg(feature = "replay")]
+        {
+            report.replay_sessions = pending.sessions.len() as u64;
+            if !pending.sessions.is_empty() {
+                self.completed_sessions.extend(pending.sessions);
+                self.save_replay_sessions()?;
+            }
+        }
+
+        self.dirty = true;
+        Ok(())
+    }
+}
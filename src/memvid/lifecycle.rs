@@ -6,21 +6,25 @@
 //! - Validate TOC/footer layout, recover the latest valid footer when needed.
 //! - Wire up index state (lex/vector/time) without mutating payload bytes.
 
+use std::collections::{BTreeMap, BTreeSet};
 use std::convert::TryInto;
-use std::fs::{File, OpenOptions};
+use std::fs::OpenOptions;
 use std::io::{Read, Seek, SeekFrom};
 use std::panic;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::sync::{Arc, RwLock};
 
-use crate::constants::{MAGIC, SPEC_VERSION, WAL_OFFSET, WAL_SIZE_TINY};
+use crate::constants::{MAGIC, MV2E_LIVE_VERSION, SPEC_VERSION, WAL_OFFSET, WAL_SIZE_TINY};
 use crate::error::{MemvidError, Result};
 use crate::footer::{FooterSlice, find_last_valid_footer};
+use crate::io::data_file::DataFile;
 use crate::io::header::HeaderCodec;
 #[cfg(feature = "parallel_segments")]
 use crate::io::manifest_wal::ManifestWal;
 use crate::io::wal::EmbeddedWal;
 use crate::lock::{FileLock, LockMode};
+use crate::memvid::changes::DEFAULT_CHANGE_LOG_RETENTION;
 #[cfg(feature = "lex")]
 use crate::search::{EmbeddedLexStorage, TantivyEngine};
 #[cfg(feature = "temporal_track")]
@@ -28,14 +32,13 @@ use crate::types::FrameId;
 #[cfg(feature = "parallel_segments")]
 use crate::types::IndexSegmentRef;
 use crate::types::{
-    FrameStatus, Header, IndexManifests, LogicMesh, MemoriesTrack, SchemaRegistry, SegmentCatalog,
-    SketchTrack, TicketRef, Tier, Toc, VectorCompression,
+    ChangeEvent, FrameStatus, Header, IndexManifests, LogicMesh, MemoriesTrack, PutManyOpts,
+    SchemaRegistry, SegmentCatalog, SketchTrack, TicketRef, Tier, Toc, VectorCompression,
 };
 #[cfg(feature = "temporal_track")]
 use crate::{TemporalTrack, temporal_track_read};
-use crate::{lex::LexIndex, vec::VecIndex};
+use crate::{lex::LexIndex, vec::VecIndex, vec_hnsw::HnswConfig};
 use blake3::Hasher;
-use memmap2::Mmap;
 
 const DEFAULT_LOCK_TIMEOUT_MS: u64 = 250;
 const DEFAULT_HEARTBEAT_MS: u64 = 2_000;
@@ -46,7 +49,7 @@ const DEFAULT_STALE_GRACE_MS: u64 = 10_000;
 /// Holds the file descriptor, lock, header, TOC, and in-memory index state. Mutations
 /// append to the embedded WAL and are materialized at commit time to keep the layout deterministic.
 pub struct Memvid {
-    pub(crate) file: File,
+    pub(crate) file: DataFile,
     pub(crate) path: PathBuf,
     pub(crate) lock: FileLock,
     pub(crate) read_only: bool,
@@ -67,11 +70,33 @@ pub struct Memvid {
     pub(crate) lex_storage: Arc<RwLock<EmbeddedLexStorage>>,
     pub(crate) vec_enabled: bool,
     pub(crate) vec_compression: VectorCompression,
+    /// Parameters for HNSW graphs built by this handle.
+    pub(crate) hnsw_config: HnswConfig,
     pub(crate) vec_index: Option<VecIndex>,
+    /// Indexes of the named vector spaces declared in `toc.indexes.vec_spaces`.
+    pub(crate) vec_space_indexes: BTreeMap<String, VecIndex>,
+    /// Vector spaces changed since they were last written.
+    pub(crate) dirty_vec_spaces: BTreeSet<String>,
+    /// Options of the `put_many` batch in progress, if any.
+    pub(crate) put_batch: Option<PutManyOpts>,
+    /// Set while a `Memvid::transaction` closure runs.
+    pub(crate) transaction_open: bool,
+    /// Transaction whose begin marker is logged but whose commit or abort marker is not yet;
+    /// an abort marker is logged for it ahead of the next WAL record written outside it.
+    pub(crate) unterminated_transaction: Option<u64>,
+    /// Set while an operation runs against a staging copy of the file.
+    pub(crate) staging_active: bool,
+    /// Set from starting a background commit until it publishes or its handle is dropped;
+    /// shared with that [`crate::BackgroundCommit`].
+    pub(crate) background_commit_active: Arc<AtomicBool>,
     /// CLIP visual embeddings index (separate from vec due to different dimensions)
     pub(crate) clip_enabled: bool,
     pub(crate) clip_index: Option<crate::clip::ClipIndex>,
     pub(crate) dirty: bool,
+    /// Changes made since the last commit, stamped with its generation when it publishes.
+    pub(crate) pending_changes: Vec<ChangeEvent>,
+    /// Most changes kept in the TOC's change log once a commit publishes.
+    pub(crate) change_log_retention: usize,
     #[cfg(feature = "lex")]
     pub(crate) tantivy: Option<TantivyEngine>,
     #[cfg(feature = "lex")]
@@ -146,8 +171,16 @@ impl Memvid {
             .create(true)
             .truncate(true)
             .open(path_ref)?;
-        let (mut file, lock) = FileLock::open_and_lock(path_ref)?;
+        let (file, lock) = FileLock::open_and_lock(path_ref)?;
+        Self::create_locked(DataFile::Plain(file), lock, path_ref)
+    }
 
+    /// Lay out an empty memory in the freshly truncated `file`.
+    pub(crate) fn create_locked(
+        mut file: DataFile,
+        lock: FileLock,
+        path_ref: &Path,
+    ) -> Result<Self> {
         let header = Header {
             magic: MAGIC,
             version: SPEC_VERSION,
@@ -168,7 +201,7 @@ impl Memvid {
         file.set_len(header.footer_offset)?;
         HeaderCodec::write(&mut file, &header)?;
 
-        let wal = EmbeddedWal::open(&file, &header)?;
+        let wal = EmbeddedWal::open_data_file(&file, &header)?;
         let data_end = header.footer_offset;
         #[cfg(feature = "lex")]
         let lex_storage = Arc::new(RwLock::new(EmbeddedLexStorage::new()));
@@ -195,10 +228,20 @@ impl Memvid {
             lex_storage,
             vec_enabled: cfg!(feature = "vec"), // Enable by default if feature is enabled
             vec_compression: VectorCompression::None,
+            hnsw_config: HnswConfig::default(),
             vec_index: None,
+            vec_space_indexes: BTreeMap::new(),
+            dirty_vec_spaces: BTreeSet::new(),
+            put_batch: None,
+            transaction_open: false,
+            unterminated_transaction: None,
+            staging_active: false,
+            background_commit_active: Arc::new(AtomicBool::new(false)),
             clip_enabled: cfg!(feature = "clip"), // Enable by default if feature is enabled
             clip_index: None,
             dirty: false,
+            pending_changes: Vec::new(),
+            change_log_retention: DEFAULT_CHANGE_LOG_RETENTION,
             #[cfg(feature = "lex")]
             tantivy: None,
             #[cfg(feature = "lex")]
@@ -239,6 +282,7 @@ impl Memvid {
                 bytes_offset: empty_offset,
                 bytes_length: 0,
                 checksum: empty_checksum,
+                analyzer: None,
             });
         }
 
@@ -280,6 +324,23 @@ impl Memvid {
         &self.vec_compression
     }
 
+    /// Set the HNSW parameters for the vector index
+    ///
+    /// `m` and `ef_construction` apply when a graph is next built (on promotion past
+    /// `min_vectors` or on compaction); `ef_search` also applies to a loaded graph.
+    pub fn set_hnsw_config(&mut self, config: HnswConfig) {
+        self.hnsw_config = config;
+        if let Some(VecIndex::Hnsw(graph)) = self.vec_index.as_mut() {
+            graph.set_ef_search(config.ef_search);
+        }
+    }
+
+    /// Get the HNSW parameters used for new graphs
+    #[must_use]
+    pub fn hnsw_config(&self) -> HnswConfig {
+        self.hnsw_config
+    }
+
     /// Predict the next frame ID that would be assigned to a new insert.
     ///
     /// Frame IDs are dense indices into `toc.frames`. When a memory is mutable, inserts are first
@@ -297,17 +358,28 @@ impl Memvid {
         self.toc.frames.len()
     }
 
-    fn open_locked(mut file: File, lock: FileLock, path_ref: &Path) -> Result<Self> {
+    pub(crate) fn open_locked(mut file: DataFile, lock: FileLock, path_ref: &Path) -> Result<Self> {
         // Fast-path detection for encrypted capsules (.mv2e).
         // This avoids confusing "invalid header" errors and provides an actionable hint.
-        let mut magic = [0u8; 4];
-        let is_mv2e = file.read_exact(&mut magic).is_ok() && magic == *b"MV2E";
-        file.seek(SeekFrom::Start(0))?;
-        if is_mv2e {
-            return Err(MemvidError::EncryptedFile {
-                path: path_ref.to_path_buf(),
-                hint: format!("Run: memvid unlock {}", path_ref.display()),
-            });
+        if !file.is_encrypted() {
+            let mut magic = [0u8; 4];
+            let is_mv2e = file.read_exact(&mut magic).is_ok() && magic == *b"MV2E";
+            let mut version = [0u8; 2];
+            let is_live = is_mv2e
+                && file.read_exact(&mut version).is_ok()
+                && u16::from_le_bytes(version) == MV2E_LIVE_VERSION;
+            file.seek(SeekFrom::Start(0))?;
+            if is_mv2e {
+                let hint = if is_live {
+                    "Open it with Memvid::open_encrypted and its password".to_string()
+                } else {
+                    format!("Run: memvid unlock {}", path_ref.display())
+                };
+                return Err(MemvidError::EncryptedFile {
+                    path: path_ref.to_path_buf(),
+                    hint,
+                });
+            }
         }
 
         let mut header = HeaderCodec::read(&mut file)?;
@@ -330,7 +402,7 @@ impl Memvid {
         let checksum_result = toc.verify_checksum();
 
         // Validate segment integrity early to catch corruption before loading indexes
-        let file_len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        let file_len = file.len().unwrap_or(0);
         if let Err(e) = validate_segment_integrity(&toc, &header, file_len) {
             tracing::warn!("Segment integrity validation failed: {}", e);
             // Don't fail file open - let doctor handle it
@@ -338,7 +410,7 @@ impl Memvid {
         }
         ensure_non_overlapping_frames(&toc, file_len)?;
 
-        let wal = EmbeddedWal::open(&file, &header)?;
+        let wal = EmbeddedWal::open_data_file(&file, &header)?;
         #[cfg(feature = "lex")]
         let lex_storage = Arc::new(RwLock::new(EmbeddedLexStorage::from_manifest(
             toc.indexes.lex.as_ref(),
@@ -370,10 +442,20 @@ impl Memvid {
             lex_storage,
             vec_enabled: false,
             vec_compression: VectorCompression::None,
+            hnsw_config: HnswConfig::default(),
             vec_index: None,
+            vec_space_indexes: BTreeMap::new(),
+            dirty_vec_spaces: BTreeSet::new(),
+            put_batch: None,
+            transaction_open: false,
+            unterminated_transaction: None,
+            staging_active: false,
+            background_commit_active: Arc::new(AtomicBool::new(false)),
             clip_enabled: false,
             clip_index: None,
             dirty: false,
+            pending_changes: Vec::new(),
+            change_log_retention: DEFAULT_CHANGE_LOG_RETENTION,
             #[cfg(feature = "lex")]
             tantivy: None,
             #[cfg(feature = "lex")]
@@ -407,19 +489,22 @@ impl Memvid {
         if memvid.vec_enabled {
             memvid.load_vec_index_from_manifest()?;
         }
+        memvid.load_vec_spaces_from_manifest();
         memvid.clip_enabled = memvid.toc.indexes.clip.is_some();
         if memvid.clip_enabled {
             memvid.load_clip_index_from_manifest()?;
         }
+        // Recovery replays the WAL on top of the persisted tracks and rewrites them, so they
+        // are loaded first.
+        memvid.load_memories_track()?;
+        memvid.load_logic_mesh()?;
+        memvid.load_sketch_track()?;
         memvid.recover_wal()?;
         #[cfg(feature = "parallel_segments")]
         memvid.load_manifest_segments(manifest_wal_entries);
         memvid.bootstrap_segment_catalog();
         #[cfg(feature = "temporal_track")]
         memvid.ensure_temporal_track_loaded()?;
-        memvid.load_memories_track()?;
-        memvid.load_logic_mesh()?;
-        memvid.load_sketch_track()?;
         if checksum_result.is_err() {
             memvid.toc.verify_checksum()?;
             if memvid.toc.toc_checksum != memvid.header.toc_checksum {
@@ -437,7 +522,7 @@ impl Memvid {
         ensure_single_file(path_ref)?;
 
         let (file, lock) = FileLock::open_and_lock(path_ref)?;
-        Self::open_locked(file, lock, path_ref)
+        Self::open_locked(DataFile::Plain(file), lock, path_ref)
     }
 
     pub fn open_read_only<P: AsRef<Path>>(path: P) -> Result<Self> {
@@ -459,7 +544,16 @@ impl Memvid {
     }
 
     fn open_read_only_snapshot(path_ref: &Path) -> Result<Self> {
-        let mut file = OpenOptions::new().read(true).write(true).open(path_ref)?;
+        let file = DataFile::Plain(OpenOptions::new().read(true).write(true).open(path_ref)?);
+        Self::open_snapshot_file(file, path_ref, LockMode::Shared)
+    }
+
+    /// Open a read-only handle over the committed state of `file`, locked with `lock_mode`.
+    pub(crate) fn open_snapshot_file(
+        mut file: DataFile,
+        path_ref: &Path,
+        lock_mode: LockMode,
+    ) -> Result<Self> {
         let TailSnapshot {
             toc,
             footer_offset,
@@ -471,8 +565,8 @@ impl Memvid {
         header.footer_offset = footer_offset;
         header.toc_checksum = toc.toc_checksum;
 
-        let lock = FileLock::acquire_with_mode(&file, LockMode::Shared)?;
-        let wal = EmbeddedWal::open_read_only(&file, &header)?;
+        let lock = FileLock::acquire_with_mode(file.raw(), lock_mode)?;
+        let wal = EmbeddedWal::open_data_file_read_only(&file, &header)?;
 
         #[cfg(feature = "lex")]
         let lex_storage = Arc::new(RwLock::new(EmbeddedLexStorage::from_manifest(
@@ -498,10 +592,20 @@ impl Memvid {
             lex_storage,
             vec_enabled: false,
             vec_compression: VectorCompression::None,
+            hnsw_config: HnswConfig::default(),
             vec_index: None,
+            vec_space_indexes: BTreeMap::new(),
+            dirty_vec_spaces: BTreeSet::new(),
+            put_batch: None,
+            transaction_open: false,
+            unterminated_transaction: None,
+            staging_active: false,
+            background_commit_active: Arc::new(AtomicBool::new(false)),
             clip_enabled: false,
             clip_index: None,
             dirty: false,
+            pending_changes: Vec::new(),
+            change_log_retention: DEFAULT_CHANGE_LOG_RETENTION,
             #[cfg(feature = "lex")]
             tantivy: None,
             #[cfg(feature = "lex")]
@@ -534,6 +638,7 @@ impl Memvid {
         if memvid.vec_enabled {
             memvid.load_vec_index_from_manifest()?;
         }
+        memvid.load_vec_spaces_from_manifest();
         memvid.clip_enabled = memvid.toc.indexes.clip.is_some();
         if memvid.clip_enabled {
             memvid.load_clip_index_from_manifest()?;
@@ -563,10 +668,10 @@ impl Memvid {
                 ));
             }
         };
-        Self::open_locked(file, lock, path_ref)
+        Self::open_locked(DataFile::Plain(file), lock, path_ref)
     }
 
-    fn bootstrap_segment_catalog(&mut self) {
+    pub(crate) fn bootstrap_segment_catalog(&mut self) {
         let catalog = &mut self.toc.segment_catalog;
         if catalog.version == 0 {
             catalog.version = 1;
@@ -615,7 +720,7 @@ impl Memvid {
     }
 
     /// Load the memories track from the manifest if present.
-    fn load_memories_track(&mut self) -> Result<()> {
+    pub(crate) fn load_memories_track(&mut self) -> Result<()> {
         let manifest = match &self.toc.memories_track {
             Some(m) => m,
             None => return Ok(()),
@@ -642,7 +747,7 @@ impl Memvid {
     }
 
     /// Load the Logic-Mesh from the manifest if present.
-    fn load_logic_mesh(&mut self) -> Result<()> {
+    pub(crate) fn load_logic_mesh(&mut self) -> Result<()> {
         let manifest = match &self.toc.logic_mesh {
             Some(m) => m,
             None => return Ok(()),
@@ -669,7 +774,7 @@ impl Memvid {
     }
 
     /// Load the sketch track from the manifest if present.
-    fn load_sketch_track(&mut self) -> Result<()> {
+    pub(crate) fn load_sketch_track(&mut self) -> Result<()> {
         let manifest = match &self.toc.sketch_track {
             Some(m) => m.clone(),
             None => return Ok(()),
@@ -697,7 +802,7 @@ impl Memvid {
         if manifest.bytes_length == 0 {
             return Ok(());
         }
-        let file_len = self.file.metadata()?.len();
+        let file_len = self.file.len()?;
         let Some(end) = manifest.bytes_offset.checked_add(manifest.bytes_length) else {
             return Ok(());
         };
@@ -850,10 +955,10 @@ impl Memvid {
     }
 }
 
-pub(crate) fn read_toc(file: &mut File, header: &Header) -> Result<Toc> {
+pub(crate) fn read_toc(file: &mut DataFile, header: &Header) -> Result<Toc> {
     use crate::footer::{CommitFooter, FOOTER_SIZE};
 
-    let len = file.metadata()?.len();
+    let len = file.len()?;
     if len < header.footer_offset {
         return Err(MemvidError::InvalidToc {
             reason: "footer offset beyond file length".into(),
@@ -992,10 +1097,9 @@ fn ensure_non_overlapping_frames(toc: &Toc, file_len: u64) -> Result<()> {
     Ok(())
 }
 
-pub(crate) fn recover_toc(file: &mut File, hint: Option<u64>) -> Result<(Toc, u64)> {
-    let len = file.metadata()?.len();
-    // Safety: we only create a read-only mapping over stable file bytes.
-    let mmap = unsafe { Mmap::map(&*file)? };
+pub(crate) fn recover_toc(file: &mut DataFile, hint: Option<u64>) -> Result<(Toc, u64)> {
+    let len = file.len()?;
+    let mmap = file.map()?;
     tracing::debug!(file_len = len, "attempting toc recovery");
 
     // First, try to find a valid footer which includes validated TOC bytes
@@ -1117,6 +1221,12 @@ fn scan_range_for_toc(data: &[u8], start: usize, end: usize) -> Option<(Toc, u64
 }
 
 pub(crate) fn prepare_toc_bytes(toc: &mut Toc) -> Result<Vec<u8>> {
+    // A signature only vouches for the root it was made over; any content change voids it.
+    let merkle_root = toc.compute_merkle_root()?;
+    if merkle_root != toc.merkle_root {
+        toc.merkle_root = merkle_root;
+        toc.signature = None;
+    }
     toc.toc_checksum = [0u8; 32];
     let bytes = toc.encode()?;
     let checksum = Toc::calculate_checksum(&bytes);
@@ -1146,6 +1256,8 @@ pub(crate) fn empty_toc() -> Toc {
         memory_binding: None,
         replay_manifest: None,
         enrichment_queue: crate::types::EnrichmentQueueManifest::default(),
+        signature: None,
+        change_log: crate::types::ChangeLog::default(),
         merkle_root: [0u8; 32],
         toc_checksum: [0u8; 32],
     }
@@ -1214,7 +1326,8 @@ pub(crate) fn compute_data_end(toc: &Toc, header: &Header) -> u64 {
             max_end = max_end.max(end);
         }
     }
-    if let Some(manifest) = toc.indexes.vec.as_ref() {
+    let spaces = toc.indexes.vec_spaces.iter().map(|space| &space.index);
+    for manifest in toc.indexes.vec.iter().chain(spaces) {
         if let Some(end) = manifest.bytes_offset.checked_add(manifest.bytes_length) {
             max_end = max_end.max(end);
         }
@@ -1267,11 +1380,11 @@ pub(crate) fn compute_data_end(toc: &Toc, header: &Header) -> u64 {
     max_end
 }
 
-struct TailSnapshot {
-    toc: Toc,
-    footer_offset: u64,
-    data_end: u64,
-    generation: u64,
+pub(crate) struct TailSnapshot {
+    pub(crate) toc: Toc,
+    pub(crate) footer_offset: u64,
+    pub(crate) data_end: u64,
+    pub(crate) generation: u64,
 }
 
 fn locate_footer_window(mmap: &[u8]) -> Option<(FooterSlice<'_>, usize)> {
@@ -1293,9 +1406,8 @@ fn locate_footer_window(mmap: &[u8]) -> Option<(FooterSlice<'_>, usize)> {
     None
 }
 
-fn load_tail_snapshot(file: &File) -> Result<TailSnapshot> {
-    // Safety: we only create a read-only mapping over the stable file bytes.
-    let mmap = unsafe { Mmap::map(file)? };
+pub(crate) fn load_tail_snapshot(file: &DataFile) -> Result<TailSnapshot> {
+    let mmap = file.map()?;
 
     let (slice, offset_adjustment) =
         locate_footer_window(&mmap).ok_or_else(|| MemvidError::InvalidToc {
@@ -1313,9 +1425,8 @@ fn load_tail_snapshot(file: &File) -> Result<TailSnapshot> {
     })
 }
 
-fn detect_generation(file: &File) -> Result<Option<u64>> {
-    // Safety: read-only mapping for footer inspection.
-    let mmap = unsafe { Mmap::map(file)? };
+pub(crate) fn detect_generation(file: &DataFile) -> Result<Option<u64>> {
+    let mmap = file.map()?;
 
     Ok(locate_footer_window(&mmap).map(|(slice, _)| slice.footer.generation))
 }
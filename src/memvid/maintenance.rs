@@ -1,8 +1,13 @@
 use std::path::Path;
 
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use ed25519_dalek::VerifyingKey;
+
 use crate::Result;
 use crate::io::time_index::read_track as time_index_read;
 use crate::memvid::lifecycle::Memvid;
+use crate::signature::verify_memory_signature;
 use crate::types::{
     DoctorOptions, DoctorPlan, DoctorReport, VerificationCheck, VerificationReport,
     VerificationStatus,
@@ -155,6 +160,70 @@ impl Memvid {
             ),
         }
 
+        // Merkle root over frame records and index segment checksums
+        match mem.verify_merkle_root() {
+            Ok(()) => push_check("MerkleRoot", VerificationStatus::Passed, None),
+            Err(_) if mem.toc.merkle_root == [0u8; 32] => push_check(
+                "MerkleRoot",
+                VerificationStatus::Skipped,
+                Some("written before merkle roots were recorded".into()),
+            ),
+            Err(err) => push_check(
+                "MerkleRoot",
+                VerificationStatus::Failed,
+                Some(err.to_string()),
+            ),
+        }
+
+        // Content signature, checked against the key recorded with it
+        match mem.toc.signature.clone() {
+            Some(signature) => {
+                let signed = VerifyingKey::from_bytes(&signature.public_key)
+                    .map_err(|err| err.to_string())
+                    .and_then(|key| {
+                        verify_memory_signature(&key, &mem.toc.merkle_root, &signature)
+                            .map_err(|err| err.to_string())
+                    });
+                match signed {
+                    Ok(()) => push_check(
+                        "Signature",
+                        VerificationStatus::Passed,
+                        Some(format!(
+                            "signed by {}",
+                            BASE64_STANDARD.encode(signature.public_key)
+                        )),
+                    ),
+                    Err(reason) => {
+                        push_check("Signature", VerificationStatus::Failed, Some(reason));
+                    }
+                }
+            }
+            None => push_check(
+                "Signature",
+                VerificationStatus::Skipped,
+                Some("memory is not signed".into()),
+            ),
+        }
+
+        if deep {
+            match mem.verify_frame_payloads() {
+                Ok(()) => push_check("FramePayloadChecksums", VerificationStatus::Passed, None),
+                Err(err) => push_check(
+                    "FramePayloadChecksums",
+                    VerificationStatus::Failed,
+                    Some(err.to_string()),
+                ),
+            }
+            match mem.verify_index_payloads() {
+                Ok(()) => push_check("IndexPayloadChecksums", VerificationStatus::Passed, None),
+                Err(err) => push_check(
+                    "IndexPayloadChecksums",
+                    VerificationStatus::Failed,
+                    Some(err.to_string()),
+                ),
+            }
+        }
+
         Ok(VerificationReport {
             file_path: path_buf,
             checks,
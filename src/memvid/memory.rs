@@ -6,9 +6,10 @@
 
 use crate::error::Result;
 use crate::memvid::lifecycle::Memvid;
+use crate::memvid::mutation::WalEntry;
 use crate::types::{
-    Cardinality, EntityKind, FrameId, MemoriesStats, MemoriesTrack, MemoryCard, MemoryCardId,
-    PredicateSchema, SchemaError, SchemaRegistry,
+    Cardinality, ChangeKind, EntityKind, FrameId, MemoriesStats, MemoriesTrack, MemoryCard,
+    MemoryCardId, PredicateSchema, SchemaError, SchemaRegistry,
 };
 use serde::Serialize;
 
@@ -74,8 +75,12 @@ impl Memvid {
     /// The assigned card ID.
     ///
     /// # Errors
-    /// Returns an error if strict schema validation is enabled and the card is invalid.
+    /// Returns an error if strict schema validation is enabled and the card is invalid,
+    /// and [`crate::MemvidError::CommitInProgress`] while a background commit is running.
     pub fn put_memory_card(&mut self, card: MemoryCard) -> Result<MemoryCardId> {
+        if self.background_commit_running() {
+            return Err(crate::error::MemvidError::CommitInProgress);
+        }
         // Validate against schema
         if let Err(e) = self.validate_card(&card) {
             if self.schema_strict {
@@ -95,6 +100,7 @@ impl Memvid {
 
         self.dirty = true;
         let id = self.memories_track.add_card(card);
+        self.record_change(ChangeKind::MemoryCardAdded { card_id: id });
         Ok(id)
     }
 
@@ -116,8 +122,21 @@ impl Memvid {
     /// # Errors
     /// Returns an error if strict schema validation is enabled and any card is invalid.
     pub fn put_memory_cards(&mut self, cards: Vec<MemoryCard>) -> Result<Vec<MemoryCardId>> {
-        // Validate all cards first
-        let validation_errors = self.validate_cards(&cards);
+        self.check_cards_for_insert(&cards)?;
+        self.dirty = true;
+        let ids = self.memories_track.add_cards(cards);
+        for &card_id in &ids {
+            self.record_change(ChangeKind::MemoryCardAdded { card_id });
+        }
+        Ok(ids)
+    }
+
+    /// Validate cards before insertion: errors in strict mode, warns otherwise.
+    pub(crate) fn check_cards_for_insert(&self, cards: &[MemoryCard]) -> Result<()> {
+        if self.background_commit_running() {
+            return Err(crate::error::MemvidError::CommitInProgress);
+        }
+        let validation_errors = self.validate_cards(cards);
 
         if !validation_errors.is_empty() {
             if self.schema_strict {
@@ -148,10 +167,7 @@ impl Memvid {
                 );
             }
         }
-
-        self.dirty = true;
-        let ids = self.memories_track.add_cards(cards);
-        Ok(ids)
+        Ok(())
     }
 
     /// Record that a frame was enriched by an engine.
@@ -177,6 +193,41 @@ impl Memvid {
         Ok(())
     }
 
+    /// Add the cards `engine_kind` extracted from `frame_id` and record the enrichment.
+    ///
+    /// Inside a transaction the cards are logged to the WAL instead, so they are added when
+    /// the transaction is applied and dropped with it when it aborts.
+    pub(crate) fn put_enriched_cards(
+        &mut self,
+        frame_id: FrameId,
+        engine_kind: &str,
+        engine_version: &str,
+        cards: Vec<MemoryCard>,
+    ) -> Result<()> {
+        self.dirty = true;
+        if self.transaction_open {
+            let cards = serde_json::to_vec(&cards).map_err(|err| {
+                crate::error::MemvidError::CheckpointFailed {
+                    reason: format!("failed to encode memory cards: {err}"),
+                }
+            })?;
+            self.append_wal_marker(&WalEntry::EnrichedCards {
+                frame_id,
+                engine_kind: engine_kind.to_string(),
+                engine_version: engine_version.to_string(),
+                cards,
+            })?;
+            return Ok(());
+        }
+        let card_ids = self.memories_track.add_cards(cards);
+        for &card_id in &card_ids {
+            self.record_change(ChangeKind::MemoryCardAdded { card_id });
+        }
+        self.memories_track
+            .record_enrichment(frame_id, engine_kind, engine_version, card_ids);
+        Ok(())
+    }
+
     /// Get frames that haven't been enriched by a specific engine version.
     ///
     /// # Arguments
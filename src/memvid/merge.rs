@@ -0,0 +1,582 @@
+//! Combining memories: copy active frames and derived state from another `.mv2`.
+//!
+//! Incoming frames are appended to the embedded WAL exactly like regular puts, so the usual
+//! commit path assigns dense frame IDs and rebuilds the lex/vec/time indexes. Because frame IDs
+//! are predictable once pending writes are committed, the merge remaps source IDs up front and
+//! rewrites memory-card provenance, Logic-Mesh mentions and CLIP entries to match.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+use crate::error::{MemvidError, Result};
+use crate::memvid::lifecycle::Memvid;
+use crate::memvid::mutation::WalEntryData;
+use crate::types::{
+    Frame, FrameId, FrameStatus, MemoryCard, MergeOptions, MergeReport, UriConflictPolicy,
+};
+use crate::vec::VecIndex;
+
+/// A top-level frame together with the active frames that name it as their parent.
+struct DocumentUnit {
+    root: FrameId,
+    children: Vec<FrameId>,
+}
+
+/// Group active frames into documents (root + chunks/extracted images), ordered by root ID.
+fn document_units(frames: &[Frame]) -> Vec<DocumentUnit> {
+    let is_active =
+        |id: FrameId| frame_at(frames, id).is_some_and(|frame| frame.status == FrameStatus::Active);
+
+    let mut children: BTreeMap<FrameId, Vec<FrameId>> = BTreeMap::new();
+    let mut roots = Vec::new();
+    for frame in frames.iter().filter(|f| f.status == FrameStatus::Active) {
+        match frame.parent_id {
+            Some(parent) if parent != frame.id && is_active(parent) => {
+                children.entry(parent).or_default().push(frame.id);
+            }
+            _ => roots.push(frame.id),
+        }
+    }
+
+    roots
+        .into_iter()
+        .map(|root| DocumentUnit {
+            root,
+            children: children.remove(&root).unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn frame_at(frames: &[Frame], id: FrameId) -> Option<&Frame> {
+    usize::try_from(id).ok().and_then(|index| frames.get(index))
+}
+
+fn missing_frame(id: FrameId) -> MemvidError {
+    MemvidError::MergeFailed {
+        reason: format!("source frame {id} is missing from the TOC"),
+    }
+}
+
+/// Content fingerprint of a document: its own payload checksum plus those of its children.
+///
+/// Text documents keep their bytes in chunk frames and store an empty parent payload, so the
+/// parent checksum alone would collapse every text document onto the same key.
+fn unit_content_key(frames: &[Frame], unit: &DocumentUnit) -> [u8; 32] {
+    let mut hasher = blake3::Hasher::new();
+    for id in std::iter::once(unit.root).chain(unit.children.iter().copied()) {
+        if let Some(frame) = frame_at(frames, id) {
+            hasher.update(&frame.checksum);
+            hasher.update(&frame.payload_length.to_le_bytes());
+        }
+    }
+    *hasher.finalize().as_bytes()
+}
+
+/// What to do with one incoming document.
+enum UnitAction {
+    Copy {
+        supersedes: Option<FrameId>,
+    },
+    /// Identical content already exists; holds the existing root followed by its children.
+    Dedup {
+        existing: Vec<FrameId>,
+    },
+    Skip,
+}
+
+impl Memvid {
+    /// Merge every active frame of another memory into this one.
+    ///
+    /// Frames are copied with their metadata, chunk manifests and parent/child roles and get
+    /// fresh frame IDs in this memory. Memory cards, Logic-Mesh nodes/edges, text embeddings,
+    /// named vector spaces and CLIP embeddings that reference copied frames are remapped and
+    /// carried over; a space missing from this memory is declared with the source identity and
+    /// compression. Duplicate
+    /// content and URI collisions are handled according to `options`. The merge is committed
+    /// before returning, which rebuilds the lex, vector and time indexes.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::MergeFailed`] when `other` is this memory or a URI collides
+    /// under [`UriConflictPolicy::Fail`], with [`MemvidError::CapacityExceeded`] when the
+    /// incoming payloads do not fit, and on any error opening or reading the source.
+    pub fn merge_from<P: AsRef<Path>>(
+        &mut self,
+        other: P,
+        options: MergeOptions,
+    ) -> Result<MergeReport> {
+        self.ensure_mutation_allowed()?;
+        let other_path = other.as_ref();
+        if same_file::is_same_file(self.path(), other_path).unwrap_or(false) {
+            return Err(MemvidError::MergeFailed {
+                reason: "cannot merge a memory into itself".into(),
+            });
+        }
+
+        let mut source = Memvid::open_read_only(other_path)?;
+        let copy_embeddings = options.include_embeddings && source.vec_enabled;
+        if copy_embeddings {
+            source.ensure_vec_index()?;
+        }
+        let copy_spaces = options.include_embeddings && !source.toc.indexes.vec_spaces.is_empty();
+        if copy_spaces {
+            source.ensure_vec_spaces_loaded()?;
+        }
+        let copy_clip = options.include_clip && source.clip_enabled;
+        if copy_clip {
+            source.ensure_clip_index()?;
+        }
+
+        // Materialise pending writes so the frame IDs assigned below are predictable.
+        self.commit()?;
+
+        let mut report = MergeReport::default();
+        let units = document_units(&source.toc.frames);
+        let actions = self.plan_merge_actions(&source, &units, options, &mut report)?;
+
+        let incoming_bytes: u64 = units
+            .iter()
+            .zip(&actions)
+            .filter(|(_, action)| matches!(action, UnitAction::Copy { .. }))
+            .flat_map(|(unit, _)| std::iter::once(unit.root).chain(unit.children.iter().copied()))
+            .filter_map(|id| frame_at(&source.toc.frames, id))
+            .map(|frame| frame.payload_length)
+            .sum();
+        let payload_tail = self.payload_region_end();
+        let capacity_limit = self.capacity_limit();
+        if payload_tail.saturating_add(incoming_bytes) > capacity_limit {
+            return Err(MemvidError::CapacityExceeded {
+                current: payload_tail,
+                limit: capacity_limit,
+                required: incoming_bytes,
+            });
+        }
+
+        if copy_embeddings {
+            self.prepare_merged_embeddings(&source)?;
+        }
+        if copy_spaces {
+            self.prepare_merged_vec_spaces(&source)?;
+        }
+
+        let mut frame_map: HashMap<FrameId, FrameId> = HashMap::new();
+        for (unit, action) in units.iter().zip(actions) {
+            match action {
+                UnitAction::Skip => {}
+                UnitAction::Dedup { existing } => {
+                    let incoming = std::iter::once(unit.root).chain(unit.children.iter().copied());
+                    frame_map.extend(incoming.zip(existing));
+                }
+                UnitAction::Copy { supersedes } => {
+                    if let Some(existing) = supersedes {
+                        self.tombstone_children_of(existing)?;
+                    }
+                    let mapping = self.copy_unit(
+                        &mut source,
+                        unit,
+                        supersedes,
+                        copy_embeddings,
+                        &mut report,
+                    )?;
+                    frame_map.extend(mapping);
+                    // Only checkpoint between documents so parent sequences stay in one batch.
+                    if self.wal.should_checkpoint() {
+                        self.commit()?;
+                    }
+                }
+            }
+        }
+
+        if copy_clip {
+            report.clip_embeddings_copied = self.merge_clip_entries(&source, &frame_map)?;
+        }
+        if options.include_memory_cards {
+            self.merge_memory_cards(&source, &frame_map, options, &mut report)?;
+        }
+        if options.include_logic_mesh {
+            self.merge_logic_mesh(&source, &frame_map, &mut report);
+        }
+        if copy_spaces {
+            // Space embeddings can only target committed frames.
+            self.commit()?;
+            report.space_embeddings_copied = self.merge_vec_space_entries(&source, &frame_map)?;
+        }
+
+        self.dirty = true;
+        self.commit()?;
+        tracing::info!(
+            source = %other_path.display(),
+            frames_copied = report.frames_copied,
+            deduplicated = report.documents_deduplicated,
+            skipped = report.documents_skipped,
+            replaced = report.frames_replaced,
+            "merge completed"
+        );
+        Ok(report)
+    }
+
+    /// Decide, per incoming document, whether to copy, deduplicate or skip it.
+    fn plan_merge_actions(
+        &self,
+        source: &Memvid,
+        units: &[DocumentUnit],
+        options: MergeOptions,
+        report: &mut MergeReport,
+    ) -> Result<Vec<UnitAction>> {
+        let existing_units = document_units(&self.toc.frames);
+        let mut existing_keys: HashMap<[u8; 32], &DocumentUnit> = HashMap::new();
+        let mut existing_uris: HashMap<&str, FrameId> = HashMap::new();
+        for unit in &existing_units {
+            existing_keys
+                .entry(unit_content_key(&self.toc.frames, unit))
+                .or_insert(unit);
+            if let Some(uri) = frame_at(&self.toc.frames, unit.root).and_then(|f| f.uri.as_deref())
+            {
+                existing_uris.insert(uri, unit.root);
+            }
+        }
+
+        let mut actions = Vec::with_capacity(units.len());
+        for unit in units {
+            let root =
+                frame_at(&source.toc.frames, unit.root).ok_or_else(|| missing_frame(unit.root))?;
+            if options.dedup_by_checksum {
+                let key = unit_content_key(&source.toc.frames, unit);
+                if let Some(existing) = existing_keys.get(&key) {
+                    report.documents_deduplicated += 1;
+                    actions.push(UnitAction::Dedup {
+                        existing: std::iter::once(existing.root)
+                            .chain(existing.children.iter().copied())
+                            .collect(),
+                    });
+                    continue;
+                }
+            }
+
+            let conflict = root
+                .uri
+                .as_deref()
+                .and_then(|uri| existing_uris.get(uri).copied());
+            let action = match (conflict, options.uri_conflict) {
+                (None, _) | (Some(_), UriConflictPolicy::KeepBoth) => {
+                    UnitAction::Copy { supersedes: None }
+                }
+                (Some(_), UriConflictPolicy::Skip) => {
+                    report.documents_skipped += 1;
+                    UnitAction::Skip
+                }
+                (Some(existing), UriConflictPolicy::Replace) => {
+                    report.frames_replaced += 1;
+                    UnitAction::Copy {
+                        supersedes: Some(existing),
+                    }
+                }
+                (Some(_), UriConflictPolicy::Fail) => {
+                    return Err(MemvidError::MergeFailed {
+                        reason: format!(
+                            "uri '{}' already exists in the target memory",
+                            root.uri.as_deref().unwrap_or_default()
+                        ),
+                    });
+                }
+            };
+            actions.push(action);
+        }
+        Ok(actions)
+    }
+
+    /// Fail fast on mismatched embedding dimensions and make sure the vector index is enabled.
+    fn prepare_merged_embeddings(&mut self, source: &Memvid) -> Result<()> {
+        let Some(incoming) = source
+            .vec_index
+            .as_ref()
+            .and_then(|index| index.entries().next().map(|(_, emb)| emb.len()))
+        else {
+            return Ok(());
+        };
+        if !self.vec_enabled {
+            self.enable_vec()?;
+        }
+        if let Some(existing) = self.effective_vec_index_dimension()? {
+            if existing as usize != incoming {
+                return Err(MemvidError::VecDimensionMismatch {
+                    expected: existing,
+                    actual: incoming,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Declare the source vector spaces missing here and fail fast on spaces that cannot be
+    /// copied: mismatched dimensions, or product-quantized indexes that no longer hold the
+    /// original vectors.
+    fn prepare_merged_vec_spaces(&mut self, source: &Memvid) -> Result<()> {
+        for incoming in &source.toc.indexes.vec_spaces {
+            if matches!(
+                source.vec_space_indexes.get(&incoming.name),
+                Some(VecIndex::Compressed(_))
+            ) {
+                return Err(MemvidError::MergeFailed {
+                    reason: format!(
+                        "vector space '{}' is product quantized and cannot be copied",
+                        incoming.name
+                    ),
+                });
+            }
+            match self.toc.indexes.vec_space(&incoming.name) {
+                Some(existing) => {
+                    let (expected, actual) = (existing.index.dimension, incoming.index.dimension);
+                    if expected != 0 && actual != 0 && expected != actual {
+                        return Err(MemvidError::VecDimensionMismatch {
+                            expected,
+                            actual: actual as usize,
+                        });
+                    }
+                }
+                None => self.create_vec_space(
+                    &incoming.name,
+                    incoming.identity.clone(),
+                    incoming.index.compression_mode.clone(),
+                )?,
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy named-space embeddings of carried-over frames; existing embeddings are kept.
+    fn merge_vec_space_entries(
+        &mut self,
+        source: &Memvid,
+        frame_map: &HashMap<FrameId, FrameId>,
+    ) -> Result<u64> {
+        let mut copied = 0;
+        for (name, index) in &source.vec_space_indexes {
+            let existing: HashSet<FrameId> = self
+                .vec_space_indexes
+                .get(name)
+                .map(|index| index.entries().map(|(id, _)| id).collect())
+                .unwrap_or_default();
+            let entries: Vec<(FrameId, Vec<f32>)> = index
+                .entries()
+                .filter_map(|(frame_id, embedding)| {
+                    frame_map
+                        .get(&frame_id)
+                        .filter(|target| !existing.contains(target))
+                        .map(|&target| (target, embedding.to_vec()))
+                })
+                .collect();
+            if !entries.is_empty() {
+                copied += self.add_embeddings_to_space(name, entries)? as u64;
+            }
+        }
+        Ok(copied)
+    }
+
+    /// Append WAL inserts for one document and return the source → target frame ID mapping.
+    fn copy_unit(
+        &mut self,
+        source: &mut Memvid,
+        unit: &DocumentUnit,
+        supersedes: Option<FrameId>,
+        copy_embeddings: bool,
+        report: &mut MergeReport,
+    ) -> Result<Vec<(FrameId, FrameId)>> {
+        let mut mapping = Vec::with_capacity(1 + unit.children.len());
+        let mut parent_sequence = None;
+        for (position, id) in std::iter::once(unit.root)
+            .chain(unit.children.iter().copied())
+            .enumerate()
+        {
+            let frame = frame_at(&source.toc.frames, id)
+                .ok_or_else(|| missing_frame(id))?
+                .clone();
+            let payload = source.read_frame_payload_bytes(&frame)?;
+            let embedding = if copy_embeddings {
+                source
+                    .vec_index
+                    .as_ref()
+                    .and_then(|index| index.embedding_for(id))
+                    .map(<[f32]>::to_vec)
+            } else {
+                None
+            };
+            if embedding.is_some() {
+                report.embeddings_copied += 1;
+            }
+
+            let is_root = position == 0;
+            let mut entry = WalEntryData::insert_from_frame(frame, payload);
+            entry.embedding = embedding;
+            if is_root {
+                entry.supersedes_frame_id = supersedes;
+            } else {
+                entry.parent_sequence = parent_sequence;
+            }
+
+            let new_id = self.next_frame_id();
+            let sequence = self.append_frame_entry(entry)?;
+            if is_root {
+                parent_sequence = Some(sequence);
+            }
+            mapping.push((id, new_id));
+            report.frames_copied += 1;
+        }
+        Ok(mapping)
+    }
+
+    /// Retire the chunks and extracted images of a document that is being replaced.
+    fn tombstone_children_of(&mut self, parent: FrameId) -> Result<()> {
+        let children: Vec<Frame> = self
+            .toc
+            .frames
+            .iter()
+            .filter(|frame| frame.status == FrameStatus::Active && frame.parent_id == Some(parent))
+            .cloned()
+            .collect();
+        for frame in children {
+            self.append_frame_entry(WalEntryData::tombstone_for(&frame))?;
+        }
+        Ok(())
+    }
+
+    fn merge_clip_entries(
+        &mut self,
+        source: &Memvid,
+        frame_map: &HashMap<FrameId, FrameId>,
+    ) -> Result<u64> {
+        let entries: Vec<(FrameId, Option<u32>, Vec<f32>)> = source
+            .clip_index
+            .as_ref()
+            .map(|index| {
+                index
+                    .entries()
+                    .filter_map(|(frame_id, page, embedding)| {
+                        frame_map
+                            .get(&frame_id)
+                            .map(|&target| (target, page, embedding.to_vec()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if entries.is_empty() {
+            return Ok(0);
+        }
+
+        if !self.clip_enabled {
+            self.enable_clip()?;
+        }
+        self.ensure_clip_index()?;
+        let existing: HashSet<(FrameId, Option<u32>)> = self
+            .clip_index
+            .as_ref()
+            .map(|index| index.entries().map(|(id, page, _)| (id, page)).collect())
+            .unwrap_or_default();
+
+        let mut copied = 0;
+        for (frame_id, page, embedding) in entries {
+            if existing.contains(&(frame_id, page)) {
+                continue;
+            }
+            self.add_clip_embedding_with_page(frame_id, page, embedding)?;
+            copied += 1;
+        }
+        Ok(copied)
+    }
+
+    fn merge_memory_cards(
+        &mut self,
+        source: &Memvid,
+        frame_map: &HashMap<FrameId, FrameId>,
+        options: MergeOptions,
+        report: &mut MergeReport,
+    ) -> Result<()> {
+        let card_key = |card: &MemoryCard| {
+            (
+                card.kind,
+                card.entity.clone(),
+                card.slot.clone(),
+                card.value.clone(),
+                card.source_frame_id,
+            )
+        };
+        let existing: HashSet<_> = self.memories_track.cards().iter().map(card_key).collect();
+
+        let mut cards = Vec::new();
+        for card in source.memories_track.cards() {
+            let Some(&target) = frame_map.get(&card.source_frame_id) else {
+                report.cards_skipped += 1;
+                continue;
+            };
+            let mut card = card.clone();
+            card.source_frame_id = target;
+            if options.dedup_by_checksum && existing.contains(&card_key(&card)) {
+                continue;
+            }
+            cards.push(card);
+        }
+
+        report.cards_copied = cards.len() as u64;
+        if !cards.is_empty() {
+            self.put_memory_cards(cards)?;
+        }
+        Ok(())
+    }
+
+    fn merge_logic_mesh(
+        &mut self,
+        source: &Memvid,
+        frame_map: &HashMap<FrameId, FrameId>,
+        report: &mut MergeReport,
+    ) {
+        if source.logic_mesh.is_empty() {
+            return;
+        }
+
+        // Node IDs are derived from name + kind, but map explicitly in case the target already
+        // holds the entity under a different ID.
+        let mut node_map: HashMap<u64, u64> = HashMap::new();
+        for node in &source.logic_mesh.nodes {
+            let source_id = node.id;
+            let mut node = node.clone();
+            let had_refs = !node.frame_ids.is_empty();
+            node.frame_ids = node
+                .frame_ids
+                .iter()
+                .filter_map(|id| frame_map.get(id).copied())
+                .collect();
+            node.mentions = node
+                .mentions
+                .iter()
+                .filter_map(|&(id, start, len)| frame_map.get(&id).map(|&t| (t, start, len)))
+                .collect();
+            if had_refs && node.frame_ids.is_empty() {
+                continue;
+            }
+            if let Some(merged_id) = self.merge_mesh_node(node) {
+                node_map.insert(source_id, merged_id);
+            }
+            report.mesh_nodes_merged += 1;
+        }
+
+        for edge in &source.logic_mesh.edges {
+            let Some(&frame_id) = frame_map.get(&edge.frame_id) else {
+                continue;
+            };
+            let (Some(&from_node), Some(&to_node)) =
+                (node_map.get(&edge.from_node), node_map.get(&edge.to_node))
+            else {
+                continue;
+            };
+            let mut edge = edge.clone();
+            edge.from_node = from_node;
+            edge.to_node = to_node;
+            edge.frame_id = frame_id;
+            self.merge_mesh_edge(edge);
+            report.mesh_edges_merged += 1;
+        }
+
+        self.logic_mesh.finalize();
+        self.dirty = true;
+    }
+}
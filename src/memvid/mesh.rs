@@ -6,8 +6,8 @@
 
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{
-    EntityKind, FollowResult, FrameId, LogicMesh, LogicMeshStats, MeshEdge, MeshNode,
-    SearchHitEntity,
+    ChangeKind, EntityKind, FollowResult, FrameId, LinkType, LogicMesh, LogicMeshStats, MeshEdge,
+    MeshNode, MeshPath, MeshSubgraph, SearchHitEntity,
 };
 
 impl Memvid {
@@ -47,7 +47,7 @@ impl Memvid {
     /// * `node` - The mesh node to add
     pub fn add_mesh_node(&mut self, node: MeshNode) {
         self.dirty = true;
-        self.logic_mesh.merge_node(node);
+        self.merge_mesh_node(node);
     }
 
     /// Add multiple mesh nodes at once.
@@ -57,7 +57,7 @@ impl Memvid {
     pub fn add_mesh_nodes(&mut self, nodes: Vec<MeshNode>) {
         self.dirty = true;
         for node in nodes {
-            self.logic_mesh.merge_node(node);
+            self.merge_mesh_node(node);
         }
     }
 
@@ -69,7 +69,7 @@ impl Memvid {
     /// * `edge` - The mesh edge to add
     pub fn add_mesh_edge(&mut self, edge: MeshEdge) {
         self.dirty = true;
-        self.logic_mesh.merge_edge(edge);
+        self.merge_mesh_edge(edge);
     }
 
     /// Add multiple mesh edges at once.
@@ -79,10 +79,38 @@ impl Memvid {
     pub fn add_mesh_edges(&mut self, edges: Vec<MeshEdge>) {
         self.dirty = true;
         for edge in edges {
-            self.logic_mesh.merge_edge(edge);
+            self.merge_mesh_edge(edge);
         }
     }
 
+    /// Merge `node` into the mesh and log the change, returning the ID it was merged under.
+    pub(crate) fn merge_mesh_node(&mut self, node: MeshNode) -> Option<u64> {
+        let (canonical_name, kind) = (node.canonical_name.clone(), node.kind);
+        self.logic_mesh.merge_node(node);
+        let node_id = self
+            .logic_mesh
+            .nodes
+            .iter()
+            .find(|n| n.canonical_name == canonical_name && n.kind == kind)?
+            .id;
+        self.record_change(ChangeKind::MeshNodeMerged {
+            node_id,
+            canonical_name,
+            kind,
+        });
+        Some(node_id)
+    }
+
+    /// Merge `edge` into the mesh and log the change.
+    pub(crate) fn merge_mesh_edge(&mut self, edge: MeshEdge) {
+        self.record_change(ChangeKind::MeshEdgeMerged {
+            from_node: edge.from_node,
+            to_node: edge.to_node,
+            link: edge.link.clone(),
+        });
+        self.logic_mesh.merge_edge(edge);
+    }
+
     /// Follow relationships from an entity in the graph.
     ///
     /// Traverses the Logic-Mesh starting from the named entity,
@@ -99,6 +127,46 @@ impl Memvid {
         self.logic_mesh.follow(start, link, hops)
     }
 
+    /// Find the most confident chain of relationships between two entities.
+    ///
+    /// # Arguments
+    /// * `from` - The entity name to start from (case-insensitive)
+    /// * `to` - The entity name to reach (case-insensitive)
+    /// * `allowed_links` - Relationship types that may be walked; empty allows all
+    ///
+    /// # Returns
+    /// The path with the highest product of edge confidences, if the entities are connected.
+    /// Its edges cite the frames each relationship was detected in.
+    #[must_use]
+    pub fn shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        allowed_links: &[LinkType],
+    ) -> Option<MeshPath> {
+        self.logic_mesh.shortest_path(from, to, allowed_links)
+    }
+
+    /// Extract the neighborhood of an entity.
+    ///
+    /// # Arguments
+    /// * `start` - The entity name at the center (case-insensitive)
+    /// * `hops` - Maximum number of hops from the center
+    /// * `allowed_links` - Relationship types that may be walked; empty allows all
+    ///
+    /// # Returns
+    /// The entities and relationships within reach, weighted by edge confidence, if the
+    /// entity exists.
+    #[must_use]
+    pub fn neighborhood(
+        &self,
+        start: &str,
+        hops: usize,
+        allowed_links: &[LinkType],
+    ) -> Option<MeshSubgraph> {
+        self.logic_mesh.neighborhood(start, hops, allowed_links)
+    }
+
     /// Find an entity node by name.
     ///
     /// # Arguments
@@ -143,7 +211,8 @@ impl Memvid {
     /// Get statistics about the Logic-Mesh.
     ///
     /// # Returns
-    /// Statistics including node count, edge count, and breakdowns by kind/link type.
+    /// Statistics including node count, edge count, breakdowns by kind/link type,
+    /// connected components and the most central entities.
     pub fn logic_mesh_stats(&self) -> LogicMeshStats {
         self.logic_mesh.stats()
     }
@@ -2,30 +2,42 @@
 
 pub mod ask;
 pub mod audit;
+pub mod background_commit;
 #[cfg(feature = "parallel_segments")]
 pub mod builder;
+pub mod changes;
 pub mod chunks;
 pub mod doctor;
+#[cfg(feature = "encryption")]
+pub mod encrypted;
 pub mod enrichment;
 pub mod frame;
 mod helpers;
+pub mod jsonl;
 pub mod lifecycle;
 pub mod maintenance;
 pub mod memory;
+pub mod merge;
 pub mod mesh;
 pub mod mutation;
 #[cfg(feature = "parallel_segments")]
 pub mod planner;
+pub mod reader;
+pub mod refresh;
 #[cfg(feature = "replay")]
 pub mod replay_ops;
 pub mod search;
 mod segments;
+pub mod signing;
 pub mod sketch;
 pub mod ticket;
 pub mod timeline;
+pub mod transaction;
+pub mod vec_spaces;
 #[cfg(feature = "parallel_segments")]
 pub mod workers;
 
+pub use background_commit::BackgroundCommit;
 #[cfg(feature = "parallel_segments")]
 pub use builder::{BuildOpts, ParallelInput, ParallelPayload};
 pub use enrichment::{
@@ -34,4 +46,7 @@ pub use enrichment::{
 };
 pub use frame::BlobReader;
 pub use lifecycle::{LockSettings, Memvid, OpenReadOptions};
+pub use reader::MemvidReader;
+pub use refresh::RefreshReport;
 pub use sketch::{SketchCandidate, SketchSearchOptions, SketchSearchStats};
+pub use transaction::Transaction;
@@ -10,9 +10,12 @@
 use std::cmp::min;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs::{File, OpenOptions};
-use std::io::{Read, Seek, SeekFrom, Write};
+use std::io::{Cursor, Read, Seek, SeekFrom, Write};
 use std::path::Path;
-use std::sync::OnceLock;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, OnceLock};
+#[cfg(feature = "lex")]
+use std::sync::{PoisonError, RwLock};
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 
 use bincode::serde::{decode_from_slice, encode_to_vec};
@@ -39,26 +42,34 @@ use super::{
 #[cfg(feature = "temporal_track")]
 use crate::TemporalTrackManifest;
 use crate::analysis::auto_tag::AutoTagger;
-use crate::constants::{WAL_SIZE_LARGE, WAL_SIZE_MEDIUM};
+use crate::constants::{WAL_SIZE_LARGE, WAL_SIZE_MEDIUM, WAL_SIZE_TINY};
+use crate::enrich::{EnrichmentContext, EnrichmentEngine, RulesEngine};
 use crate::footer::CommitFooter;
+use crate::io::data_file::DataFile;
 use crate::io::wal::{EmbeddedWal, WalRecord};
+use crate::lock::FileLock;
 use crate::memvid::chunks::{plan_document_chunks, plan_text_chunks};
 use crate::memvid::lifecycle::{Memvid, prepare_toc_bytes};
+#[cfg(feature = "lex")]
+use crate::memvid::search::index_tantivy_docs;
+use crate::memvid::search::merge_vec_index;
+use crate::memvid::segments::TimeSegmentArtifact;
 use crate::reader::{
     DocumentFormat, DocumentReader, PassthroughReader, ReaderDiagnostics, ReaderHint, ReaderOutput,
     ReaderRegistry,
 };
 #[cfg(feature = "lex")]
-use crate::search::{EmbeddedLexSegment, LexWalBatch, TantivySnapshot};
+use crate::search::{EmbeddedLexSegment, LexWalBatch, TantivyEngine, TantivySnapshot};
 use crate::triplet::TripletExtractor;
 #[cfg(feature = "lex")]
 use crate::types::TantivySegmentDescriptor;
 use crate::types::{
-    CanonicalEncoding, DocMetadata, Frame, FrameId, FrameRole, FrameStatus, PutOptions,
-    SegmentCommon, TextChunkManifest, Tier,
+    CanonicalEncoding, ChangeKind, DocMetadata, Frame, FrameId, FrameRole, FrameStatus, MemoryCard,
+    PutManyOpts, PutOptions, PutRequest, SegmentCommon, TextChunkManifest, Tier, VacuumReport,
 };
 #[cfg(feature = "parallel_segments")]
 use crate::types::{IndexSegmentRef, SegmentKind, SegmentSpan, SegmentStats};
+use crate::vec_hnsw::HnswConfig;
 #[cfg(feature = "temporal_track")]
 use crate::{
     AnchorSource, TemporalAnchor, TemporalContext, TemporalMention, TemporalMentionFlags,
@@ -67,14 +78,16 @@ use crate::{
 };
 use crate::{
     DEFAULT_SEARCH_TEXT_LIMIT, ExtractedDocument, MemvidError, Result, TimeIndexEntry,
-    TimeIndexManifest, VecIndexManifest, normalize_text, time_index_append, wal_config,
+    TimeIndexManifest, VecIndex, VecIndexManifest, normalize_text, time_index_append, wal_config,
 };
 #[cfg(feature = "temporal_track")]
 use time::{Date, Month, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
 
 const MAGIC_SNIFF_BYTES: usize = 16;
-const WAL_ENTRY_HEADER_SIZE: u64 = 48;
 const WAL_SHIFT_BUFFER_SIZE: usize = 8 * 1024 * 1024;
+/// Smallest factor the embedded WAL region grows by; see `Memvid::grow_wal_region`.
+const WAL_GROWTH_FACTOR: u64 = 2;
+const DEFAULT_PAYLOAD_COMPRESSION_LEVEL: i32 = 3;
 
 #[cfg(feature = "temporal_track")]
 const DEFAULT_TEMPORAL_TZ: &str = "America/Chicago";
@@ -145,6 +158,21 @@ impl CommitStaging {
         Ok(())
     }
 
+    /// Seeds the staging image with only the first `len` bytes of `source` (header + WAL),
+    /// leaving the remainder to be written by the caller.
+    fn copy_prefix_from(&mut self, source: &File, len: u64) -> Result<()> {
+        let mut reader = source.try_clone()?;
+        reader.seek(SeekFrom::Start(0))?;
+
+        let writer = self.atomic.as_file_mut();
+        writer.set_len(0)?;
+        writer.seek(SeekFrom::Start(0))?;
+        std::io::copy(&mut reader.take(len), writer)?;
+        writer.flush()?;
+        writer.sync_all()?;
+        Ok(())
+    }
+
     fn clone_file(&self) -> Result<File> {
         Ok(self.atomic.as_file().try_clone()?)
     }
@@ -158,11 +186,59 @@ impl CommitStaging {
     }
 }
 
+/// A background commit, from the WAL records it snapshotted until it is staged.
+///
+/// `mem` is a detached copy of the handle's state over the same file, with its own copies
+/// of the in-memory indexes and tracks. [`PendingCommit::stage`] moves it onto a staging
+/// copy of the file and commits there, without touching the handle.
+pub(crate) struct PendingCommit {
+    mem: Memvid,
+    records: Vec<WalRecord>,
+    mode: CommitMode,
+    /// Whether the handle's Tantivy engine held changes the file lacks, so the lex index
+    /// is rebuilt from the frames instead of reopened from the file.
+    rebuild_lex: bool,
+}
+
+impl PendingCommit {
+    /// Copy the file to a staging image and commit the snapshotted records into it.
+    ///
+    /// Runs off the handle's thread: the handle rejects writes until the commit publishes,
+    /// so the file does not change while it is copied.
+    pub(crate) fn stage(self) -> Result<StagedCommit> {
+        let Self {
+            mut mem,
+            records,
+            mode,
+            rebuild_lex,
+        } = self;
+        let mut staging = CommitStaging::prepare(mem.path())?;
+        staging.copy_from(mem.file.raw())?;
+        mem.file = mem.file.with_staging_file(staging.clone_file()?);
+        mem.wal = EmbeddedWal::open_data_file(&mem.file, &mem.header)?;
+        match mem.commit_staged_records(records, mode, rebuild_lex) {
+            Ok(()) => Ok(StagedCommit { staging, mem }),
+            Err(err) => {
+                let _ = staging.discard();
+                Err(err)
+            }
+        }
+    }
+}
+
+/// A background commit written to a staging copy of the file, ready to publish.
+pub(crate) struct StagedCommit {
+    staging: CommitStaging,
+    /// The committed state over the staging copy.
+    mem: Memvid,
+}
+
 #[derive(Debug, Default)]
 struct IngestionDelta {
     inserted_frames: Vec<FrameId>,
     inserted_embeddings: Vec<(FrameId, Vec<f32>)>,
     inserted_time_entries: Vec<TimeIndexEntry>,
+    inserted_cards: usize,
     mutated_frames: bool,
     #[cfg(feature = "temporal_track")]
     inserted_temporal_mentions: Vec<TemporalMention>,
@@ -176,6 +252,7 @@ impl IngestionDelta {
         let mut empty = self.inserted_frames.is_empty()
             && self.inserted_embeddings.is_empty()
             && self.inserted_time_entries.is_empty()
+            && self.inserted_cards == 0
             && !self.mutated_frames;
         #[cfg(feature = "temporal_track")]
         {
@@ -202,6 +279,8 @@ impl Default for CommitMode {
 #[derive(Clone, Copy, Debug, Default)]
 pub struct CommitOptions {
     pub mode: CommitMode,
+    /// Build the indexes on a worker thread. [`Memvid::commit_in_background`] then returns
+    /// while they are built; [`Memvid::commit_with_options`] still waits for the commit.
     pub background: bool,
 }
 
@@ -219,6 +298,83 @@ impl CommitOptions {
     }
 }
 
+/// Inputs of an index rebuild, detached from the handle.
+struct IndexBuild {
+    vec: Option<VecIndexBuild>,
+    time_entries: Vec<TimeIndexEntry>,
+    #[cfg(feature = "lex")]
+    lex: Option<LexIndexBuild>,
+}
+
+struct VecIndexBuild {
+    existing: Option<VecIndex>,
+    new_docs: Vec<(FrameId, Vec<f32>)>,
+    /// Whether each frame, indexed by id, is still active.
+    active: Vec<bool>,
+    hnsw_config: HnswConfig,
+}
+
+#[cfg(feature = "lex")]
+struct LexIndexBuild {
+    engine: TantivyEngine,
+    /// `(frame, text)` documents to replace the engine's contents with, or `None` to keep
+    /// them.
+    docs: Option<Vec<(Frame, String)>>,
+}
+
+/// Indexes built by [`IndexBuild::run`], ready for [`Memvid::write_rebuilt_indexes`].
+struct BuiltIndexes {
+    vec: Option<VecIndex>,
+    time_index: TimeSegmentArtifact,
+    #[cfg(feature = "lex")]
+    tantivy: Option<TantivyEngine>,
+}
+
+impl IndexBuild {
+    /// Build the vector, time and lex indexes without touching the file.
+    fn run(self) -> Result<BuiltIndexes> {
+        let vec = self
+            .vec
+            .map(|build| {
+                let active = build.active;
+                merge_vec_index(
+                    build.existing,
+                    &build.new_docs,
+                    build.hnsw_config,
+                    |frame_id| active.get(frame_id as usize).copied().unwrap_or(false),
+                )
+            })
+            .transpose()?;
+
+        let mut time_entries = self.time_entries;
+        let mut cursor = Cursor::new(Vec::new());
+        let (_, _, checksum) = time_index_append(&mut cursor, &mut time_entries)?;
+        let time_index = TimeSegmentArtifact {
+            bytes: cursor.into_inner(),
+            entry_count: time_entries.len() as u64,
+            checksum,
+        };
+
+        #[cfg(feature = "lex")]
+        let tantivy = self
+            .lex
+            .map(|mut build| {
+                if let Some(docs) = build.docs {
+                    index_tantivy_docs(&mut build.engine, &docs)?;
+                }
+                Ok::<_, MemvidError>(build.engine)
+            })
+            .transpose()?;
+
+        Ok(BuiltIndexes {
+            vec,
+            time_index,
+            #[cfg(feature = "lex")]
+            tantivy,
+        })
+    }
+}
+
 fn default_reader_registry() -> &'static ReaderRegistry {
     static REGISTRY: OnceLock<ReaderRegistry> = OnceLock::new();
     REGISTRY.get_or_init(ReaderRegistry::default)
@@ -411,15 +567,36 @@ impl Memvid {
     // -- Public ingestion entrypoints ---------------------------------------------------------
 
     fn with_staging_lock<F>(&mut self, op: F) -> Result<()>
+    where
+        F: FnOnce(&mut Self) -> Result<()>,
+    {
+        self.with_staging_image(None, op)
+    }
+
+    /// Runs `op` against a sibling staging copy of the file and atomically swaps it in.
+    ///
+    /// With `seed_len` set, only that many leading bytes are copied into the staging image,
+    /// which lets vacuum write a compacted data region instead of cloning the whole file.
+    fn with_staging_image<F>(&mut self, seed_len: Option<u64>, op: F) -> Result<()>
     where
         F: FnOnce(&mut Self) -> Result<()>,
     {
         self.file.sync_all()?;
         let mut staging = CommitStaging::prepare(self.path())?;
-        staging.copy_from(&self.file)?;
+        match seed_len {
+            Some(len) => {
+                let physical = self.file.physical_prefix_len(len)?;
+                staging.copy_prefix_from(self.file.raw(), physical)?;
+            }
+            None => staging.copy_from(self.file.raw())?,
+        }
 
-        let staging_handle = staging.clone_file()?;
-        let new_wal = EmbeddedWal::open(&staging_handle, &self.header)?;
+        // Stored bytes are copied verbatim, so an encrypted image keeps its key envelope.
+        let staging_handle = self.file.with_staging_file(staging.clone_file()?);
+        if let Some(len) = seed_len {
+            staging_handle.set_len(len)?;
+        }
+        let new_wal = EmbeddedWal::open_data_file(&staging_handle, &self.header)?;
         let original_file = std::mem::replace(&mut self.file, staging_handle);
         let original_wal = std::mem::replace(&mut self.wal, new_wal);
         let original_header = self.header.clone();
@@ -427,6 +604,8 @@ impl Memvid {
         let original_data_end = self.data_end;
         let original_generation = self.generation;
         let original_dirty = self.dirty;
+        // Publishing drains these into the TOC, which a failed commit restores.
+        let original_pending_changes = self.pending_changes.clone();
         #[cfg(feature = "lex")]
         let original_tantivy_dirty = self.tantivy_dirty;
 
@@ -434,18 +613,23 @@ impl Memvid {
         let mut original_file = Some(original_file);
         let mut original_wal = Some(original_wal);
 
-        match op(self) {
+        self.staging_active = true;
+        let outcome = op(self);
+        self.staging_active = false;
+        match outcome {
             Ok(()) => {
                 self.file.sync_all()?;
                 match staging.commit() {
                     Ok(()) => {
                         drop(original_file.take());
                         drop(original_wal.take());
-                        self.file = OpenOptions::new()
-                            .read(true)
-                            .write(true)
-                            .open(&destination_path)?;
-                        self.wal = EmbeddedWal::open(&self.file, &self.header)?;
+                        self.file = self.file.with_file(
+                            OpenOptions::new()
+                                .read(true)
+                                .write(true)
+                                .open(&destination_path)?,
+                        );
+                        self.wal = EmbeddedWal::open_data_file(&self.file, &self.header)?;
                         Ok(())
                     }
                     Err(commit_err) => {
@@ -460,6 +644,7 @@ impl Memvid {
                         self.data_end = original_data_end;
                         self.generation = original_generation;
                         self.dirty = original_dirty;
+                        self.pending_changes = original_pending_changes;
                         #[cfg(feature = "lex")]
                         {
                             self.tantivy_dirty = original_tantivy_dirty;
@@ -481,6 +666,7 @@ impl Memvid {
                 self.data_end = original_data_end;
                 self.generation = original_generation;
                 self.dirty = original_dirty;
+                self.pending_changes = original_pending_changes;
                 #[cfg(feature = "lex")]
                 {
                     self.tantivy_dirty = original_tantivy_dirty;
@@ -490,6 +676,179 @@ impl Memvid {
         }
     }
 
+    /// Snapshot the pending WAL records and the handle's state for a background commit.
+    ///
+    /// Returns `None` when there is nothing to commit. Otherwise the handle keeps serving
+    /// the previous generation and rejects writes until the commit publishes or is dropped.
+    pub(crate) fn begin_background_commit(
+        &mut self,
+        mode: CommitMode,
+    ) -> Result<Option<PendingCommit>> {
+        self.ensure_writable()?;
+        let records = self.wal.pending_records()?;
+        if records.is_empty() && !self.dirty && !self.tantivy_index_pending() {
+            return Ok(None);
+        }
+
+        self.file.sync_all()?;
+        let rebuild_lex = self.tantivy_index_pending();
+        let mem = self.fork_for_commit()?;
+        self.background_commit_active.store(true, Ordering::Release);
+        Ok(Some(PendingCommit {
+            mem,
+            records,
+            mode: if rebuild_lex { CommitMode::Full } else { mode },
+            rebuild_lex,
+        }))
+    }
+
+    /// Copy the state a commit changes into a detached handle over the same file.
+    ///
+    /// The copy holds no lock on the file and rejects writes, so dropping it never commits.
+    ///
+    /// The Tantivy engine is left out; [`Memvid::commit_staged_records`] reopens it from
+    /// the staging copy.
+    fn fork_for_commit(&self) -> Result<Memvid> {
+        let file = self.file.with_file(self.file.raw().try_clone()?);
+        let lock = FileLock::unlocked(file.raw())?;
+        let wal = EmbeddedWal::open_data_file_read_only(&file, &self.header)?;
+        #[cfg(feature = "lex")]
+        let lex_storage = self
+            .lex_storage
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        Ok(Memvid {
+            file,
+            path: self.path.clone(),
+            lock,
+            read_only: false,
+            header: self.header.clone(),
+            toc: self.toc.clone(),
+            wal,
+            pending_frame_inserts: self.pending_frame_inserts,
+            data_end: self.data_end,
+            generation: self.generation,
+            lock_settings: self.lock_settings.clone(),
+            lex_enabled: self.lex_enabled,
+            lex_index: self.lex_index.clone(),
+            #[cfg(feature = "lex")]
+            lex_storage: Arc::new(RwLock::new(lex_storage)),
+            vec_enabled: self.vec_enabled,
+            vec_compression: self.vec_compression.clone(),
+            hnsw_config: self.hnsw_config,
+            vec_index: self.vec_index.clone(),
+            vec_space_indexes: self.vec_space_indexes.clone(),
+            dirty_vec_spaces: self.dirty_vec_spaces.clone(),
+            put_batch: None,
+            transaction_open: false,
+            unterminated_transaction: None,
+            // The copy only ever works on a staging image that is swapped in as a whole.
+            staging_active: true,
+            // Rejects writes, so dropping the copy never commits it.
+            background_commit_active: Arc::new(AtomicBool::new(true)),
+            clip_enabled: self.clip_enabled,
+            clip_index: self.clip_index.clone(),
+            dirty: self.dirty,
+            pending_changes: self.pending_changes.clone(),
+            change_log_retention: self.change_log_retention,
+            #[cfg(feature = "lex")]
+            tantivy: None,
+            #[cfg(feature = "lex")]
+            tantivy_dirty: false,
+            #[cfg(feature = "temporal_track")]
+            temporal_track: None,
+            #[cfg(feature = "parallel_segments")]
+            manifest_wal: None,
+            memories_track: self.memories_track.clone(),
+            logic_mesh: self.logic_mesh.clone(),
+            sketch_track: self.sketch_track.clone(),
+            schema_registry: self.schema_registry.clone(),
+            schema_strict: self.schema_strict,
+            #[cfg(feature = "replay")]
+            active_session: None,
+            #[cfg(feature = "replay")]
+            completed_sessions: Vec::new(),
+        })
+    }
+
+    /// Commit `records` into the staging copy this handle was moved onto.
+    fn commit_staged_records(
+        &mut self,
+        records: Vec<WalRecord>,
+        mode: CommitMode,
+        rebuild_lex: bool,
+    ) -> Result<()> {
+        #[cfg(feature = "lex")]
+        if self.lex_enabled {
+            if rebuild_lex {
+                self.tantivy = Some(self.create_tantivy_engine()?);
+            } else {
+                self.init_tantivy()?;
+            }
+        }
+        self.commit_from_records(records, mode, rebuild_lex)
+    }
+
+    /// Atomically swap the staging copy of `staged` in and adopt its state.
+    ///
+    /// If the swap fails, the handle stays at the previous generation with the WAL records
+    /// still pending.
+    pub(crate) fn publish_background_commit(&mut self, staged: StagedCommit) -> Result<()> {
+        let StagedCommit { staging, mut mem } = staged;
+        staging.commit()?;
+        self.swap_commit_state(&mut mem);
+        drop(mem);
+
+        let destination_path = self.path().to_path_buf();
+        self.file = self.file.with_file(
+            OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(&destination_path)?,
+        );
+        self.wal = EmbeddedWal::open_data_file(&self.file, &self.header)?;
+        #[cfg(feature = "parallel_segments")]
+        if let Some(wal) = self.manifest_wal.as_mut() {
+            wal.flush()?;
+            wal.truncate()?;
+        }
+        Ok(())
+    }
+
+    /// Exchange the state a commit changes with `other`.
+    fn swap_commit_state(&mut self, other: &mut Memvid) {
+        std::mem::swap(&mut self.file, &mut other.file);
+        std::mem::swap(&mut self.wal, &mut other.wal);
+        std::mem::swap(&mut self.header, &mut other.header);
+        std::mem::swap(&mut self.toc, &mut other.toc);
+        std::mem::swap(&mut self.data_end, &mut other.data_end);
+        std::mem::swap(&mut self.generation, &mut other.generation);
+        std::mem::swap(&mut self.dirty, &mut other.dirty);
+        std::mem::swap(
+            &mut self.pending_frame_inserts,
+            &mut other.pending_frame_inserts,
+        );
+        std::mem::swap(&mut self.pending_changes, &mut other.pending_changes);
+        std::mem::swap(&mut self.lex_enabled, &mut other.lex_enabled);
+        std::mem::swap(&mut self.lex_index, &mut other.lex_index);
+        std::mem::swap(&mut self.vec_index, &mut other.vec_index);
+        std::mem::swap(&mut self.vec_space_indexes, &mut other.vec_space_indexes);
+        std::mem::swap(&mut self.dirty_vec_spaces, &mut other.dirty_vec_spaces);
+        std::mem::swap(&mut self.clip_index, &mut other.clip_index);
+        std::mem::swap(&mut self.memories_track, &mut other.memories_track);
+        std::mem::swap(&mut self.logic_mesh, &mut other.logic_mesh);
+        std::mem::swap(&mut self.sketch_track, &mut other.sketch_track);
+        #[cfg(feature = "lex")]
+        {
+            std::mem::swap(&mut self.tantivy, &mut other.tantivy);
+            std::mem::swap(&mut self.tantivy_dirty, &mut other.tantivy_dirty);
+            std::mem::swap(&mut self.lex_storage, &mut other.lex_storage);
+        }
+        #[cfg(feature = "temporal_track")]
+        std::mem::swap(&mut self.temporal_track, &mut other.temporal_track);
+    }
+
     pub(crate) fn catalog_data_end(&self) -> u64 {
         let mut max_end = self.header.wal_offset + self.header.wal_size;
 
@@ -558,7 +917,7 @@ impl Memvid {
         max_end
     }
 
-    fn payload_region_end(&self) -> u64 {
+    pub(crate) fn payload_region_end(&self) -> u64 {
         let wal_region_end = self.header.wal_offset + self.header.wal_size;
         let frames_with_payload: Vec<_> = self
             .toc
@@ -592,14 +951,46 @@ impl Memvid {
                     Some(end) => max_end.max(end),
                     None => max_end,
                 }
-            });
+            })
+            .max(self.vec_segments_end());
 
         tracing::info!("payload_region_end: returning {}", result);
         result
     }
 
-    fn append_wal_entry(&mut self, payload: &[u8]) -> Result<u64> {
+    /// End of the vector index data kept in the payload region, or 0 without any.
+    ///
+    /// The segment chain of the resident HNSW graph and the vector spaces are written to the
+    /// payload region, so new payloads and the rebuilt indexes must start after them.
+    fn vec_segments_end(&self) -> u64 {
+        let chain_end = match (&self.vec_index, &self.toc.indexes.vec) {
+            (Some(VecIndex::Hnsw(graph)), Some(manifest)) if graph.is_chained() => {
+                manifest.bytes_offset.saturating_add(manifest.bytes_length)
+            }
+            _ => 0,
+        };
+        self.toc
+            .indexes
+            .vec_spaces
+            .iter()
+            .filter(|space| space.index.bytes_length != 0)
+            .map(|space| {
+                space
+                    .index
+                    .bytes_offset
+                    .saturating_add(space.index.bytes_length)
+            })
+            .fold(chain_end, u64::max)
+    }
+
+    pub(crate) fn append_wal_entry(&mut self, payload: &[u8]) -> Result<u64> {
+        if !self.transaction_open {
+            self.terminate_open_transaction()?;
+        }
         loop {
+            // A `put_many` batch syncs once at the end instead of after every record; the WAL
+            // is reopened by a growth, so the setting is applied on every attempt.
+            self.wal.set_sync_on_append(self.put_batch.is_none());
             match self.wal.append_entry(payload) {
                 Ok(seq) => return Ok(seq),
                 Err(MemvidError::CheckpointFailed { reason })
@@ -608,9 +999,7 @@ impl Memvid {
                 {
                     // WAL is either too small for this entry or full with pending entries.
                     // Grow the WAL to accommodate - doubling ensures we have space.
-                    let required = WAL_ENTRY_HEADER_SIZE
-                        .saturating_add(payload.len() as u64)
-                        .max(self.header.wal_size + 1);
+                    let required = self.wal.required_region_size(payload.len());
                     self.grow_wal_region(required)?;
                 }
                 Err(err) => return Err(err),
@@ -618,24 +1007,74 @@ impl Memvid {
         }
     }
 
-    fn grow_wal_region(&mut self, required_entry_size: u64) -> Result<()> {
-        let mut new_size = self.header.wal_size;
-        let mut target = required_entry_size;
-        if target == 0 {
-            target = self.header.wal_size;
+    /// Whether the WAL is full enough to checkpoint and nothing holds checkpoints back.
+    ///
+    /// Open transactions and `put_many` batches with `disable_auto_checkpoint` defer the
+    /// checkpoint to the caller.
+    pub(crate) fn auto_checkpoint_due(&self) -> bool {
+        if self.transaction_open {
+            return false;
+        }
+        if self
+            .put_batch
+            .as_ref()
+            .is_some_and(|batch| batch.disable_auto_checkpoint)
+        {
+            return false;
         }
-        while new_size <= target {
+        self.wal.should_checkpoint()
+    }
+
+    /// Append a frame operation to the WAL, tracking pending inserts for frame ID allocation.
+    pub(crate) fn append_frame_entry(&mut self, entry: WalEntryData) -> Result<u64> {
+        let is_insert = entry.op == FrameWalOp::Insert;
+        let bytes = encode_to_vec(WalEntry::Frame(Box::new(entry)), wal_config())?;
+        let seq = self.append_wal_entry(&bytes)?;
+        if is_insert {
+            self.pending_frame_inserts = self.pending_frame_inserts.saturating_add(1);
+        }
+        self.dirty = true;
+        Ok(seq)
+    }
+
+    /// Grow the embedded WAL region to at least `required_size` bytes.
+    ///
+    /// The region grows by at least [`WAL_GROWTH_FACTOR`], starting from `WAL_SIZE_TINY`,
+    /// and keeps doubling until `required_size` fits. Everything after the region is shifted
+    /// by the growth, which is done on a staging copy of the file: a crash mid-growth leaves
+    /// the previous file, WAL records included, intact. Each growth therefore reads and
+    /// writes the whole file and briefly needs room for a second copy on disk; growing
+    /// geometrically keeps that cost amortised over the appends in between.
+    fn grow_wal_region(&mut self, required_size: u64) -> Result<()> {
+        let overflow = || MemvidError::CheckpointFailed {
+            reason: "wal_size overflow".into(),
+        };
+        let mut new_size = self
+            .header
+            .wal_size
+            .checked_mul(WAL_GROWTH_FACTOR)
+            .ok_or_else(overflow)?
+            .max(WAL_SIZE_TINY);
+        while new_size < required_size {
             new_size = new_size
-                .checked_mul(2)
-                .ok_or_else(|| MemvidError::CheckpointFailed {
-                    reason: "wal_size overflow".into(),
-                })?;
+                .checked_mul(WAL_GROWTH_FACTOR)
+                .ok_or_else(overflow)?;
         }
         let delta = new_size - self.header.wal_size;
-        if delta == 0 {
-            return Ok(());
+
+        tracing::debug!(
+            wal.size = self.header.wal_size,
+            wal.new_size = new_size,
+            "growing embedded wal region"
+        );
+        if self.staging_active {
+            // Already working on a staging copy that is swapped in as a whole.
+            return self.relocate_for_wal_growth(new_size, delta);
         }
+        self.with_staging_lock(move |mem| mem.relocate_for_wal_growth(new_size, delta))
+    }
 
+    fn relocate_for_wal_growth(&mut self, new_size: u64, delta: u64) -> Result<()> {
         self.shift_data_for_wal_growth(delta)?;
         self.header.wal_size = new_size;
         self.header.footer_offset = self.header.footer_offset.saturating_add(delta);
@@ -650,8 +1089,7 @@ impl Memvid {
         self.rewrite_toc_footer()?;
         self.header.toc_checksum = self.toc.toc_checksum;
         crate::persist_header(&mut self.file, &self.header)?;
-        self.file.sync_all()?;
-        self.wal = EmbeddedWal::open(&self.file, &self.header)?;
+        self.wal = EmbeddedWal::open_data_file(&self.file, &self.header)?;
         Ok(())
     }
 
@@ -659,7 +1097,7 @@ impl Memvid {
         if delta == 0 {
             return Ok(());
         }
-        let original_len = self.file.metadata()?.len();
+        let original_len = self.file.len()?;
         let data_start = self.header.wal_offset + self.header.wal_size;
         self.file.set_len(original_len + delta)?;
 
@@ -719,6 +1157,11 @@ impl Memvid {
                 vec.bytes_offset += delta;
             }
         }
+        for space in &mut self.toc.indexes.vec_spaces {
+            if space.index.bytes_offset != 0 {
+                space.index.bytes_offset += delta;
+            }
+        }
         if let Some(time_index) = self.toc.time_index.as_mut() {
             if time_index.bytes_offset != 0 {
                 time_index.bytes_offset += delta;
@@ -767,14 +1210,14 @@ impl Memvid {
     pub fn commit_with_options(&mut self, options: CommitOptions) -> Result<()> {
         self.ensure_writable()?;
         if options.background {
-            tracing::debug!("commit background flag ignored; running synchronously");
+            return self.commit_in_background(options)?.wait(self);
         }
         let mode = options.mode;
         let records = self.wal.pending_records()?;
         if records.is_empty() && !self.dirty && !self.tantivy_index_pending() {
             return Ok(());
         }
-        self.with_staging_lock(move |mem| mem.commit_from_records(records, mode))
+        self.with_staging_lock(move |mem| mem.commit_from_records(records, mode, false))
     }
 
     pub fn commit(&mut self) -> Result<()> {
@@ -782,7 +1225,13 @@ impl Memvid {
         self.commit_with_options(CommitOptions::new(CommitMode::Full))
     }
 
-    fn commit_from_records(&mut self, records: Vec<WalRecord>, _mode: CommitMode) -> Result<()> {
+    /// Apply `records` and rebuild the indexes they change, or all of them with `rebuild_lex`.
+    fn commit_from_records(
+        &mut self,
+        records: Vec<WalRecord>,
+        mode: CommitMode,
+        rebuild_lex: bool,
+    ) -> Result<()> {
         self.generation = self.generation.wrapping_add(1);
 
         let delta = self.apply_records(records)?;
@@ -794,18 +1243,28 @@ impl Memvid {
             .as_ref()
             .map_or(false, |idx| !idx.is_empty());
 
-        if !delta.is_empty() || clip_needs_persist {
+        // Vector spaces are written into the payload region, which only a rebuild extends.
+        let vec_spaces_pending = self.vec_spaces_pending();
+
+        if rebuild_lex || !delta.is_empty() || clip_needs_persist || vec_spaces_pending {
             tracing::debug!(
                 inserted_frames = delta.inserted_frames.len(),
                 inserted_embeddings = delta.inserted_embeddings.len(),
                 inserted_time_entries = delta.inserted_time_entries.len(),
                 clip_needs_persist = clip_needs_persist,
+                vec_spaces_pending = vec_spaces_pending,
+                rebuild_lex = rebuild_lex,
                 "commit applied delta"
             );
-            self.rebuild_indexes(&delta.inserted_embeddings)?;
+            self.rebuild_indexes_with_mode(&delta.inserted_embeddings, mode)?;
             indexes_rebuilt = true;
         }
+        self.finish_commit(indexes_rebuilt)
+    }
 
+    /// Persist the tracks a commit carries alongside its indexes, then publish its changes
+    /// and checkpoint the WAL behind a new TOC and header.
+    fn finish_commit(&mut self, indexes_rebuilt: bool) -> Result<()> {
         if !indexes_rebuilt && self.tantivy_index_pending() {
             self.flush_tantivy()?;
         }
@@ -837,6 +1296,7 @@ impl Memvid {
         // flush_tantivy() and rebuild_indexes() have already set footer_offset correctly.
         // DO NOT overwrite it with catalog_data_end() as that would include orphaned segments.
 
+        self.publish_changes();
         self.rewrite_toc_footer()?;
         self.header.toc_checksum = self.toc.toc_checksum;
         self.wal.record_checkpoint(&mut self.header)?;
@@ -1031,6 +1491,11 @@ impl Memvid {
             self.persist_logic_mesh()?;
         }
 
+        // Persist named vector spaces if they weren't already written by rebuild_indexes
+        if !indexes_rebuilt {
+            self.persist_vec_spaces()?;
+        }
+
         // Persist sketch track if it has entries
         if !self.sketch_track.is_empty() {
             self.persist_sketch_track()?;
@@ -1038,6 +1503,7 @@ impl Memvid {
 
         // flush_tantivy() has already set footer_offset correctly
         // DO NOT overwrite with catalog_data_end()
+        self.publish_changes();
         self.rewrite_toc_footer()?;
         self.header.toc_checksum = self.toc.toc_checksum;
         self.wal.record_checkpoint(&mut self.header)?;
@@ -1062,7 +1528,7 @@ impl Memvid {
             return Ok(());
         }
         let delta = self.apply_records(records)?;
-        if !delta.is_empty() {
+        if !delta.is_empty() || self.vec_spaces_pending() {
             tracing::debug!(
                 inserted_frames = delta.inserted_frames.len(),
                 inserted_embeddings = delta.inserted_embeddings.len(),
@@ -1070,6 +1536,13 @@ impl Memvid {
                 "recover applied delta"
             );
             self.rebuild_indexes(&delta.inserted_embeddings)?;
+            // The rebuild may have overwritten the sketch track; it is written after the
+            // other indexes, like on commit.
+            if !self.sketch_track.is_empty() {
+                self.persist_sketch_track()?;
+                self.rewrite_toc_footer()?;
+                self.header.toc_checksum = self.toc.toc_checksum;
+            }
         } else if self.tantivy_index_pending() {
             self.flush_tantivy()?;
         }
@@ -1102,14 +1575,73 @@ impl Memvid {
 
         if !records.is_empty() {
             self.file.seek(SeekFrom::Start(data_cursor))?;
-            for record in records {
-                let mut entry = match decode_wal_entry(&record.payload)? {
-                    WalEntry::Frame(entry) => entry,
+            for (sequence, entry) in committed_wal_entries(records)? {
+                let mut entry = match entry {
+                    WalEntry::Frame(entry) => *entry,
                     #[cfg(feature = "lex")]
                     WalEntry::Lex(batch) => {
                         self.apply_lex_wal(batch)?;
                         continue;
                     }
+                    WalEntry::MemoryCards(bytes) => {
+                        let cards: Vec<MemoryCard> =
+                            serde_json::from_slice(&bytes).map_err(|err| {
+                                MemvidError::CheckpointFailed {
+                                    reason: format!(
+                                        "invalid memory cards in WAL record {sequence}: {err}"
+                                    ),
+                                }
+                            })?;
+                        delta.inserted_cards += cards.len();
+                        let card_ids = self.memories_track.add_cards(cards);
+                        self.record_wal_changes(
+                            sequence,
+                            card_ids
+                                .into_iter()
+                                .map(|card_id| ChangeKind::MemoryCardAdded { card_id }),
+                        );
+                        continue;
+                    }
+                    WalEntry::EnrichedCards {
+                        frame_id,
+                        engine_kind,
+                        engine_version,
+                        cards,
+                    } => {
+                        let cards: Vec<MemoryCard> =
+                            serde_json::from_slice(&cards).map_err(|err| {
+                                MemvidError::CheckpointFailed {
+                                    reason: format!(
+                                        "invalid memory cards in WAL record {sequence}: {err}"
+                                    ),
+                                }
+                            })?;
+                        delta.inserted_cards += cards.len();
+                        let card_ids = self.memories_track.add_cards(cards);
+                        self.record_wal_changes(
+                            sequence,
+                            card_ids
+                                .iter()
+                                .map(|&card_id| ChangeKind::MemoryCardAdded { card_id }),
+                        );
+                        self.memories_track.record_enrichment(
+                            frame_id,
+                            &engine_kind,
+                            &engine_version,
+                            card_ids,
+                        );
+                        continue;
+                    }
+                    WalEntry::SpaceEmbeddings { space, embeddings } => {
+                        for (frame_id, embedding) in embeddings {
+                            self.insert_space_embedding(&space, frame_id, embedding)?;
+                        }
+                        continue;
+                    }
+                    // Markers are consumed by `committed_wal_entries`.
+                    WalEntry::TxBegin(_) | WalEntry::TxCommit(_) | WalEntry::TxAbort(_) => {
+                        continue;
+                    }
                 };
 
                 match entry.op {
@@ -1314,9 +1846,13 @@ impl Memvid {
                         }
 
                         if let Some(embedding) = entry.embedding.take() {
-                            delta
-                                .inserted_embeddings
-                                .push((frame_id, embedding.clone()));
+                            if let Some(space) = entry.embedding_space.as_deref() {
+                                self.insert_space_embedding(space, frame_id, embedding)?;
+                            } else {
+                                delta
+                                    .inserted_embeddings
+                                    .push((frame_id, embedding.clone()));
+                            }
                         }
 
                         if entry.role == FrameRole::Document {
@@ -1342,11 +1878,26 @@ impl Memvid {
 
                         if let Some(predecessor) = frame.supersedes {
                             self.mark_frame_superseded(predecessor, frame_id)?;
+                            self.record_wal_changes(
+                                sequence,
+                                [
+                                    ChangeKind::FrameUpdated {
+                                        frame_id,
+                                        previous: predecessor,
+                                    },
+                                    ChangeKind::FrameSuperseded {
+                                        frame_id: predecessor,
+                                        superseded_by: frame_id,
+                                    },
+                                ],
+                            );
+                        } else {
+                            self.record_wal_changes(sequence, [ChangeKind::FramePut { frame_id }]);
                         }
 
                         self.toc.frames.push(frame);
                         delta.inserted_frames.push(frame_id);
-                        sequence_to_frame.insert(record.sequence, frame_id);
+                        sequence_to_frame.insert(sequence, frame_id);
                     }
                     FrameWalOp::Tombstone => {
                         let target = entry.target_frame_id.ok_or(MemvidError::InvalidFrame {
@@ -1354,6 +1905,10 @@ impl Memvid {
                             reason: "tombstone missing frame reference",
                         })?;
                         self.mark_frame_deleted(target)?;
+                        self.record_wal_changes(
+                            sequence,
+                            [ChangeKind::FrameDeleted { frame_id: target }],
+                        );
                         delta.mutated_frames = true;
                     }
                 }
@@ -1900,18 +2455,113 @@ impl Memvid {
     }
 
     pub(crate) fn rebuild_indexes(&mut self, new_vec_docs: &[(FrameId, Vec<f32>)]) -> Result<()> {
-        if self.toc.frames.is_empty() && !self.lex_enabled && !self.vec_enabled {
+        self.rebuild_indexes_with_mode(new_vec_docs, CommitMode::Full)
+    }
+
+    /// Rebuild the indexes; [`CommitMode::Incremental`] keeps the lex index the handle
+    /// already maintains instead of re-extracting the text of every frame.
+    fn rebuild_indexes_with_mode(
+        &mut self,
+        new_vec_docs: &[(FrameId, Vec<f32>)],
+        mode: CommitMode,
+    ) -> Result<()> {
+        if !self.has_indexes_to_rebuild() {
             return Ok(());
         }
+        let built = self.prepare_index_build(new_vec_docs, mode)?.run()?;
+        self.write_rebuilt_indexes(built)
+    }
 
-        let payload_end = self.payload_region_end();
-        self.data_end = payload_end;
+    fn has_indexes_to_rebuild(&self) -> bool {
+        !self.toc.frames.is_empty() || self.lex_enabled || self.vec_enabled
+    }
+
+    /// Snapshot what a rebuild of the vector, time and lex indexes needs from the handle.
+    fn prepare_index_build(
+        &mut self,
+        new_vec_docs: &[(FrameId, Vec<f32>)],
+        mode: CommitMode,
+    ) -> Result<IndexBuild> {
+        let vec = self.vec_enabled.then(|| VecIndexBuild {
+            existing: self.vec_index.take(),
+            new_docs: new_vec_docs.to_vec(),
+            active: self
+                .toc
+                .frames
+                .iter()
+                .map(|frame| frame.status == FrameStatus::Active)
+                .collect(),
+            hnsw_config: self.hnsw_config,
+        });
+        let time_entries = self
+            .toc
+            .frames
+            .iter()
+            .filter(|frame| {
+                frame.status == FrameStatus::Active && frame.role == FrameRole::Document
+            })
+            .map(|frame| TimeIndexEntry::new(frame.timestamp, frame.id))
+            .collect();
+        #[cfg(feature = "lex")]
+        let lex = if !self.lex_enabled {
+            None
+        } else if mode == CommitMode::Incremental && self.tantivy.is_some() {
+            // `apply_records` already added and removed the changed frames.
+            self.tantivy
+                .take()
+                .map(|engine| LexIndexBuild { engine, docs: None })
+        } else {
+            let docs = self.tantivy_rebuild_docs()?;
+            Some(LexIndexBuild {
+                engine: self.create_tantivy_engine()?,
+                docs: Some(docs),
+            })
+        };
+        Ok(IndexBuild {
+            vec,
+            time_entries,
+            #[cfg(feature = "lex")]
+            lex,
+        })
+    }
+
+    /// Write indexes built by [`IndexBuild::run`] after the payload region, followed by the
+    /// CLIP index, memories track, logic mesh, TOC and header.
+    fn write_rebuilt_indexes(&mut self, built: BuiltIndexes) -> Result<()> {
+        let mut payload_end = self.payload_region_end();
         // Don't truncate if footer_offset is higher - there may be replay segments
         // or other data written after payload_end that must be preserved.
         let safe_truncate_len = self.header.footer_offset.max(payload_end);
-        if self.file.metadata()?.len() > safe_truncate_len {
+        if self.file.len()? > safe_truncate_len {
             self.file.set_len(safe_truncate_len)?;
         }
+
+        // An HNSW graph only appends its changes as the next segment of its chain; the
+        // chain stays in the payload region so later commits don't overwrite it. Flat
+        // indexes are rewritten with the other indexes below.
+        let mut flat_vec_index = None;
+        match built.vec {
+            Some(VecIndex::Hnsw(mut graph)) => {
+                let previous = self.toc.indexes.vec.clone();
+                if let Some(manifest) =
+                    self.append_hnsw_segment(&mut graph, previous.as_ref(), payload_end)?
+                {
+                    payload_end += manifest.bytes_length;
+                    self.toc.indexes.vec = Some(manifest);
+                }
+                self.vec_index = Some(VecIndex::Hnsw(graph));
+            }
+            Some(index) => flat_vec_index = Some(index),
+            None => {
+                // Only clear manifest if vec is disabled, keep empty placeholder if enabled
+                if !self.vec_enabled {
+                    self.toc.indexes.vec = None;
+                }
+                self.vec_index = None;
+            }
+        }
+        payload_end = self.write_vec_spaces(payload_end)?;
+        self.data_end = payload_end;
         self.file.seek(SeekFrom::Start(payload_end))?;
 
         // Clear legacy per-segment catalogs; full rebuild emits fresh manifests.
@@ -1927,22 +2577,15 @@ impl Memvid {
         // Drop any stale embedded lex manifest entries before rebuilding Tantivy.
         self.toc.indexes.lex_segments.clear();
 
-        let mut time_entries: Vec<TimeIndexEntry> = self
-            .toc
-            .frames
-            .iter()
-            .filter(|frame| {
-                frame.status == FrameStatus::Active && frame.role == FrameRole::Document
-            })
-            .map(|frame| TimeIndexEntry::new(frame.timestamp, frame.id))
-            .collect();
-        let (ti_offset, ti_length, ti_checksum) =
-            time_index_append(&mut self.file, &mut time_entries)?;
+        let time_index = built.time_index;
+        let ti_offset = payload_end;
+        let ti_length = time_index.bytes.len() as u64;
+        self.file.write_all(&time_index.bytes)?;
         self.toc.time_index = Some(TimeIndexManifest {
             bytes_offset: ti_offset,
             bytes_length: ti_length,
-            entry_count: time_entries.len() as u64,
-            checksum: ti_checksum,
+            entry_count: time_index.entry_count,
+            checksum: time_index.checksum,
         });
 
         let mut footer_offset = ti_offset + ti_length;
@@ -1963,17 +2606,12 @@ impl Memvid {
                     storage.set_generation(0);
                 }
 
-                // Initialize Tantivy engine (loads existing segments if any)
-                self.init_tantivy()?;
-
-                if let Some(mut engine) = self.tantivy.take() {
-                    self.rebuild_tantivy_engine(&mut engine)?;
-                    self.tantivy = Some(engine);
-                } else {
+                let Some(engine) = built.tantivy else {
                     return Err(MemvidError::InvalidToc {
                         reason: "tantivy engine missing during doctor rebuild".into(),
                     });
-                }
+                };
+                self.tantivy = Some(engine);
 
                 // Set lex_enabled to ensure it persists
                 self.lex_enabled = true;
@@ -2008,7 +2646,8 @@ impl Memvid {
             }
         }
 
-        if let Some((artifact, index)) = self.build_vec_artifact(new_vec_docs)? {
+        if let Some(index) = flat_vec_index {
+            let artifact = index.encode()?;
             let vec_offset = footer_offset;
             self.file.seek(SeekFrom::Start(vec_offset))?;
             self.file.write_all(&artifact.bytes)?;
@@ -2022,12 +2661,6 @@ impl Memvid {
                 compression_mode: self.vec_compression.clone(),
             });
             self.vec_index = Some(index);
-        } else {
-            // Only clear manifest if vec is disabled, keep empty placeholder if enabled
-            if !self.vec_enabled {
-                self.toc.indexes.vec = None;
-            }
-            self.vec_index = None;
         }
 
         // Persist CLIP index if it has embeddings
@@ -2088,12 +2721,11 @@ impl Memvid {
             self.file.write_all(&mesh_bytes)?;
             footer_offset += mesh_bytes.len() as u64;
 
-            let stats = self.logic_mesh.stats();
             self.toc.logic_mesh = Some(crate::types::LogicMeshManifest {
                 bytes_offset: mesh_offset,
                 bytes_length: mesh_bytes.len() as u64,
-                node_count: stats.node_count as u64,
-                edge_count: stats.edge_count as u64,
+                node_count: self.logic_mesh.nodes.len() as u64,
+                edge_count: self.logic_mesh.edges.len() as u64,
                 checksum: mesh_checksum,
             });
         } else {
@@ -2114,7 +2746,7 @@ impl Memvid {
         self.header.footer_offset = self.header.footer_offset.max(footer_offset);
 
         // Ensure the file length covers rebuilt indexes to avoid out-of-bounds manifests.
-        if self.file.metadata()?.len() < self.header.footer_offset {
+        if self.file.len()? < self.header.footer_offset {
             self.file.set_len(self.header.footer_offset)?;
         }
 
@@ -2198,7 +2830,7 @@ impl Memvid {
         self.header.footer_offset = memories_offset + memories_bytes.len() as u64;
 
         // Ensure the file length covers the memories track
-        if self.file.metadata()?.len() < self.header.footer_offset {
+        if self.file.len()? < self.header.footer_offset {
             self.file.set_len(self.header.footer_offset)?;
         }
 
@@ -2250,7 +2882,7 @@ impl Memvid {
         self.header.footer_offset = clip_offset + artifact.bytes.len() as u64;
 
         // Ensure the file length covers the CLIP index
-        if self.file.metadata()?.len() < self.header.footer_offset {
+        if self.file.len()? < self.header.footer_offset {
             self.file.set_len(self.header.footer_offset)?;
         }
 
@@ -2275,12 +2907,11 @@ impl Memvid {
         self.file.seek(SeekFrom::Start(mesh_offset))?;
         self.file.write_all(&mesh_bytes)?;
 
-        let stats = self.logic_mesh.stats();
         self.toc.logic_mesh = Some(crate::types::LogicMeshManifest {
             bytes_offset: mesh_offset,
             bytes_length: mesh_bytes.len() as u64,
-            node_count: stats.node_count as u64,
-            edge_count: stats.edge_count as u64,
+            node_count: self.logic_mesh.nodes.len() as u64,
+            edge_count: self.logic_mesh.edges.len() as u64,
             checksum: mesh_checksum,
         });
 
@@ -2288,7 +2919,7 @@ impl Memvid {
         self.header.footer_offset = mesh_offset + mesh_bytes.len() as u64;
 
         // Ensure the file length covers the logic mesh
-        if self.file.metadata()?.len() < self.header.footer_offset {
+        if self.file.len()? < self.header.footer_offset {
             self.file.set_len(self.header.footer_offset)?;
         }
 
@@ -2326,7 +2957,7 @@ impl Memvid {
         self.header.footer_offset = sketch_offset + sketch_length;
 
         // Ensure the file length covers the sketch track
-        if self.file.metadata()?.len() < self.header.footer_offset {
+        if self.file.len()? < self.header.footer_offset {
             self.file.set_len(self.header.footer_offset)?;
         }
 
@@ -2372,15 +3003,10 @@ impl Memvid {
             (None, Vec::new())
         };
 
-        // Update the manifest
-        if let Some(storage_manifest) = index_manifest {
-            // Old LexIndexArtifact format: set the manifest with actual offset/length
-            self.toc.indexes.lex = Some(storage_manifest);
-        } else {
-            // Tantivy segments OR lex disabled: clear the manifest
-            // Stats will check lex_segments instead of manifest
-            self.toc.indexes.lex = None;
-        }
+        // Update the manifest. Old LexIndexArtifact format sets the manifest with actual
+        // offset/length; Tantivy segments clear it (stats check lex_segments instead) while
+        // keeping the configured analyzer.
+        self.toc.indexes.replace_lex(index_manifest);
 
         self.toc.indexes.lex_segments = segments;
 
@@ -2503,6 +3129,12 @@ impl Memvid {
         if let Some(index) = self.vec_index.as_mut() {
             index.remove(frame_id);
         }
+        for (name, index) in &mut self.vec_space_indexes {
+            if index.contains(frame_id) {
+                index.remove(frame_id);
+                self.dirty_vec_spaces.insert(name.clone());
+            }
+        }
         Ok(())
     }
 
@@ -2583,7 +3215,7 @@ impl Memvid {
         Ok(())
     }
 
-    fn ensure_mutation_allowed(&mut self) -> Result<()> {
+    pub(crate) fn ensure_mutation_allowed(&mut self) -> Result<()> {
         self.ensure_writable()?;
         if self.toc.ticket_ref.issuer == "free-tier" {
             return Ok(());
@@ -2760,42 +3392,130 @@ impl Memvid {
 }
 
 impl Memvid {
-    pub fn vacuum(&mut self) -> Result<()> {
+    /// Compact the memory by dropping payload bytes of deleted and superseded frames.
+    ///
+    /// Pending WAL records are committed first. The compacted image (header, WAL, live
+    /// payloads, rebuilt indexes, TOC and footer) is written to a sibling staging file that
+    /// atomically replaces the original, so a crash mid-vacuum leaves the previous generation
+    /// untouched. The returned report records how many bytes were reclaimed.
+    ///
+    /// # Errors
+    ///
+    /// Fails on a read-only handle, when pending records cannot be committed, or when the
+    /// staging image cannot be written; the original file is left as it was.
+    pub fn vacuum(&mut self) -> Result<VacuumReport> {
+        self.ensure_writable()?;
         self.commit()?;
 
-        let mut active_payloads: HashMap<FrameId, Vec<u8>> = HashMap::new();
-        let frames: Vec<Frame> = self
+        // Index state must be resident before the data region is rewritten, since
+        // manifests still point into the original layout.
+        self.ensure_vec_index()?;
+        self.ensure_clip_index()?;
+        self.ensure_vec_spaces_loaded()?;
+
+        let bytes_before = self.file.len()?;
+        let data_start = self.header.wal_offset + self.header.wal_size;
+        let source = self.file.try_clone()?;
+        let mut report = VacuumReport {
+            bytes_before,
+            ..VacuumReport::default()
+        };
+        self.with_staging_image(Some(data_start), |mem| {
+            mem.write_compacted_image(&source, data_start, &mut report)
+        })?;
+
+        report.bytes_after = self.file.len()?;
+        report.bytes_reclaimed = bytes_before.saturating_sub(report.bytes_after);
+        tracing::info!(
+            bytes_before = report.bytes_before,
+            bytes_after = report.bytes_after,
+            active_frames = report.active_frames,
+            released_frames = report.released_frames,
+            "vacuum completed"
+        );
+        Ok(report)
+    }
+
+    /// Stream live payloads from `source` into the staging image and rebuild everything
+    /// that follows them. `self.file` is the staging handle seeded with header + WAL.
+    fn write_compacted_image(
+        &mut self,
+        source: &DataFile,
+        data_start: u64,
+        report: &mut VacuumReport,
+    ) -> Result<()> {
+        let mut source = source.try_clone()?;
+
+        // Replay sessions sit after the indexes; carry their bytes over verbatim.
+        let replay_bytes = match self.toc.replay_manifest.as_ref() {
+            Some(manifest) if manifest.segment_size != 0 => {
+                let size = usize::try_from(manifest.segment_size).map_err(|_| {
+                    MemvidError::InvalidToc {
+                        reason: "replay segment exceeds addressable memory".into(),
+                    }
+                })?;
+                let mut bytes = vec![0u8; size];
+                source.seek(SeekFrom::Start(manifest.segment_offset))?;
+                source.read_exact(&mut bytes)?;
+                Some(bytes)
+            }
+            _ => None,
+        };
+
+        // Frames that reuse another frame's payload share an extent, so copy each once.
+        let mut extents: Vec<(u64, u64)> = self
             .toc
             .frames
             .iter()
-            .filter(|frame| frame.status == FrameStatus::Active)
-            .cloned()
+            .filter(|frame| frame.status == FrameStatus::Active && frame.payload_length != 0)
+            .map(|frame| (frame.payload_offset, frame.payload_length))
             .collect();
-        for frame in frames {
-            let bytes = self.read_frame_payload_bytes(&frame)?;
-            active_payloads.insert(frame.id, bytes);
-        }
+        extents.sort_unstable();
+        extents.dedup();
 
-        let mut cursor = self.header.wal_offset + self.header.wal_size;
+        let mut relocated: HashMap<(u64, u64), u64> = HashMap::with_capacity(extents.len());
+        let mut buffer = vec![0u8; WAL_SHIFT_BUFFER_SIZE];
+        let mut cursor = data_start;
         self.file.seek(SeekFrom::Start(cursor))?;
+        for (offset, length) in extents {
+            source.seek(SeekFrom::Start(offset))?;
+            let mut remaining = length;
+            while remaining > 0 {
+                let chunk =
+                    usize::try_from(remaining).map_or(buffer.len(), |r| r.min(buffer.len()));
+                source.read_exact(&mut buffer[..chunk])?;
+                self.file.write_all(&buffer[..chunk])?;
+                remaining -= chunk as u64;
+            }
+            relocated.insert((offset, length), cursor);
+            cursor += length;
+        }
+
         for frame in &mut self.toc.frames {
             if frame.status == FrameStatus::Active {
-                if let Some(bytes) = active_payloads.get(&frame.id) {
-                    self.file.write_all(bytes)?;
-                    frame.payload_offset = cursor;
-                    frame.payload_length = bytes.len() as u64;
-                    cursor += bytes.len() as u64;
-                } else {
-                    frame.payload_offset = 0;
-                    frame.payload_length = 0;
+                report.active_frames += 1;
+                if let Some(&new_offset) =
+                    relocated.get(&(frame.payload_offset, frame.payload_length))
+                {
+                    frame.payload_offset = new_offset;
+                    continue;
                 }
-            } else {
-                frame.payload_offset = 0;
-                frame.payload_length = 0;
+            } else if frame.payload_length != 0 {
+                report.released_frames += 1;
             }
+            frame.payload_offset = 0;
+            frame.payload_length = 0;
+        }
+
+        // Vector segments stay behind in the source file; write them afresh here.
+        if let Some(VecIndex::Hnsw(graph)) = self.vec_index.as_mut() {
+            graph.detach_chain();
         }
+        self.detach_vec_spaces();
 
+        // Everything after the payloads is regenerated, so the footer starts over here.
         self.data_end = cursor;
+        self.header.footer_offset = cursor;
 
         self.toc.segments.clear();
         self.toc.indexes.lex_segments.clear();
@@ -2824,6 +3544,19 @@ impl Memvid {
         }
 
         self.rebuild_indexes(&[])?;
+        self.persist_sketch_track()?;
+
+        if let (Some(bytes), Some(manifest)) = (replay_bytes, self.toc.replay_manifest.as_mut()) {
+            let segment_offset = self.header.footer_offset;
+            self.file.seek(SeekFrom::Start(segment_offset))?;
+            self.file.write_all(&bytes)?;
+            manifest.segment_offset = segment_offset;
+            self.header.footer_offset = segment_offset + bytes.len() as u64;
+        }
+
+        self.rewrite_toc_footer()?;
+        self.header.toc_checksum = self.toc.toc_checksum;
+        crate::persist_header(&mut self.file, &self.header)?;
         self.file.sync_all()?;
         Ok(())
     }
@@ -2909,6 +3642,82 @@ impl Memvid {
         )
     }
 
+    /// Ingest many text documents as one WAL transaction.
+    ///
+    /// Every request is appended between a single pair of transaction markers without instant
+    /// indexing, so a torn batch is discarded as a whole on recovery and the batch costs a
+    /// single Tantivy commit and TOC rewrite at the next [`Self::commit`]. Precomputed
+    /// embeddings go straight into the vector index, and `enable_enrichment` runs the rules
+    /// enrichment engine over each document. One result is returned per request, holding the
+    /// new frame id or the error that rejected that document; a document whose enrichment
+    /// cards fail schema validation is rejected without being written.
+    ///
+    /// # Errors
+    ///
+    /// Fails as a whole on a read-only handle or when the WAL cannot be written; errors of
+    /// individual documents are reported per request instead.
+    pub fn put_many(
+        &mut self,
+        requests: Vec<PutRequest>,
+        opts: &PutManyOpts,
+    ) -> Result<Vec<Result<FrameId>>> {
+        self.ensure_mutation_allowed()?;
+
+        let rules = opts.enable_enrichment.then(RulesEngine::new);
+        let engine = rules.as_ref().map(|rules| rules as &dyn EnrichmentEngine);
+        self.put_batch = Some(opts.clone());
+        let outcome = self.transaction(|tx| {
+            Ok(requests
+                .into_iter()
+                .map(|request| tx.put_request(request, opts, engine))
+                .collect::<Vec<_>>())
+        });
+        self.put_batch = None;
+        self.wal.set_sync_on_append(true);
+        let results = outcome?;
+
+        if !opts.skip_sync {
+            self.file.sync_all()?;
+        }
+        Ok(results)
+    }
+
+    /// Stage one [`PutRequest`] of a [`Self::put_many`] batch and enrich it with `engine`.
+    pub(crate) fn put_request(
+        &mut self,
+        request: PutRequest,
+        opts: &PutManyOpts,
+        engine: Option<&dyn EnrichmentEngine>,
+    ) -> Result<FrameId> {
+        let frame_id = self.next_frame_id();
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| {
+                i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+            });
+        // Enrich and validate the cards before the frame is staged, so a document whose
+        // cards are rejected is not written either.
+        let mut enrichment = None;
+        if let Some(engine) = engine {
+            let result = engine.enrich(&enrichment_context(frame_id, timestamp, &request));
+            if result.success {
+                self.check_cards_for_insert(&result.cards)?;
+                enrichment = Some((engine, result.cards));
+            } else if let Some(error) = result.error {
+                tracing::warn!(frame_id, engine = engine.kind(), %error, "enrichment failed");
+            }
+        }
+
+        let (payload, embedding, mut options) = put_request_into_options(request, opts);
+        options.timestamp = Some(timestamp);
+        self.put_internal(Some(&payload), None, embedding, None, options, None)?;
+
+        if let Some((engine, cards)) = enrichment {
+            self.put_enriched_cards(frame_id, engine.kind(), engine.version(), cards)?;
+        }
+        Ok(frame_id)
+    }
+
     /// Replace an existing frame's payload/metadata, keeping its identity and URI.
     pub fn update_frame(
         &mut self,
@@ -3001,45 +3810,8 @@ impl Memvid {
             });
         }
 
-        let mut tombstone = WalEntryData {
-            timestamp: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .map(|d| d.as_secs() as i64)
-                .unwrap_or(frame.timestamp),
-            kind: None,
-            track: None,
-            payload: Vec::new(),
-            embedding: None,
-            uri: frame.uri.clone(),
-            title: frame.title.clone(),
-            canonical_encoding: frame.canonical_encoding,
-            canonical_length: frame.canonical_length,
-            metadata: None,
-            search_text: None,
-            tags: Vec::new(),
-            labels: Vec::new(),
-            extra_metadata: BTreeMap::new(),
-            content_dates: Vec::new(),
-            chunk_manifest: None,
-            role: frame.role,
-            parent_sequence: None,
-            chunk_index: frame.chunk_index,
-            chunk_count: frame.chunk_count,
-            op: FrameWalOp::Tombstone,
-            target_frame_id: Some(frame_id),
-            supersedes_frame_id: None,
-            reuse_payload_from: None,
-            source_sha256: None,
-            source_path: None,
-            enrichment_state: crate::types::EnrichmentState::default(),
-        };
-        tombstone.kind = frame.kind.clone();
-        tombstone.track = frame.track.clone();
-
-        let payload_bytes = encode_to_vec(&WalEntry::Frame(tombstone), wal_config())?;
-        let seq = self.append_wal_entry(&payload_bytes)?;
-        self.dirty = true;
-        if self.wal.should_checkpoint() {
+        let seq = self.append_frame_entry(WalEntryData::tombstone_for(&frame))?;
+        if self.auto_checkpoint_due() {
             self.commit()?;
         }
         info!("frame_delete frame_id={} seq={}", frame_id, seq);
@@ -3119,7 +3891,13 @@ impl Memvid {
             dim
         };
 
-        if let Some(incoming_dimension) = incoming_dimension {
+        // Embeddings targeting a named vector space are checked against that space only.
+        let vector_space = options.vector_space.take();
+        if let (Some(space), Some(incoming_dimension)) =
+            (vector_space.as_deref(), incoming_dimension)
+        {
+            self.ensure_vec_space_dimension(space, incoming_dimension as usize)?;
+        } else if let Some(incoming_dimension) = incoming_dimension {
             // Embeddings imply vector search should be enabled.
             if !self.vec_enabled {
                 self.enable_vec()?;
@@ -3142,10 +3920,16 @@ impl Memvid {
             }
         }
 
+        let compression_level = self
+            .put_batch
+            .as_ref()
+            .map_or(DEFAULT_PAYLOAD_COMPRESSION_LEVEL, |batch| {
+                batch.compression_level
+            });
         let mut prepared_payload: Option<(Vec<u8>, CanonicalEncoding, Option<u64>)> = None;
         let payload_tail = self.payload_region_end();
         let projected = if let Some(bytes) = payload {
-            let (prepared, encoding, length) = prepare_canonical_payload(bytes)?;
+            let (prepared, encoding, length) = prepare_canonical_payload(bytes, compression_level)?;
             let len = prepared.len();
             prepared_payload = Some((prepared, encoding, length));
             payload_tail.saturating_add(len as u64)
@@ -3218,7 +4002,8 @@ impl Memvid {
             } else if let Some((prepared, encoding, length)) = prepared_payload.take() {
                 (prepared, encoding, length, None)
             } else if let Some(bytes) = payload {
-                let (prepared, encoding, length) = prepare_canonical_payload(bytes)?;
+                let (prepared, encoding, length) =
+                    prepare_canonical_payload(bytes, compression_level)?;
                 (prepared, encoding, length, None)
             } else if let Some(frame) = reuse_frame.as_ref() {
                 (
@@ -3443,7 +4228,7 @@ impl Memvid {
 
             for (idx, chunk_text) in plan.chunks.iter().enumerate() {
                 let (chunk_payload, chunk_encoding, chunk_length) =
-                    prepare_canonical_payload(chunk_text.as_bytes())?;
+                    prepare_canonical_payload(chunk_text.as_bytes(), compression_level)?;
                 let chunk_search_text = normalize_text(chunk_text, DEFAULT_SEARCH_TEXT_LIMIT)
                     .map(|n| n.text)
                     .filter(|text| !text.trim().is_empty());
@@ -3489,6 +4274,7 @@ impl Memvid {
                     source_path: None,
                     // Chunks are already extracted, so mark as Enriched
                     enrichment_state: crate::types::EnrichmentState::Enriched,
+                    embedding_space: vector_space.clone(),
                 });
             }
         }
@@ -3530,8 +4316,8 @@ impl Memvid {
 
         // Determine enrichment state: Searchable if needs background work, Enriched if complete
         #[cfg(feature = "lex")]
-        let needs_enrichment =
-            options.instant_index && (options.enable_embedding || is_skim_extraction);
+        let needs_enrichment = (options.instant_index || self.put_batch.is_some())
+            && (options.enable_embedding || is_skim_extraction);
         #[cfg(feature = "lex")]
         let enrichment_state = if needs_enrichment {
             crate::types::EnrichmentState::Searchable
@@ -3569,9 +4355,10 @@ impl Memvid {
             source_sha256,
             source_path: source_path_value,
             enrichment_state,
+            embedding_space: vector_space,
         };
 
-        let parent_bytes = encode_to_vec(&WalEntry::Frame(entry), wal_config())?;
+        let parent_bytes = encode_to_vec(WalEntry::Frame(Box::new(entry)), wal_config())?;
         let parent_seq = self.append_wal_entry(&parent_bytes)?;
         self.pending_frame_inserts = self.pending_frame_inserts.saturating_add(1);
 
@@ -3656,13 +4443,13 @@ impl Memvid {
 
         for mut chunk_entry in chunk_entries {
             chunk_entry.parent_sequence = Some(parent_seq);
-            let chunk_bytes = encode_to_vec(&WalEntry::Frame(chunk_entry), wal_config())?;
+            let chunk_bytes = encode_to_vec(WalEntry::Frame(Box::new(chunk_entry)), wal_config())?;
             self.append_wal_entry(&chunk_bytes)?;
             self.pending_frame_inserts = self.pending_frame_inserts.saturating_add(1);
         }
 
         self.dirty = true;
-        if self.wal.should_checkpoint() {
+        if self.auto_checkpoint_due() {
             self.commit()?;
         }
 
@@ -3688,12 +4475,9 @@ impl Memvid {
                     );
 
                     if !cards.is_empty() {
-                        // Add cards to memories track
-                        let card_ids = self.memories_track.add_cards(cards);
-
-                        // Record enrichment for incremental processing
-                        self.memories_track
-                            .record_enrichment(frame_id, "rules", "1.0.0", card_ids);
+                        // Add cards to memories track, recording the enrichment for
+                        // incremental processing
+                        self.put_enriched_cards(frame_id, "rules", "1.0.0", cards)?;
                     }
                 }
             }
@@ -3717,18 +4501,187 @@ impl Default for FrameWalOp {
 }
 
 #[derive(Debug, Serialize, Deserialize)]
-enum WalEntry {
-    Frame(WalEntryData),
+pub(crate) enum WalEntry {
+    Frame(Box<WalEntryData>),
     #[cfg(feature = "lex")]
     Lex(LexWalBatch),
+    /// Opens transaction `id`; its entries only apply once the matching `TxCommit` is logged.
+    TxBegin(u64),
+    TxCommit(u64),
+    TxAbort(u64),
+    /// Memory cards written by a transaction, JSON-encoded.
+    MemoryCards(Vec<u8>),
+    /// Embeddings of existing frames added to the named vector space `space`.
+    SpaceEmbeddings {
+        space: String,
+        embeddings: Vec<(FrameId, Vec<f32>)>,
+    },
+    /// Memory cards an enrichment engine extracted from `frame_id` inside a transaction,
+    /// JSON-encoded.
+    EnrichedCards {
+        frame_id: FrameId,
+        engine_kind: String,
+        engine_version: String,
+        cards: Vec<u8>,
+    },
 }
 
+/// Decode WAL records, keeping entries of complete transactions only.
+///
+/// Entries logged between `TxBegin` and the matching `TxCommit` are released together. An
+/// aborted transaction, or a torn one whose commit marker never made it to disk before the
+/// next `TxBegin` or the end of the log, is discarded as a whole.
+fn committed_wal_entries(records: Vec<WalRecord>) -> Result<Vec<(u64, WalEntry)>> {
+    let mut committed = Vec::with_capacity(records.len());
+    let mut open: Option<(u64, Vec<(u64, WalEntry)>)> = None;
+    for record in records {
+        match decode_wal_entry(&record.payload)? {
+            WalEntry::TxBegin(id) => {
+                if let Some((torn, entries)) = open.replace((id, Vec::new())) {
+                    tracing::warn!(
+                        transaction = torn,
+                        entries = entries.len(),
+                        "discarding torn WAL transaction"
+                    );
+                }
+            }
+            WalEntry::TxCommit(id) => match open.take() {
+                Some((open_id, entries)) if open_id == id => committed.extend(entries),
+                Some((open_id, entries)) => tracing::warn!(
+                    transaction = open_id,
+                    entries = entries.len(),
+                    "discarding WAL transaction closed by a foreign commit marker"
+                ),
+                None => tracing::warn!(transaction = id, "ignoring stray WAL commit marker"),
+            },
+            WalEntry::TxAbort(id) => {
+                if let Some((open_id, entries)) = open.take() {
+                    tracing::debug!(
+                        transaction = open_id,
+                        aborted = id,
+                        entries = entries.len(),
+                        "discarding aborted WAL transaction"
+                    );
+                }
+            }
+            entry => match open.as_mut() {
+                Some((_, entries)) => entries.push((record.sequence, entry)),
+                None => committed.push((record.sequence, entry)),
+            },
+        }
+    }
+    if let Some((torn, entries)) = open {
+        tracing::warn!(
+            transaction = torn,
+            entries = entries.len(),
+            "discarding torn WAL transaction"
+        );
+    }
+    Ok(committed)
+}
+
+/// Decode a WAL record, falling back to the layouts written before named vector spaces and
+/// to bare frame entries from before the `WalEntry` envelope.
 fn decode_wal_entry(bytes: &[u8]) -> Result<WalEntry> {
     if let Ok((entry, _)) = decode_from_slice::<WalEntry, _>(bytes, wal_config()) {
         return Ok(entry);
     }
-    let (legacy, _) = decode_from_slice::<WalEntryData, _>(bytes, wal_config())?;
-    Ok(WalEntry::Frame(legacy))
+    if let Ok((legacy, _)) = decode_from_slice::<LegacyWalEntry, _>(bytes, wal_config()) {
+        return Ok(legacy.into());
+    }
+    if let Ok((entry, _)) = decode_from_slice::<WalEntryData, _>(bytes, wal_config()) {
+        return Ok(WalEntry::Frame(Box::new(entry)));
+    }
+    let (legacy, _) = decode_from_slice::<LegacyWalEntryData, _>(bytes, wal_config())?;
+    Ok(WalEntry::Frame(Box::new(legacy.into())))
+}
+
+/// Legacy WAL envelope, written before transactions and named vector spaces.
+#[derive(Debug, Serialize, Deserialize)]
+enum LegacyWalEntry {
+    Frame(Box<LegacyWalEntryData>),
+    #[cfg(feature = "lex")]
+    Lex(LexWalBatch),
+}
+
+impl From<LegacyWalEntry> for WalEntry {
+    fn from(legacy: LegacyWalEntry) -> Self {
+        match legacy {
+            LegacyWalEntry::Frame(entry) => WalEntry::Frame(Box::new((*entry).into())),
+            #[cfg(feature = "lex")]
+            LegacyWalEntry::Lex(batch) => WalEntry::Lex(batch),
+        }
+    }
+}
+
+/// Legacy frame entry without `embedding_space`.
+///
+/// The WAL is bincode-encoded, so `#[serde(default)]` cannot fill in a trailing field that
+/// older builds never wrote; their pending records decode through this layout instead.
+#[derive(Debug, Serialize, Deserialize)]
+struct LegacyWalEntryData {
+    timestamp: i64,
+    kind: Option<String>,
+    track: Option<String>,
+    payload: Vec<u8>,
+    embedding: Option<Vec<f32>>,
+    uri: Option<String>,
+    title: Option<String>,
+    canonical_encoding: CanonicalEncoding,
+    canonical_length: Option<u64>,
+    metadata: Option<DocMetadata>,
+    search_text: Option<String>,
+    tags: Vec<String>,
+    labels: Vec<String>,
+    extra_metadata: BTreeMap<String, String>,
+    content_dates: Vec<String>,
+    chunk_manifest: Option<TextChunkManifest>,
+    role: FrameRole,
+    parent_sequence: Option<u64>,
+    chunk_index: Option<u32>,
+    chunk_count: Option<u32>,
+    op: FrameWalOp,
+    target_frame_id: Option<FrameId>,
+    supersedes_frame_id: Option<FrameId>,
+    reuse_payload_from: Option<FrameId>,
+    source_sha256: Option<[u8; 32]>,
+    source_path: Option<String>,
+    enrichment_state: crate::types::EnrichmentState,
+}
+
+impl From<LegacyWalEntryData> for WalEntryData {
+    fn from(legacy: LegacyWalEntryData) -> Self {
+        Self {
+            timestamp: legacy.timestamp,
+            kind: legacy.kind,
+            track: legacy.track,
+            payload: legacy.payload,
+            embedding: legacy.embedding,
+            uri: legacy.uri,
+            title: legacy.title,
+            canonical_encoding: legacy.canonical_encoding,
+            canonical_length: legacy.canonical_length,
+            metadata: legacy.metadata,
+            search_text: legacy.search_text,
+            tags: legacy.tags,
+            labels: legacy.labels,
+            extra_metadata: legacy.extra_metadata,
+            content_dates: legacy.content_dates,
+            chunk_manifest: legacy.chunk_manifest,
+            role: legacy.role,
+            parent_sequence: legacy.parent_sequence,
+            chunk_index: legacy.chunk_index,
+            chunk_count: legacy.chunk_count,
+            op: legacy.op,
+            target_frame_id: legacy.target_frame_id,
+            supersedes_frame_id: legacy.supersedes_frame_id,
+            reuse_payload_from: legacy.reuse_payload_from,
+            source_sha256: legacy.source_sha256,
+            source_path: legacy.source_path,
+            enrichment_state: legacy.enrichment_state,
+            embedding_space: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -3785,13 +4738,164 @@ pub(crate) struct WalEntryData {
     /// Enrichment state for progressive ingestion.
     #[serde(default)]
     pub(crate) enrichment_state: crate::types::EnrichmentState,
+    /// Named vector space the embedding belongs to; `None` targets the default index.
+    #[serde(default)]
+    pub(crate) embedding_space: Option<String>,
 }
 
+impl WalEntryData {
+    /// Insert entry recreating `frame` from its stored (canonically encoded) payload bytes.
+    ///
+    /// Parent links, embeddings and supersede targets are left unset for the caller to fill in.
+    pub(crate) fn insert_from_frame(frame: Frame, payload: Vec<u8>) -> Self {
+        Self {
+            timestamp: frame.timestamp,
+            kind: frame.kind,
+            track: frame.track,
+            payload,
+            embedding: None,
+            uri: frame.uri,
+            title: frame.title,
+            canonical_encoding: frame.canonical_encoding,
+            canonical_length: frame.canonical_length,
+            metadata: frame.metadata,
+            search_text: frame.search_text,
+            tags: frame.tags,
+            labels: frame.labels,
+            extra_metadata: frame.extra_metadata,
+            content_dates: frame.content_dates,
+            chunk_manifest: frame.chunk_manifest,
+            role: frame.role,
+            parent_sequence: None,
+            chunk_index: frame.chunk_index,
+            chunk_count: frame.chunk_count,
+            op: FrameWalOp::Insert,
+            target_frame_id: None,
+            supersedes_frame_id: None,
+            reuse_payload_from: None,
+            source_sha256: frame.source_sha256,
+            source_path: frame.source_path,
+            enrichment_state: frame.enrichment_state,
+            embedding_space: None,
+        }
+    }
+
+    /// Tombstone entry retiring `frame`.
+    pub(crate) fn tombstone_for(frame: &Frame) -> Self {
+        Self {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .ok()
+                .and_then(|d| i64::try_from(d.as_secs()).ok())
+                .unwrap_or(frame.timestamp),
+            kind: frame.kind.clone(),
+            track: frame.track.clone(),
+            payload: Vec::new(),
+            embedding: None,
+            uri: frame.uri.clone(),
+            title: frame.title.clone(),
+            canonical_encoding: frame.canonical_encoding,
+            canonical_length: frame.canonical_length,
+            metadata: None,
+            search_text: None,
+            tags: Vec::new(),
+            labels: Vec::new(),
+            extra_metadata: BTreeMap::new(),
+            content_dates: Vec::new(),
+            chunk_manifest: None,
+            role: frame.role,
+            parent_sequence: None,
+            chunk_index: frame.chunk_index,
+            chunk_count: frame.chunk_count,
+            op: FrameWalOp::Tombstone,
+            target_frame_id: Some(frame.id),
+            supersedes_frame_id: None,
+            reuse_payload_from: None,
+            source_sha256: None,
+            source_path: None,
+            enrichment_state: crate::types::EnrichmentState::default(),
+            embedding_space: None,
+        }
+    }
+}
+
+/// Describe a batch request to an enrichment engine as the frame `frame_id` it becomes.
+fn enrichment_context(
+    frame_id: FrameId,
+    timestamp: i64,
+    request: &PutRequest,
+) -> EnrichmentContext {
+    let metadata = (!request.metadata.is_empty())
+        .then(|| serde_json::to_string(&request.metadata).ok())
+        .flatten();
+    EnrichmentContext::new(
+        frame_id,
+        request
+            .uri
+            .clone()
+            .unwrap_or_else(|| crate::default_uri(frame_id)),
+        request.text.clone(),
+        Some(request.title.clone()),
+        timestamp,
+        metadata,
+    )
+}
+
+/// Split a batch request into the payload, embedding and options `put_internal` expects.
+fn put_request_into_options(
+    request: PutRequest,
+    opts: &PutManyOpts,
+) -> (Vec<u8>, Option<Vec<f32>>, PutOptions) {
+    let PutRequest {
+        title,
+        label,
+        text,
+        uri,
+        metadata,
+        tags,
+        labels,
+        embedding,
+    } = request;
+
+    let mut all_labels = Vec::with_capacity(labels.len() + 1);
+    all_labels.push(label);
+    for extra in labels {
+        if !all_labels.contains(&extra) {
+            all_labels.push(extra);
+        }
+    }
+    let extra_metadata = metadata
+        .into_iter()
+        .map(|(key, value)| match value {
+            serde_json::Value::String(text) => (key, text),
+            other => (key, other.to_string()),
+        })
+        .collect();
+
+    let options = PutOptions {
+        uri,
+        title: Some(title),
+        tags,
+        labels: all_labels,
+        extra_metadata,
+        enable_embedding: opts.enable_embedding && embedding.is_none(),
+        auto_tag: opts.auto_tag,
+        extract_dates: opts.extract_dates,
+        extract_triplets: false,
+        no_raw: opts.no_raw,
+        instant_index: false,
+        ..PutOptions::default()
+    };
+    (text.into_bytes(), embedding, options)
+}
+
+/// Encode a payload for storage; UTF-8 text is zstd-compressed at `level` (0 stores it as-is).
 pub(crate) fn prepare_canonical_payload(
     payload: &[u8],
+    level: i32,
 ) -> Result<(Vec<u8>, CanonicalEncoding, Option<u64>)> {
-    if std::str::from_utf8(payload).is_ok() {
-        let compressed = zstd::encode_all(std::io::Cursor::new(payload), 3)?;
+    if level != 0 && std::str::from_utf8(payload).is_ok() {
+        let compressed = zstd::encode_all(std::io::Cursor::new(payload), level)?;
         Ok((
             compressed,
             CanonicalEncoding::Zstd,
@@ -3893,3 +4997,126 @@ pub(crate) fn merge_unique(target: &mut Vec<String>, additions: Vec<String>) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn legacy_insert(payload: &[u8]) -> LegacyWalEntryData {
+        LegacyWalEntryData {
+            timestamp: 1_700_000_000,
+            kind: None,
+            track: None,
+            payload: payload.to_vec(),
+            embedding: None,
+            uri: Some("mv2://legacy/note".into()),
+            title: None,
+            canonical_encoding: CanonicalEncoding::Plain,
+            canonical_length: Some(payload.len() as u64),
+            metadata: None,
+            search_text: Some(String::from_utf8_lossy(payload).into_owned()),
+            tags: vec!["legacy".into()],
+            labels: Vec::new(),
+            extra_metadata: BTreeMap::new(),
+            content_dates: Vec::new(),
+            chunk_manifest: None,
+            role: FrameRole::Document,
+            parent_sequence: None,
+            chunk_index: None,
+            chunk_count: None,
+            op: FrameWalOp::Insert,
+            target_frame_id: None,
+            supersedes_frame_id: None,
+            reuse_payload_from: None,
+            source_sha256: None,
+            source_path: None,
+            enrichment_state: crate::types::EnrichmentState::default(),
+        }
+    }
+
+    #[test]
+    fn legacy_wal_records_replay_on_open() {
+        let dir = tempdir().expect("tmp");
+        let path = dir.path().join("legacy-wal.mv2");
+        let crashed = dir.path().join("legacy-wal.crashed.mv2");
+
+        {
+            let mut mem = Memvid::create(&path).expect("create");
+            mem.put_bytes(b"committed before the upgrade").expect("put");
+            mem.commit().expect("commit");
+        }
+        {
+            let mut mem = Memvid::open(&path).expect("open");
+            let envelope =
+                LegacyWalEntry::Frame(Box::new(legacy_insert(b"pending in the envelope")));
+            let bytes = encode_to_vec(&envelope, wal_config()).expect("encode");
+            mem.append_wal_entry(&bytes).expect("append");
+            let bare = encode_to_vec(legacy_insert(b"pending as a bare entry"), wal_config())
+                .expect("encode");
+            mem.append_wal_entry(&bare).expect("append");
+            // Leave the records pending, as an older build would after a crash.
+            std::fs::copy(&path, &crashed).expect("copy crash image");
+        }
+
+        let mut mem = Memvid::open(&crashed).expect("recover legacy WAL");
+        assert_eq!(mem.frame_count(), 3);
+        assert_eq!(
+            mem.frame_text_by_id(1).expect("envelope frame"),
+            "pending in the envelope"
+        );
+        assert_eq!(
+            mem.frame_text_by_id(2).expect("bare frame"),
+            "pending as a bare entry"
+        );
+        assert_eq!(mem.frame_by_id(1).expect("frame").tags, vec!["legacy"]);
+    }
+
+    #[test]
+    fn background_commit_publishes_staged_records() {
+        let dir = tempdir().expect("tmp");
+        let path = dir.path().join("background.mv2");
+
+        let mut mem = Memvid::create(&path).expect("create");
+        mem.put_bytes(b"first generation").expect("put");
+        mem.commit().expect("commit");
+        mem.put_bytes(b"second generation").expect("put");
+
+        // Dropping the handle abandons the commit and leaves the records pending.
+        let options = CommitOptions::default().background(true);
+        let commit = mem.commit_in_background(options).expect("start");
+        assert!(matches!(
+            mem.put_bytes(b"too early"),
+            Err(MemvidError::CommitInProgress)
+        ));
+        drop(commit);
+        assert_eq!(mem.frame_count(), 1);
+
+        let pending = mem
+            .begin_background_commit(CommitMode::Full)
+            .expect("begin")
+            .expect("pending records");
+        let staged = pending.stage().expect("stage");
+        assert_eq!(mem.frame_count(), 1);
+        mem.publish_background_commit(staged).expect("publish");
+        mem.background_commit_active.store(false, Ordering::Release);
+        assert_eq!(mem.frame_count(), 2);
+        assert!(
+            mem.frame_text_by_id(1)
+                .expect("staged frame")
+                .starts_with("second generation")
+        );
+        mem.put_bytes(b"third generation")
+            .expect("put after publish");
+        mem.commit().expect("commit");
+        drop(mem);
+
+        let mut mem = Memvid::open_read_only(&path).expect("reopen");
+        assert_eq!(mem.frame_count(), 3);
+        assert!(
+            mem.frame_text_by_id(1)
+                .expect("published frame")
+                .starts_with("second generation")
+        );
+    }
+}
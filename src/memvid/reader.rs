@@ -0,0 +1,208 @@
+//! Shared read-only query handle.
+//!
+//! `Memvid` queries take `&mut self` because they read blobs through a single file cursor and
+//! keep per-handle caches. A [`MemvidReader`] instead owns a small pool of read-only handles
+//! opened on the same committed generation, each with its own file descriptor and fully
+//! loaded indexes. Every call checks out an idle handle, so up to `handles` queries run in
+//! parallel and the reader can be cloned into any number of threads.
+//!
+//! Because each handle holds its own copy of the indexes, a reader costs roughly `handles`
+//! times the memory of one open handle. [`Memvid::reader`] therefore defaults to a small
+//! fixed pool; use [`Memvid::reader_with_handles`] to trade memory for more parallelism.
+
+use crate::error::{MemvidError, Result};
+use crate::lock::LockMode;
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    AskRequest, AskResponse, Frame, FrameId, SearchRequest, SearchResponse, Stats, TimelineEntry,
+    TimelineQuery,
+};
+use crate::{VecEmbedder, VecSearchHit};
+use std::fs::OpenOptions;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+/// Cloneable, `Send + Sync` query handle over one committed generation of a memory.
+///
+/// Created with [`Memvid::reader`]. The reader never sees later commits; create a new one to
+/// pick them up. Its handles take the same shared locks as [`Memvid::open_read_only`], unless
+/// the reader was created from a writable handle, whose exclusive lock covers them.
+#[derive(Clone)]
+pub struct MemvidReader {
+    pool: Arc<ReaderPool>,
+}
+
+/// Handles opened by [`Memvid::reader`].
+const DEFAULT_READER_HANDLES: usize = 4;
+
+/// Attempts at opening every handle on the same generation before giving up.
+const MAX_OPEN_ATTEMPTS: usize = 8;
+
+struct ReaderPool {
+    path: PathBuf,
+    generation: u64,
+    handles: Vec<Mutex<Memvid>>,
+    next: AtomicUsize,
+}
+
+impl Memvid {
+    /// Create a [`MemvidReader`] with a pool of four handles.
+    ///
+    /// The reader serves the last committed generation; changes pending on this handle are
+    /// not visible to it. Every handle loads its own copy of the indexes, so the pool size
+    /// multiplies the memory of one open handle.
+    ///
+    /// # Errors
+    ///
+    /// Same as [`Memvid::reader_with_handles`].
+    pub fn reader(&self) -> Result<MemvidReader> {
+        self.reader_with_handles(DEFAULT_READER_HANDLES)
+    }
+
+    /// Create a [`MemvidReader`] backed by `handles` read-only handles (at least one).
+    ///
+    /// # Errors
+    ///
+    /// Fails when a handle cannot be opened, and with [`MemvidError::Lock`] if commits keep
+    /// landing while the handles open, so that no single generation can be pinned.
+    pub fn reader_with_handles(&self, handles: usize) -> Result<MemvidReader> {
+        for _ in 0..MAX_OPEN_ATTEMPTS {
+            let pool = (0..handles.max(1))
+                .map(|_| self.open_reader_handle())
+                .collect::<Result<Vec<_>>>()?;
+            let generation = pool[0].generation;
+            // A commit landing while the pool opens would split it across generations.
+            if pool.iter().any(|handle| handle.generation != generation) {
+                continue;
+            }
+            return Ok(MemvidReader {
+                pool: Arc::new(ReaderPool {
+                    path: self.path.clone(),
+                    generation,
+                    handles: pool.into_iter().map(Mutex::new).collect(),
+                    next: AtomicUsize::new(0),
+                }),
+            });
+        }
+        Err(MemvidError::Lock(format!(
+            "commits kept landing while opening {handles} reader handles; gave up after \
+             {MAX_OPEN_ATTEMPTS} attempts"
+        )))
+    }
+
+    fn open_reader_handle(&self) -> Result<Self> {
+        let raw = OpenOptions::new().read(true).write(true).open(&self.path)?;
+        // Shared locks from other descriptors would wait on our own exclusive lock, which
+        // already keeps other processes out.
+        let lock_mode = match self.lock.mode() {
+            LockMode::Exclusive => LockMode::None,
+            _ => LockMode::Shared,
+        };
+        Self::open_snapshot_file(self.file.with_file(raw), &self.path, lock_mode)
+    }
+}
+
+impl ReaderPool {
+    /// Take an idle handle, or wait for one if all are busy.
+    fn checkout(&self) -> MutexGuard<'_, Memvid> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed) % self.handles.len();
+        let rotated = self.handles[start..].iter().chain(&self.handles[..start]);
+        for handle in rotated {
+            if let Ok(guard) = handle.try_lock() {
+                return guard;
+            }
+        }
+        // A panic mid-query leaves the read-only handle usable, so poisoning is ignored.
+        self.handles[start]
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+// Every query forwards to its `Memvid` counterpart and fails exactly as that call does.
+#[allow(clippy::missing_errors_doc)]
+impl MemvidReader {
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.pool.path
+    }
+
+    /// Commit generation the reader was opened on.
+    #[must_use]
+    pub fn generation(&self) -> u64 {
+        self.pool.generation
+    }
+
+    /// Number of handles, i.e. how many calls can run at the same time.
+    #[must_use]
+    pub fn handles(&self) -> usize {
+        self.pool.handles.len()
+    }
+
+    #[must_use]
+    pub fn frame_count(&self) -> usize {
+        self.pool.checkout().frame_count()
+    }
+
+    pub fn stats(&self) -> Result<Stats> {
+        self.pool.checkout().stats()
+    }
+
+    pub fn search(&self, request: SearchRequest) -> Result<SearchResponse> {
+        self.pool.checkout().search(request)
+    }
+
+    pub fn ask<E>(&self, request: AskRequest, embedder: Option<&E>) -> Result<AskResponse>
+    where
+        E: VecEmbedder + ?Sized,
+    {
+        self.pool.checkout().ask(request, embedder)
+    }
+
+    pub fn search_vec(&self, query: &[f32], limit: usize) -> Result<Vec<VecSearchHit>> {
+        self.pool.checkout().search_vec(query, limit)
+    }
+
+    pub fn timeline(&self, query: TimelineQuery) -> Result<Vec<TimelineEntry>> {
+        self.pool.checkout().timeline(query)
+    }
+
+    pub fn frame_by_id(&self, frame_id: FrameId) -> Result<Frame> {
+        self.pool.checkout().frame_by_id(frame_id)
+    }
+
+    pub fn frame_by_uri(&self, uri: &str) -> Result<Frame> {
+        self.pool.checkout().frame_by_uri(uri)
+    }
+
+    pub fn frame_text_by_id(&self, frame_id: FrameId) -> Result<String> {
+        self.pool.checkout().frame_text_by_id(frame_id)
+    }
+
+    pub fn frame_preview_by_id(&self, frame_id: FrameId) -> Result<String> {
+        self.pool.checkout().frame_preview_by_id(frame_id)
+    }
+
+    pub fn frame_canonical_payload(&self, frame_id: FrameId) -> Result<Vec<u8>> {
+        self.pool.checkout().frame_canonical_payload(frame_id)
+    }
+
+    pub fn frame_embedding(&self, frame_id: FrameId) -> Result<Option<Vec<f32>>> {
+        self.pool.checkout().frame_embedding(frame_id)
+    }
+
+    pub fn frame_context(&self, frame_id: FrameId, query: &str) -> Result<(String, usize)> {
+        self.pool.checkout().frame_context(frame_id, query)
+    }
+}
+
+impl std::fmt::Debug for MemvidReader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemvidReader")
+            .field("path", &self.pool.path)
+            .field("generation", &self.pool.generation)
+            .field("handles", &self.pool.handles.len())
+            .finish()
+    }
+}
@@ -0,0 +1,226 @@
+//! Catching read-only handles up with commits published after they were opened.
+//!
+//! Commits swap a new image of the file into place, so a handle keeps reading the generation
+//! it opened until it re-opens the path. [`Memvid::refresh`] does that in place: it loads the
+//! new TOC, compares it part by part with the current one and reloads only what changed,
+//! copying just the new Tantivy and vector segments into the indexes already in memory.
+
+use std::fs::OpenOptions;
+use std::mem;
+
+use crate::error::{MemvidError, Result};
+use crate::io::header::HeaderCodec;
+use crate::io::wal::EmbeddedWal;
+use crate::lock::FileLock;
+use crate::memvid::lifecycle::{
+    Memvid, TailSnapshot, detect_generation, has_lex_index, load_tail_snapshot,
+};
+#[cfg(feature = "lex")]
+use crate::search::EmbeddedLexStorage;
+use crate::types::{LogicMesh, MemoriesTrack, SketchTrack, Toc, TocPart};
+
+/// Outcome of a [`Memvid::refresh`] that found a newer commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RefreshReport {
+    /// Generation the handle was at before the refresh.
+    pub previous_generation: u64,
+    /// Generation the handle is at now.
+    pub generation: u64,
+    /// Frames appended since the previous generation.
+    pub frames_added: usize,
+    /// TOC parts that changed and were reloaded.
+    pub changed: Vec<TocPart>,
+}
+
+impl RefreshReport {
+    /// Whether `part` changed between the two generations.
+    #[must_use]
+    pub fn changed(&self, part: TocPart) -> bool {
+        self.changed.contains(&part)
+    }
+}
+
+impl Memvid {
+    /// Commit generation this handle is reading.
+    #[must_use]
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Check whether a commit newer than `generation` has been published to the file.
+    ///
+    /// Only the commit footer is read, so this is cheap enough to poll.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the file cannot be reopened or its commit footer cannot be read.
+    pub fn changed_since(&self, generation: u64) -> Result<bool> {
+        let published = self.published_generation()?;
+        Ok(published.is_some_and(|published| published != generation))
+    }
+
+    /// Generation in the commit footer currently at the file's path.
+    pub(crate) fn published_generation(&self) -> Result<Option<u64>> {
+        let raw = OpenOptions::new().read(true).open(&self.path)?;
+        detect_generation(&self.file.with_file(raw))
+    }
+
+    /// Move a read-only handle to the latest committed generation of its file.
+    ///
+    /// Returns `None` when the handle is already current. Indexes whose part of the TOC is
+    /// unchanged are kept as they are; appended Tantivy and vector segments are loaded into
+    /// the open indexes instead of rebuilding them.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::RequiresReadOnly`] on a writable handle, and when the new
+    /// TOC or any of its index segments cannot be read.
+    pub fn refresh(&mut self) -> Result<Option<RefreshReport>> {
+        if !self.read_only {
+            return Err(MemvidError::RequiresReadOnly);
+        }
+        if !self.changed_since(self.generation)? {
+            return Ok(None);
+        }
+
+        let raw = OpenOptions::new().read(true).write(true).open(&self.path)?;
+        let mut file = self.file.with_file(raw);
+        let lock = FileLock::acquire_with_mode(file.raw(), self.lock.mode())?;
+        let TailSnapshot {
+            toc,
+            footer_offset,
+            data_end,
+            generation,
+        } = load_tail_snapshot(&file)?;
+        let mut header = HeaderCodec::read(&mut file)?;
+        header.footer_offset = footer_offset;
+        header.toc_checksum = toc.toc_checksum;
+        let wal = EmbeddedWal::open_data_file_read_only(&file, &header)?;
+
+        let changed = self.toc.changed_parts(&toc)?;
+        let previous = mem::replace(&mut self.toc, toc);
+        self.file = file;
+        self.lock = lock;
+        self.header = header;
+        self.wal = wal;
+        self.data_end = data_end;
+        let previous_generation = mem::replace(&mut self.generation, generation);
+        self.bootstrap_segment_catalog();
+        self.reload_changed_parts(&previous, &changed)?;
+
+        tracing::debug!(
+            previous_generation,
+            generation,
+            changed = ?changed,
+            "refreshed read-only handle"
+        );
+        Ok(Some(RefreshReport {
+            previous_generation,
+            generation,
+            frames_added: self.toc.frames.len().saturating_sub(previous.frames.len()),
+            changed,
+        }))
+    }
+
+    fn reload_changed_parts(&mut self, previous: &Toc, changed: &[TocPart]) -> Result<()> {
+        let is_changed = |part| changed.contains(&part);
+
+        if is_changed(TocPart::LexIndex) {
+            self.lex_enabled = has_lex_index(&self.toc);
+            #[cfg(feature = "lex")]
+            if let Ok(mut storage) = self.lex_storage.write() {
+                *storage = EmbeddedLexStorage::from_manifest(
+                    self.toc.indexes.lex.as_ref(),
+                    &self.toc.indexes.lex_segments,
+                );
+            }
+            self.lex_index = None;
+            if self.lex_enabled {
+                self.load_lex_index_from_manifest()?;
+            }
+        }
+        // Without embedded segments the engine is rebuilt from frame text.
+        #[cfg(feature = "lex")]
+        if is_changed(TocPart::LexIndex)
+            || (is_changed(TocPart::Frames) && self.toc.segment_catalog.tantivy_segments.is_empty())
+        {
+            let analyzer_changed =
+                previous.indexes.lex_analyzer() != self.toc.indexes.lex_analyzer();
+            self.refresh_tantivy(&previous.segment_catalog.tantivy_segments, analyzer_changed)?;
+        }
+
+        if is_changed(TocPart::VecIndex) {
+            self.refresh_vec_index(previous)?;
+        }
+        if is_changed(TocPart::VecSpaces) {
+            self.load_vec_spaces_from_manifest();
+        }
+        if is_changed(TocPart::ClipIndex) {
+            self.clip_enabled = self.toc.indexes.clip.is_some();
+            self.clip_index = None;
+            if self.clip_enabled {
+                self.load_clip_index_from_manifest()?;
+            }
+        }
+        // The time index is read from the file on every query.
+        #[cfg(feature = "temporal_track")]
+        if is_changed(TocPart::TemporalTrack) {
+            self.clear_temporal_track_cache();
+            self.ensure_temporal_track_loaded()?;
+        }
+        if is_changed(TocPart::MemoriesTrack) {
+            self.memories_track = MemoriesTrack::new();
+            self.load_memories_track()?;
+        }
+        if is_changed(TocPart::LogicMesh) {
+            self.logic_mesh = LogicMesh::new();
+            self.load_logic_mesh()?;
+        }
+        if is_changed(TocPart::SketchTrack) {
+            self.sketch_track = SketchTrack::default();
+            self.load_sketch_track()?;
+        }
+        Ok(())
+    }
+
+    /// Fold newly appended vec segments into the loaded index, or reload it.
+    fn refresh_vec_index(&mut self, previous: &Toc) -> Result<()> {
+        self.vec_enabled =
+            self.toc.indexes.vec.is_some() || !self.toc.segment_catalog.vec_segments.is_empty();
+        let manifest_checksum = |toc: &Toc| {
+            toc.indexes
+                .vec
+                .as_ref()
+                .filter(|manifest| manifest.bytes_length > 0)
+                .map(|manifest| manifest.checksum)
+        };
+        let old_segments = &previous.segment_catalog.vec_segments;
+        let new_segments = &self.toc.segment_catalog.vec_segments;
+        // An index built from segments only grows by the segments appended after them.
+        let appended = manifest_checksum(previous).is_none()
+            && manifest_checksum(&self.toc).is_none()
+            && new_segments.len() >= old_segments.len()
+            && old_segments.iter().zip(new_segments).all(|(old, new)| {
+                old.common.segment_id == new.common.segment_id
+                    && old.common.checksum == new.common.checksum
+            });
+
+        match self.vec_index.take() {
+            Some(existing) if appended => {
+                let fresh = new_segments[old_segments.len()..].to_vec();
+                let mut documents = Vec::new();
+                for descriptor in &fresh {
+                    documents.extend(self.vec_segment_entries(descriptor));
+                }
+                let index = self.merge_vec_index(Some(existing), &documents)?;
+                self.vec_index = Some(index);
+            }
+            _ => {
+                if self.vec_enabled {
+                    self.load_vec_index_from_manifest()?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -421,6 +421,14 @@ impl Memvid {
         use crate::replay::storage;
         use std::io::Write;
 
+        // The sidecar is plaintext, which would leak an encrypted memory's contents.
+        if self.file.is_encrypted() {
+            return Err(crate::MemvidError::EncryptedFile {
+                path: self.path.clone(),
+                hint: "Replay sessions cannot be saved beside an encrypted memory".to_string(),
+            });
+        }
+
         let session = match &self.active_session {
             Some(s) => s,
             None => {
@@ -1,17 +1,23 @@
 #[cfg(feature = "lex")]
 use crate::search::{EmbeddedLexSegment, TantivyEngine};
 #[cfg(feature = "lex")]
+use std::collections::HashSet;
+#[cfg(feature = "lex")]
 use std::fs::{self, File};
 #[cfg(feature = "lex")]
 use std::io::{Read, Seek, SeekFrom, Write};
 #[cfg(feature = "lex")]
+use std::path::Path;
+#[cfg(feature = "lex")]
+use tantivy::directory::{Directory, RamDirectory};
+#[cfg(feature = "lex")]
 use tempfile::TempDir;
 
 use crate::memvid::lifecycle::Memvid;
 use crate::types::{
     AdaptiveConfig, AdaptiveResult, AdaptiveStats, EmbeddingQualityStats, Frame, FrameId,
-    FrameStatus, SearchHit, TimelineEntry, TimelineQuery, VecSegmentDescriptor,
-    compute_embedding_quality, find_adaptive_cutoff,
+    FrameStatus, LexAnalyzerConfig, SearchHit, TantivySegmentDescriptor, TimelineEntry,
+    TimelineQuery, VecSegmentDescriptor, compute_embedding_quality, find_adaptive_cutoff,
 };
 use crate::{LexSearchHit, MemvidError, Result, VecSearchHit};
 
@@ -45,12 +51,62 @@ impl Memvid {
                 bytes_offset: empty_offset,
                 bytes_length: 0,
                 checksum: empty_checksum,
+                analyzer: None,
             });
         }
 
         self.commit()
     }
 
+    /// Analyzer the lexical index applies to frame text and queries.
+    #[must_use]
+    pub fn lex_analyzer(&self) -> LexAnalyzerConfig {
+        self.toc.indexes.lex_analyzer().cloned().unwrap_or_default()
+    }
+
+    /// Change the lexical analyzer and reindex every frame with it.
+    ///
+    /// The configuration is stored in the lex manifest, so later opens analyse queries the
+    /// same way the frames were indexed. Pending changes are committed along with the new
+    /// index.
+    ///
+    /// # Errors
+    ///
+    /// Fails on a read-only handle, with [`MemvidError::LexNotEnabled`] when the lexical index
+    /// is disabled, and when `analyzer` is invalid or the reindex fails.
+    pub fn set_lex_analyzer(&mut self, analyzer: LexAnalyzerConfig) -> Result<()> {
+        self.ensure_writable()?;
+        analyzer.validate()?;
+        if !self.lex_enabled {
+            return Err(MemvidError::LexNotEnabled);
+        }
+        if analyzer == self.lex_analyzer() {
+            return Ok(());
+        }
+
+        let manifest = match self.toc.indexes.lex.take() {
+            Some(mut manifest) => {
+                manifest.analyzer = Some(analyzer);
+                manifest
+            }
+            None => crate::types::LexIndexManifest::analyzer_placeholder(analyzer),
+        };
+        self.toc.indexes.lex = Some(manifest);
+        self.dirty = true;
+
+        #[cfg(feature = "lex")]
+        {
+            // The schema is shared, but every stored term was produced by the old analyzer,
+            // so rebuild from the frames into a fresh index.
+            let mut engine = self.create_tantivy_engine()?;
+            self.rebuild_tantivy_engine(&mut engine)?;
+            self.tantivy = Some(engine);
+            self.tantivy_dirty = true;
+        }
+
+        self.commit()
+    }
+
     pub fn search_lex(&mut self, query: &str, limit: usize) -> Result<Vec<LexSearchHit>> {
         if !self.lex_enabled {
             return Err(MemvidError::LexNotEnabled);
@@ -235,6 +291,83 @@ impl Memvid {
         top_k: usize,
         snippet_chars: usize,
         scope: Option<&str>,
+    ) -> Result<crate::types::SearchResponse> {
+        self.vec_search_with_filter(query, query_embedding, top_k, snippet_chars, scope, None)
+    }
+
+    /// Vector search restricted to frames matching a [`crate::types::MetadataFilter`].
+    ///
+    /// Only vectors of frames matching the filter are ranked, so selective filters still
+    /// return up to `top_k` hits.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the vector index cannot be loaded or its dimension does not match the query.
+    pub fn vec_search_with_filter(
+        &mut self,
+        query: &str,
+        query_embedding: &[f32],
+        top_k: usize,
+        snippet_chars: usize,
+        scope: Option<&str>,
+        filter: Option<&crate::types::MetadataFilter>,
+    ) -> Result<crate::types::SearchResponse> {
+        self.vec_search_in(
+            None,
+            query,
+            query_embedding,
+            top_k,
+            snippet_chars,
+            scope,
+            None,
+            filter,
+        )
+    }
+
+    /// Vector search over the named vector space `space` instead of the default index.
+    ///
+    /// The query embedding must match the space dimension.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::VecSpaceNotFound`] for an unknown space and with
+    /// [`MemvidError::VecDimensionMismatch`] when the embedding does not match it.
+    pub fn vec_search_in_space(
+        &mut self,
+        space: &str,
+        query: &str,
+        query_embedding: &[f32],
+        top_k: usize,
+        snippet_chars: usize,
+        scope: Option<&str>,
+    ) -> Result<crate::types::SearchResponse> {
+        self.vec_search_in(
+            Some(space),
+            query,
+            query_embedding,
+            top_k,
+            snippet_chars,
+            scope,
+            None,
+            None,
+        )
+    }
+
+    /// Vector search behind the public entry points.
+    ///
+    /// `scope` keeps frames whose URI starts with it; `uri`, when set, takes precedence and
+    /// keeps only frames with exactly that URI (ignoring ASCII case, like the lexical index).
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn vec_search_in(
+        &mut self,
+        space: Option<&str>,
+        query: &str,
+        query_embedding: &[f32],
+        top_k: usize,
+        snippet_chars: usize,
+        scope: Option<&str>,
+        uri: Option<&str>,
+        filter: Option<&crate::types::MetadataFilter>,
     ) -> Result<crate::types::SearchResponse> {
         use super::helpers::{build_context, timestamp_to_rfc3339};
         use crate::types::{
@@ -246,38 +379,46 @@ impl Memvid {
             return Err(MemvidError::VecNotEnabled);
         }
 
-        // Validate embedding dimension BEFORE searching to prevent silent wrong results.
-        // For segment-only memories, dimension may only be discoverable after loading segments.
-        let mut ensured_vec_index = false;
-        let expected_dim = match self.effective_vec_index_dimension()? {
-            Some(dim) => dim,
-            None => {
+        let start_time = Instant::now();
+        // Filtered queries rank only the frames that can survive the filters below.
+        let allowed = (filter.is_some() || uri.is_some())
+            .then(|| self.vec_candidate_frames(scope, uri, filter));
+        let vec_index = if let Some(space) = space {
+            self.vec_space_index(space, query_embedding.len())?
+        } else {
+            // Validate embedding dimension BEFORE searching to prevent silent wrong results.
+            // For segment-only memories, dimension may only be discoverable after loading segments.
+            let mut ensured_vec_index = false;
+            let expected_dim = if let Some(dim) = self.effective_vec_index_dimension()? {
+                dim
+            } else {
                 self.ensure_vec_index()?;
                 ensured_vec_index = true;
                 self.vec_index
                     .as_ref()
-                    .and_then(|index| index.entries().next().map(|(_, emb)| emb.len() as u32))
+                    .and_then(|index| index.entries().next())
+                    .and_then(|(_, emb)| u32::try_from(emb.len()).ok())
                     .unwrap_or(0)
+            };
+            if expected_dim > 0 && u32::try_from(query_embedding.len()).ok() != Some(expected_dim) {
+                return Err(MemvidError::VecDimensionMismatch {
+                    expected: expected_dim,
+                    actual: query_embedding.len(),
+                });
             }
-        };
-        if expected_dim > 0 && query_embedding.len() as u32 != expected_dim {
-            return Err(MemvidError::VecDimensionMismatch {
-                expected: expected_dim,
-                actual: query_embedding.len(),
-            });
-        }
 
-        let start_time = Instant::now();
-
-        // Ensure vector index is loaded
-        if !ensured_vec_index {
-            self.ensure_vec_index()?;
-        }
+            // Ensure vector index is loaded
+            if !ensured_vec_index {
+                self.ensure_vec_index()?;
+            }
 
-        let vec_index = self.vec_index.as_ref().ok_or(MemvidError::VecNotEnabled)?;
+            self.vec_index.as_ref().ok_or(MemvidError::VecNotEnabled)?
+        };
 
-        // Do pure vector search over entire index
-        let vec_hits = vec_index.search(query_embedding, top_k * 2);
+        let vec_hits = match &allowed {
+            Some(allowed) => vec_index.search_within(query_embedding, top_k * 2, allowed),
+            None => vec_index.search(query_embedding, top_k * 2),
+        };
 
         if vec_hits.is_empty() {
             let elapsed_ms = start_time.elapsed().as_millis();
@@ -294,6 +435,7 @@ impl Memvid {
                 context: build_context(&[]),
                 next_cursor: None,
                 engine: SearchEngineKind::Hybrid,
+                facets: Vec::new(),
             });
         }
 
@@ -308,10 +450,18 @@ impl Memvid {
                 None => continue,
             };
 
-            if let Some(scope_prefix) = scope {
-                let default_uri = crate::default_uri(frame.id);
-                let uri = frame.uri.as_ref().unwrap_or(&default_uri);
-                if !uri.starts_with(scope_prefix) {
+            if filter.is_some_and(|filter| !filter.matches(&frame)) {
+                continue;
+            }
+
+            let default_uri = crate::default_uri(frame.id);
+            let frame_uri = frame.uri.as_ref().unwrap_or(&default_uri);
+            if let Some(expected) = uri {
+                if !frame_uri.eq_ignore_ascii_case(expected) {
+                    continue;
+                }
+            } else if let Some(scope_prefix) = scope {
+                if !frame_uri.starts_with(scope_prefix) {
                     continue;
                 }
             }
@@ -362,6 +512,8 @@ impl Memvid {
                 chunk_text: Some(snippet),
                 score: Some(similarity_score),
                 metadata: Some(metadata),
+                score_breakdown: None,
+                highlights: None,
             });
 
             if hits.len() >= top_k {
@@ -389,9 +541,37 @@ impl Memvid {
             context,
             next_cursor: None,
             engine: SearchEngineKind::Hybrid,
+            facets: Vec::new(),
         })
     }
 
+    /// Frames a filtered vector search may return: those matching `filter` and, like
+    /// [`Self::vec_search_in`], the exact `uri` or else the `scope` prefix.
+    fn vec_candidate_frames(
+        &self,
+        scope: Option<&str>,
+        uri: Option<&str>,
+        filter: Option<&crate::types::MetadataFilter>,
+    ) -> HashSet<FrameId> {
+        self.toc
+            .frames
+            .iter()
+            .filter(|frame| filter.is_none_or(|filter| filter.matches(frame)))
+            .filter(|frame| {
+                let frame_uri = frame
+                    .uri
+                    .clone()
+                    .unwrap_or_else(|| crate::default_uri(frame.id));
+                match (uri, scope) {
+                    (Some(expected), _) => frame_uri.eq_ignore_ascii_case(expected),
+                    (None, Some(scope_prefix)) => frame_uri.starts_with(scope_prefix),
+                    (None, None) => true,
+                }
+            })
+            .map(|frame| frame.id)
+            .collect()
+    }
+
     /// Perform adaptive vector search that dynamically determines how many results to return.
     ///
     /// Unlike fixed `top_k` retrieval, adaptive search examines relevancy score distribution
@@ -419,17 +599,33 @@ impl Memvid {
         config: AdaptiveConfig,
         snippet_chars: usize,
         scope: Option<&str>,
+    ) -> Result<AdaptiveResult<SearchHit>> {
+        self.search_adaptive_in(None, query, query_embedding, &config, snippet_chars, scope)
+    }
+
+    /// [`Self::search_adaptive`] over an optional named vector space.
+    pub(crate) fn search_adaptive_in(
+        &mut self,
+        space: Option<&str>,
+        query: &str,
+        query_embedding: &[f32],
+        config: &AdaptiveConfig,
+        snippet_chars: usize,
+        scope: Option<&str>,
     ) -> Result<AdaptiveResult<SearchHit>> {
         use std::time::Instant;
 
         if !config.enabled {
             // Fall back to standard search with max_results as top_k
-            let response = self.vec_search_with_embedding(
+            let response = self.vec_search_in(
+                space,
                 query,
                 query_embedding,
                 config.max_results,
                 snippet_chars,
                 scope,
+                None,
+                None,
             )?;
             return Ok(AdaptiveResult {
                 results: response.hits,
@@ -448,12 +644,15 @@ impl Memvid {
         let start_time = Instant::now();
 
         // Over-retrieve: get max_results to have enough candidates
-        let response = self.vec_search_with_embedding(
+        let response = self.vec_search_in(
+            space,
             query,
             query_embedding,
             config.max_results,
             snippet_chars,
             scope,
+            None,
+            None,
         )?;
 
         if response.hits.is_empty() {
@@ -480,7 +679,7 @@ impl Memvid {
         }
 
         // Find adaptive cutoff
-        let (cutoff_index, triggered_by) = find_adaptive_cutoff(&scores, &config);
+        let (cutoff_index, triggered_by) = find_adaptive_cutoff(&scores, config);
 
         // Apply cutoff
         let mut results: Vec<SearchHit> = response.hits.into_iter().take(cutoff_index).collect();
@@ -624,19 +823,15 @@ impl Memvid {
     #[allow(dead_code)]
     fn materialize_tantivy_segments(&mut self, segments: &[EmbeddedLexSegment]) -> Result<TempDir> {
         let dir = TempDir::new().map_err(|err| MemvidError::Tantivy {
-            reason: format!("failed to allocate Tantivy work directory: {}", err),
+            reason: format!("failed to allocate Tantivy work directory: {err}"),
         })?;
         if segments.is_empty() {
             return Ok(dir);
         }
 
-        let mut file_len =
-            self.file
-                .metadata()
-                .map(|meta| meta.len())
-                .map_err(|err| MemvidError::Tantivy {
-                    reason: format!("failed to inspect memvid file metadata: {}", err),
-                })?;
+        let mut file_len = self.file.len().map_err(|err| MemvidError::Tantivy {
+            reason: format!("failed to inspect memvid file metadata: {err}"),
+        })?;
         let mut data_limit = self.header.footer_offset;
         let mut buffer = vec![0u8; 64 * 1024];
         let cursor = self.file.seek(SeekFrom::Current(0))?;
@@ -672,10 +867,8 @@ impl Memvid {
                 })?;
             if end > file_len || end > data_limit {
                 if self.align_footer_with_catalog()? {
-                    file_len = self.file.metadata().map(|meta| meta.len()).map_err(|err| {
-                        MemvidError::Tantivy {
-                            reason: format!("failed to refresh memvid file metadata: {}", err),
-                        }
+                    file_len = self.file.len().map_err(|err| MemvidError::Tantivy {
+                        reason: format!("failed to refresh memvid file metadata: {err}"),
                     })?;
                     data_limit = self.header.footer_offset;
                 }
@@ -712,6 +905,49 @@ impl Memvid {
         Ok(dir)
     }
 
+    /// Load embedded Tantivy segments into memory, so that the index of an encrypted
+    /// memory never reaches disk in plaintext.
+    fn load_tantivy_segments_in_ram(
+        &mut self,
+        segments: &[EmbeddedLexSegment],
+    ) -> Result<RamDirectory> {
+        let directory = RamDirectory::create();
+        let data_limit = self.file.len()?.min(self.header.footer_offset);
+        let cursor = self.file.stream_position()?;
+        for segment in segments {
+            let len = segment
+                .bytes_offset
+                .checked_add(segment.bytes_length)
+                .filter(|end| *end <= data_limit)
+                .and_then(|_| usize::try_from(segment.bytes_length).ok())
+                .ok_or_else(|| MemvidError::Tantivy {
+                    reason: format!(
+                        "embedded segment {} out of bounds (offset {} length {} data_limit {})",
+                        segment.path, segment.bytes_offset, segment.bytes_length, data_limit
+                    ),
+                })?;
+            let mut bytes = vec![0u8; len];
+            self.file.seek(SeekFrom::Start(segment.bytes_offset))?;
+            self.file.read_exact(&mut bytes)?;
+            directory
+                .atomic_write(Path::new(&segment.path), &bytes)
+                .map_err(|err| MemvidError::Tantivy {
+                    reason: format!("failed to load Tantivy segment {}: {}", segment.path, err),
+                })?;
+        }
+        self.file.seek(SeekFrom::Start(cursor))?;
+        Ok(directory)
+    }
+
+    pub(crate) fn create_tantivy_engine(&self) -> Result<TantivyEngine> {
+        let analyzer = self.lex_analyzer();
+        if self.file.is_encrypted() {
+            TantivyEngine::create_in_ram(&analyzer)
+        } else {
+            TantivyEngine::create(&analyzer)
+        }
+    }
+
     pub(crate) fn init_tantivy(&mut self) -> Result<()> {
         if !self.lex_enabled {
             self.tantivy = None;
@@ -746,23 +982,28 @@ impl Memvid {
             }
         };
 
+        let analyzer = self.lex_analyzer();
         let mut engine = match segments {
             Some(segments) => {
-                match self
-                    .materialize_tantivy_segments(&segments)
-                    .and_then(TantivyEngine::open_from_dir)
-                {
+                let opened = if self.file.is_encrypted() {
+                    self.load_tantivy_segments_in_ram(&segments)
+                        .and_then(|directory| TantivyEngine::open_in_ram(directory, &analyzer))
+                } else {
+                    self.materialize_tantivy_segments(&segments)
+                        .and_then(|dir| TantivyEngine::open_from_dir(dir, &analyzer))
+                };
+                match opened {
                     Ok(engine) => engine,
                     Err(err) => {
                         tracing::debug!(
                             "failed to open embedded Tantivy index: {}, rebuilding",
                             err
                         );
-                        TantivyEngine::create()?
+                        self.create_tantivy_engine()?
                     }
                 }
             }
-            None => TantivyEngine::create()?,
+            None => self.create_tantivy_engine()?,
         };
 
         // Use consolidated helper for expected doc count
@@ -807,6 +1048,56 @@ impl Memvid {
         Ok(())
     }
 
+    /// Catch the Tantivy engine up with a catalog that replaced `previous`.
+    ///
+    /// Segment files that are new or changed are copied into the open index; anything the
+    /// catalog cannot express incrementally (a new analyzer, legacy lex storage) reopens it.
+    pub(crate) fn refresh_tantivy(
+        &mut self,
+        previous: &[TantivySegmentDescriptor],
+        analyzer_changed: bool,
+    ) -> Result<()> {
+        let current = &self.toc.segment_catalog.tantivy_segments;
+        if analyzer_changed || previous.is_empty() || current.is_empty() || self.tantivy.is_none() {
+            return self.init_tantivy();
+        }
+        let known: HashSet<(&str, [u8; 32])> = previous
+            .iter()
+            .map(|descriptor| (descriptor.path.as_str(), descriptor.common.checksum))
+            .collect();
+        let fresh: Vec<TantivySegmentDescriptor> = current
+            .iter()
+            .filter(|descriptor| {
+                !known.contains(&(descriptor.path.as_str(), descriptor.common.checksum))
+            })
+            .cloned()
+            .collect();
+
+        let mut files = Vec::with_capacity(fresh.len());
+        for descriptor in fresh {
+            let length = usize::try_from(descriptor.common.bytes_length).map_err(|_| {
+                MemvidError::Tantivy {
+                    reason: format!("segment {} does not fit in memory", descriptor.path),
+                }
+            })?;
+            let mut bytes = vec![0u8; length];
+            self.file
+                .seek(SeekFrom::Start(descriptor.common.bytes_offset))?;
+            self.file.read_exact(&mut bytes)?;
+            files.push((descriptor.path, bytes));
+        }
+        let applied = match self.tantivy.as_mut() {
+            Some(engine) => engine.apply_index_files(&files),
+            None => return self.init_tantivy(),
+        };
+        if let Err(err) = applied {
+            tracing::debug!("failed to apply new Tantivy segments: {}, reopening", err);
+            return self.init_tantivy();
+        }
+        self.tantivy_dirty = false;
+        Ok(())
+    }
+
     pub fn vec_segment_descriptor(&self, segment_id: u64) -> Option<VecSegmentDescriptor> {
         self.toc
             .segment_catalog
@@ -930,6 +1221,13 @@ pub fn is_frame_text_indexable(frame: &crate::types::Frame) -> bool {
 #[cfg(feature = "lex")]
 impl Memvid {
     pub(crate) fn rebuild_tantivy_engine(&mut self, engine: &mut TantivyEngine) -> Result<bool> {
+        let prepared_docs = self.tantivy_rebuild_docs()?;
+        index_tantivy_docs(engine, &prepared_docs)?;
+        Ok(true)
+    }
+
+    /// Collect the active frames a Tantivy rebuild indexes, paired with their search text.
+    pub(crate) fn tantivy_rebuild_docs(&mut self) -> Result<Vec<(Frame, String)>> {
         let mut prepared_docs: Vec<(Frame, String)> = Vec::new();
         let frames = self.toc.frames.clone();
         let active_frames: Vec<_> = frames
@@ -984,18 +1282,20 @@ impl Memvid {
             }
             prepared_docs.push((frame, text));
         }
+        Ok(prepared_docs)
+    }
+}
 
-        if prepared_docs.is_empty() {
-            engine.reset()?;
-            engine.commit()?;
-            return Ok(true);
-        }
-
-        engine.reset()?;
-        for (frame, text) in &prepared_docs {
-            engine.add_frame(frame, text)?;
-        }
-        engine.commit()?;
-        Ok(true)
+/// Replace the contents of `engine` with `docs` and commit them.
+#[cfg(feature = "lex")]
+pub(crate) fn index_tantivy_docs(
+    engine: &mut TantivyEngine,
+    docs: &[(Frame, String)],
+) -> Result<()> {
+    engine.reset()?;
+    for (frame, text) in docs {
+        engine.add_frame(frame, text)?;
     }
+    engine.commit()?;
+    Ok(())
 }
@@ -1,11 +1,15 @@
 use std::collections::HashMap;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::{Read, Seek, SeekFrom, Write};
 use std::panic::{AssertUnwindSafe, catch_unwind};
 
 use crate::lex::{LexIndex, LexIndexArtifact, LexIndexBuilder};
 use crate::memvid::lifecycle::Memvid;
-use crate::types::{Frame, FrameId, FrameStatus, VectorCompression};
-use crate::{MemvidError, Result, VecIndex, VecIndexArtifact};
+use crate::types::{
+    Frame, FrameId, FrameStatus, VecIndexManifest, VecSegmentDescriptor, VectorCompression,
+};
+use crate::vec::VecDocument;
+use crate::vec_hnsw::{HnswConfig, HnswIndex, HnswSegmentLink};
+use crate::{MemvidError, Result, VecIndex};
 
 impl Memvid {
     #[allow(dead_code)]
@@ -41,27 +45,58 @@ impl Memvid {
         Ok(Some((artifact, index)))
     }
 
-    pub(crate) fn build_vec_artifact(
-        &mut self,
+    /// Fold `new_docs` into `existing`, dropping vectors of inactive frames.
+    ///
+    /// Flat indexes are promoted to an HNSW graph once they reach `hnsw_config.min_vectors`.
+    pub(crate) fn merge_vec_index(
+        &self,
+        existing: Option<VecIndex>,
         new_docs: &[(FrameId, Vec<f32>)],
-    ) -> Result<Option<(VecIndexArtifact, VecIndex)>> {
-        if !self.vec_enabled {
+    ) -> Result<VecIndex> {
+        merge_vec_index(existing, new_docs, self.hnsw_config, |frame_id| {
+            self.frame_is_active(frame_id)
+        })
+    }
+
+    /// Append the changes of `graph` since its last persisted segment at `offset`.
+    ///
+    /// The segment extends the chain headed by `previous` when `graph` was loaded from or
+    /// last written to it, and starts a new chain otherwise. Returns the manifest of the new
+    /// chain head, or `None` when the graph had no unpersisted changes.
+    pub(crate) fn append_hnsw_segment(
+        &mut self,
+        graph: &mut HnswIndex,
+        previous: Option<&VecIndexManifest>,
+        offset: u64,
+    ) -> Result<Option<VecIndexManifest>> {
+        let link = previous
+            .filter(|manifest| graph.is_chained() && manifest.bytes_length != 0)
+            .filter(|manifest| manifest.bytes_offset < offset)
+            .map(|manifest| HnswSegmentLink {
+                back: offset - manifest.bytes_offset,
+                length: manifest.bytes_length,
+                checksum: manifest.checksum,
+            });
+        let Some((artifact, depth)) = graph.encode_segment(link)? else {
             return Ok(None);
-        }
-        let mut builder = VecIndexBuilder::new();
-        if let Some(index) = self.vec_index.as_ref() {
-            for (frame_id, embedding) in index.entries() {
-                if self.frame_is_active(frame_id) {
-                    builder.add_document(frame_id, embedding.to_vec());
-                }
-            }
-        }
-        for (frame_id, embedding) in new_docs {
-            builder.add_document(*frame_id, embedding.clone());
-        }
-        let artifact = builder.finish()?;
-        let index = VecIndex::decode(&artifact.bytes)?;
-        Ok(Some((artifact, index)))
+        };
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(&artifact.bytes)?;
+        graph.mark_persisted(depth);
+        tracing::debug!(
+            offset,
+            bytes = artifact.bytes.len(),
+            depth,
+            "appended HNSW segment"
+        );
+        Ok(Some(VecIndexManifest {
+            vector_count: artifact.vector_count,
+            dimension: artifact.dimension,
+            bytes_offset: offset,
+            bytes_length: artifact.bytes.len() as u64,
+            checksum: artifact.checksum,
+            compression_mode: VectorCompression::None,
+        }))
     }
 
     pub(crate) fn ensure_lex_index(&mut self) -> Result<()> {
@@ -130,6 +165,7 @@ impl Memvid {
                 return Ok(());
             }
 
+            let manifest = manifest.clone();
             let bytes = match self.read_range(manifest.bytes_offset, manifest.bytes_length) {
                 Ok(bytes) => bytes,
                 Err(_) => {
@@ -139,7 +175,15 @@ impl Memvid {
                     return Ok(());
                 }
             };
-            match catch_unwind(AssertUnwindSafe(|| VecIndex::decode(&bytes))) {
+            let decoded = catch_unwind(AssertUnwindSafe(|| {
+                VecIndex::decode_chain(
+                    &bytes,
+                    manifest.bytes_offset,
+                    manifest.compression_mode.clone(),
+                    |offset, length| self.read_range(offset, length),
+                )
+            }));
+            match decoded {
                 Ok(Ok(index)) => self.vec_index = Some(index),
                 Ok(Err(_)) | Err(_) => {
                     self.vec_index = None;
@@ -186,7 +230,7 @@ impl Memvid {
     }
 
     pub fn read_range(&mut self, offset: u64, length: u64) -> Result<Vec<u8>> {
-        let file_len = self.file.metadata()?.len();
+        let file_len = self.file.len()?;
         let end = offset.checked_add(length).ok_or(MemvidError::InvalidToc {
             reason: "manifest range overflow".into(),
         })?;
@@ -217,47 +261,8 @@ impl Memvid {
         let segments = self.toc.segment_catalog.vec_segments.clone();
 
         for segment_desc in &segments {
-            let bytes = match self.read_range(
-                segment_desc.common.bytes_offset,
-                segment_desc.common.bytes_length,
-            ) {
-                Ok(bytes) => bytes,
-                Err(err) => {
-                    tracing::warn!(
-                        error = %err,
-                        segment_id = segment_desc.common.segment_id,
-                        "failed to load vec segment, skipping"
-                    );
-                    continue;
-                }
-            };
-
-            // Use the compression stored in the descriptor - it's already correct
-            // The descriptor reflects the actual encoding used when the segment was written
-            let compression_hint = segment_desc.vector_compression.clone();
-
-            tracing::debug!(
-                segment_id = segment_desc.common.segment_id,
-                compression_hint = ?compression_hint,
-                bytes_len = bytes.len(),
-                "attempting to decode vec segment"
-            );
-
-            match VecIndex::decode_with_compression(&bytes, compression_hint) {
-                Ok(segment_index) => {
-                    for (frame_id, embedding) in segment_index.entries() {
-                        if self.frame_is_active(frame_id) {
-                            builder.add_document(frame_id, embedding.to_vec());
-                        }
-                    }
-                }
-                Err(err) => {
-                    tracing::warn!(
-                        error = %err,
-                        segment_id = segment_desc.common.segment_id,
-                        "failed to decode vec segment, skipping"
-                    );
-                }
+            for (frame_id, embedding) in self.vec_segment_entries(segment_desc) {
+                builder.add_document(frame_id, embedding);
             }
         }
 
@@ -271,6 +276,54 @@ impl Memvid {
         Ok(())
     }
 
+    /// Vectors of active frames stored in one vec segment; unreadable segments are skipped.
+    pub(crate) fn vec_segment_entries(
+        &mut self,
+        segment_desc: &VecSegmentDescriptor,
+    ) -> Vec<(FrameId, Vec<f32>)> {
+        let bytes = match self.read_range(
+            segment_desc.common.bytes_offset,
+            segment_desc.common.bytes_length,
+        ) {
+            Ok(bytes) => bytes,
+            Err(err) => {
+                tracing::warn!(
+                    error = %err,
+                    segment_id = segment_desc.common.segment_id,
+                    "failed to load vec segment, skipping"
+                );
+                return Vec::new();
+            }
+        };
+
+        // Use the compression stored in the descriptor - it's already correct
+        // The descriptor reflects the actual encoding used when the segment was written
+        let compression_hint = segment_desc.vector_compression.clone();
+
+        tracing::debug!(
+            segment_id = segment_desc.common.segment_id,
+            compression_hint = ?compression_hint,
+            bytes_len = bytes.len(),
+            "attempting to decode vec segment"
+        );
+
+        match VecIndex::decode_with_compression(&bytes, compression_hint) {
+            Ok(segment_index) => segment_index
+                .entries()
+                .filter(|(frame_id, _)| self.frame_is_active(*frame_id))
+                .map(|(frame_id, embedding)| (frame_id, embedding.to_vec()))
+                .collect(),
+            Err(err) => {
+                tracing::warn!(
+                    error = %err,
+                    segment_id = segment_desc.common.segment_id,
+                    "failed to decode vec segment, skipping"
+                );
+                Vec::new()
+            }
+        }
+    }
+
     fn hydrate_lex_index_metadata(&self, index: &mut LexIndex) {
         for document in index.documents_mut() {
             let frame_meta = self.toc.frames.get(document.frame_id as usize);
@@ -297,4 +350,55 @@ impl Memvid {
     }
 }
 
-use crate::vec::VecIndexBuilder;
+/// Fold `new_docs` into `existing`, dropping vectors of inactive frames.
+///
+/// `is_active` reports whether a frame is still active; flat indexes are promoted to an
+/// HNSW graph once they reach `hnsw_config.min_vectors`.
+pub(crate) fn merge_vec_index(
+    existing: Option<VecIndex>,
+    new_docs: &[(FrameId, Vec<f32>)],
+    hnsw_config: HnswConfig,
+    is_active: impl Fn(FrameId) -> bool,
+) -> Result<VecIndex> {
+    // An HNSW graph is updated in place: inactive frames are tombstoned and new vectors
+    // are linked into the existing layers instead of re-inserting the whole corpus.
+    if let Some(VecIndex::Hnsw(mut graph)) = existing {
+        let stale: Vec<FrameId> = graph
+            .entries()
+            .map(|(frame_id, _)| frame_id)
+            .filter(|frame_id| !is_active(*frame_id))
+            .collect();
+        for frame_id in stale {
+            graph.remove(frame_id);
+        }
+        for (frame_id, embedding) in new_docs {
+            graph.insert(*frame_id, embedding.clone())?;
+        }
+        if graph.needs_compaction() {
+            graph = graph.compacted(hnsw_config)?;
+        }
+        return Ok(VecIndex::Hnsw(graph));
+    }
+
+    let mut documents: Vec<(FrameId, Vec<f32>)> = Vec::new();
+    if let Some(index) = existing.as_ref() {
+        for (frame_id, embedding) in index.entries() {
+            if is_active(frame_id) {
+                documents.push((frame_id, embedding.to_vec()));
+            }
+        }
+    }
+    documents.extend(new_docs.iter().cloned());
+
+    if documents.len() >= hnsw_config.min_vectors as usize {
+        return HnswIndex::build(hnsw_config, documents).map(VecIndex::Hnsw);
+    }
+    let documents = documents
+        .into_iter()
+        .map(|(frame_id, embedding)| VecDocument {
+            frame_id,
+            embedding,
+        })
+        .collect();
+    Ok(VecIndex::Uncompressed { documents })
+}
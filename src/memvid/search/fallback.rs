@@ -2,7 +2,10 @@
 
 #[cfg(feature = "temporal_track")]
 use super::helpers::attach_temporal_metadata;
-use super::helpers::{build_context, empty_search_response, parse_cursor, timestamp_to_rfc3339};
+use super::helpers::{
+    build_context, chunk_passages, compute_facets, empty_search_response, parse_cursor,
+    timestamp_to_rfc3339,
+};
 use crate::lex::{LexMatch, compute_snippet_slices};
 use crate::memvid::lifecycle::Memvid;
 use crate::search::{EvaluationContext, ParsedQuery};
@@ -11,6 +14,7 @@ use crate::types::{
     SearchResponse,
 };
 use crate::{MemvidError, Result};
+use std::cmp::Ordering;
 use std::collections::HashSet;
 use std::time::Instant;
 
@@ -23,6 +27,17 @@ pub(super) fn search_with_lex_fallback(
     start_time: Instant,
     candidate_filter: Option<&HashSet<FrameId>>,
 ) -> Result<SearchResponse> {
+    if query_tokens.is_empty() {
+        // Only fuzzy terms: nothing to look up by substring, so scan every frame.
+        return search_with_filters_only(
+            memvid,
+            parsed,
+            request,
+            params,
+            start_time,
+            candidate_filter,
+        );
+    }
     let index = memvid
         .lex_index
         .as_ref()
@@ -58,6 +73,7 @@ pub(super) fn search_with_lex_fallback(
         if !parsed.evaluate(&ctx) {
             continue;
         }
+        let score = matched.score * parsed.boost_factor(&ctx);
 
         let slices = compute_snippet_slices(
             &matched.content,
@@ -65,10 +81,23 @@ pub(super) fn search_with_lex_fallback(
             snippet_window,
             max_snippets_per_doc,
         );
-        evaluated.push((matched, slices));
+        let passages = chunk_passages(
+            &matched.content,
+            matched.chunk_offset,
+            &matched.occurrences,
+            slices,
+            request.fragments,
+            snippet_window,
+        );
+        evaluated.push((matched, score, passages));
     }
+    // Boosts can reorder sections that `compute_matches` ranked by raw score.
+    evaluated.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(Ordering::Equal));
 
-    let total_slices: usize = evaluated.iter().map(|(_, slices)| slices.len()).sum();
+    let total_slices: usize = evaluated
+        .iter()
+        .map(|(_, _, passages)| passages.len())
+        .sum();
     if total_slices == 0 {
         let elapsed_ms = start_time.elapsed().as_millis();
         return Ok(empty_search_response(
@@ -79,12 +108,20 @@ pub(super) fn search_with_lex_fallback(
         ));
     }
 
+    let facets = compute_facets(
+        memvid,
+        &request.facets,
+        evaluated
+            .iter()
+            .filter(|(_, _, passages)| !passages.is_empty())
+            .map(|(matched, _, _)| matched.frame_id),
+    );
     let offset = parse_cursor(request.cursor.as_deref(), total_slices)?;
     let effective_top_k = request.top_k.max(1);
 
     let mut hits = Vec::new();
     let mut produced = 0usize;
-    for (matched, slices) in evaluated {
+    for (matched, score, passages) in evaluated {
         let frame_meta = memvid
             .toc
             .frames
@@ -111,7 +148,8 @@ pub(super) fn search_with_lex_fallback(
             .or_else(|| frame_meta.title.clone())
             .or_else(|| crate::infer_title_from_uri(&uri));
 
-        for (start, end) in slices {
+        for passage in passages {
+            let (start, end) = passage.range;
             if produced < offset {
                 produced += 1;
                 continue;
@@ -166,8 +204,10 @@ pub(super) fn search_with_lex_fallback(
                 matches: matches_in_slice,
                 chunk_range: Some(chunk_range),
                 chunk_text: Some(chunk_text),
-                score: Some(matched.score),
+                score: Some(score),
                 metadata: Some(metadata),
+                score_breakdown: None,
+                highlights: Some(passage.highlights),
             });
             produced += 1;
         }
@@ -193,6 +233,7 @@ pub(super) fn search_with_lex_fallback(
         context,
         next_cursor,
         engine: SearchEngineKind::LexFallback,
+        facets,
     })
 }
 
@@ -243,9 +284,15 @@ pub(super) fn search_with_filters_only(
             context: build_context(&[]),
             next_cursor: None,
             engine: SearchEngineKind::LexFallback,
+            facets: Vec::new(),
         });
     }
 
+    let facets = compute_facets(
+        memvid,
+        &request.facets,
+        matches.iter().map(|(frame_id, _, _)| *frame_id),
+    );
     let offset = parse_cursor(request.cursor.as_deref(), total_hits)?;
     let effective_top_k = request.top_k.max(1);
     let mut hits = Vec::new();
@@ -290,6 +337,8 @@ pub(super) fn search_with_filters_only(
             chunk_text: Some(snippet),
             score: None,
             metadata: Some(metadata),
+            score_breakdown: None,
+            highlights: None,
         });
         produced += 1;
     }
@@ -314,5 +363,6 @@ pub(super) fn search_with_filters_only(
         context,
         next_cursor,
         engine: SearchEngineKind::LexFallback,
+        facets,
     })
 }
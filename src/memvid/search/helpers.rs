@@ -1,14 +1,20 @@
 use crate::MemvidError;
 use crate::Result;
+use crate::lex::compute_snippet_slices;
 use crate::memvid::lifecycle::Memvid;
 #[cfg(not(feature = "temporal_track"))]
 #[allow(unused_imports)]
 use crate::types::FrameId;
+use crate::types::reranker::{RerankerDocument, RerankerHook};
+use crate::types::{
+    FacetCounts, FacetField, FacetValueCount, FrameStatus, HitHighlights, MetadataFilter,
+    SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams, SearchResponse, SnippetFragment,
+    SnippetFragments,
+};
 #[cfg(feature = "temporal_track")]
 use crate::types::{
     FrameId, SearchHitTemporal, SearchHitTemporalAnchor, SearchHitTemporalMention, TemporalMention,
 };
-use crate::types::{SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams, SearchResponse};
 #[cfg(feature = "temporal_track")]
 use std::collections::HashMap;
 #[cfg(feature = "temporal_track")]
@@ -31,6 +37,7 @@ pub(super) fn empty_search_response(
         context: String::new(),
         next_cursor: None,
         engine,
+        facets: Vec::new(),
     }
 }
 
@@ -64,10 +71,158 @@ pub(super) fn parse_cursor(cursor: Option<&str>, total_hits: usize) -> Result<us
     Ok(value)
 }
 
+/// Count the requested facets over the distinct frames in a matched set.
+pub(super) fn compute_facets(
+    memvid: &Memvid,
+    fields: &[FacetField],
+    frame_ids: impl IntoIterator<Item = crate::types::FrameId>,
+) -> Vec<FacetCounts> {
+    if fields.is_empty() {
+        return Vec::new();
+    }
+    let frame_ids: StdHashSet<crate::types::FrameId> = frame_ids.into_iter().collect();
+    let mut counters: Vec<BTreeMap<String, usize>> = vec![BTreeMap::new(); fields.len()];
+    for frame_id in frame_ids {
+        let Some(frame) = usize::try_from(frame_id)
+            .ok()
+            .and_then(|index| memvid.toc.frames.get(index))
+        else {
+            continue;
+        };
+        for (field, counter) in fields.iter().zip(counters.iter_mut()) {
+            let values: StdHashSet<String> = match field {
+                FacetField::Tag => frame.tags.iter().cloned().collect(),
+                FacetField::Label => frame.labels.iter().cloned().collect(),
+                FacetField::Track => frame.track.iter().cloned().collect(),
+                FacetField::Kind => frame.kind.iter().cloned().collect(),
+                FacetField::Month => OffsetDateTime::from_unix_timestamp(frame.timestamp)
+                    .ok()
+                    .map(|dt| format!("{:04}-{:02}", dt.year(), u8::from(dt.month())))
+                    .into_iter()
+                    .collect(),
+            };
+            for value in values {
+                *counter.entry(value).or_default() += 1;
+            }
+        }
+    }
+
+    fields
+        .iter()
+        .zip(counters)
+        .map(|(field, counter)| {
+            let mut values: Vec<FacetValueCount> = counter
+                .into_iter()
+                .map(|(value, count)| FacetValueCount { value, count })
+                .collect();
+            // Stable sort keeps values alphabetical within equal counts.
+            values.sort_by(|a, b| b.count.cmp(&a.count));
+            FacetCounts {
+                field: *field,
+                values,
+            }
+        })
+        .collect()
+}
+
+/// Active frames satisfying a metadata filter, used to narrow every retrieval path.
+pub(crate) fn frame_ids_matching_filter(
+    memvid: &Memvid,
+    filter: &MetadataFilter,
+) -> StdHashSet<crate::types::FrameId> {
+    memvid
+        .toc
+        .frames
+        .iter()
+        .filter(|frame| frame.status == FrameStatus::Active && filter.matches(frame))
+        .map(|frame| frame.id)
+        .collect()
+}
+
+/// Drop hits whose frames do not satisfy `filter`.
+pub(crate) fn retain_hits_matching_filter(
+    hits: &mut Vec<SearchHit>,
+    memvid: &Memvid,
+    filter: &MetadataFilter,
+) {
+    hits.retain(|hit| {
+        usize::try_from(hit.frame_id)
+            .ok()
+            .and_then(|index| memvid.toc.frames.get(index))
+            .is_some_and(|frame| filter.matches(frame))
+    });
+    for (index, hit) in hits.iter_mut().enumerate() {
+        hit.rank = index + 1;
+    }
+}
+
+/// Rerank the leading `hook.config.max_candidates` hits on their chunk text, keeping at most
+/// `min(limit, hook.config.top_k)` hits that score at least `hook.config.min_score`.
+///
+/// Also returns how many candidates scored at least `hook.config.min_score`, which is the
+/// `total_hits` of the reranked response.
+pub(crate) fn rerank_hits(
+    query: &str,
+    mut hits: Vec<SearchHit>,
+    hook: &RerankerHook,
+    limit: usize,
+) -> Result<(Vec<SearchHit>, usize)> {
+    hits.truncate(hook.config.max_candidates.max(1));
+    if hits.is_empty() {
+        return Ok((hits, 0));
+    }
+
+    // Documents are keyed by candidate position: one frame may contribute several snippets.
+    let documents: Vec<RerankerDocument> = hits
+        .iter()
+        .enumerate()
+        .map(|(idx, hit)| {
+            let text = hit.chunk_text.as_deref().unwrap_or(&hit.text);
+            if hook.config.use_metadata {
+                let metadata = match &hit.title {
+                    Some(title) => format!("{title} {}", hit.uri),
+                    None => hit.uri.clone(),
+                };
+                RerankerDocument::with_metadata(idx as u64, text, metadata)
+            } else {
+                RerankerDocument::new(idx as u64, text)
+            }
+        })
+        .collect();
+    let keep = limit.min(hook.config.top_k).max(1);
+    // Score every candidate so the ones passing `min_score` can be counted.
+    let results = hook.reranker().rerank(query, &documents, documents.len())?;
+    let matched = results
+        .iter()
+        .filter(|result| result.score >= hook.config.min_score)
+        .count();
+
+    let mut slots: Vec<Option<SearchHit>> = hits.into_iter().map(Some).collect();
+    let mut reranked = Vec::with_capacity(keep);
+    for result in results {
+        if reranked.len() == keep {
+            break;
+        }
+        if result.score < hook.config.min_score {
+            continue;
+        }
+        if let Some(mut hit) = usize::try_from(result.id)
+            .ok()
+            .and_then(|index| slots.get_mut(index))
+            .and_then(Option::take)
+        {
+            hit.score = Some(result.score);
+            hit.rank = reranked.len() + 1;
+            reranked.push(hit);
+        }
+    }
+    Ok((reranked, matched))
+}
+
 /// Build context for LLM from search hits using a multi-document strategy.
 ///
 /// Key design decisions for deterministic, comprehensive context:
-/// 1. Uses BTreeMap for deterministic iteration order (sorted by URI)
+/// 1. Uses `BTreeMap` for deterministic iteration order (sorted by URI)
 /// 2. Includes top hits from MULTIPLE documents for diverse context
 /// 3. Prioritizes by rank while ensuring document diversity
 /// 4. Maximum 24 hits for balanced context (not too much noise, not too little coverage)
@@ -201,6 +356,89 @@ pub(super) fn collect_token_occurrences(
     occurrences
 }
 
+/// A passage of a matched chunk returned as one hit.
+pub(super) struct Passage {
+    /// Byte range within the chunk text.
+    pub(super) range: (usize, usize),
+    pub(super) highlights: HitHighlights,
+}
+
+/// Split a matched chunk into the passages returned as hits.
+///
+/// `spans` are the highlighted byte ranges in `chunk_text`. Without `fragments` every snippet
+/// slice is its own passage; with it the chunk yields a single passage for its best fragment,
+/// and up to `max_fragments` fragments are listed best first.
+pub(super) fn chunk_passages(
+    chunk_text: &str,
+    chunk_start: usize,
+    spans: &[(usize, usize)],
+    slices: Vec<(usize, usize)>,
+    fragments: Option<SnippetFragments>,
+    window: usize,
+) -> Vec<Passage> {
+    let Some(fragments) = fragments else {
+        return slices
+            .into_iter()
+            .map(|range| Passage {
+                range,
+                highlights: HitHighlights {
+                    text: spans_within(spans, range),
+                    chunk: spans.to_vec(),
+                    fragments: Vec::new(),
+                },
+            })
+            .collect();
+    };
+
+    let mut candidates = if spans.is_empty() {
+        slices
+    } else {
+        compute_snippet_slices(chunk_text, spans, window, usize::MAX)
+    };
+    // Most distinct matched words first, then most matches; earlier fragments win ties.
+    candidates.sort_by_cached_key(|range| {
+        let inside = spans_within(spans, *range);
+        let words: StdHashSet<String> = inside
+            .iter()
+            .map(|(start, end)| chunk_text[range.0 + start..range.0 + end].to_lowercase())
+            .collect();
+        (
+            std::cmp::Reverse(words.len()),
+            std::cmp::Reverse(inside.len()),
+        )
+    });
+    candidates.truncate(fragments.max_fragments.max(1));
+    let Some(&best) = candidates.first() else {
+        return Vec::new();
+    };
+
+    let fragments = candidates
+        .into_iter()
+        .map(|(start, end)| SnippetFragment {
+            range: (chunk_start + start, chunk_start + end),
+            text: chunk_text[start..end].to_string(),
+            highlights: spans_within(spans, (start, end)),
+        })
+        .collect();
+    vec![Passage {
+        range: best,
+        highlights: HitHighlights {
+            text: spans_within(spans, best),
+            chunk: spans.to_vec(),
+            fragments,
+        },
+    }]
+}
+
+/// The `spans` lying inside `range`, made relative to its start.
+fn spans_within(spans: &[(usize, usize)], range: (usize, usize)) -> Vec<(usize, usize)> {
+    spans
+        .iter()
+        .filter(|(start, end)| *start >= range.0 && *end <= range.1)
+        .map(|(start, end)| (start - range.0, end - range.0))
+        .collect()
+}
+
 pub(crate) fn reorder_hits_by_token_matches(hits: &mut Vec<SearchHit>, tokens: &[String]) {
     if hits.is_empty() || tokens.is_empty() {
         return;
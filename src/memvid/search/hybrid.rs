@@ -0,0 +1,191 @@
+//! Hybrid retrieval: lexical search fused with vector search over the same request.
+//!
+//! Each retriever is asked for a candidate pool larger than `top_k`; lexical snippets are
+//! collapsed to one hit per frame before fusion so both rankings are frame rankings.
+
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+use super::helpers::build_context;
+use crate::Result;
+use crate::memvid::lifecycle::Memvid;
+use crate::types::{
+    FrameId, FusionStrategy, HitScoreBreakdown, HybridSearch, SearchEngineKind, SearchHit,
+    SearchParams, SearchRequest, SearchResponse, VecEmbedder, normalize_scores,
+};
+
+impl Memvid {
+    /// Hybrid search that embeds the query text with `embedder` and fuses the vector results
+    /// with the lexical results using `fusion`.
+    ///
+    /// # Errors
+    ///
+    /// Fails when `embedder` cannot embed the query or when the search itself fails.
+    pub fn search_with_embedder<E>(
+        &mut self,
+        mut request: SearchRequest,
+        embedder: &E,
+        fusion: FusionStrategy,
+    ) -> Result<SearchResponse>
+    where
+        E: VecEmbedder + ?Sized,
+    {
+        let query_embedding = embedder.embed_query(&request.query)?;
+        request.hybrid = Some(HybridSearch::new(query_embedding).fusion(fusion));
+        self.search(request)
+    }
+}
+
+pub(super) fn search_hybrid(
+    memvid: &mut Memvid,
+    mut request: SearchRequest,
+    hybrid: &HybridSearch,
+) -> Result<SearchResponse> {
+    let start_time = Instant::now();
+    let top_k = request.top_k.max(1);
+    let candidates = hybrid
+        .candidates
+        .unwrap_or_else(|| top_k.saturating_mul(3).max(20))
+        .max(top_k);
+    let params = SearchParams {
+        top_k: request.top_k,
+        snippet_chars: request.snippet_chars,
+        cursor: None,
+    };
+
+    // Fused rankings are not pageable, so both retrievers start from the first result.
+    request.top_k = candidates;
+    request.cursor = None;
+    request.hybrid = None;
+    let lexical = memvid.search(request.clone())?;
+    let lexical_hits = first_hit_per_frame(lexical.hits);
+
+    let replay_ids: Option<HashSet<FrameId>> =
+        if request.as_of_frame.is_some() || request.as_of_ts.is_some() {
+            Some(memvid.get_replay_frame_ids(&request)?.into_iter().collect())
+        } else {
+            None
+        };
+    let vector = memvid.vec_search_in(
+        None,
+        &request.query,
+        &hybrid.query_embedding,
+        candidates,
+        request.snippet_chars,
+        request.scope.as_deref(),
+        request.uri.as_deref(),
+        request.filter.as_ref(),
+    )?;
+    let vector_hits: Vec<SearchHit> = vector
+        .hits
+        .into_iter()
+        .filter(|hit| {
+            replay_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&hit.frame_id))
+        })
+        .collect();
+
+    let mut fused = fuse(lexical_hits, vector_hits, hybrid.fusion);
+    let total_hits = fused.len();
+    fused.truncate(top_k);
+    for (index, hit) in fused.iter_mut().enumerate() {
+        hit.rank = index + 1;
+    }
+
+    let context = build_context(&fused);
+    Ok(SearchResponse {
+        query: request.query,
+        elapsed_ms: start_time.elapsed().as_millis(),
+        total_hits,
+        params,
+        hits: fused,
+        context,
+        next_cursor: None,
+        engine: SearchEngineKind::Hybrid,
+        facets: lexical.facets,
+    })
+}
+
+/// Keep the best-ranked snippet of every frame, preserving order.
+fn first_hit_per_frame(hits: Vec<SearchHit>) -> Vec<SearchHit> {
+    let mut seen = HashSet::new();
+    hits.into_iter()
+        .filter(|hit| seen.insert(hit.frame_id))
+        .collect()
+}
+
+// Ranks are bounded by the candidate pool, far below where `f32` loses integer precision.
+#[allow(clippy::cast_precision_loss)]
+fn fuse(
+    lexical: Vec<SearchHit>,
+    vector: Vec<SearchHit>,
+    strategy: FusionStrategy,
+) -> Vec<SearchHit> {
+    let lexical_norm = normalize_scores(
+        &lexical
+            .iter()
+            .map(|hit| hit.score.unwrap_or(0.0))
+            .collect::<Vec<_>>(),
+    );
+    let vector_norm = normalize_scores(
+        &vector
+            .iter()
+            .map(|hit| hit.score.unwrap_or(0.0))
+            .collect::<Vec<_>>(),
+    );
+
+    // Lexical hits carry highlighted snippets, so they represent frames found by both.
+    let mut order: Vec<FrameId> = Vec::new();
+    let mut entries: HashMap<FrameId, (SearchHit, HitScoreBreakdown, f32, f32)> = HashMap::new();
+    for (index, hit) in lexical.into_iter().enumerate() {
+        let breakdown = HitScoreBreakdown {
+            lexical_rank: Some(index + 1),
+            lexical_score: hit.score,
+            ..HitScoreBreakdown::default()
+        };
+        order.push(hit.frame_id);
+        entries.insert(hit.frame_id, (hit, breakdown, lexical_norm[index], 0.0));
+    }
+    for (index, hit) in vector.into_iter().enumerate() {
+        let rank = index + 1;
+        let score = hit.score;
+        let entry = entries.entry(hit.frame_id).or_insert_with(|| {
+            order.push(hit.frame_id);
+            (hit, HitScoreBreakdown::default(), 0.0, 0.0)
+        });
+        entry.1.vector_rank = Some(rank);
+        entry.1.vector_score = score;
+        entry.3 = vector_norm[index];
+    }
+
+    let mut fused: Vec<SearchHit> = order
+        .into_iter()
+        .filter_map(|frame_id| entries.remove(&frame_id))
+        .map(|(mut hit, mut breakdown, lexical_norm, vector_norm)| {
+            breakdown.fused_score = match strategy {
+                FusionStrategy::Rrf { k } => [breakdown.lexical_rank, breakdown.vector_rank]
+                    .into_iter()
+                    .flatten()
+                    .map(|rank| 1.0 / (k + rank as f32))
+                    .sum(),
+                FusionStrategy::Weighted { lexical, vector } => {
+                    lexical * lexical_norm + vector * vector_norm
+                }
+            };
+            hit.score = Some(breakdown.fused_score);
+            hit.score_breakdown = Some(breakdown);
+            hit
+        })
+        .collect();
+
+    // Stable sort: ties keep lexical order first, then vector order.
+    fused.sort_by(|a, b| {
+        b.score
+            .unwrap_or(0.0)
+            .partial_cmp(&a.score.unwrap_or(0.0))
+            .unwrap_or(Ordering::Equal)
+    });
+    fused
+}
@@ -1,7 +1,8 @@
 //! Search orchestration for `Memvid`.
 //!
 //! The search entrypoint chooses an engine (Tantivy lexical, optional temporal filters,
-//! and lex-only fallback) and returns fully decorated snippets with chunk metadata.
+//! lex-only fallback, or hybrid lexical+vector fusion) and returns fully decorated snippets
+//! with chunk metadata.
 //! Invariants: refuses empty queries, respects cursor/limit bounds, and never mutates
 //! the underlying file.
 
@@ -20,6 +21,8 @@ mod builders;
 mod fallback;
 pub(crate) mod helpers;
 #[cfg(feature = "lex")]
+mod hybrid;
+#[cfg(feature = "lex")]
 mod tantivy;
 #[cfg(any(feature = "lex", feature = "temporal_track"))]
 mod time_filter;
@@ -29,6 +32,10 @@ pub use api::{
     DEFAULT_MAX_INDEX_PAYLOAD, is_frame_text_indexable, is_text_indexable_mime, max_index_payload,
 };
 
+#[cfg(feature = "lex")]
+pub(crate) use api::index_tantivy_docs;
+pub(crate) use builders::merge_vec_index;
+
 #[cfg(feature = "lex")]
 use fallback::{search_with_filters_only, search_with_lex_fallback};
 use helpers::empty_search_response;
@@ -43,10 +50,42 @@ use time_filter::frame_ids_in_date_range;
 
 #[cfg(feature = "lex")]
 impl Memvid {
-    pub fn search(&mut self, request: SearchRequest) -> Result<SearchResponse> {
+    /// Run a lexical search, or a hybrid one when `request.hybrid` is set.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::LexNotEnabled`] when the lexical index is disabled, and when
+    /// an index or a hybrid query embedding cannot be used.
+    pub fn search(&mut self, mut request: SearchRequest) -> Result<SearchResponse> {
         if !self.lex_enabled {
             return Err(MemvidError::LexNotEnabled);
         }
+        if let Some(hook) = request.reranker.take() {
+            // Rerank the same candidate pool on every page, then page through the reranked
+            // list; the cursor is an offset into it.
+            let top_k = request.top_k.max(1);
+            let query = request.query.clone();
+            let cursor = request.cursor.take();
+            request.top_k = top_k.max(hook.config.max_candidates);
+            let mut response = self.search(request)?;
+            let (reranked, total_hits) =
+                helpers::rerank_hits(&query, response.hits, &hook, hook.config.top_k)?;
+            let offset = helpers::parse_cursor(cursor.as_deref(), reranked.len())?;
+            let end = offset.saturating_add(top_k).min(reranked.len());
+            response.next_cursor = (end < reranked.len()).then(|| end.to_string());
+            response.hits = reranked.into_iter().skip(offset).take(top_k).collect();
+            for (index, hit) in response.hits.iter_mut().enumerate() {
+                hit.rank = index + 1;
+            }
+            response.total_hits = total_hits;
+            response.params.top_k = top_k;
+            response.params.cursor = cursor;
+            response.context = helpers::build_context(&response.hits);
+            return Ok(response);
+        }
+        if let Some(hybrid) = request.hybrid.take() {
+            return hybrid::search_hybrid(self, request, &hybrid);
+        }
 
         let start_time = Instant::now();
         // parse_query can return structured tokens; we only keep non-empty, lower-cased terms.
@@ -59,7 +98,8 @@ impl Memvid {
             .collect::<BTreeSet<_>>()
             .into_iter()
             .collect();
-        let has_text_terms = !query_tokens.is_empty();
+        // Fuzzy terms leave no substring tokens but still constrain the text.
+        let has_text_terms = !query_tokens.is_empty() || parsed.contains_inexact_terms();
         let has_field_terms = parsed.contains_field_terms();
 
         if !has_text_terms && !has_field_terms {
@@ -123,7 +163,8 @@ impl Memvid {
                         candidate_filter = match candidate_filter {
                             Some(existing) => {
                                 let filtered: HashSet<FrameId> = existing
-                                    .into_iter()
+                                    .iter()
+                                    .copied()
                                     .filter(|id| new_set.contains(id))
                                     .collect();
                                 if filtered.is_empty() {
@@ -179,9 +220,40 @@ impl Memvid {
             };
         }
 
+        // METADATA FILTER: Narrow candidates to frames matching the typed filter
+        if let Some(ref filter) = request.filter {
+            let filter_set = helpers::frame_ids_matching_filter(self, filter);
+            candidate_filter = match candidate_filter {
+                Some(existing) => Some(
+                    existing
+                        .into_iter()
+                        .filter(|id| filter_set.contains(id))
+                        .collect(),
+                ),
+                None => Some(filter_set),
+            };
+            if candidate_filter.as_ref().is_some_and(HashSet::is_empty) {
+                let elapsed = start_time.elapsed().as_millis();
+                return Ok(empty_search_response(
+                    request.query.clone(),
+                    params.clone(),
+                    elapsed,
+                    SearchEngineKind::Tantivy,
+                ));
+            }
+        }
+
         // SKETCH PRE-FILTER: Use sketch track for fast candidate generation if available
-        // This dramatically reduces the number of documents sent to BM25/Tantivy
-        if self.has_sketches() && has_text_terms && !request.no_sketch {
+        // This dramatically reduces the number of documents sent to BM25/Tantivy.
+        // Sketches hash raw words, so skip them when a custom lex analyzer can match terms
+        // they never saw (stems, folded letters, identifier parts, n-grams), and for fuzzy,
+        // prefix and wildcard terms, which match words absent from the query.
+        if self.has_sketches()
+            && has_text_terms
+            && !request.no_sketch
+            && self.toc.indexes.lex_analyzer().is_none()
+            && !parsed.contains_inexact_terms()
+        {
             let sketch_start = Instant::now();
             let sketch_options = crate::SketchSearchOptions {
                 // Use relaxed threshold for better recall - BM25 will rerank anyway
@@ -207,12 +279,14 @@ impl Memvid {
                     Some(existing) => {
                         // Intersection: keep only IDs that pass both filters
                         let filtered: HashSet<FrameId> = existing
-                            .into_iter()
+                            .iter()
+                            .copied()
                             .filter(|id| sketch_set.contains(id))
                             .collect();
                         if filtered.is_empty() {
-                            // Fall back to sketch-only if intersection is empty
-                            Some(sketch_set)
+                            // The sketch missed every candidate; keep the existing filter
+                            // rather than dropping the caller's constraints.
+                            Some(existing)
                         } else {
                             Some(filtered)
                         }
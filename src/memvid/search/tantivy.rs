@@ -3,12 +3,13 @@
 #[cfg(feature = "temporal_track")]
 use super::helpers::attach_temporal_metadata;
 use super::helpers::{
-    build_context, collect_token_occurrences, parse_cursor, timestamp_to_rfc3339,
+    build_context, chunk_passages, collect_token_occurrences, compute_facets, parse_cursor,
+    timestamp_to_rfc3339,
 };
 use crate::lex::compute_snippet_slices;
 use crate::memvid::frame::ChunkInfo;
 use crate::memvid::lifecycle::Memvid;
-use crate::search::{EvaluationContext, ParsedQuery};
+use crate::search::{EvaluationContext, Highlighter, ParsedQuery};
 use crate::types::{
     FrameId, SearchEngineKind, SearchHit, SearchHitMetadata, SearchParams, SearchRequest,
     SearchResponse,
@@ -43,6 +44,7 @@ pub(super) fn try_tantivy_search(
         stemmed_tokens.extend(analyzed);
     }
     let stemmed_tokens = stemmed_tokens;
+    let mut highlighter = Highlighter::new(engine, parsed);
 
     let offset_hint = request
         .cursor
@@ -78,6 +80,18 @@ pub(super) fn try_tantivy_search(
             return Ok(None);
         }
     };
+    // Facets cover every matching document, not only the ranked window fetched above.
+    let facet_frame_ids = if request.facets.is_empty() || search_hits.is_empty() {
+        Vec::new()
+    } else {
+        match engine.matching_frame_ids(parsed, uri_filter, scope_filter, frame_filter_slice) {
+            Ok(ids) => ids,
+            Err(err) => {
+                warn!("tantivy facet collection failed: {err}");
+                search_hits.iter().map(|hit| hit.frame_id).collect()
+            }
+        }
+    };
     tracing::debug!(
         "tantivy hits for query '{}': {}",
         request.query,
@@ -174,14 +188,23 @@ pub(super) fn try_tantivy_search(
             snippet_window,
             max_snippets_per_doc,
         );
-        if slices.is_empty() {
+        let spans = highlighter.spans(&chunk_info.text);
+        let passages = chunk_passages(
+            &chunk_info.text,
+            chunk_info.start,
+            &spans,
+            slices,
+            request.fragments,
+            snippet_window,
+        );
+        if passages.is_empty() {
             tracing::debug!("tantivy hit {} culled: no snippet slices", frame_meta.id);
             continue;
         }
         // Use content_dates if available, otherwise fall back to frame timestamp
         let effective_ts = parse_content_date_to_timestamp(&frame_meta.content_dates)
             .unwrap_or(frame_meta.timestamp);
-        evaluated.push((hit, occurrences, slices, chunk_info, effective_ts));
+        evaluated.push((hit, occurrences, passages, chunk_info, effective_ts));
     }
 
     // Apply recency boosting: re-sort by combined score (BM25 + recency)
@@ -198,7 +221,7 @@ pub(super) fn try_tantivy_search(
         // Calculate recency-boosted scores and attach to items
         let mut with_scores: Vec<(f32, _)> = evaluated
             .into_iter()
-            .map(|(hit, occurrences, slices, chunk_info, timestamp)| {
+            .map(|(hit, occurrences, passages, chunk_info, timestamp)| {
                 let bm25_score = hit.score;
                 // Age relative to the most recent document in results
                 let age_seconds = (max_ts - timestamp).max(0) as f32;
@@ -210,7 +233,7 @@ pub(super) fn try_tantivy_search(
                 let combined_score = bm25_score * 0.4 + (bm25_score * recency_boost * 0.6);
                 (
                     combined_score,
-                    (hit, occurrences, slices, chunk_info, timestamp),
+                    (hit, occurrences, passages, chunk_info, timestamp),
                 )
             })
             .collect();
@@ -238,7 +261,7 @@ pub(super) fn try_tantivy_search(
 
     let total_slices: usize = evaluated
         .iter()
-        .map(|(_, _, slices, _, _)| slices.len())
+        .map(|(_, _, passages, _, _)| passages.len())
         .sum();
     if total_slices == 0 {
         tracing::debug!(
@@ -261,7 +284,7 @@ pub(super) fn try_tantivy_search(
 
     let mut hits = Vec::new();
     let mut produced = 0usize;
-    for (hit, occurrences, slices, chunk_info, _timestamp) in evaluated {
+    for (hit, occurrences, passages, chunk_info, _timestamp) in evaluated {
         if hits.len() == effective_top_k && produced >= offset {
             break;
         }
@@ -290,7 +313,8 @@ pub(super) fn try_tantivy_search(
         let chunk_bytes = chunk_text.as_bytes();
         let chunk_range = (chunk_start, chunk_end);
 
-        for (start, end) in slices {
+        for passage in passages {
+            let (start, end) = passage.range;
             if produced < offset {
                 produced += 1;
                 continue;
@@ -339,6 +363,8 @@ pub(super) fn try_tantivy_search(
                 chunk_text: Some(chunk_text.clone()),
                 score: Some(hit.score),
                 metadata: Some(metadata),
+                score_breakdown: None,
+                highlights: Some(passage.highlights),
             });
             produced += 1;
         }
@@ -349,6 +375,23 @@ pub(super) fn try_tantivy_search(
     } else {
         None
     };
+    let facets = compute_facets(
+        memvid,
+        &request.facets,
+        facet_frame_ids.into_iter().filter(|frame_id| {
+            let uri = usize::try_from(*frame_id)
+                .ok()
+                .and_then(|index| memvid.toc.frames.get(index))
+                .and_then(|frame| frame.uri.as_deref());
+            if let Some(uri_expected) = uri_filter {
+                uri_matches(uri, uri_expected)
+            } else if let Some(scope) = scope_filter {
+                uri.is_some_and(|uri| uri.starts_with(scope))
+            } else {
+                true
+            }
+        }),
+    );
     #[cfg(feature = "temporal_track")]
     attach_temporal_metadata(memvid, &mut hits)?;
     let elapsed_ms = start_time.elapsed().as_millis().max(1);
@@ -363,6 +406,7 @@ pub(super) fn try_tantivy_search(
         context,
         next_cursor,
         engine: SearchEngineKind::Tantivy,
+        facets,
     }))
 }
 
@@ -28,7 +28,7 @@ use crate::search::{EmbeddedLexSegment, TantivySnapshot};
 /// Minimum number of vectors required to use Product Quantization.
 /// Below this threshold, we fall back to uncompressed vectors.
 /// PQ requires training k-means on many vectors to learn good codebooks.
-const MIN_VECTORS_FOR_PQ: usize = 100;
+pub(crate) const MIN_VECTORS_FOR_PQ: usize = 100;
 
 #[derive(Debug)]
 pub(crate) struct LexSegmentArtifact {
@@ -712,10 +712,10 @@ impl Memvid {
                     existing.bytes_length = storage_manifest.bytes_length;
                 }
             } else {
-                self.toc.indexes.lex = Some(storage_manifest);
+                self.toc.indexes.replace_lex(Some(storage_manifest));
             }
         } else {
-            self.toc.indexes.lex = None;
+            self.toc.indexes.replace_lex(None);
         }
         self.toc.indexes.lex_segments = manifest_segments;
 
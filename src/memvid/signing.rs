@@ -0,0 +1,146 @@
+//! Content-signed memories.
+//!
+//! Every commit records a Merkle root over the frame records and index segment checksums in
+//! the TOC (see [`Toc::compute_merkle_root`](crate::types::Toc::compute_merkle_root)).
+//! Signing stores an Ed25519 signature over that root next to it, so a recipient holding the
+//! signer's public key can check that the memory has not been altered since it was signed.
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+use ed25519_dalek::{SigningKey, VerifyingKey};
+
+use crate::error::{MemvidError, Result};
+use crate::memvid::lifecycle::Memvid;
+use crate::signature::{sign_memory_root, verify_memory_signature};
+
+impl Memvid {
+    /// Sign the memory's content with `signing_key`.
+    ///
+    /// Pending changes are committed first. The signature is dropped by the next commit that
+    /// changes any frame or index, so sign again after further edits.
+    ///
+    /// # Errors
+    ///
+    /// Fails on a read-only handle, when pending changes cannot be committed, or when the
+    /// signed TOC cannot be written.
+    pub fn sign(&mut self, signing_key: &SigningKey) -> Result<()> {
+        self.ensure_writable()?;
+        self.commit()?;
+
+        let merkle_root = self.toc.compute_merkle_root()?;
+        self.toc.merkle_root = merkle_root;
+        self.toc.signature = Some(sign_memory_root(signing_key, &merkle_root)?);
+
+        self.generation = self.generation.wrapping_add(1);
+        self.rewrite_toc_footer()?;
+        self.header.toc_checksum = self.toc.toc_checksum;
+        crate::persist_header(&mut self.file, &self.header)?;
+        self.file.sync_all()?;
+        Ok(())
+    }
+
+    /// Public key of the signer, if the memory is signed.
+    #[must_use]
+    pub fn signer(&self) -> Option<VerifyingKey> {
+        let signature = self.toc.signature.as_ref()?;
+        VerifyingKey::from_bytes(&signature.public_key).ok()
+    }
+
+    /// Verify that `verifying_key` signed this memory and that nothing changed since.
+    ///
+    /// Checks the stored Merkle root against the TOC, every frame payload and index artifact
+    /// against its checksum, and the signature against the root.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::MemorySignatureInvalid`] when the memory is unsigned, was
+    /// signed by another key, or changed since it was signed.
+    pub fn verify_signature(&mut self, verifying_key: &VerifyingKey) -> Result<()> {
+        let signature =
+            self.toc
+                .signature
+                .clone()
+                .ok_or_else(|| MemvidError::MemorySignatureInvalid {
+                    reason: "memory is not signed".into(),
+                })?;
+        self.verify_merkle_root()?;
+        self.verify_frame_payloads()?;
+        self.verify_index_payloads()?;
+        verify_memory_signature(verifying_key, &self.toc.merkle_root, &signature)
+    }
+
+    /// Check that the stored Merkle root matches the frames and indexes in the TOC.
+    pub(crate) fn verify_merkle_root(&self) -> Result<()> {
+        if self.toc.compute_merkle_root()? != self.toc.merkle_root {
+            return Err(MemvidError::ChecksumMismatch {
+                context: "merkle root",
+            });
+        }
+        Ok(())
+    }
+
+    /// Re-hash every stored frame payload and compare it with the checksum in the TOC.
+    pub(crate) fn verify_frame_payloads(&mut self) -> Result<()> {
+        let frames: Vec<_> = self
+            .toc
+            .frames
+            .iter()
+            .filter(|frame| frame.payload_length > 0)
+            .cloned()
+            .collect();
+        for frame in frames {
+            let bytes = self.read_frame_payload_bytes(&frame)?;
+            if *blake3::hash(&bytes).as_bytes() != frame.checksum {
+                return Err(MemvidError::InvalidFrame {
+                    frame_id: frame.id,
+                    reason: "payload checksum mismatch",
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-hash every index artifact referenced by the TOC and compare it with its checksum.
+    ///
+    /// HNSW delta segments are covered through the chain head, which records the checksum
+    /// of its predecessor.
+    pub(crate) fn verify_index_payloads(&mut self) -> Result<()> {
+        let artifacts: Vec<_> = self
+            .toc
+            .index_artifacts()
+            .into_iter()
+            .filter(|artifact| artifact.bytes_length > 0)
+            .map(|artifact| {
+                (
+                    artifact.label,
+                    artifact.bytes_offset,
+                    artifact.bytes_length,
+                    *artifact.checksum,
+                )
+            })
+            .collect();
+        let file_len = self.file.len()?;
+        for (label, offset, length, checksum) in artifacts {
+            // Temporal tracks embed their checksum in their own header; the reader checks it.
+            if label == "temporal_track" || label.starts_with("catalog_temporal:") {
+                #[cfg(feature = "temporal_track")]
+                crate::io::temporal_index::read_track(&mut self.file, offset, length)?;
+                continue;
+            }
+            if offset.checked_add(length).is_none_or(|end| end > file_len) {
+                return Err(MemvidError::InvalidToc {
+                    reason: format!("index artifact {label} lies outside the file").into(),
+                });
+            }
+            self.file.seek(SeekFrom::Start(offset))?;
+            let mut hasher = blake3::Hasher::new();
+            io::copy(&mut (&mut self.file).take(length), &mut hasher)?;
+            if *hasher.finalize().as_bytes() != checksum {
+                return Err(MemvidError::InvalidToc {
+                    reason: format!("index artifact {label} does not match its checksum").into(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
@@ -2,11 +2,11 @@ use crate::constants::MEMVID_TICKET_PUBKEY;
 use crate::error::{MemvidError, Result};
 use crate::memvid::lifecycle::Memvid;
 use crate::signature::{parse_ed25519_public_key_base64, verify_ticket_signature};
-use crate::types::{FrameStatus, SignedTicket, Stats, Ticket, TicketRef};
+use crate::types::{ChangeKind, FrameStatus, SignedTicket, Stats, Ticket, TicketRef};
 
 impl Memvid {
     pub fn stats(&self) -> Result<Stats> {
-        let metadata = self.file.metadata()?;
+        let size_bytes = self.file.len()?;
         let mut payload_bytes = 0u64;
         let mut logical_bytes = 0u64;
         let mut active_frames = 0u64;
@@ -39,11 +39,11 @@ impl Memvid {
             0.0
         };
         let storage_utilisation_percent = if self.capacity_limit() > 0 {
-            round2((metadata.len() as f64 / self.capacity_limit() as f64) * 100.0)
+            round2((size_bytes as f64 / self.capacity_limit() as f64) * 100.0)
         } else {
             0.0
         };
-        let remaining_capacity_bytes = self.capacity_limit().saturating_sub(metadata.len());
+        let remaining_capacity_bytes = self.capacity_limit().saturating_sub(size_bytes);
         let average_payload = if active_frames > 0 {
             payload_bytes / active_frames
         } else {
@@ -96,7 +96,7 @@ impl Memvid {
 
         Ok(Stats {
             frame_count: self.toc.frames.len() as u64,
-            size_bytes: metadata.len(),
+            size_bytes,
             tier: self.tier(),
             // Use consolidated helper for consistent lex index detection
             has_lex_index: crate::memvid::lifecycle::has_lex_index(&self.toc),
@@ -153,6 +153,10 @@ impl Memvid {
         self.toc.ticket_ref.verified = false; // Unsigned tickets are not verified
 
         self.generation = self.generation.wrapping_add(1);
+        self.publish_change(ChangeKind::TicketApplied {
+            issuer: self.toc.ticket_ref.issuer.clone(),
+            seq_no: ticket.seq_no,
+        });
         self.rewrite_toc_footer()?;
         self.header.toc_checksum = self.toc.toc_checksum;
         crate::persist_header(&mut self.file, &self.header)?;
@@ -241,6 +245,10 @@ impl Memvid {
         self.toc.ticket_ref.verified = true; // Mark as cryptographically verified
 
         self.generation = self.generation.wrapping_add(1);
+        self.publish_change(ChangeKind::TicketApplied {
+            issuer: self.toc.ticket_ref.issuer.clone(),
+            seq_no: ticket.seq_no,
+        });
         self.rewrite_toc_footer()?;
         self.header.toc_checksum = self.toc.toc_checksum;
         crate::persist_header(&mut self.file, &self.header)?;
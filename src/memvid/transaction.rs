@@ -0,0 +1,283 @@
+//! Multi-operation transactions over the embedded WAL.
+//!
+//! Operations staged through a [`Transaction`] are logged between a begin and a commit
+//! marker. Commits and crash recovery only replay transactions whose commit marker reached
+//! the WAL; aborted and torn transactions are discarded as a whole.
+
+// `MemvidError` is the crate-wide error type; boxing it for this module alone buys nothing.
+#![allow(clippy::result_large_err)]
+
+use std::collections::BTreeSet;
+
+use bincode::serde::encode_to_vec;
+
+use crate::enrich::EnrichmentEngine;
+use crate::memvid::lifecycle::Memvid;
+use crate::memvid::mutation::WalEntry;
+use crate::types::{
+    FrameId, MemoryCard, PutManyOpts, PutOptions, PutRequest, VecIndexManifest, VecSpaceManifest,
+};
+use crate::{MemvidError, Result, wal_config};
+
+/// Operations of an open [`Memvid::transaction`].
+///
+/// Nothing staged here is visible until the transaction is committed to the WAL and the
+/// memory itself is committed.
+pub struct Transaction<'a> {
+    memvid: &'a mut Memvid,
+}
+
+impl<'a> Transaction<'a> {
+    /// Hold checkpoints back until the transaction is dropped, even by a panic.
+    fn open(memvid: &'a mut Memvid) -> Self {
+        memvid.transaction_open = true;
+        Self { memvid }
+    }
+
+    /// Stage a document frame; see [`Memvid::put_bytes`].
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Memvid::put_bytes`]; the caller decides whether that aborts the transaction.
+    pub fn put_bytes(&mut self, payload: &[u8]) -> Result<u64> {
+        self.put_bytes_with_options(payload, PutOptions::default())
+    }
+
+    /// Stage a document frame; see [`Memvid::put_bytes_with_options`].
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Memvid::put_bytes_with_options`].
+    pub fn put_bytes_with_options(&mut self, payload: &[u8], options: PutOptions) -> Result<u64> {
+        self.memvid
+            .put_bytes_with_options(payload, staged_options(options))
+    }
+
+    /// Stage a document frame with its embedding; see [`Memvid::put_with_embedding_and_options`].
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Memvid::put_with_embedding_and_options`].
+    pub fn put_with_embedding_and_options(
+        &mut self,
+        payload: &[u8],
+        embedding: Vec<f32>,
+        options: PutOptions,
+    ) -> Result<u64> {
+        self.memvid
+            .put_with_embedding_and_options(payload, embedding, staged_options(options))
+    }
+
+    /// Stage a replacement of an existing frame; see [`Memvid::update_frame`].
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Memvid::update_frame`].
+    pub fn update_frame(
+        &mut self,
+        frame_id: FrameId,
+        payload: Option<Vec<u8>>,
+        options: PutOptions,
+        embedding: Option<Vec<f32>>,
+    ) -> Result<u64> {
+        self.memvid
+            .update_frame(frame_id, payload, staged_options(options), embedding)
+    }
+
+    /// Stage one document of a [`Memvid::put_many`] batch.
+    pub(crate) fn put_request(
+        &mut self,
+        request: PutRequest,
+        opts: &PutManyOpts,
+        engine: Option<&dyn EnrichmentEngine>,
+    ) -> Result<FrameId> {
+        self.memvid.put_request(request, opts, engine)
+    }
+
+    /// Stage the deletion of a frame; see [`Memvid::delete_frame`].
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Memvid::delete_frame`].
+    pub fn delete_frame(&mut self, frame_id: FrameId) -> Result<u64> {
+        self.memvid.delete_frame(frame_id)
+    }
+
+    /// Stage embeddings of existing frames in a named vector space; see
+    /// [`Memvid::add_embeddings_to_space`].
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Memvid::add_embeddings_to_space`].
+    pub fn add_embeddings_to_space(
+        &mut self,
+        space: &str,
+        embeddings: Vec<(FrameId, Vec<f32>)>,
+    ) -> Result<usize> {
+        self.memvid.add_embeddings_to_space(space, embeddings)
+    }
+
+    /// Stage a memory card; it is assigned an id when the transaction is applied.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Transaction::put_memory_cards`].
+    pub fn put_memory_card(&mut self, card: MemoryCard) -> Result<()> {
+        self.put_memory_cards(&[card])
+    }
+
+    /// Stage several memory cards; see [`Memvid::put_memory_cards`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if strict schema validation rejects a card or the cards cannot be
+    /// logged to the WAL.
+    pub fn put_memory_cards(&mut self, cards: &[MemoryCard]) -> Result<()> {
+        if cards.is_empty() {
+            return Ok(());
+        }
+        self.memvid.check_cards_for_insert(cards)?;
+        let json = serde_json::to_vec(cards).map_err(|err| MemvidError::CheckpointFailed {
+            reason: format!("failed to encode memory cards: {err}"),
+        })?;
+        self.memvid
+            .append_wal_marker(&WalEntry::MemoryCards(json))?;
+        self.memvid.dirty = true;
+        Ok(())
+    }
+}
+
+impl Drop for Transaction<'_> {
+    fn drop(&mut self) {
+        self.memvid.transaction_open = false;
+    }
+}
+
+/// Frames staged by a transaction must not reach the live Tantivy index before it commits.
+fn staged_options(mut options: PutOptions) -> PutOptions {
+    options.instant_index = false;
+    options
+}
+
+/// In-memory state touched by staged writes, restored when a transaction aborts.
+///
+/// Memory cards are logged to the WAL while a transaction is open, so only counters and
+/// manifests are kept here; the queue and change log are append-only and truncated back.
+struct RollbackState {
+    pending_frame_inserts: u64,
+    pending_changes: usize,
+    vec_manifest: Option<VecIndexManifest>,
+    /// Staged space embeddings only reach the space indexes once applied from the WAL, but
+    /// the first one fixes the space dimension in its manifest.
+    vec_spaces: Vec<VecSpaceManifest>,
+    dirty_vec_spaces: BTreeSet<String>,
+    /// Staged puts queue their frames for background enrichment.
+    enrichment_tasks: usize,
+    enrichment_updated_at: u64,
+    dirty: bool,
+}
+
+impl Memvid {
+    /// Run `operations` as one all-or-nothing unit.
+    ///
+    /// When the closure returns `Ok`, a commit marker is logged and every staged operation is
+    /// applied by the next [`Memvid::commit`] (or by WAL recovery after a crash). When it
+    /// returns `Err`, the transaction is aborted and none of its operations are applied.
+    ///
+    /// ```ignore
+    /// mem.transaction(|tx| {
+    ///     tx.delete_frame(old_id)?;
+    ///     tx.put_bytes_with_options(b"new version", options)?;
+    ///     tx.put_memory_card(card)
+    /// })?;
+    /// mem.commit()?;
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the closure's error after aborting, or the error that kept the begin or commit
+    /// marker from being logged. An abort marker that cannot be logged is retried before the
+    /// next WAL record, which fails instead of being swallowed by the aborted transaction.
+    pub fn transaction<T, F>(&mut self, operations: F) -> Result<T>
+    where
+        F: FnOnce(&mut Transaction<'_>) -> Result<T>,
+    {
+        self.ensure_mutation_allowed()?;
+        let id = self.wal.stats().sequence.wrapping_add(1);
+        let rollback = RollbackState {
+            pending_frame_inserts: self.pending_frame_inserts,
+            pending_changes: self.pending_changes.len(),
+            vec_manifest: self.toc.indexes.vec.clone(),
+            vec_spaces: self.toc.indexes.vec_spaces.clone(),
+            dirty_vec_spaces: self.dirty_vec_spaces.clone(),
+            enrichment_tasks: self.toc.enrichment_queue.tasks.len(),
+            enrichment_updated_at: self.toc.enrichment_queue.updated_at,
+            dirty: self.dirty,
+        };
+
+        self.append_wal_marker(&WalEntry::TxBegin(id))?;
+        self.unterminated_transaction = Some(id);
+        let outcome = operations(&mut Transaction::open(self));
+        let outcome = outcome.and_then(|value| {
+            self.close_transaction(&WalEntry::TxCommit(id))
+                .map(|()| value)
+        });
+
+        match outcome {
+            Ok(value) => {
+                self.dirty = true;
+                tracing::debug!(transaction = id, "wal transaction committed");
+                if self.auto_checkpoint_due() {
+                    self.commit()?;
+                }
+                Ok(value)
+            }
+            Err(err) => {
+                // Without a commit marker the staged entries are never applied; the abort
+                // marker only lets later entries replay outside the transaction.
+                if let Err(marker_err) = self.close_transaction(&WalEntry::TxAbort(id)) {
+                    tracing::error!(
+                        transaction = id,
+                        error = %marker_err,
+                        "failed to log transaction abort; retrying before the next WAL record"
+                    );
+                }
+                self.pending_frame_inserts = rollback.pending_frame_inserts;
+                self.pending_changes.truncate(rollback.pending_changes);
+                self.toc.indexes.vec = rollback.vec_manifest;
+                self.toc.indexes.vec_spaces = rollback.vec_spaces;
+                self.dirty_vec_spaces = rollback.dirty_vec_spaces;
+                let queue = &mut self.toc.enrichment_queue;
+                queue.tasks.truncate(rollback.enrichment_tasks);
+                queue.updated_at = rollback.enrichment_updated_at;
+                self.dirty = rollback.dirty;
+                tracing::debug!(transaction = id, error = %err, "wal transaction aborted");
+                Err(err)
+            }
+        }
+    }
+
+    /// Log the abort marker of a transaction left open by a failed abort or a panic, so the
+    /// records that follow it are not discarded along with it on replay.
+    pub(crate) fn terminate_open_transaction(&mut self) -> Result<()> {
+        match self.unterminated_transaction {
+            Some(id) => self.close_transaction(&WalEntry::TxAbort(id)),
+            None => Ok(()),
+        }
+    }
+
+    /// Log `marker` for the open transaction, which stays open if the marker is not written.
+    fn close_transaction(&mut self, marker: &WalEntry) -> Result<()> {
+        let open = self.unterminated_transaction.take();
+        let logged = self.append_wal_marker(marker);
+        if logged.is_err() {
+            self.unterminated_transaction = open;
+        }
+        logged.map(drop)
+    }
+
+    pub(crate) fn append_wal_marker(&mut self, entry: &WalEntry) -> Result<u64> {
+        let bytes = encode_to_vec(entry, wal_config())?;
+        self.append_wal_entry(&bytes)
+    }
+}
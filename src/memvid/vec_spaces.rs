@@ -0,0 +1,433 @@
+//! Named vector spaces for `Memvid`.
+//!
+//! A vector space is an additional vector index stored next to the default `vec` index,
+//! with its own dimension, embedding model identity and compression mode. Spaces let a
+//! memory carry embeddings from several models side by side, e.g. while re-embedding a
+//! corpus from one model to another.
+
+use std::io::{Seek, SeekFrom, Write};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+
+use super::segments::MIN_VECTORS_FOR_PQ;
+use crate::memvid::lifecycle::Memvid;
+use crate::memvid::mutation::WalEntry;
+use crate::types::{
+    EmbeddingIdentity, FrameId, PutOptions, VecIndexManifest, VecSpaceManifest, VectorCompression,
+};
+use crate::vec::{VecDocument, VecIndex};
+use crate::vec_pq::{PQ_DIMENSION, QuantizedVecIndex, QuantizedVecIndexBuilder};
+use crate::{MemvidError, Result};
+
+impl Memvid {
+    /// Declare a named vector space.
+    ///
+    /// The space dimension comes from `identity.dimension` when set, otherwise from the first
+    /// vector stored in it. Declaring a space also enables vector search.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::VecSpaceExists`] when `name` is taken and with
+    /// [`MemvidError::VecDimensionMismatch`] when the dimension does not suit `compression`.
+    pub fn create_vec_space(
+        &mut self,
+        name: &str,
+        identity: Option<EmbeddingIdentity>,
+        compression: VectorCompression,
+    ) -> Result<()> {
+        self.ensure_writable()?;
+        if self.toc.indexes.vec_space(name).is_some() {
+            return Err(MemvidError::VecSpaceExists {
+                name: name.to_string(),
+            });
+        }
+        if !self.vec_enabled {
+            self.enable_vec()?;
+        }
+
+        let dimension = identity
+            .as_ref()
+            .and_then(|identity| identity.dimension)
+            .unwrap_or(0);
+        if dimension != 0 {
+            check_space_dimension(&compression, dimension as usize)?;
+        }
+        self.toc.indexes.vec_spaces.push(VecSpaceManifest {
+            name: name.to_string(),
+            identity,
+            index: VecIndexManifest {
+                vector_count: 0,
+                dimension,
+                bytes_offset: 0,
+                bytes_length: 0,
+                checksum: *blake3::hash(&[]).as_bytes(),
+                compression_mode: compression,
+            },
+        });
+        self.vec_space_indexes.insert(
+            name.to_string(),
+            VecIndex::Uncompressed {
+                documents: Vec::new(),
+            },
+        );
+        self.dirty_vec_spaces.insert(name.to_string());
+        self.dirty = true;
+        Ok(())
+    }
+
+    /// Manifests of every named vector space in this memory.
+    #[must_use]
+    pub fn vec_spaces(&self) -> &[VecSpaceManifest] {
+        &self.toc.indexes.vec_spaces
+    }
+
+    /// Ingest a document whose embedding belongs to the named vector space `space`.
+    ///
+    /// Shorthand for [`Self::put_with_embedding_and_options`] with
+    /// [`PutOptions::vector_space`] set.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`Self::put_with_embedding_and_options`].
+    pub fn put_with_embedding_in_space(
+        &mut self,
+        payload: &[u8],
+        space: &str,
+        embedding: Vec<f32>,
+        mut options: PutOptions,
+    ) -> Result<u64> {
+        options.vector_space = Some(space.to_string());
+        self.put_with_embedding_and_options(payload, embedding, options)
+    }
+
+    /// Add or replace embeddings of existing frames in the named vector space `space`.
+    ///
+    /// This is how an existing corpus is re-embedded with a new model. The embeddings are
+    /// logged to the WAL and applied by the next commit, or by recovery after a crash.
+    ///
+    /// # Errors
+    ///
+    /// Fails when `space` is unknown or failed to load, when a frame is not active, or when an
+    /// embedding does not match the space dimension.
+    pub fn add_embeddings_to_space(
+        &mut self,
+        space: &str,
+        embeddings: Vec<(FrameId, Vec<f32>)>,
+    ) -> Result<usize> {
+        self.ensure_writable()?;
+        if embeddings.is_empty() {
+            return Ok(0);
+        }
+        for (frame_id, embedding) in &embeddings {
+            if !self.frame_is_active(*frame_id) {
+                return Err(MemvidError::InvalidFrame {
+                    frame_id: *frame_id,
+                    reason: "frame is not active",
+                });
+            }
+            self.ensure_vec_space_dimension(space, embedding.len())?;
+        }
+        let count = embeddings.len();
+        self.append_wal_marker(&WalEntry::SpaceEmbeddings {
+            space: space.to_string(),
+            embeddings,
+        })?;
+        self.dirty = true;
+        if self.auto_checkpoint_due() {
+            self.commit()?;
+        }
+        Ok(count)
+    }
+
+    /// Validate `dimension` against the space, fixing the space dimension on first use.
+    ///
+    /// Also rejects spaces whose index failed to load, so an embedding is never logged for
+    /// a space that cannot take it.
+    pub(crate) fn ensure_vec_space_dimension(
+        &mut self,
+        space: &str,
+        dimension: usize,
+    ) -> Result<()> {
+        let manifest = self
+            .toc
+            .indexes
+            .vec_spaces
+            .iter_mut()
+            .find(|manifest| manifest.name == space)
+            .ok_or_else(|| MemvidError::VecSpaceNotFound {
+                name: space.to_string(),
+            })?;
+        if !self.vec_space_indexes.contains_key(space) {
+            return Err(MemvidError::InvalidToc {
+                reason: format!("vector space '{space}' failed to load").into(),
+            });
+        }
+        if manifest.index.dimension == 0 {
+            check_space_dimension(&manifest.index.compression_mode, dimension)?;
+            manifest.index.dimension =
+                u32::try_from(dimension).map_err(|_| MemvidError::EmbeddingFailed {
+                    reason: "embedding dimension exceeds the vector index limit".into(),
+                })?;
+        } else if manifest.index.dimension as usize != dimension {
+            return Err(MemvidError::VecDimensionMismatch {
+                expected: manifest.index.dimension,
+                actual: dimension,
+            });
+        }
+        Ok(())
+    }
+
+    /// Index of the named vector space, after checking the query dimension.
+    pub(crate) fn vec_space_index(&self, space: &str, query_dimension: usize) -> Result<&VecIndex> {
+        let manifest =
+            self.toc
+                .indexes
+                .vec_space(space)
+                .ok_or_else(|| MemvidError::VecSpaceNotFound {
+                    name: space.to_string(),
+                })?;
+        let expected = manifest.index.dimension;
+        if expected > 0 && query_dimension != expected as usize {
+            return Err(MemvidError::VecDimensionMismatch {
+                expected,
+                actual: query_dimension,
+            });
+        }
+        self.vec_space_indexes
+            .get(space)
+            .ok_or_else(|| MemvidError::VecSpaceNotFound {
+                name: space.to_string(),
+            })
+    }
+
+    pub(crate) fn insert_space_embedding(
+        &mut self,
+        space: &str,
+        frame_id: FrameId,
+        embedding: Vec<f32>,
+    ) -> Result<()> {
+        let index =
+            self.vec_space_indexes
+                .get_mut(space)
+                .ok_or_else(|| MemvidError::VecSpaceNotFound {
+                    name: space.to_string(),
+                })?;
+        index.insert(frame_id, embedding)?;
+        self.dirty_vec_spaces.insert(space.to_string());
+        Ok(())
+    }
+
+    pub(crate) fn load_vec_spaces_from_manifest(&mut self) {
+        self.vec_space_indexes.clear();
+        self.dirty_vec_spaces.clear();
+        for manifest in self.toc.indexes.vec_spaces.clone() {
+            let index = if manifest.index.bytes_length == 0 {
+                VecIndex::Uncompressed {
+                    documents: Vec::new(),
+                }
+            } else {
+                let decoded = self
+                    .read_range(manifest.index.bytes_offset, manifest.index.bytes_length)
+                    .and_then(|bytes| {
+                        catch_unwind(AssertUnwindSafe(|| {
+                            VecIndex::decode_chain(
+                                &bytes,
+                                manifest.index.bytes_offset,
+                                manifest.index.compression_mode.clone(),
+                                |offset, length| self.read_range(offset, length),
+                            )
+                        }))
+                        .unwrap_or_else(|_| {
+                            Err(MemvidError::InvalidToc {
+                                reason: "vector space index decode panicked".into(),
+                            })
+                        })
+                    });
+                match decoded {
+                    Ok(index) => index,
+                    Err(err) => {
+                        // Leave the space unloaded; its manifest is kept untouched.
+                        tracing::warn!(space = %manifest.name, error = %err, "failed to load vector space");
+                        continue;
+                    }
+                }
+            };
+            self.vec_space_indexes.insert(manifest.name, index);
+        }
+    }
+
+    /// Fail when a declared vector space has no resident index, i.e. it failed to load.
+    pub(crate) fn ensure_vec_spaces_loaded(&self) -> Result<()> {
+        match self
+            .toc
+            .indexes
+            .vec_spaces
+            .iter()
+            .find(|space| !self.vec_space_indexes.contains_key(&space.name))
+        {
+            Some(space) => Err(MemvidError::InvalidToc {
+                reason: format!("vector space '{}' failed to load", space.name).into(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Forget where every vector space was stored so the next commit writes each one afresh.
+    pub(crate) fn detach_vec_spaces(&mut self) {
+        for space in &mut self.toc.indexes.vec_spaces {
+            space.index.bytes_offset = 0;
+            space.index.bytes_length = 0;
+            self.dirty_vec_spaces.insert(space.name.clone());
+        }
+        for index in self.vec_space_indexes.values_mut() {
+            if let VecIndex::Hnsw(graph) = index {
+                graph.detach_chain();
+            }
+        }
+    }
+
+    /// Whether a vector space changed since it was last written.
+    pub(crate) fn vec_spaces_pending(&self) -> bool {
+        !self.dirty_vec_spaces.is_empty()
+    }
+
+    /// Write the vector spaces changed since they were last written, starting at `offset`;
+    /// returns the end offset.
+    ///
+    /// Unchanged spaces keep their bytes, so they must live where commits don't overwrite
+    /// them: the payload region, or past the footer on append-only commits. HNSW spaces only
+    /// append a segment to their chain; `Pq96` spaces are product quantized once they hold
+    /// enough vectors.
+    pub(crate) fn write_vec_spaces(&mut self, mut offset: u64) -> Result<u64> {
+        let names: Vec<String> = self.dirty_vec_spaces.iter().cloned().collect();
+        for name in names {
+            let Some(previous) = self
+                .toc
+                .indexes
+                .vec_space(&name)
+                .map(|manifest| manifest.index.clone())
+            else {
+                self.dirty_vec_spaces.remove(&name);
+                continue;
+            };
+            let Some(existing) = self.vec_space_indexes.remove(&name) else {
+                self.dirty_vec_spaces.remove(&name);
+                continue;
+            };
+            let mut index = self.prepare_space_index(existing, &previous.compression_mode)?;
+            let written = match &mut index {
+                VecIndex::Hnsw(graph) => {
+                    self.append_hnsw_segment(graph, Some(&previous), offset)?
+                }
+                flat => {
+                    let artifact = flat.encode()?;
+                    self.file.seek(SeekFrom::Start(offset))?;
+                    self.file.write_all(&artifact.bytes)?;
+                    Some(VecIndexManifest {
+                        vector_count: artifact.vector_count,
+                        dimension: artifact.dimension,
+                        bytes_offset: offset,
+                        bytes_length: artifact.bytes.len() as u64,
+                        checksum: artifact.checksum,
+                        compression_mode: previous.compression_mode.clone(),
+                    })
+                }
+            };
+            self.vec_space_indexes.insert(name.clone(), index);
+            self.dirty_vec_spaces.remove(&name);
+
+            let Some(written) = written else {
+                continue;
+            };
+            if let Some(manifest) = self
+                .toc
+                .indexes
+                .vec_spaces
+                .iter_mut()
+                .find(|manifest| manifest.name == name)
+            {
+                manifest.index.vector_count = written.vector_count;
+                if written.dimension > 0 {
+                    manifest.index.dimension = written.dimension;
+                }
+                manifest.index.bytes_offset = written.bytes_offset;
+                manifest.index.bytes_length = written.bytes_length;
+                manifest.index.checksum = written.checksum;
+            }
+            offset += written.bytes_length;
+        }
+        Ok(offset)
+    }
+
+    /// Bring a changed space index into the form it is stored in.
+    ///
+    /// `Pq96` spaces are quantized once they reach [`MIN_VECTORS_FOR_PQ`] vectors and then
+    /// keep their codebooks; other spaces follow the default index, flat until promoted to
+    /// an HNSW graph.
+    fn prepare_space_index(
+        &self,
+        existing: VecIndex,
+        compression: &VectorCompression,
+    ) -> Result<VecIndex> {
+        match (compression, existing) {
+            (_, VecIndex::Compressed(quantized)) => Ok(VecIndex::Compressed(quantized)),
+            (VectorCompression::Pq96, index) => {
+                let documents: Vec<(FrameId, Vec<f32>)> = index
+                    .entries()
+                    .filter(|(frame_id, _)| self.frame_is_active(*frame_id))
+                    .map(|(frame_id, embedding)| (frame_id, embedding.to_vec()))
+                    .collect();
+                let Some(dimension) = documents
+                    .first()
+                    .and_then(|(_, embedding)| u32::try_from(embedding.len()).ok())
+                    .filter(|_| documents.len() >= MIN_VECTORS_FOR_PQ)
+                else {
+                    let documents = documents
+                        .into_iter()
+                        .map(|(frame_id, embedding)| VecDocument {
+                            frame_id,
+                            embedding,
+                        })
+                        .collect();
+                    return Ok(VecIndex::Uncompressed { documents });
+                };
+                let training: Vec<Vec<f32>> = documents
+                    .iter()
+                    .map(|(_, embedding)| embedding.clone())
+                    .collect();
+                let mut builder = QuantizedVecIndexBuilder::new();
+                builder.train_quantizer(&training, dimension)?;
+                for (frame_id, embedding) in documents {
+                    builder.add_document(frame_id, embedding)?;
+                }
+                let artifact = builder.finish()?;
+                QuantizedVecIndex::decode(&artifact.bytes).map(VecIndex::Compressed)
+            }
+            (_, index) => self.merge_vec_index(Some(index), &[]),
+        }
+    }
+
+    /// Append the changed vector spaces after the current footer without a full rebuild.
+    #[cfg(feature = "parallel_segments")]
+    pub(crate) fn persist_vec_spaces(&mut self) -> Result<()> {
+        if !self.vec_spaces_pending() {
+            return Ok(());
+        }
+        let end = self.write_vec_spaces(self.header.footer_offset)?;
+        self.header.footer_offset = end;
+        if self.file.len()? < end {
+            self.file.set_len(end)?;
+        }
+        Ok(())
+    }
+}
+
+/// Product quantization needs vectors of exactly [`PQ_DIMENSION`] dimensions.
+fn check_space_dimension(compression: &VectorCompression, dimension: usize) -> Result<()> {
+    if *compression == VectorCompression::Pq96 && dimension != PQ_DIMENSION as usize {
+        return Err(MemvidError::VecDimensionMismatch {
+            expected: PQ_DIMENSION,
+            actual: dimension,
+        });
+    }
+    Ok(())
+}
@@ -154,23 +154,7 @@ pub fn verify_models(root: &Path, options: &ModelVerifyOptions) -> Result<Vec<Mo
 }
 
 pub fn verify_model_dir(dir: &Path, options: &ModelVerifyOptions) -> Result<ModelVerification> {
-    let manifest_path = dir.join("manifest.json");
-    if !manifest_path.exists() {
-        return Err(MemvidError::ModelIntegrity {
-            reason: format!("missing manifest.json in {}", dir.display()).into_boxed_str(),
-        });
-    }
-
-    let manifest_data = fs::read_to_string(&manifest_path)?;
-    let manifest: ModelManifest =
-        serde_json::from_str(&manifest_data).map_err(|err| MemvidError::ModelManifestInvalid {
-            reason: format!(
-                "failed to parse manifest {}: {err}",
-                manifest_path.display()
-            )
-            .into_boxed_str(),
-        })?;
-
+    let manifest = load_model_manifest(dir)?;
     if manifest.digest.trim().is_empty() {
         return Err(MemvidError::ModelManifestInvalid {
             reason: "manifest digest is empty".into(),
@@ -277,6 +261,30 @@ pub fn verify_model_dir(dir: &Path, options: &ModelVerifyOptions) -> Result<Mode
     })
 }
 
+/// Read and parse `manifest.json` from a model directory without verifying any files.
+///
+/// # Errors
+///
+/// Fails with [`MemvidError::ModelIntegrity`] when the manifest is missing and with
+/// [`MemvidError::ModelManifestInvalid`] when it cannot be parsed.
+pub fn load_model_manifest(dir: &Path) -> Result<ModelManifest> {
+    let manifest_path = dir.join("manifest.json");
+    if !manifest_path.exists() {
+        return Err(MemvidError::ModelIntegrity {
+            reason: format!("missing manifest.json in {}", dir.display()).into_boxed_str(),
+        });
+    }
+
+    let manifest_data = fs::read_to_string(&manifest_path)?;
+    serde_json::from_str(&manifest_data).map_err(|err| MemvidError::ModelManifestInvalid {
+        reason: format!(
+            "failed to parse manifest {}: {err}",
+            manifest_path.display()
+        )
+        .into_boxed_str(),
+    })
+}
+
 fn validate_entry(entry: &ModelManifestEntry) -> Result<()> {
     if entry.path.trim().is_empty() {
         return Err(MemvidError::ModelManifestInvalid {
@@ -297,7 +305,7 @@ fn validate_entry(entry: &ModelManifestEntry) -> Result<()> {
     Ok(())
 }
 
-fn resolve_entry_path(base: &Path, relative: &str) -> Result<PathBuf> {
+pub(crate) fn resolve_entry_path(base: &Path, relative: &str) -> Result<PathBuf> {
     let path = Path::new(relative);
     if path.is_absolute() {
         return Err(MemvidError::ModelManifestInvalid {
@@ -353,7 +361,7 @@ fn compute_sha256_hex(path: &Path) -> Result<String> {
     Ok(hex::encode(hasher.finalize()))
 }
 
-fn select_weights_entry<'a>(manifest: &'a ModelManifest) -> Option<&'a ModelManifestEntry> {
+pub(crate) fn select_weights_entry(manifest: &ModelManifest) -> Option<&ModelManifestEntry> {
     if let Some(quant) = manifest.quant.as_deref() {
         if let Some(entry) = manifest
             .files
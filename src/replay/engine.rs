@@ -235,6 +235,11 @@ impl<'a> ReplayEngine<'a> {
                             as_of_frame: None,
                             as_of_ts: None,
                             no_sketch: false,
+                            filter: None,
+                            facets: Vec::new(),
+                            hybrid: None,
+                            reranker: None,
+                            fragments: None,
                         };
                         match self.mem.search(search_request) {
                             Ok(response) => {
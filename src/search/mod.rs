@@ -12,7 +12,8 @@ pub(crate) use parser::{DateRange, ParsedQuery};
 #[cfg(feature = "lex")]
 #[allow(unused_imports)]
 pub(crate) use tantivy::{
-    EmbeddedLexSegment, EmbeddedLexStorage, LexWalBatch, TantivyEngine, TantivySnapshot,
+    EmbeddedLexSegment, EmbeddedLexStorage, Highlighter, LexWalBatch, TantivyEngine,
+    TantivySnapshot,
 };
 
 pub struct EvaluationContext<'a> {
@@ -36,6 +37,17 @@ impl ParsedQuery {
     pub fn contains_field_terms(&self) -> bool {
         self.expr.contains_field_terms()
     }
+
+    /// Whether the query has fuzzy, prefix or wildcard terms, which match words that never
+    /// appear verbatim in the query text.
+    pub fn contains_inexact_terms(&self) -> bool {
+        self.expr.contains_inexact_terms()
+    }
+
+    /// Product of the boosts of every `expr^boost` that matches the frame.
+    pub fn boost_factor(&self, ctx: &EvaluationContext<'_>) -> f32 {
+        self.expr.boost_factor(ctx)
+    }
 }
 
 impl TextTerm {
@@ -50,10 +62,80 @@ impl TextTerm {
                 haystack.contains(&needle)
             }
             TextTerm::Wildcard(pattern) => pattern.regex.is_match(haystack),
+            TextTerm::Prefix(prefix) => haystack.match_indices(prefix.as_str()).any(|(idx, _)| {
+                !haystack[..idx]
+                    .chars()
+                    .next_back()
+                    .is_some_and(char::is_alphanumeric)
+            }),
+            TextTerm::Fuzzy { word, distance } => words(haystack)
+                .any(|candidate| within_edit_distance(word, candidate, usize::from(*distance))),
+            TextTerm::Proximity { phrase, slop } => {
+                let needle: Vec<&str> = words(phrase).collect();
+                let haystack: Vec<&str> = words(haystack).collect();
+                within_slop(&needle, &haystack, *slop as usize)
+            }
         }
     }
 }
 
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+/// Whether `a` turns into `b` with at most `max` single-character insertions, deletions,
+/// substitutions or adjacent transpositions.
+fn within_edit_distance(a: &str, b: &str, max: usize) -> bool {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    if a.len().abs_diff(b.len()) > max {
+        return false;
+    }
+    // Optimal string alignment distance, keeping the last two rows.
+    let mut before: Vec<usize> = Vec::new();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before[j - 2] + 1);
+            }
+        }
+        before = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()] <= max
+}
+
+/// Whether `needle` occurs in `haystack` in order with at most `slop` other words between
+/// its first and last word.
+fn within_slop(needle: &[&str], haystack: &[&str], slop: usize) -> bool {
+    let Some((first, rest)) = needle.split_first() else {
+        return true;
+    };
+    haystack
+        .iter()
+        .enumerate()
+        .filter(|(_, word)| *word == first)
+        .any(|(start, _)| {
+            let mut position = start;
+            for word in rest {
+                match haystack[position + 1..]
+                    .iter()
+                    .position(|candidate| candidate == word)
+                {
+                    Some(offset) => position += offset + 1,
+                    None => return false,
+                }
+            }
+            position - start + 1 - needle.len() <= slop
+        })
+}
+
 impl FieldTerm {
     pub(crate) fn matches(&self, ctx: &EvaluationContext<'_>) -> bool {
         match self {
@@ -153,6 +235,7 @@ impl Expr {
             Expr::Or(children) => children.iter().any(|child| child.evaluate(ctx)),
             Expr::And(children) => children.iter().all(|child| child.evaluate(ctx)),
             Expr::Not(child) => !child.evaluate(ctx),
+            Expr::Boost(child, _) => child.evaluate(ctx),
             Expr::Term(term) => term.evaluate(ctx),
         }
     }
@@ -170,14 +253,21 @@ impl Expr {
                     child.collect_into(tokens);
                 }
             }
-            Expr::Not(child) => child.collect_into(tokens),
+            Expr::Not(child) | Expr::Boost(child, _) => child.collect_into(tokens),
             Expr::Term(Term::Text(text)) => match text {
-                TextTerm::Word(word) | TextTerm::Phrase(word) => tokens.push(word.clone()),
+                TextTerm::Word(word) | TextTerm::Phrase(word) | TextTerm::Prefix(word) => {
+                    tokens.push(word.clone());
+                }
                 TextTerm::Wildcard(pattern) => {
                     if let Some(seed) = pattern.seed() {
                         tokens.push(seed);
                     }
                 }
+                TextTerm::Proximity { phrase, .. } => {
+                    tokens.extend(words(phrase).map(str::to_string));
+                }
+                // A misspelt word is not a substring of the text it should match.
+                TextTerm::Fuzzy { .. } => {}
             },
             Expr::Term(Term::Field(_)) => {}
         }
@@ -198,6 +288,7 @@ impl Expr {
                 }
                 combined
             }
+            Expr::Boost(child, _) => child.required_date_range(),
             Expr::Or(_) | Expr::Not(_) => None,
             Expr::Term(_) => None,
         }
@@ -208,10 +299,35 @@ impl Expr {
             Expr::Or(children) | Expr::And(children) => {
                 children.iter().any(|child| child.contains_field_terms())
             }
-            Expr::Not(child) => child.contains_field_terms(),
+            Expr::Not(child) | Expr::Boost(child, _) => child.contains_field_terms(),
             Expr::Term(term) => term.contains_field_terms(),
         }
     }
+
+    fn contains_inexact_terms(&self) -> bool {
+        match self {
+            Expr::Or(children) | Expr::And(children) => {
+                children.iter().any(parser::Expr::contains_inexact_terms)
+            }
+            Expr::Not(child) | Expr::Boost(child, _) => child.contains_inexact_terms(),
+            Expr::Term(Term::Text(text)) => matches!(
+                text,
+                TextTerm::Wildcard(_) | TextTerm::Prefix(_) | TextTerm::Fuzzy { .. }
+            ),
+            Expr::Term(Term::Field(_)) => false,
+        }
+    }
+
+    fn boost_factor(&self, ctx: &EvaluationContext<'_>) -> f32 {
+        match self {
+            Expr::Or(children) | Expr::And(children) => children
+                .iter()
+                .map(|child| child.boost_factor(ctx))
+                .product(),
+            Expr::Boost(child, boost) if child.evaluate(ctx) => boost * child.boost_factor(ctx),
+            Expr::Not(_) | Expr::Boost(..) | Expr::Term(_) => 1.0,
+        }
+    }
 }
 
 impl Term {
@@ -226,3 +342,40 @@ impl Term {
         matches!(self, Term::Field(_))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_term(query: &str) -> TextTerm {
+        match parse_query(query).expect("parse").expr {
+            Expr::Term(Term::Text(term)) => term,
+            other => panic!("expected a text term, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn fuzzy_terms_match_words_within_distance() {
+        let haystack = "please receive the parcel";
+        assert!(text_term("recieve~1").matches(haystack));
+        assert!(text_term("parcle~1").matches(haystack));
+        assert!(!text_term("parsel~0").matches(haystack));
+        assert!(!text_term("rcv~1").matches(haystack));
+        assert!(text_term("rceive~2").matches(haystack));
+    }
+
+    #[test]
+    fn prefix_terms_match_word_starts() {
+        assert!(text_term("mach*").matches("a machine learning model"));
+        assert!(!text_term("chine*").matches("a machine learning model"));
+    }
+
+    #[test]
+    fn proximity_phrases_allow_slop_between_words() {
+        let haystack = "the quick brown fox jumps";
+        assert!(text_term("\"quick fox\"~1").matches(haystack));
+        assert!(!text_term("\"quick jumps\"~1").matches(haystack));
+        assert!(text_term("\"quick jumps\"~2").matches(haystack));
+        assert!(!text_term("\"fox quick\"~5").matches(haystack));
+    }
+}
@@ -21,6 +21,8 @@ pub(crate) enum Expr {
     Or(Vec<Expr>),
     And(Vec<Expr>),
     Not(Box<Expr>),
+    /// `expr^boost`: scales the score contributed by `expr`.
+    Boost(Box<Expr>, f32),
     Term(Term),
 }
 
@@ -35,8 +37,27 @@ pub(crate) enum TextTerm {
     Word(String),
     Phrase(String),
     Wildcard(WildcardPattern),
+    /// `term*`: words starting with the prefix.
+    Prefix(String),
+    /// `term~N`: words within `distance` edits (insertions, deletions, substitutions or
+    /// transpositions) of `word`.
+    Fuzzy {
+        word: String,
+        distance: u8,
+    },
+    /// `"a b"~N`: the phrase words in order with at most `slop` other words between them.
+    Proximity {
+        phrase: String,
+        slop: u32,
+    },
 }
 
+/// Largest edit distance accepted by `term~N`.
+const MAX_FUZZY_DISTANCE: u8 = 2;
+
+/// Edit distance of a bare `term~`.
+const DEFAULT_FUZZY_DISTANCE: u8 = 2;
+
 #[derive(Debug, Clone)]
 pub(crate) struct WildcardPattern {
     pub raw: String,
@@ -65,6 +86,10 @@ enum Token {
     Phrase(String),
     Field(String, String),
     DateRange(String, String, String),
+    /// `~` attached to the preceding word or phrase, with its optional number.
+    Tilde(Option<u32>),
+    /// `^boost` attached to the preceding term or group.
+    Boost(f32),
     LParen,
     RParen,
     And,
@@ -95,6 +120,10 @@ impl<'a> Lexer<'a> {
                 self.index += 1;
                 continue;
             }
+            if self.modifier_ahead() && self.attached() {
+                tokens.push(self.read_modifier()?);
+                continue;
+            }
             match ch {
                 '(' => {
                     self.index += 1;
@@ -131,6 +160,9 @@ impl<'a> Lexer<'a> {
             if ch.is_whitespace() || ch == '(' || ch == ')' {
                 break;
             }
+            if self.index > start && colon_pos.is_none() && self.modifier_ahead() {
+                break;
+            }
             if ch == ':' && colon_pos.is_none() {
                 colon_pos = Some(self.index);
             }
@@ -182,6 +214,9 @@ impl<'a> Lexer<'a> {
                     if ch.is_whitespace() || ch == '(' || ch == ')' {
                         break;
                     }
+                    if ch == '^' && self.index > value_start && self.modifier_ahead() {
+                        break;
+                    }
                     self.index += 1;
                 }
                 let value: String = self.chars[value_start..self.index].iter().collect();
@@ -234,11 +269,72 @@ impl<'a> Lexer<'a> {
         })
     }
 
+    /// Whether the input at the cursor is a `~N` or `^N` modifier: the sigil followed by a
+    /// number that runs up to whitespace, a parenthesis, another modifier or the end.
+    fn modifier_ahead(&self) -> bool {
+        let Some(sigil @ ('~' | '^')) = self.peek() else {
+            return false;
+        };
+        let start = self.index + 1;
+        let mut end = start;
+        while let Some(&ch) = self.chars.get(end) {
+            if ch.is_whitespace() || matches!(ch, '(' | ')' | '~' | '^') {
+                break;
+            }
+            end += 1;
+        }
+        let number: String = self.chars[start..end].iter().collect();
+        if sigil == '~' {
+            number.chars().all(|c| c.is_ascii_digit())
+        } else {
+            parse_boost(&number).is_some()
+        }
+    }
+
+    /// Whether the cursor directly follows a word, phrase or group (no whitespace between).
+    fn attached(&self) -> bool {
+        self.index > 0 && {
+            let prev = self.chars[self.index - 1];
+            !prev.is_whitespace() && prev != '('
+        }
+    }
+
+    fn read_modifier(&mut self) -> Result<Token, MemvidError> {
+        let sigil = self.chars[self.index];
+        self.index += 1; // skip sigil
+        let start = self.index;
+        while let Some(ch) = self.peek() {
+            if ch.is_whitespace() || matches!(ch, '(' | ')' | '~' | '^') {
+                break;
+            }
+            self.index += 1;
+        }
+        let number: String = self.chars[start..self.index].iter().collect();
+        if sigil == '^' {
+            let boost = parse_boost(&number).ok_or_else(|| MemvidError::InvalidQuery {
+                reason: format!("invalid boost '^{number}'"),
+            })?;
+            return Ok(Token::Boost(boost));
+        }
+        if number.is_empty() {
+            return Ok(Token::Tilde(None));
+        }
+        let value = number.parse().map_err(|_| MemvidError::InvalidQuery {
+            reason: format!("invalid distance '~{number}'"),
+        })?;
+        Ok(Token::Tilde(Some(value)))
+    }
+
     fn peek(&self) -> Option<char> {
         self.chars.get(self.index).copied()
     }
 }
 
+fn parse_boost(value: &str) -> Option<f32> {
+    let boost: f32 = value.parse().ok()?;
+    (boost.is_finite() && boost > 0.0).then_some(boost)
+}
+
 struct Parser {
     tokens: Vec<Token>,
     position: usize,
@@ -305,16 +401,43 @@ impl Parser {
     }
 
     fn parse_primary(&mut self) -> Result<Expr, MemvidError> {
+        let expr = self.parse_atom()?;
+        if let Some(Token::Boost(boost)) = self.tokens.get(self.position) {
+            let boost = *boost;
+            self.position += 1;
+            return Ok(Expr::Boost(Box::new(expr), boost));
+        }
+        Ok(expr)
+    }
+
+    fn parse_atom(&mut self) -> Result<Expr, MemvidError> {
         if self.match_token(TokenKind::LParen) {
             let expr = self.parse_expression()?;
             self.consume(TokenKind::RParen, "expected ')' after expression")?;
             return Ok(expr);
         }
         match self.advance() {
-            Some(Token::Word(word)) => Ok(Expr::Term(Term::Text(TextTerm::from_word(word)))),
-            Some(Token::Phrase(phrase)) => Ok(Expr::Term(Term::Text(TextTerm::Phrase(
-                phrase.to_ascii_lowercase(),
-            )))),
+            Some(Token::Word(word)) => {
+                let term = match self.take_tilde() {
+                    Tilde::Absent => TextTerm::from_word(word),
+                    Tilde::Bare => TextTerm::fuzzy(word, None)?,
+                    Tilde::Distance(distance) => TextTerm::fuzzy(word, Some(distance))?,
+                };
+                Ok(Expr::Term(Term::Text(term)))
+            }
+            Some(Token::Phrase(phrase)) => {
+                let phrase = phrase.to_ascii_lowercase();
+                let term = match self.take_tilde() {
+                    Tilde::Distance(slop) => TextTerm::Proximity { phrase, slop },
+                    Tilde::Bare => {
+                        return Err(MemvidError::InvalidQuery {
+                            reason: "proximity search needs a distance, e.g. \"a b\"~3".into(),
+                        });
+                    }
+                    Tilde::Absent => TextTerm::Phrase(phrase),
+                };
+                Ok(Expr::Term(Term::Text(term)))
+            }
             Some(Token::Field(field, value)) => {
                 let term = FieldTerm::from_pair(&field, &value)?;
                 Ok(Expr::Term(Term::Field(term)))
@@ -332,6 +455,18 @@ impl Parser {
         }
     }
 
+    /// Consume a `~` modifier following a word or phrase.
+    fn take_tilde(&mut self) -> Tilde {
+        match self.tokens.get(self.position) {
+            Some(Token::Tilde(value)) => {
+                let value = *value;
+                self.position += 1;
+                value.map_or(Tilde::Bare, Tilde::Distance)
+            }
+            _ => Tilde::Absent,
+        }
+    }
+
     fn advance(&mut self) -> Option<Token> {
         if self.is_end() {
             None
@@ -374,6 +509,13 @@ impl Parser {
     }
 }
 
+/// A `~` modifier after a word or phrase, with or without its distance.
+enum Tilde {
+    Absent,
+    Bare,
+    Distance(u32),
+}
+
 enum TokenKind {
     LParen,
     RParen,
@@ -407,7 +549,10 @@ impl TextTerm {
 
         // Only treat * or ? as wildcards when they're NOT at the end
         // (i.e., "mach?ne" or "mach*" are wildcards, but "machine?" is just "machine")
-        if cleaned.contains('*') || cleaned.contains('?') {
+        let prefix = cleaned.strip_suffix('*').unwrap_or_default();
+        if !prefix.is_empty() && !prefix.contains(['*', '?']) {
+            TextTerm::Prefix(prefix.to_string())
+        } else if cleaned.contains('*') || cleaned.contains('?') {
             TextTerm::Wildcard(WildcardPattern::new(cleaned.to_string()))
         } else if cleaned.is_empty() || !cleaned.chars().any(|c| c.is_alphanumeric()) {
             // If the word has no alphanumeric chars, treat as empty
@@ -419,6 +564,26 @@ impl TextTerm {
     }
 }
 
+impl TextTerm {
+    fn fuzzy(word: String, distance: Option<u32>) -> Result<Self, MemvidError> {
+        let distance = distance.map_or(Ok(DEFAULT_FUZZY_DISTANCE), |value| {
+            u8::try_from(value)
+                .ok()
+                .filter(|value| *value <= MAX_FUZZY_DISTANCE)
+                .ok_or_else(|| MemvidError::InvalidQuery {
+                    reason: format!("fuzzy distance must be at most {MAX_FUZZY_DISTANCE}"),
+                })
+        })?;
+        match Self::from_word(word) {
+            TextTerm::Word(word) if word.is_empty() || distance == 0 => Ok(TextTerm::Word(word)),
+            TextTerm::Word(word) => Ok(TextTerm::Fuzzy { word, distance }),
+            _ => Err(MemvidError::InvalidQuery {
+                reason: "fuzzy terms cannot contain wildcards".into(),
+            }),
+        }
+    }
+}
+
 impl FieldTerm {
     fn from_pair(field: &str, value: &str) -> Result<Self, MemvidError> {
         let normalized = value.trim_matches('"').to_ascii_lowercase();
@@ -597,4 +762,60 @@ mod tests {
             _ => panic!("expected Word variant"),
         }
     }
+
+    #[test]
+    fn parses_fuzzy_prefix_proximity_and_boost() {
+        let parsed = parse_query("recieve~1 mach* \"quick fox\"~2 rust^2.5").expect("parse");
+        let Expr::And(terms) = parsed.expr else {
+            panic!("expected conjunction");
+        };
+        assert!(matches!(
+            &terms[0],
+            Expr::Term(Term::Text(TextTerm::Fuzzy { word, distance: 1 })) if word == "recieve"
+        ));
+        assert!(matches!(
+            &terms[1],
+            Expr::Term(Term::Text(TextTerm::Prefix(prefix))) if prefix == "mach"
+        ));
+        assert!(matches!(
+            &terms[2],
+            Expr::Term(Term::Text(TextTerm::Proximity { phrase, slop: 2 })) if phrase == "quick fox"
+        ));
+        assert!(matches!(&terms[3], Expr::Boost(inner, boost)
+            if (*boost - 2.5).abs() < f32::EPSILON
+                && matches!(**inner, Expr::Term(Term::Text(TextTerm::Word(ref word))) if word == "rust")));
+    }
+
+    #[test]
+    fn modifiers_combine_and_apply_to_groups() {
+        let parsed = parse_query("colour~^3 (alpha OR beta)^2").expect("parse");
+        let Expr::And(terms) = parsed.expr else {
+            panic!("expected conjunction");
+        };
+        assert!(matches!(&terms[0], Expr::Boost(inner, _)
+            if matches!(**inner, Expr::Term(Term::Text(TextTerm::Fuzzy { distance: 2, .. })))));
+        assert!(matches!(&terms[1], Expr::Boost(inner, _) if matches!(**inner, Expr::Or(_))));
+    }
+
+    #[test]
+    fn rejects_invalid_modifiers() {
+        assert!(parse_query("colour~3").is_err());
+        assert!(parse_query("\"quick fox\"~").is_err());
+        assert!(parse_query("ma*ch~1").is_err());
+    }
+
+    #[test]
+    fn unattached_modifier_characters_stay_text() {
+        // "^" without a number and "~" after whitespace are ordinary characters
+        match parse_query("e^x").expect("parse").expr {
+            Expr::Term(Term::Text(TextTerm::Word(word))) => assert_eq!(word, "e^x"),
+            other => panic!("unexpected {other:?}"),
+        }
+        assert!(parse_query("roughly ~5 items").is_ok());
+        // Interior wildcards still use a pattern
+        assert!(matches!(
+            parse_query("mach?ne").expect("parse").expr,
+            Expr::Term(Term::Text(TextTerm::Wildcard(_)))
+        ));
+    }
 }
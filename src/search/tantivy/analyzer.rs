@@ -0,0 +1,406 @@
+//! Text analyzers built from a persisted [`LexAnalyzerConfig`].
+
+use std::collections::VecDeque;
+
+use tantivy::tokenizer::{
+    AsciiFoldingFilter, Language, LowerCaser, RawTokenizer, SimpleTokenizer, Stemmer,
+    StopWordFilter, TextAnalyzer, Token, TokenFilter, TokenStream, Tokenizer,
+};
+
+use crate::types::{LexAnalyzerConfig, LexLanguage, LexTokenizer};
+
+/// Build the analyzer described by `config`.
+pub(super) fn build_analyzer(config: &LexAnalyzerConfig) -> TextAnalyzer {
+    let mut builder = match config.tokenizer {
+        LexTokenizer::CjkBigram => TextAnalyzer::builder(CjkBigramTokenizer).dynamic(),
+        LexTokenizer::Words | LexTokenizer::Ngram { .. } => {
+            TextAnalyzer::builder(SimpleTokenizer::default()).dynamic()
+        }
+    };
+    // Identifier boundaries are case transitions, so split before lowercasing.
+    if config.split_identifiers {
+        builder = builder.filter_dynamic(ExpandFilter(Expansion::Identifiers));
+    }
+    builder = builder.filter_dynamic(LowerCaser);
+    // Built-in stopword lists are spelled with accents ("für", "über"), so drop stopwords
+    // before folding.
+    if let Some(filter) = config
+        .stopwords
+        .and_then(|language| StopWordFilter::new(tantivy_language(language)))
+    {
+        builder = builder.filter_dynamic(filter);
+    }
+    if !config.custom_stopwords.is_empty() {
+        let words = config
+            .custom_stopwords
+            .iter()
+            .map(|word| word.to_lowercase());
+        builder = builder.filter_dynamic(StopWordFilter::remove(words));
+    }
+    if config.ascii_folding {
+        builder = builder.filter_dynamic(AsciiFoldingFilter);
+    }
+    if let Some(language) = config.stemmer {
+        builder = builder.filter_dynamic(Stemmer::new(tantivy_language(language)));
+    }
+    if let LexTokenizer::Ngram { min_gram, max_gram } = config.tokenizer {
+        builder = builder.filter_dynamic(ExpandFilter(Expansion::Ngrams {
+            min: usize::from(min_gram),
+            max: usize::from(max_gram),
+        }));
+    }
+    builder.build()
+}
+
+/// Build the analyzer that maps a `term*` prefix onto indexed terms: the prefix is kept
+/// whole and only lowercased and folded, since stemming a partial word changes its letters.
+pub(super) fn build_prefix_normalizer(config: &LexAnalyzerConfig) -> TextAnalyzer {
+    let builder = TextAnalyzer::builder(RawTokenizer::default())
+        .dynamic()
+        .filter_dynamic(LowerCaser);
+    if config.ascii_folding {
+        builder.filter_dynamic(AsciiFoldingFilter).build()
+    } else {
+        builder.build()
+    }
+}
+
+fn tantivy_language(language: LexLanguage) -> Language {
+    match language {
+        LexLanguage::Arabic => Language::Arabic,
+        LexLanguage::Danish => Language::Danish,
+        LexLanguage::Dutch => Language::Dutch,
+        LexLanguage::English => Language::English,
+        LexLanguage::Finnish => Language::Finnish,
+        LexLanguage::French => Language::French,
+        LexLanguage::German => Language::German,
+        LexLanguage::Greek => Language::Greek,
+        LexLanguage::Hungarian => Language::Hungarian,
+        LexLanguage::Italian => Language::Italian,
+        LexLanguage::Norwegian => Language::Norwegian,
+        LexLanguage::Portuguese => Language::Portuguese,
+        LexLanguage::Romanian => Language::Romanian,
+        LexLanguage::Russian => Language::Russian,
+        LexLanguage::Spanish => Language::Spanish,
+        LexLanguage::Swedish => Language::Swedish,
+        LexLanguage::Tamil => Language::Tamil,
+        LexLanguage::Turkish => Language::Turkish,
+    }
+}
+
+/// Han, Hiragana, Katakana and Hangul characters, which are written without spaces.
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{1100}'..='\u{11FF}'
+            | '\u{3040}'..='\u{30FF}'
+            | '\u{3130}'..='\u{318F}'
+            | '\u{31F0}'..='\u{31FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{AC00}'..='\u{D7AF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{FF66}'..='\u{FF9F}'
+            | '\u{20000}'..='\u{2A6DF}'
+    )
+}
+
+/// Words for alphabetic scripts, overlapping bigrams for CJK runs (a lone CJK character
+/// is kept as a unigram).
+#[derive(Clone)]
+struct CjkBigramTokenizer;
+
+impl Tokenizer for CjkBigramTokenizer {
+    type TokenStream<'a> = VecTokenStream;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> VecTokenStream {
+        let mut tokens = Vec::new();
+        let mut push = |from: usize, to: usize| {
+            tokens.push(Token {
+                offset_from: from,
+                offset_to: to,
+                position: tokens.len(),
+                text: text[from..to].to_string(),
+                position_length: 1,
+            });
+        };
+
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+        let end_of = |index: usize| chars.get(index).map_or(text.len(), |(offset, _)| *offset);
+        let mut index = 0;
+        while index < chars.len() {
+            let (start, c) = chars[index];
+            if is_cjk(c) {
+                let run_start = index;
+                while index < chars.len() && is_cjk(chars[index].1) {
+                    index += 1;
+                }
+                if index - run_start == 1 {
+                    push(start, end_of(index));
+                } else {
+                    for (pair, (from, _)) in chars[run_start..index - 1].iter().enumerate() {
+                        push(*from, end_of(run_start + pair + 2));
+                    }
+                }
+            } else if c.is_alphanumeric() {
+                while index < chars.len()
+                    && chars[index].1.is_alphanumeric()
+                    && !is_cjk(chars[index].1)
+                {
+                    index += 1;
+                }
+                push(start, end_of(index));
+            } else {
+                index += 1;
+            }
+        }
+
+        VecTokenStream { tokens, cursor: 0 }
+    }
+}
+
+struct VecTokenStream {
+    tokens: Vec<Token>,
+    cursor: usize,
+}
+
+impl TokenStream for VecTokenStream {
+    fn advance(&mut self) -> bool {
+        self.cursor += 1;
+        self.cursor <= self.tokens.len()
+    }
+
+    fn token(&self) -> &Token {
+        &self.tokens[self.cursor - 1]
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.tokens[self.cursor - 1]
+    }
+}
+
+/// How [`ExpandFilter`] turns one token into several.
+#[derive(Clone, Copy)]
+enum Expansion {
+    /// camelCase parts at consecutive positions.
+    Identifiers,
+    /// Character n-grams sharing the source token's position.
+    Ngrams { min: usize, max: usize },
+}
+
+/// Token filter that replaces each token with the tokens produced by an [`Expansion`].
+#[derive(Clone)]
+struct ExpandFilter(Expansion);
+
+impl TokenFilter for ExpandFilter {
+    type Tokenizer<T: Tokenizer> = ExpandFilterWrapper<T>;
+
+    fn transform<T: Tokenizer>(self, tokenizer: T) -> ExpandFilterWrapper<T> {
+        ExpandFilterWrapper {
+            expansion: self.0,
+            inner: tokenizer,
+        }
+    }
+}
+
+#[derive(Clone)]
+struct ExpandFilterWrapper<T> {
+    expansion: Expansion,
+    inner: T,
+}
+
+impl<T: Tokenizer> Tokenizer for ExpandFilterWrapper<T> {
+    type TokenStream<'a> = ExpandFilterStream<T::TokenStream<'a>>;
+
+    fn token_stream<'a>(&'a mut self, text: &'a str) -> Self::TokenStream<'a> {
+        ExpandFilterStream {
+            expansion: self.expansion,
+            tail: self.inner.token_stream(text),
+            pending: VecDeque::new(),
+            token: Token::default(),
+            shift: 0,
+        }
+    }
+}
+
+struct ExpandFilterStream<S> {
+    expansion: Expansion,
+    tail: S,
+    pending: VecDeque<Token>,
+    token: Token,
+    /// Positions added by earlier expansions, applied to every later token.
+    shift: usize,
+}
+
+impl<S: TokenStream> ExpandFilterStream<S> {
+    fn expand(&mut self) {
+        let source = self.tail.token();
+        let position = source.position + self.shift;
+        match self.expansion {
+            Expansion::Identifiers => {
+                let parts = identifier_parts(&source.text);
+                self.shift += parts.len() - 1;
+                for (index, (from, to)) in parts.into_iter().enumerate() {
+                    self.pending.push_back(Token {
+                        offset_from: source.offset_from + from,
+                        offset_to: source.offset_from + to,
+                        position: position + index,
+                        text: source.text[from..to].to_string(),
+                        position_length: 1,
+                    });
+                }
+            }
+            Expansion::Ngrams { min, max } => {
+                let chars: Vec<usize> = source
+                    .text
+                    .char_indices()
+                    .map(|(offset, _)| offset)
+                    .chain(std::iter::once(source.text.len()))
+                    .collect();
+                let len = chars.len() - 1;
+                if len < min {
+                    let mut token = source.clone();
+                    token.position = position;
+                    self.pending.push_back(token);
+                    return;
+                }
+                for size in min..=max.min(len) {
+                    for start in 0..=len - size {
+                        self.pending.push_back(Token {
+                            offset_from: source.offset_from,
+                            offset_to: source.offset_to,
+                            position,
+                            text: source.text[chars[start]..chars[start + size]].to_string(),
+                            position_length: 1,
+                        });
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<S: TokenStream> TokenStream for ExpandFilterStream<S> {
+    fn advance(&mut self) -> bool {
+        loop {
+            if let Some(token) = self.pending.pop_front() {
+                self.token = token;
+                return true;
+            }
+            if !self.tail.advance() {
+                return false;
+            }
+            self.expand();
+        }
+    }
+
+    fn token(&self) -> &Token {
+        &self.token
+    }
+
+    fn token_mut(&mut self) -> &mut Token {
+        &mut self.token
+    }
+}
+
+/// Byte ranges of the camelCase parts of `text` ("parseHTTPHeader" gives "parse", "HTTP",
+/// "Header").
+fn identifier_parts(text: &str) -> Vec<(usize, usize)> {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let mut parts = Vec::new();
+    let mut start = 0;
+    for index in 1..chars.len() {
+        let (offset, c) = chars[index];
+        let previous = chars[index - 1].1;
+        let next_is_lower = chars
+            .get(index + 1)
+            .is_some_and(|(_, next)| next.is_lowercase());
+        let boundary = c.is_uppercase()
+            && (previous.is_lowercase()
+                || previous.is_numeric()
+                || (previous.is_uppercase() && next_is_lower));
+        if boundary {
+            parts.push((start, offset));
+            start = offset;
+        }
+    }
+    parts.push((start, text.len()));
+    parts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyse(config: &LexAnalyzerConfig, text: &str) -> Vec<(String, usize)> {
+        let mut analyzer = build_analyzer(config);
+        let mut stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        while stream.advance() {
+            let token = stream.token();
+            tokens.push((token.text.clone(), token.position));
+        }
+        tokens
+    }
+
+    fn texts(tokens: &[(String, usize)]) -> Vec<&str> {
+        tokens.iter().map(|(text, _)| text.as_str()).collect()
+    }
+
+    #[test]
+    fn default_analyzer_stems_english() {
+        let tokens = analyse(&LexAnalyzerConfig::default(), "Running servers");
+        assert_eq!(texts(&tokens), ["run", "server"]);
+    }
+
+    #[test]
+    fn german_folds_and_drops_stopwords() {
+        let config = LexAnalyzerConfig::for_language(LexLanguage::German);
+        let tokens = analyse(&config, "Die Häuser für die Straße über Köln");
+        assert_eq!(texts(&tokens), ["haus", "strass", "koln"]);
+    }
+
+    #[test]
+    fn cjk_text_becomes_bigrams() {
+        let tokens = analyse(&LexAnalyzerConfig::cjk(), "東京都 rust 日");
+        assert_eq!(
+            tokens,
+            [
+                ("東京".to_string(), 0),
+                ("京都".to_string(), 1),
+                ("rust".to_string(), 2),
+                ("日".to_string(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn identifiers_split_at_case_and_underscore() {
+        let tokens = analyse(
+            &LexAnalyzerConfig::code(),
+            "parseHTTPHeader max_retry_count next",
+        );
+        assert_eq!(
+            texts(&tokens),
+            ["parse", "http", "header", "max", "retry", "count", "next"]
+        );
+        let positions: Vec<usize> = tokens.iter().map(|(_, position)| *position).collect();
+        assert_eq!(positions, [0, 1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn ngrams_share_the_word_position() {
+        let config = LexAnalyzerConfig {
+            tokenizer: LexTokenizer::Ngram {
+                min_gram: 3,
+                max_gram: 4,
+            },
+            stemmer: None,
+            ..LexAnalyzerConfig::default()
+        };
+        let tokens = analyse(&config, "Kafka go");
+        assert_eq!(texts(&tokens), ["kaf", "afk", "fka", "kafk", "afka", "go"]);
+        assert!(tokens[..5].iter().all(|(_, position)| *position == 0));
+        assert_eq!(tokens[5].1, 1);
+    }
+}
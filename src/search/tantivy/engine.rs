@@ -1,19 +1,24 @@
+use super::analyzer::build_prefix_normalizer;
 use super::query;
 use super::schema::{build_schema, initialise_tokenizer};
 use super::util::to_search_value;
 use crate::search::parser::ParsedQuery;
-use crate::types::{Frame, FrameId};
+use crate::types::{Frame, FrameId, LexAnalyzerConfig};
 use crate::{MemvidError, Result};
 use blake3::{Hasher, hash};
-use tantivy::collector::TopDocs;
+use std::path::Path;
+use tantivy::collector::{DocSetCollector, TopDocs};
+use tantivy::directory::{Directory, RamDirectory};
 use tantivy::indexer::IndexWriter;
 use tantivy::schema::{Field, OwnedValue, Schema, TantivyDocument};
+use tantivy::tokenizer::TextAnalyzer;
 use tantivy::{Index, IndexReader, Term, doc};
 use tempfile::TempDir;
 
 /// Tantivy-backed search index used when the `lex` feature is enabled.
 pub struct TantivyEngine {
-    pub(super) work_dir: TempDir,
+    /// On-disk work directory; `None` when the index lives in memory.
+    pub(super) work_dir: Option<TempDir>,
     pub(super) index: Index,
     pub(super) _schema: Schema,
     pub(super) content: Field,
@@ -26,6 +31,10 @@ pub struct TantivyEngine {
     pub(super) index_writer: Option<IndexWriter>,
     pub(super) reader: IndexReader,
     pub(super) tokenizer: Option<String>,
+    /// Whether analysed query terms keep consecutive positions (false for n-grams).
+    pub(super) phrase_queries: bool,
+    /// Lowercases and folds `term*` prefixes the way indexed terms were.
+    pub(super) prefix_normalizer: TextAnalyzer,
 }
 
 /// Search hit returned from Tantivy queries.
@@ -50,7 +59,8 @@ pub struct TantivySegmentBlob {
 }
 
 impl TantivyEngine {
-    pub fn create() -> Result<Self> {
+    /// Create an empty on-disk index that analyses content with `analyzer`.
+    pub fn create(analyzer: &LexAnalyzerConfig) -> Result<Self> {
         let dir = TempDir::new().map_err(|err| MemvidError::Tantivy {
             reason: format!("failed to allocate Tantivy work directory: {}", err),
         })?;
@@ -60,20 +70,67 @@ impl TantivyEngine {
                 reason: err.to_string(),
             }
         })?;
-        initialise_tokenizer(&index);
-        Self::from_parts(dir, index, schema)
+        initialise_tokenizer(&index, analyzer);
+        Self::from_parts(Some(dir), index, schema, analyzer)
     }
 
-    pub fn open_from_dir(dir: TempDir) -> Result<Self> {
+    /// Create an empty index that never touches disk (used for encrypted memories).
+    pub(crate) fn create_in_ram(analyzer: &LexAnalyzerConfig) -> Result<Self> {
+        let schema = build_schema();
+        let index = Index::create_in_ram(schema.clone());
+        initialise_tokenizer(&index, analyzer);
+        Self::from_parts(None, index, schema, analyzer)
+    }
+
+    /// Open an on-disk index whose content was analysed with `analyzer`.
+    pub fn open_from_dir(dir: TempDir, analyzer: &LexAnalyzerConfig) -> Result<Self> {
         let index = Index::open_in_dir(dir.path()).map_err(|err| MemvidError::Tantivy {
             reason: err.to_string(),
         })?;
-        initialise_tokenizer(&index);
+        initialise_tokenizer(&index, analyzer);
         let schema = index.schema();
-        Self::from_parts(dir, index, schema)
+        Self::from_parts(Some(dir), index, schema, analyzer)
+    }
+
+    /// Open an index whose files were loaded into `directory`.
+    pub(crate) fn open_in_ram(
+        directory: RamDirectory,
+        analyzer: &LexAnalyzerConfig,
+    ) -> Result<Self> {
+        let index = Index::open(directory).map_err(|err| MemvidError::Tantivy {
+            reason: err.to_string(),
+        })?;
+        initialise_tokenizer(&index, analyzer);
+        let schema = index.schema();
+        Self::from_parts(None, index, schema, analyzer)
+    }
+
+    /// Write index files published since the engine was opened, e.g. by a commit from
+    /// another process, and reopen the reader and writer over them.
+    pub(crate) fn apply_index_files(&mut self, files: &[(String, Vec<u8>)]) -> Result<()> {
+        // The writer caches the segment list, so it is recreated over the new files.
+        drop(self.index_writer.take());
+        let directory = self.index.directory();
+        for (path, bytes) in files {
+            directory
+                .atomic_write(Path::new(path), bytes)
+                .map_err(|err| MemvidError::Tantivy {
+                    reason: format!("failed to write Tantivy file {path}: {err}"),
+                })?;
+        }
+        self.reader.reload().map_err(|err| MemvidError::Tantivy {
+            reason: err.to_string(),
+        })?;
+        self.index_writer = Some(self.create_writer()?);
+        Ok(())
     }
 
-    fn from_parts(dir: TempDir, index: Index, schema: Schema) -> Result<Self> {
+    fn from_parts(
+        dir: Option<TempDir>,
+        index: Index,
+        schema: Schema,
+        analyzer: &LexAnalyzerConfig,
+    ) -> Result<Self> {
         let content = schema
             .get_field("content")
             .map_err(|err| MemvidError::Tantivy {
@@ -133,6 +190,8 @@ impl TantivyEngine {
             index_writer: Some(writer),
             reader,
             tokenizer: Some("memvid_default".to_string()),
+            phrase_queries: analyzer.supports_phrases(),
+            prefix_normalizer: build_prefix_normalizer(analyzer),
         })
     }
 
@@ -290,21 +349,7 @@ impl TantivyEngine {
                 searcher.doc(address).map_err(|err| MemvidError::Tantivy {
                     reason: err.to_string(),
                 })?;
-            let frame_id = match document.get_first(self.frame_id) {
-                Some(value) => match OwnedValue::from(value) {
-                    OwnedValue::U64(id) => id,
-                    _ => {
-                        return Err(MemvidError::Tantivy {
-                            reason: "tantivy doc missing frame_id".into(),
-                        });
-                    }
-                },
-                None => {
-                    return Err(MemvidError::Tantivy {
-                        reason: "tantivy doc missing frame_id".into(),
-                    });
-                }
-            };
+            let frame_id = self.document_frame_id(&document)?;
             let content = match document.get_first(self.content) {
                 Some(value) => match OwnedValue::from(value) {
                     OwnedValue::Str(text) => text,
@@ -321,12 +366,71 @@ impl TantivyEngine {
         Ok(results)
     }
 
+    /// Frame IDs of every document matching the query, without ranking or a result limit.
+    /// Used for aggregations (such as facet counts) over the full matched set.
+    pub fn matching_frame_ids(
+        &self,
+        parsed: &ParsedQuery,
+        uri_filter: Option<&str>,
+        scope_filter: Option<&str>,
+        frame_filter: Option<&[u64]>,
+    ) -> Result<Vec<FrameId>> {
+        if frame_filter.is_some_and(<[u64]>::is_empty) {
+            return Ok(Vec::new());
+        }
+
+        let query = query::build_root_query(self, parsed, uri_filter, scope_filter, frame_filter)?;
+        let searcher = self.reader.searcher();
+        let addresses =
+            searcher
+                .search(&query, &DocSetCollector)
+                .map_err(|err| MemvidError::Tantivy {
+                    reason: err.to_string(),
+                })?;
+        // Indexes written before `frame_id` became a fast field only have it stored.
+        let columns: Vec<_> = searcher
+            .segment_readers()
+            .iter()
+            .map(|segment| segment.fast_fields().u64("frame_id").ok())
+            .collect();
+        let mut frame_ids = Vec::with_capacity(addresses.len());
+        for address in addresses {
+            let column = columns
+                .get(address.segment_ord as usize)
+                .and_then(Option::as_ref);
+            if let Some(frame_id) = column.and_then(|column| column.first(address.doc_id)) {
+                frame_ids.push(frame_id);
+                continue;
+            }
+            let document: TantivyDocument =
+                searcher.doc(address).map_err(|err| MemvidError::Tantivy {
+                    reason: err.to_string(),
+                })?;
+            frame_ids.push(self.document_frame_id(&document)?);
+        }
+        frame_ids.sort_unstable();
+        frame_ids.dedup();
+        Ok(frame_ids)
+    }
+
+    fn document_frame_id(&self, document: &TantivyDocument) -> Result<FrameId> {
+        match document.get_first(self.frame_id).map(OwnedValue::from) {
+            Some(OwnedValue::U64(id)) => Ok(id),
+            _ => Err(MemvidError::Tantivy {
+                reason: "tantivy doc missing frame_id".into(),
+            }),
+        }
+    }
+
     pub fn snapshot_segments(&self) -> Result<TantivySnapshot> {
+        let Some(work_dir) = self.work_dir.as_ref() else {
+            return self.snapshot_ram_segments();
+        };
         let mut entries =
-            std::fs::read_dir(self.work_dir.path()).map_err(|err| MemvidError::Tantivy {
+            std::fs::read_dir(work_dir.path()).map_err(|err| MemvidError::Tantivy {
                 reason: format!(
                     "failed to read Tantivy index directory {}: {}",
-                    self.work_dir.path().display(),
+                    work_dir.path().display(),
                     err
                 ),
             })?;
@@ -335,7 +439,7 @@ impl TantivyEngine {
             let entry = entry.map_err(|err| MemvidError::Tantivy {
                 reason: format!(
                     "failed to iterate Tantivy index directory {}: {}",
-                    self.work_dir.path().display(),
+                    work_dir.path().display(),
                     err
                 ),
             })?;
@@ -357,14 +461,52 @@ impl TantivyEngine {
         }
         file_names.sort();
 
-        let mut segments = Vec::with_capacity(file_names.len());
-        let mut index_hasher = Hasher::new();
-
+        let mut files = Vec::with_capacity(file_names.len());
         for name in file_names {
-            let path = self.work_dir.path().join(&name);
+            let path = work_dir.path().join(&name);
             let bytes = std::fs::read(&path).map_err(|err| MemvidError::Tantivy {
                 reason: format!("failed to read Tantivy segment {}: {}", path.display(), err),
             })?;
+            files.push((name, bytes));
+        }
+        Ok(self.snapshot_from_files(files))
+    }
+
+    /// Snapshot an in-memory index from the files its managed directory tracks.
+    fn snapshot_ram_segments(&self) -> Result<TantivySnapshot> {
+        let directory = self.index.directory();
+        let mut file_names: Vec<String> = directory
+            .list_managed_files()
+            .into_iter()
+            .filter_map(|path| path.to_str().map(str::to_string))
+            .collect();
+        for name in ["meta.json", ".managed.json"] {
+            if !file_names.iter().any(|existing| existing == name)
+                && directory.exists(Path::new(name)).unwrap_or(false)
+            {
+                file_names.push(name.to_string());
+            }
+        }
+        file_names.sort();
+
+        let mut files = Vec::with_capacity(file_names.len());
+        for name in file_names {
+            let bytes =
+                directory
+                    .atomic_read(Path::new(&name))
+                    .map_err(|err| MemvidError::Tantivy {
+                        reason: format!("failed to read Tantivy segment {name}: {err}"),
+                    })?;
+            files.push((name, bytes));
+        }
+        Ok(self.snapshot_from_files(files))
+    }
+
+    fn snapshot_from_files(&self, files: Vec<(String, Vec<u8>)>) -> TantivySnapshot {
+        let mut segments = Vec::with_capacity(files.len());
+        let mut index_hasher = Hasher::new();
+
+        for (name, bytes) in files {
             let checksum = *hash(&bytes).as_bytes();
             index_hasher.update(&checksum);
             index_hasher.update(name.as_bytes());
@@ -376,23 +518,39 @@ impl TantivyEngine {
         }
 
         let checksum = *index_hasher.finalize().as_bytes();
-        Ok(TantivySnapshot {
+        TantivySnapshot {
             doc_count: self.reader.searcher().num_docs(),
             checksum,
             segments,
-        })
+        }
+    }
+
+    /// The analyzer applied to the content field, if one is registered.
+    pub(super) fn text_analyzer(&self) -> Option<TextAnalyzer> {
+        self.tokenizer
+            .as_ref()
+            .and_then(|name| self.index.tokenizers().get(name))
+    }
+
+    /// `prefix` lowercased and folded like the indexed terms, without stemming.
+    pub(super) fn normalise_prefix(&self, prefix: &str) -> String {
+        let mut normalizer = self.prefix_normalizer.clone();
+        let mut stream = normalizer.token_stream(prefix);
+        let mut normalised = String::new();
+        while stream.advance() {
+            normalised.push_str(&stream.token().text);
+        }
+        normalised
     }
 
     pub(crate) fn analyse_text(&self, text: &str) -> Vec<String> {
-        if let Some(name) = &self.tokenizer {
-            if let Some(mut analyzer) = self.index.tokenizers().get(name) {
-                let mut stream = analyzer.token_stream(text);
-                let mut tokens = Vec::new();
-                while stream.advance() {
-                    tokens.push(stream.token().text.to_string());
-                }
-                return tokens;
+        if let Some(mut analyzer) = self.text_analyzer() {
+            let mut stream = analyzer.token_stream(text);
+            let mut tokens = Vec::new();
+            while stream.advance() {
+                tokens.push(stream.token().text.to_string());
             }
+            return tokens;
         }
         if text.trim().is_empty() {
             Vec::new()
@@ -0,0 +1,104 @@
+//! Query-term highlighting with the index analyzer.
+//!
+//! Text is tokenized exactly as it was indexed, so a query for "running" marks "runs" when
+//! both stem to "run", and folded or fuzzy matches are marked where they occur.
+
+use std::collections::HashSet;
+
+use regex::Regex;
+use tantivy::tokenizer::TextAnalyzer;
+
+use super::engine::TantivyEngine;
+use crate::search::parser::{Expr, ParsedQuery, Term, TextTerm};
+use crate::search::within_edit_distance;
+
+/// Finds the words of a text matched by the positive text terms of a query.
+pub(crate) struct Highlighter {
+    analyzer: Option<TextAnalyzer>,
+    terms: HashSet<String>,
+    prefixes: Vec<String>,
+    fuzzy: Vec<(String, u8)>,
+    patterns: Vec<Regex>,
+}
+
+impl Highlighter {
+    pub(crate) fn new(engine: &TantivyEngine, parsed: &ParsedQuery) -> Self {
+        let mut highlighter = Self {
+            analyzer: engine.text_analyzer(),
+            terms: HashSet::new(),
+            prefixes: Vec::new(),
+            fuzzy: Vec::new(),
+            patterns: Vec::new(),
+        };
+        highlighter.collect(engine, &parsed.expr);
+        highlighter
+    }
+
+    fn collect(&mut self, engine: &TantivyEngine, expr: &Expr) {
+        match expr {
+            Expr::Or(children) | Expr::And(children) => {
+                for child in children {
+                    self.collect(engine, child);
+                }
+            }
+            Expr::Boost(child, _) => self.collect(engine, child),
+            // Excluded terms do not occur in matching text.
+            Expr::Not(_) | Expr::Term(Term::Field(_)) => {}
+            Expr::Term(Term::Text(text)) => match text {
+                TextTerm::Word(text)
+                | TextTerm::Phrase(text)
+                | TextTerm::Proximity { phrase: text, .. } => {
+                    self.terms.extend(engine.analyse_text(text));
+                }
+                TextTerm::Prefix(prefix) => self.prefixes.push(engine.normalise_prefix(prefix)),
+                TextTerm::Fuzzy { word, distance } => self.fuzzy.extend(
+                    engine
+                        .analyse_text(word)
+                        .into_iter()
+                        .map(|token| (token, *distance)),
+                ),
+                TextTerm::Wildcard(pattern) => self.patterns.push(pattern.regex.clone()),
+            },
+        }
+    }
+
+    fn is_match(&self, token: &str) -> bool {
+        self.terms.contains(token)
+            || self.prefixes.iter().any(|prefix| token.starts_with(prefix))
+            || self
+                .fuzzy
+                .iter()
+                .any(|(word, distance)| within_edit_distance(word, token, usize::from(*distance)))
+            || self.patterns.iter().any(|pattern| pattern.is_match(token))
+    }
+
+    /// Byte ranges of the matched words in `text`, sorted, with overlaps merged.
+    pub(crate) fn spans(&mut self, text: &str) -> Vec<(usize, usize)> {
+        let Some(analyzer) = self.analyzer.as_mut() else {
+            return Vec::new();
+        };
+        let mut tokens = Vec::new();
+        {
+            let mut stream = analyzer.token_stream(text);
+            while stream.advance() {
+                let token = stream.token();
+                tokens.push((token.text.clone(), token.offset_from, token.offset_to));
+            }
+        }
+
+        let mut spans: Vec<(usize, usize)> = tokens
+            .into_iter()
+            .filter(|(token, _, _)| self.is_match(token))
+            .map(|(_, start, end)| (start, end))
+            .collect();
+        spans.sort_unstable();
+        let mut merged: Vec<(usize, usize)> = Vec::with_capacity(spans.len());
+        for (start, end) in spans {
+            match merged.last_mut() {
+                Some(last) if start < last.1 => last.1 = last.1.max(end),
+                _ => merged.push((start, end)),
+            }
+        }
+        merged
+    }
+}
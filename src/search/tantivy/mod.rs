@@ -1,6 +1,8 @@
 //! Tantivy-backed lexical search integration.
 
+mod analyzer;
 mod engine;
+mod highlight;
 mod query;
 mod schema;
 mod storage;
@@ -9,6 +11,7 @@ mod wal;
 
 #[allow(unused_imports)]
 pub use engine::{TantivyDocHit, TantivyEngine, TantivySnapshot};
+pub(crate) use highlight::Highlighter;
 #[allow(unused_imports)]
 pub(crate) use storage::{EmbeddedLexSegment, EmbeddedLexStorage};
 #[allow(unused_imports)]
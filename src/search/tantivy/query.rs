@@ -6,11 +6,14 @@ use crate::search::parser::{Expr, FieldTerm, ParsedQuery, Term as ParsedTerm, Te
 use crate::{MemvidError, Result};
 use tantivy::Term;
 use tantivy::query::{
-    AllQuery, BooleanQuery, Occur, PhraseQuery, Query, RangeQuery, RegexQuery, TermQuery,
-    TermSetQuery,
+    AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhrasePrefixQuery, PhraseQuery,
+    Query, RangeQuery, RegexQuery, TermQuery, TermSetQuery,
 };
 use tantivy::schema::IndexRecordOption;
 
+/// Most index terms a `term*` prefix query expands to.
+const MAX_PREFIX_EXPANSIONS: u32 = 1024;
+
 pub(super) fn build_root_query(
     engine: &TantivyEngine,
     parsed: &ParsedQuery,
@@ -96,6 +99,10 @@ impl<'a> QueryPlanner<'a> {
                 (Occur::Must, Box::new(AllQuery)),
                 (Occur::MustNot, self.build_expr_query(child)?),
             ]))),
+            Expr::Boost(child, boost) => Ok(Box::new(BoostQuery::new(
+                self.build_expr_query(child)?,
+                *boost,
+            ))),
             Expr::Term(term) => self.build_term_query(term),
         }
     }
@@ -121,6 +128,17 @@ impl<'a> QueryPlanner<'a> {
                     })?;
                 Ok(Box::new(query))
             }
+            TextTerm::Prefix(prefix) => {
+                let term = Term::from_field_text(
+                    self.engine.content,
+                    &self.engine.normalise_prefix(prefix),
+                );
+                let mut query = PhrasePrefixQuery::new(vec![term]);
+                query.set_max_expansions(MAX_PREFIX_EXPANSIONS);
+                Ok(Box::new(query))
+            }
+            TextTerm::Fuzzy { word, distance } => Ok(self.build_fuzzy_query(word, *distance)),
+            TextTerm::Proximity { phrase, slop } => Ok(self.build_proximity_query(phrase, *slop)),
         }
     }
 
@@ -185,19 +203,7 @@ impl<'a> QueryPlanner<'a> {
             // This can happen with punctuation-only or stop-word-only terms
             return Ok(Box::new(AllQuery));
         }
-        let mut queries: Vec<Box<dyn Query>> = Vec::new();
-        if tokens.len() == 1 {
-            queries.push(Box::new(TermQuery::new(
-                Term::from_field_text(self.engine.content, &tokens[0]),
-                IndexRecordOption::WithFreqsAndPositions,
-            )));
-        } else {
-            let terms: Vec<Term> = tokens
-                .iter()
-                .map(|token| Term::from_field_text(self.engine.content, token))
-                .collect();
-            queries.push(Box::new(PhraseQuery::new(terms)));
-        }
+        let mut queries: Vec<Box<dyn Query>> = vec![self.build_content_query(&tokens)];
 
         let normalized = to_search_value(word);
         queries.push(Box::new(TermQuery::new(
@@ -220,6 +226,78 @@ impl<'a> QueryPlanner<'a> {
         Ok(combine_should_queries(queries))
     }
 
+    /// Match analysed `tokens` in the content field: a phrase when the analyzer keeps term
+    /// positions, otherwise every distinct term (n-grams of one word share a position).
+    fn build_content_query(&self, tokens: &[String]) -> Box<dyn Query> {
+        let mut terms: Vec<Term> = tokens
+            .iter()
+            .map(|token| Term::from_field_text(self.engine.content, token))
+            .collect();
+        if terms.len() == 1 {
+            return Box::new(TermQuery::new(
+                terms.remove(0),
+                IndexRecordOption::WithFreqsAndPositions,
+            ));
+        }
+        if self.engine.phrase_queries {
+            return Box::new(PhraseQuery::new(terms));
+        }
+        terms.sort();
+        terms.dedup();
+        let clauses = terms
+            .into_iter()
+            .map(|term| {
+                let query: Box<dyn Query> =
+                    Box::new(TermQuery::new(term, IndexRecordOption::WithFreqs));
+                (Occur::Must, query)
+            })
+            .collect();
+        Box::new(BooleanQuery::new(clauses))
+    }
+
+    /// Every analysed term of `word` within `distance` edits, a transposition counting as one.
+    fn build_fuzzy_query(&self, word: &str, distance: u8) -> Box<dyn Query> {
+        let mut tokens = self.engine.analyse_text(word);
+        tokens.sort();
+        tokens.dedup();
+        let mut queries: Vec<Box<dyn Query>> = tokens
+            .iter()
+            .map(|token| {
+                let term = Term::from_field_text(self.engine.content, token);
+                Box::new(FuzzyTermQuery::new(term, distance, true)) as Box<dyn Query>
+            })
+            .collect();
+        match queries.len() {
+            0 => Box::new(AllQuery),
+            1 => queries.remove(0),
+            _ => Box::new(BooleanQuery::new(
+                queries
+                    .into_iter()
+                    .map(|query| (Occur::Must, query))
+                    .collect(),
+            )),
+        }
+    }
+
+    /// The analysed phrase terms with up to `slop` positions of leeway. Analyzers without
+    /// term positions match the terms anywhere in the document instead.
+    fn build_proximity_query(&self, phrase: &str, slop: u32) -> Box<dyn Query> {
+        let tokens = self.engine.analyse_text(phrase);
+        if tokens.is_empty() {
+            return Box::new(AllQuery);
+        }
+        if tokens.len() == 1 || !self.engine.phrase_queries {
+            return self.build_content_query(&tokens);
+        }
+        let terms = tokens
+            .iter()
+            .map(|token| Term::from_field_text(self.engine.content, token))
+            .collect();
+        let mut query = PhraseQuery::new(terms);
+        query.set_slop(slop);
+        Box::new(query)
+    }
+
     fn build_phrase_query(&self, phrase: &str) -> Result<Box<dyn Query>> {
         // Handle empty phrases gracefully
         if phrase.is_empty() {
@@ -231,19 +309,7 @@ impl<'a> QueryPlanner<'a> {
             // Phrase produced no tokens after analysis - match all instead of erroring
             return Ok(Box::new(AllQuery));
         }
-        let mut queries: Vec<Box<dyn Query>> = Vec::new();
-        if tokens.len() == 1 {
-            queries.push(Box::new(TermQuery::new(
-                Term::from_field_text(self.engine.content, &tokens[0]),
-                IndexRecordOption::WithFreqsAndPositions,
-            )));
-        } else {
-            let terms: Vec<Term> = tokens
-                .iter()
-                .map(|token| Term::from_field_text(self.engine.content, token))
-                .collect();
-            queries.push(Box::new(PhraseQuery::new(terms)));
-        }
+        let mut queries: Vec<Box<dyn Query>> = vec![self.build_content_query(&tokens)];
 
         let normalized = to_search_value(phrase);
         queries.push(Box::new(TermQuery::new(
@@ -1,15 +1,21 @@
 use tantivy::Index;
 use tantivy::schema::{IndexRecordOption, NumericOptions, STRING, Schema, TEXT, TextFieldIndexing};
-use tantivy::tokenizer::{
-    Language, LowerCaser, RawTokenizer, SimpleTokenizer, Stemmer, TextAnalyzer,
-};
-
-pub(super) fn initialise_tokenizer(index: &Index) {
-    let analyzer = TextAnalyzer::builder(SimpleTokenizer::default())
-        .filter(LowerCaser)
-        .filter(Stemmer::new(Language::English))
-        .build();
-    index.tokenizers().register("memvid_default", analyzer);
+use tantivy::tokenizer::RawTokenizer;
+
+use super::analyzer::build_analyzer;
+use crate::types::LexAnalyzerConfig;
+
+/// Register the tokenizers used by the schema. `memvid_default` analyses frame content with
+/// the memory's configured analyzer; `memvid_keyword` keeps tags, labels and tracks on the
+/// default analyzer so keyword lookups do not depend on the content language.
+pub(super) fn initialise_tokenizer(index: &Index, analyzer: &LexAnalyzerConfig) {
+    index
+        .tokenizers()
+        .register("memvid_default", build_analyzer(analyzer));
+    index.tokenizers().register(
+        "memvid_keyword",
+        build_analyzer(&LexAnalyzerConfig::default()),
+    );
     index.tokenizers().register("raw", RawTokenizer::default());
 }
 
@@ -23,7 +29,7 @@ pub(super) fn build_schema() -> Schema {
     schema_builder.add_text_field("content", content_field);
 
     let keyword_indexing = TextFieldIndexing::default()
-        .set_tokenizer("memvid_default")
+        .set_tokenizer("memvid_keyword")
         .set_index_option(IndexRecordOption::Basic);
     let keyword_field = STRING
         .set_stored()
@@ -44,7 +50,10 @@ pub(super) fn build_schema() -> Schema {
         .set_stored();
     schema_builder.add_i64_field("timestamp", timestamp_options);
 
-    let frame_id_options = NumericOptions::default().set_indexed().set_stored();
+    let frame_id_options = NumericOptions::default()
+        .set_indexed()
+        .set_fast()
+        .set_stored();
     schema_builder.add_u64_field("frame_id", frame_id_options);
 
     schema_builder.build()
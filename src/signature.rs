@@ -1,11 +1,12 @@
 use base64::Engine;
 use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
-use ed25519_dalek::{Signature, VerifyingKey};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
 use serde::Serialize;
 use std::convert::TryInto;
 use uuid::Uuid;
 
 use crate::error::{MemvidError, Result};
+use crate::types::MemorySignature;
 
 const SIGNING_SCHEMA_VERSION: u8 = 1;
 
@@ -28,6 +29,12 @@ struct ModelSignaturePayload<'a> {
     size_bytes: u64,
 }
 
+#[derive(Serialize)]
+struct MemorySignaturePayload<'a> {
+    version: u8,
+    merkle_root: &'a str,
+}
+
 fn ticket_message_bytes(
     memory_id: &Uuid,
     issuer: &str,
@@ -66,6 +73,54 @@ fn model_message_bytes(
     })
 }
 
+fn memory_message_bytes(merkle_root: &[u8; 32]) -> Result<Vec<u8>> {
+    let merkle_root = hex::encode(merkle_root);
+    let payload = MemorySignaturePayload {
+        version: SIGNING_SCHEMA_VERSION,
+        merkle_root: &merkle_root,
+    };
+    serde_json::to_vec(&payload).map_err(|err| MemvidError::MemorySignatureInvalid {
+        reason: format!("failed to serialize memory payload: {err}").into_boxed_str(),
+    })
+}
+
+/// Signs a memory's Merkle root.
+pub(crate) fn sign_memory_root(
+    signing_key: &SigningKey,
+    merkle_root: &[u8; 32],
+) -> Result<MemorySignature> {
+    let message = memory_message_bytes(merkle_root)?;
+    Ok(MemorySignature {
+        public_key: signing_key.verifying_key().to_bytes(),
+        signature: signing_key.sign(&message).to_bytes().to_vec(),
+    })
+}
+
+/// Verifies that `signature` was made by `verifying_key` over `merkle_root`.
+///
+/// # Errors
+///
+/// Fails with [`MemvidError::MemorySignatureInvalid`] when the key or signature does not match.
+pub fn verify_memory_signature(
+    verifying_key: &VerifyingKey,
+    merkle_root: &[u8; 32],
+    signature: &MemorySignature,
+) -> Result<()> {
+    if signature.public_key != verifying_key.to_bytes() {
+        return Err(MemvidError::MemorySignatureInvalid {
+            reason: "memory was signed by a different key".into(),
+        });
+    }
+    let message = memory_message_bytes(merkle_root)?;
+    let signature = to_signature(&signature.signature)
+        .map_err(|reason| MemvidError::MemorySignatureInvalid { reason })?;
+    verifying_key
+        .verify_strict(&message, &signature)
+        .map_err(|_| MemvidError::MemorySignatureInvalid {
+            reason: "memory signature mismatch".into(),
+        })
+}
+
 pub fn verify_ticket_signature(
     verifying_key: &VerifyingKey,
     memory_id: &Uuid,
@@ -133,7 +188,6 @@ pub fn parse_ed25519_public_key_base64(encoded: &str) -> Result<VerifyingKey> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use ed25519_dalek::{Signer, SigningKey};
 
     fn test_signing_key() -> SigningKey {
         let seed = [7u8; 32];
@@ -194,6 +248,15 @@ mod tests {
         .unwrap();
     }
 
+    #[test]
+    fn memory_root_roundtrip() {
+        let signing = test_signing_key();
+        let root = [3u8; 32];
+        let signature = sign_memory_root(&signing, &root).unwrap();
+        verify_memory_signature(&signing.verifying_key(), &root, &signature).unwrap();
+        assert!(verify_memory_signature(&signing.verifying_key(), &[4u8; 32], &signature).is_err());
+    }
+
     #[test]
     fn parse_public_key() {
         let signing = test_signing_key();
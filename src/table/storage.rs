@@ -137,6 +137,7 @@ fn store_table_impl(
         dedup: false,
         instant_index: false,    // Tables are batch operations, commit at end
         extraction_budget_ms: 0, // No budget for table metadata
+        vector_space: None,
     };
 
     let meta_frame_id = mem.next_frame_id();
@@ -209,6 +210,7 @@ fn store_table_impl(
             dedup: false,
             instant_index: false, // Tables are batch operations, commit at end
             extraction_budget_ms: 0, // No budget for table rows
+            vector_space: None,
         };
 
         let should_embed = embed_rows && embedder.is_some();
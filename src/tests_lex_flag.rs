@@ -68,6 +68,11 @@ mod tests {
                         as_of_frame: None,
                         as_of_ts: None,
                         no_sketch: false,
+                        filter: None,
+                        facets: Vec::new(),
+                        hybrid: None,
+                        reranker: None,
+                        fragments: None,
                     })
                     .expect("search must succeed");
 
@@ -0,0 +1,644 @@
+//! Local ONNX text embedder for BGE / nomic style sentence-embedding models.
+//!
+//! Models are loaded from a verified model directory (see [`crate::models::verify_model_dir`])
+//! holding the ONNX weights and a Hugging Face `tokenizer.json`. The embedder implements both
+//! [`EmbeddingProvider`] and [`VecEmbedder`], so it can be passed to `Memvid::ask`,
+//! `Memvid::search_with_embedder` and `start_enrichment_worker_with_embeddings`.
+
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use ndarray::Array2;
+use ort::session::{Session, SessionInputValue, builder::GraphOptimizationLevel};
+use ort::value::Tensor;
+use tokenizers::{PaddingParams, PaddingStrategy, Tokenizer, TruncationParams};
+
+use crate::error::{MemvidError, Result};
+use crate::models::{
+    ModelManifest, ModelVerificationStatus, ModelVerifyOptions, load_model_manifest,
+    resolve_entry_path, select_weights_entry, verify_model_dir,
+};
+use crate::types::{EmbeddingConfig, EmbeddingProvider, VecEmbedder};
+
+/// Token budget used when the manifest does not declare `context-length`.
+const DEFAULT_CONTEXT_LENGTH: usize = 512;
+/// Texts per ONNX run when the config does not set `batch_size`.
+const DEFAULT_BATCH_SIZE: usize = 32;
+
+/// How per-token hidden states are reduced to one sentence embedding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmbeddingPooling {
+    /// First (`[CLS]`) token, as used by BGE models.
+    Cls,
+    /// Attention-masked mean over all tokens, as used by nomic models.
+    Mean,
+}
+
+/// Options for [`LocalOnnxEmbedder::open`].
+#[derive(Debug, Clone)]
+pub struct LocalOnnxEmbedderConfig {
+    /// Model name, expected dimension, batch size and normalisation.
+    pub embedding: EmbeddingConfig,
+    pub pooling: EmbeddingPooling,
+    /// Prepended to query text (`VecEmbedder::embed_query`).
+    pub query_prefix: Option<String>,
+    /// Prepended to document text (`embed_text`, `embed_batch`, `embed_chunks`).
+    pub document_prefix: Option<String>,
+    /// Intra-op threads for the ONNX session.
+    pub intra_threads: usize,
+}
+
+impl LocalOnnxEmbedderConfig {
+    /// BAAI/bge-small-en-v1.5 with the BGE retrieval instruction on queries.
+    #[must_use]
+    pub fn bge_small() -> Self {
+        Self::bge(EmbeddingConfig::bge_small())
+    }
+
+    /// BAAI/bge-base-en-v1.5 with the BGE retrieval instruction on queries.
+    #[must_use]
+    pub fn bge_base() -> Self {
+        Self::bge(EmbeddingConfig::bge_base())
+    }
+
+    /// nomic-embed-text-v1.5 with its `search_query:` / `search_document:` task prefixes.
+    #[must_use]
+    pub fn nomic() -> Self {
+        Self {
+            embedding: EmbeddingConfig::nomic(),
+            pooling: EmbeddingPooling::Mean,
+            query_prefix: Some("search_query: ".to_string()),
+            document_prefix: Some("search_document: ".to_string()),
+            intra_threads: 4,
+        }
+    }
+
+    /// Reject settings the embedder cannot run with.
+    pub fn validate(&self) -> Result<()> {
+        let reason = if self.embedding.model.trim().is_empty() {
+            "model name is empty"
+        } else if self.embedding.dimension == 0 {
+            "dimension must be positive"
+        } else if self.embedding.batch_size == Some(0) {
+            "batch size must be positive"
+        } else {
+            return Ok(());
+        };
+        Err(MemvidError::EmbeddingFailed {
+            reason: format!("invalid embedder config: {reason}").into_boxed_str(),
+        })
+    }
+
+    fn bge(embedding: EmbeddingConfig) -> Self {
+        Self {
+            embedding,
+            pooling: EmbeddingPooling::Cls,
+            query_prefix: Some(
+                "Represent this sentence for searching relevant passages: ".to_string(),
+            ),
+            document_prefix: None,
+            intra_threads: 4,
+        }
+    }
+}
+
+/// Sentence embedder running a local ONNX model.
+pub struct LocalOnnxEmbedder {
+    config: LocalOnnxEmbedderConfig,
+    model_dir: PathBuf,
+    session: Mutex<Session>,
+    tokenizer: Tokenizer,
+}
+
+impl LocalOnnxEmbedder {
+    /// Load a model directory after verifying its manifest and file checksums.
+    ///
+    /// The directory must be named `sha256-<digest>` and contain a `manifest.json` whose
+    /// `dims` match `config.embedding.dimension`. The tokenizer is the manifest entry with the
+    /// `tokenizer` role, or `tokenizer.json` next to the manifest.
+    pub fn open(dir: impl AsRef<Path>, config: LocalOnnxEmbedderConfig) -> Result<Self> {
+        config.validate()?;
+        let dir = dir.as_ref();
+        let report = verify_model_dir(
+            dir,
+            &ModelVerifyOptions {
+                run_onnx_smoke: false,
+            },
+        )?;
+        if report.status == ModelVerificationStatus::Fail {
+            return Err(MemvidError::ModelIntegrity {
+                reason: report.errors.join("; ").into_boxed_str(),
+            });
+        }
+        if report.dims != Some(config.embedding.dimension as u32) {
+            return Err(MemvidError::ModelManifestInvalid {
+                reason: format!(
+                    "model {} declares {:?} dims but {} expects {}",
+                    report.digest, report.dims, config.embedding.model, config.embedding.dimension
+                )
+                .into_boxed_str(),
+            });
+        }
+
+        let manifest = load_model_manifest(dir)?;
+        let weights =
+            select_weights_entry(&manifest).ok_or_else(|| MemvidError::ModelManifestInvalid {
+                reason: "manifest does not declare a model .onnx file".into(),
+            })?;
+        let weights_path = resolve_entry_path(dir, &weights.path)?;
+        let tokenizer_path = tokenizer_path(dir, &manifest)?;
+        let context_length = manifest
+            .context_length
+            .map_or(DEFAULT_CONTEXT_LENGTH, |len| len as usize);
+
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|err| embedding_error("tokenizer", err))?;
+        tokenizer.with_padding(Some(PaddingParams {
+            strategy: PaddingStrategy::BatchLongest,
+            ..PaddingParams::default()
+        }));
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: context_length,
+                ..TruncationParams::default()
+            }))
+            .map_err(|err| embedding_error("tokenizer truncation", err))?;
+
+        let session = Session::builder()
+            .map_err(|err| embedding_error("session", err))?
+            .with_optimization_level(GraphOptimizationLevel::Level3)
+            .map_err(|err| embedding_error("session", err))?
+            .with_intra_threads(config.intra_threads.max(1))
+            .map_err(|err| embedding_error("session", err))?
+            .commit_from_file(&weights_path)
+            .map_err(|err| embedding_error("model load", err))?;
+
+        tracing::info!(
+            model = %config.embedding.model,
+            path = %weights_path.display(),
+            "local ONNX embedder loaded"
+        );
+
+        Ok(Self {
+            config,
+            model_dir: dir.to_path_buf(),
+            session: Mutex::new(session),
+            tokenizer,
+        })
+    }
+
+    /// Directory the model was loaded from.
+    #[must_use]
+    pub fn model_dir(&self) -> &Path {
+        &self.model_dir
+    }
+
+    #[must_use]
+    pub fn config(&self) -> &LocalOnnxEmbedderConfig {
+        &self.config
+    }
+
+    fn embed_with_prefix(&self, prefix: Option<&str>, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let batch_size = self
+            .config
+            .embedding
+            .batch_size
+            .unwrap_or(DEFAULT_BATCH_SIZE)
+            .max(1);
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for chunk in texts.chunks(batch_size) {
+            let inputs: Vec<String> = chunk
+                .iter()
+                .map(|text| format!("{}{text}", prefix.unwrap_or_default()))
+                .collect();
+            embeddings.extend(self.run_batch(inputs)?);
+        }
+        Ok(embeddings)
+    }
+
+    /// Tokenize, run the model once and pool one embedding per input.
+    fn run_batch(&self, inputs: Vec<String>) -> Result<Vec<Vec<f32>>> {
+        if inputs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let encodings = self
+            .tokenizer
+            .encode_batch(inputs, true)
+            .map_err(|err| embedding_error("tokenization", err))?;
+        let batch = encodings.len();
+        let seq_len = encodings.first().map_or(0, tokenizers::Encoding::len);
+
+        let mut input_ids = Vec::with_capacity(batch * seq_len);
+        let mut attention_mask = Vec::with_capacity(batch * seq_len);
+        let mut type_ids = Vec::with_capacity(batch * seq_len);
+        for encoding in &encodings {
+            input_ids.extend(encoding.get_ids().iter().map(|id| i64::from(*id)));
+            attention_mask.extend(encoding.get_attention_mask().iter().map(|m| i64::from(*m)));
+            type_ids.extend(encoding.get_type_ids().iter().map(|id| i64::from(*id)));
+        }
+        let mask: Vec<f32> = attention_mask.iter().map(|m| *m as f32).collect();
+
+        let mut session = self
+            .session
+            .lock()
+            .map_err(|_| MemvidError::Lock("Failed to lock embedding session".into()))?;
+        let input_names: Vec<String> = session
+            .inputs
+            .iter()
+            .map(|input| input.name.clone())
+            .collect();
+        let output_name = session
+            .outputs
+            .first()
+            .map(|output| output.name.clone())
+            .ok_or_else(|| MemvidError::EmbeddingFailed {
+                reason: "model declares no outputs".into(),
+            })?;
+
+        let mut session_inputs: Vec<(Cow<'_, str>, SessionInputValue<'_>)> = Vec::new();
+        for name in input_names {
+            let values = match name.as_str() {
+                "input_ids" => input_ids.clone(),
+                "attention_mask" => attention_mask.clone(),
+                "token_type_ids" => type_ids.clone(),
+                other => {
+                    return Err(MemvidError::EmbeddingFailed {
+                        reason: format!("unsupported model input '{other}'").into_boxed_str(),
+                    });
+                }
+            };
+            let array = Array2::from_shape_vec((batch, seq_len), values)
+                .map_err(|err| embedding_error("input shape", err))?;
+            let tensor = Tensor::from_array(array).map_err(|err| embedding_error("tensor", err))?;
+            session_inputs.push((Cow::Owned(name), tensor.into()));
+        }
+
+        let outputs = session
+            .run(session_inputs)
+            .map_err(|err| embedding_error("inference", err))?;
+        let output = outputs
+            .get(&output_name)
+            .ok_or_else(|| MemvidError::EmbeddingFailed {
+                reason: format!("missing model output '{output_name}'").into_boxed_str(),
+            })?;
+        let (shape, data) = output
+            .try_extract_tensor::<f32>()
+            .map_err(|err| embedding_error("output", err))?;
+        check_output_shape(shape, batch, seq_len)?;
+
+        let embeddings = match shape.len() {
+            // Sentence embeddings: [batch, hidden].
+            2 => {
+                let hidden = row_width(data, batch)?;
+                data.chunks(hidden).map(<[f32]>::to_vec).collect()
+            }
+            // Token embeddings: [batch, seq, hidden].
+            3 => {
+                let hidden = row_width(data, batch * seq_len)?;
+                (0..batch)
+                    .map(|row| {
+                        let tokens = &data[row * seq_len * hidden..(row + 1) * seq_len * hidden];
+                        let mask = &mask[row * seq_len..(row + 1) * seq_len];
+                        pool(tokens, mask, hidden, self.config.pooling)
+                    })
+                    .collect::<Vec<_>>()
+            }
+            rank => {
+                return Err(MemvidError::EmbeddingFailed {
+                    reason: format!("unexpected output rank {rank}").into_boxed_str(),
+                });
+            }
+        };
+
+        embeddings
+            .into_iter()
+            .map(|embedding| finish(&self.config.embedding, embedding))
+            .collect()
+    }
+}
+
+/// Reject outputs whose leading dimensions are not `[batch]` (sentence embeddings) or
+/// `[batch, seq_len]` (token embeddings), so rows are never split out of the wrong shape.
+fn check_output_shape(shape: &[i64], batch: usize, seq_len: usize) -> Result<()> {
+    let dim = |index: usize| shape.get(index).and_then(|dim| usize::try_from(*dim).ok());
+    let matches = match shape.len() {
+        2 => dim(0) == Some(batch),
+        3 => dim(0) == Some(batch) && dim(1) == Some(seq_len),
+        // Other ranks are reported when the output is split.
+        _ => true,
+    };
+    if matches {
+        Ok(())
+    } else {
+        Err(MemvidError::EmbeddingFailed {
+            reason: format!(
+                "model output shape {shape:?} does not match batch {batch} of {seq_len} tokens"
+            )
+            .into_boxed_str(),
+        })
+    }
+}
+
+/// Width of each of `rows` equal rows in a model output, rejecting empty or ragged tensors.
+fn row_width(data: &[f32], rows: usize) -> Result<usize> {
+    if rows == 0 || data.is_empty() || data.len() % rows != 0 {
+        return Err(MemvidError::EmbeddingFailed {
+            reason: format!(
+                "degenerate model output: {} values for {rows} rows",
+                data.len()
+            )
+            .into_boxed_str(),
+        });
+    }
+    Ok(data.len() / rows)
+}
+
+/// Validate dimension and finiteness, then normalise if configured.
+fn finish(config: &EmbeddingConfig, mut embedding: Vec<f32>) -> Result<Vec<f32>> {
+    if embedding.len() != config.dimension {
+        return Err(MemvidError::VecDimensionMismatch {
+            expected: config.dimension as u32,
+            actual: embedding.len(),
+        });
+    }
+    if embedding.iter().any(|value| !value.is_finite()) {
+        return Err(MemvidError::EmbeddingFailed {
+            reason: "embedding contains non-finite values".into(),
+        });
+    }
+    if config.normalize {
+        let norm = embedding.iter().map(|v| v * v).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for value in &mut embedding {
+                *value /= norm;
+            }
+        }
+    }
+    Ok(embedding)
+}
+
+fn pool(tokens: &[f32], mask: &[f32], hidden: usize, pooling: EmbeddingPooling) -> Vec<f32> {
+    match pooling {
+        EmbeddingPooling::Cls => tokens[..hidden].to_vec(),
+        EmbeddingPooling::Mean => {
+            let mut sum = vec![0.0f32; hidden];
+            for (token, weight) in tokens.chunks(hidden).zip(mask) {
+                for (acc, value) in sum.iter_mut().zip(token) {
+                    *acc += value * weight;
+                }
+            }
+            let count = mask.iter().sum::<f32>().max(1.0);
+            sum.iter().map(|value| value / count).collect()
+        }
+    }
+}
+
+fn tokenizer_path(dir: &Path, manifest: &ModelManifest) -> Result<PathBuf> {
+    let declared = manifest.files.iter().find(|entry| {
+        entry.roles.iter().any(|role| role == "tokenizer") || entry.path.ends_with("tokenizer.json")
+    });
+    let path = match declared {
+        Some(entry) => resolve_entry_path(dir, &entry.path)?,
+        None => dir.join("tokenizer.json"),
+    };
+    if !path.exists() {
+        return Err(MemvidError::ModelIntegrity {
+            reason: format!("tokenizer missing at {}", path.display()).into_boxed_str(),
+        });
+    }
+    Ok(path)
+}
+
+fn embedding_error(stage: &str, err: impl std::fmt::Display) -> MemvidError {
+    MemvidError::EmbeddingFailed {
+        reason: format!("{stage}: {err}").into_boxed_str(),
+    }
+}
+
+impl EmbeddingProvider for LocalOnnxEmbedder {
+    fn kind(&self) -> &str {
+        "local"
+    }
+
+    fn model(&self) -> &str {
+        &self.config.embedding.model
+    }
+
+    fn dimension(&self) -> usize {
+        self.config.embedding.dimension
+    }
+
+    fn embed_text(&self, text: &str) -> Result<Vec<f32>> {
+        let mut embeddings = self.embed_batch(&[text])?;
+        embeddings
+            .pop()
+            .ok_or_else(|| MemvidError::EmbeddingFailed {
+                reason: "model returned no embedding".into(),
+            })
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        self.embed_with_prefix(self.config.document_prefix.as_deref(), texts)
+    }
+}
+
+impl VecEmbedder for LocalOnnxEmbedder {
+    fn embed_query(&self, text: &str) -> Result<Vec<f32>> {
+        let mut embeddings =
+            self.embed_with_prefix(self.config.query_prefix.as_deref(), &[text])?;
+        embeddings
+            .pop()
+            .ok_or_else(|| MemvidError::EmbeddingFailed {
+                reason: "model returned no embedding".into(),
+            })
+    }
+
+    fn embed_chunks(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        EmbeddingProvider::embed_batch(self, texts)
+    }
+
+    fn embedding_dimension(&self) -> usize {
+        self.config.embedding.dimension
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn config(dimension: usize, normalize: bool) -> EmbeddingConfig {
+        EmbeddingConfig {
+            model: "test-model".to_string(),
+            dimension,
+            batch_size: None,
+            normalize,
+        }
+    }
+
+    /// A verified model directory holding placeholder weights and no tokenizer.
+    fn model_dir(root: &Path, dims: u32) -> PathBuf {
+        let digest = "cccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccccc";
+        let dir = root.join(format!("sha256-{digest}"));
+        fs::create_dir_all(&dir).unwrap();
+        let weights = b"ONNX";
+        fs::write(dir.join("model.onnx"), weights).unwrap();
+        let manifest = serde_json::json!({
+            "digest": format!("sha256:{digest}"),
+            "dims": dims,
+            "files": [{
+                "path": "model.onnx",
+                "sha256": hex::encode(Sha256::digest(weights)),
+                "roles": ["weights"],
+            }]
+        });
+        fs::write(
+            dir.join("manifest.json"),
+            serde_json::to_vec(&manifest).unwrap(),
+        )
+        .unwrap();
+        dir
+    }
+
+    #[test]
+    fn config_validation() {
+        assert!(LocalOnnxEmbedderConfig::bge_small().validate().is_ok());
+        assert!(LocalOnnxEmbedderConfig::nomic().validate().is_ok());
+
+        let mut zero_dims = LocalOnnxEmbedderConfig::bge_small();
+        zero_dims.embedding.dimension = 0;
+        assert!(zero_dims.validate().is_err());
+
+        let mut zero_batch = LocalOnnxEmbedderConfig::bge_base();
+        zero_batch.embedding.batch_size = Some(0);
+        assert!(zero_batch.validate().is_err());
+
+        let mut unnamed = LocalOnnxEmbedderConfig::nomic();
+        unnamed.embedding.model = " ".to_string();
+        assert!(unnamed.validate().is_err());
+    }
+
+    #[test]
+    fn cls_pooling_takes_first_token() {
+        let tokens = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        let mask = [1.0, 1.0, 1.0];
+        assert_eq!(
+            pool(&tokens, &mask, 2, EmbeddingPooling::Cls),
+            vec![1.0, 2.0]
+        );
+    }
+
+    #[test]
+    fn mean_pooling_skips_padding() {
+        let tokens = [1.0, 2.0, 3.0, 4.0, 100.0, 100.0];
+        let mask = [1.0, 1.0, 0.0];
+        assert_eq!(
+            pool(&tokens, &mask, 2, EmbeddingPooling::Mean),
+            vec![2.0, 3.0]
+        );
+
+        // A fully masked row stays finite.
+        assert_eq!(
+            pool(&tokens, &[0.0, 0.0, 0.0], 2, EmbeddingPooling::Mean),
+            vec![0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn finish_normalizes_when_configured() {
+        let normalized = finish(&config(2, true), vec![3.0, 4.0]).unwrap();
+        assert!((normalized[0] - 0.6).abs() < 1e-6);
+        assert!((normalized[1] - 0.8).abs() < 1e-6);
+
+        assert_eq!(
+            finish(&config(2, false), vec![3.0, 4.0]).unwrap(),
+            vec![3.0, 4.0]
+        );
+        assert_eq!(
+            finish(&config(2, true), vec![0.0, 0.0]).unwrap(),
+            vec![0.0, 0.0]
+        );
+    }
+
+    #[test]
+    fn finish_rejects_bad_embeddings() {
+        assert!(matches!(
+            finish(&config(3, true), vec![1.0, 0.0]),
+            Err(MemvidError::VecDimensionMismatch {
+                expected: 3,
+                actual: 2
+            })
+        ));
+        assert!(matches!(
+            finish(&config(2, false), vec![f32::NAN, 1.0]),
+            Err(MemvidError::EmbeddingFailed { .. })
+        ));
+    }
+
+    #[test]
+    fn open_reports_missing_model() {
+        let temp = tempdir().unwrap();
+
+        assert!(
+            LocalOnnxEmbedder::open(
+                temp.path().join("absent"),
+                LocalOnnxEmbedderConfig::bge_small()
+            )
+            .is_err()
+        );
+
+        let dir = model_dir(temp.path(), 768);
+        assert!(matches!(
+            LocalOnnxEmbedder::open(&dir, LocalOnnxEmbedderConfig::bge_small()),
+            Err(MemvidError::ModelManifestInvalid { .. })
+        ));
+    }
+
+    #[test]
+    fn open_reports_missing_tokenizer() {
+        let temp = tempdir().unwrap();
+        let dir = model_dir(temp.path(), 384);
+        let err = LocalOnnxEmbedder::open(&dir, LocalOnnxEmbedderConfig::bge_small())
+            .err()
+            .expect("open without a tokenizer");
+        assert!(
+            matches!(&err, MemvidError::ModelIntegrity { reason } if reason.contains("tokenizer")),
+            "{err:?}"
+        );
+    }
+
+    #[test]
+    fn output_shape_must_match_the_batch() {
+        assert!(check_output_shape(&[2, 384], 2, 7).is_ok());
+        assert!(check_output_shape(&[2, 7, 384], 2, 7).is_ok());
+        for shape in [
+            &[1, 768][..],
+            &[4, 384],
+            &[1, 14, 384],
+            &[2, 14, 192],
+            &[-1, 7, 384],
+        ] {
+            assert!(
+                matches!(
+                    check_output_shape(shape, 2, 7),
+                    Err(MemvidError::EmbeddingFailed { .. })
+                ),
+                "{shape:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn row_width_rejects_degenerate_outputs() {
+        assert_eq!(row_width(&[0.0; 6], 2).unwrap(), 3);
+        for (data, rows) in [(&[][..], 2), (&[0.0; 6][..], 0), (&[0.0; 5][..], 2)] {
+            assert!(matches!(
+                row_width(data, rows),
+                Err(MemvidError::EmbeddingFailed { .. })
+            ));
+        }
+    }
+}
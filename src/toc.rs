@@ -5,8 +5,9 @@ use serde::{Deserialize, Serialize};
 use crate::{
     error::{MemvidError, Result},
     types::{
-        Frame, IndexManifests, MemoryBinding, SegmentCatalog, SegmentMeta, TemporalTrackManifest,
-        TicketRef, TimeIndexManifest, Toc,
+        ChangeLog, EnrichmentQueueManifest, Frame, IndexManifests, LexIndexManifest, MemoryBinding,
+        SegmentCatalog, SegmentMeta, SketchTrackManifest, TemporalTrackManifest, TicketRef,
+        TimeIndexManifest, Toc, TocPart, VecIndexManifest,
     },
 };
 
@@ -17,6 +18,74 @@ fn canonical_config() -> impl bincode::config::Config {
         .with_limit::<{ crate::MAX_INDEX_BYTES as usize }>()
 }
 
+/// Legacy lexical index manifest without the analyzer configuration.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyLexIndexManifest {
+    pub doc_count: u64,
+    pub generation: u64,
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub checksum: [u8; 32],
+}
+
+impl From<LegacyLexIndexManifest> for LexIndexManifest {
+    fn from(legacy: LegacyLexIndexManifest) -> Self {
+        LexIndexManifest {
+            doc_count: legacy.doc_count,
+            generation: legacy.generation,
+            bytes_offset: legacy.bytes_offset,
+            bytes_length: legacy.bytes_length,
+            checksum: legacy.checksum,
+            analyzer: None, // Default analyzer for pre-analyzer files
+        }
+    }
+}
+
+impl From<&LexIndexManifest> for LegacyLexIndexManifest {
+    fn from(manifest: &LexIndexManifest) -> Self {
+        LegacyLexIndexManifest {
+            doc_count: manifest.doc_count,
+            generation: manifest.generation,
+            bytes_offset: manifest.bytes_offset,
+            bytes_length: manifest.bytes_length,
+            checksum: manifest.checksum,
+        }
+    }
+}
+
+/// Legacy index manifests without named vector spaces.
+/// Shared by the V1-V3 legacy TOC layouts below.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyIndexManifests {
+    pub lex: Option<LegacyLexIndexManifest>,
+    pub lex_segments: Vec<crate::types::LexSegmentManifest>,
+    pub vec: Option<VecIndexManifest>,
+    pub clip: Option<crate::clip::ClipIndexManifest>,
+}
+
+impl From<LegacyIndexManifests> for IndexManifests {
+    fn from(legacy: LegacyIndexManifests) -> Self {
+        IndexManifests {
+            lex: legacy.lex.map(Into::into),
+            lex_segments: legacy.lex_segments,
+            vec: legacy.vec,
+            clip: legacy.clip,
+            vec_spaces: Vec::new(), // Default for pre-vector-space files
+        }
+    }
+}
+
+impl From<&IndexManifests> for LegacyIndexManifests {
+    fn from(indexes: &IndexManifests) -> Self {
+        LegacyIndexManifests {
+            lex: indexes.lex.as_ref().map(Into::into),
+            lex_segments: indexes.lex_segments.clone(),
+            vec: indexes.vec.clone(),
+            clip: indexes.clip.clone(),
+        }
+    }
+}
+
 /// Legacy TOC format without memories_track field (pre-v2.0.105).
 /// Used for backwards compatibility with older .mv2 files.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -24,7 +93,7 @@ struct LegacyTocV1 {
     pub toc_version: u64,
     pub segments: Vec<SegmentMeta>,
     pub frames: Vec<Frame>,
-    pub indexes: IndexManifests,
+    pub indexes: LegacyIndexManifests,
     pub time_index: Option<TimeIndexManifest>,
     pub temporal_track: Option<TemporalTrackManifest>,
     // Note: memories_track, logic_mesh, replay_manifest NOT present
@@ -42,7 +111,7 @@ struct LegacyTocV2 {
     pub toc_version: u64,
     pub segments: Vec<SegmentMeta>,
     pub frames: Vec<Frame>,
-    pub indexes: IndexManifests,
+    pub indexes: LegacyIndexManifests,
     pub time_index: Option<TimeIndexManifest>,
     pub temporal_track: Option<TemporalTrackManifest>,
     pub memories_track: Option<crate::types::MemoriesTrackManifest>,
@@ -55,13 +124,37 @@ struct LegacyTocV2 {
     pub toc_checksum: [u8; 32],
 }
 
+/// Legacy TOC format with every track but none of the fields added together in the
+/// next format bump: named vector spaces and the lex analyzer in `IndexManifests`, the
+/// memory signature and the change log.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LegacyTocV3 {
+    pub toc_version: u64,
+    pub segments: Vec<SegmentMeta>,
+    pub frames: Vec<Frame>,
+    pub indexes: LegacyIndexManifests,
+    pub time_index: Option<TimeIndexManifest>,
+    pub temporal_track: Option<TemporalTrackManifest>,
+    pub memories_track: Option<crate::types::MemoriesTrackManifest>,
+    pub logic_mesh: Option<crate::types::LogicMeshManifest>,
+    pub sketch_track: Option<SketchTrackManifest>,
+    pub segment_catalog: SegmentCatalog,
+    pub ticket_ref: TicketRef,
+    pub memory_binding: Option<MemoryBinding>,
+    pub replay_manifest: Option<crate::replay::ReplayManifest>,
+    pub enrichment_queue: EnrichmentQueueManifest,
+    // Note: signature and change_log NOT present in this version
+    pub merkle_root: [u8; 32],
+    pub toc_checksum: [u8; 32],
+}
+
 impl From<LegacyTocV1> for Toc {
     fn from(legacy: LegacyTocV1) -> Self {
         Toc {
             toc_version: legacy.toc_version,
             segments: legacy.segments,
             frames: legacy.frames,
-            indexes: legacy.indexes,
+            indexes: legacy.indexes.into(),
             time_index: legacy.time_index,
             temporal_track: legacy.temporal_track,
             memories_track: None, // Default for legacy files
@@ -72,6 +165,8 @@ impl From<LegacyTocV1> for Toc {
             memory_binding: legacy.memory_binding,
             replay_manifest: None,                // Default for legacy files
             enrichment_queue: Default::default(), // Default for legacy files
+            signature: None,                      // Default for legacy files
+            change_log: ChangeLog::default(),     // Default for legacy files
             merkle_root: legacy.merkle_root,
             toc_checksum: legacy.toc_checksum,
         }
@@ -84,7 +179,7 @@ impl From<LegacyTocV2> for Toc {
             toc_version: legacy.toc_version,
             segments: legacy.segments,
             frames: legacy.frames,
-            indexes: legacy.indexes,
+            indexes: legacy.indexes.into(),
             time_index: legacy.time_index,
             temporal_track: legacy.temporal_track,
             memories_track: legacy.memories_track,
@@ -95,98 +190,219 @@ impl From<LegacyTocV2> for Toc {
             memory_binding: legacy.memory_binding,
             replay_manifest: None, // Default for pre-replay files
             enrichment_queue: Default::default(), // Default for legacy files
+            signature: None,       // Default for legacy files
+            change_log: ChangeLog::default(), // Default for legacy files
             merkle_root: legacy.merkle_root,
             toc_checksum: legacy.toc_checksum,
         }
     }
 }
 
-impl Toc {
-    /// Serialises the TOC using the canonical bincode configuration.
-    pub fn encode(&self) -> Result<Vec<u8>> {
-        Ok(encode_to_vec(self, canonical_config())?)
+impl From<LegacyTocV3> for Toc {
+    fn from(legacy: LegacyTocV3) -> Self {
+        Toc {
+            toc_version: legacy.toc_version,
+            segments: legacy.segments,
+            frames: legacy.frames,
+            indexes: legacy.indexes.into(),
+            time_index: legacy.time_index,
+            temporal_track: legacy.temporal_track,
+            memories_track: legacy.memories_track,
+            logic_mesh: legacy.logic_mesh,
+            sketch_track: legacy.sketch_track,
+            segment_catalog: legacy.segment_catalog,
+            ticket_ref: legacy.ticket_ref,
+            memory_binding: legacy.memory_binding,
+            replay_manifest: legacy.replay_manifest,
+            enrichment_queue: legacy.enrichment_queue,
+            signature: None,                  // Default for legacy files
+            change_log: ChangeLog::default(), // Default for legacy files
+            merkle_root: legacy.merkle_root,
+            toc_checksum: legacy.toc_checksum,
+        }
     }
+}
 
-    /// Deserialises bytes into a TOC, rejecting any trailing data.
-    /// Supports current format and legacy formats (pre-replay_manifest, pre-memories_track).
-    pub fn decode(bytes: &[u8]) -> Result<Self> {
-        // Try current format first (with replay_manifest)
-        match decode_from_slice::<Toc, _>(bytes, canonical_config()) {
-            Ok((toc, bytes_read)) => {
-                if bytes_read != bytes.len() {
-                    return Err(MemvidError::InvalidToc {
-                        reason: "unexpected trailing bytes".into(),
-                    });
-                }
-                return Ok(toc);
-            }
-            Err(_) => {}
+impl LegacyTocV1 {
+    /// V1 view of `toc` with a zeroed checksum.
+    fn from_toc(toc: &Toc) -> Self {
+        Self {
+            toc_version: toc.toc_version,
+            segments: toc.segments.clone(),
+            frames: toc.frames.clone(),
+            indexes: (&toc.indexes).into(),
+            time_index: toc.time_index.clone(),
+            temporal_track: toc.temporal_track.clone(),
+            segment_catalog: toc.segment_catalog.clone(),
+            ticket_ref: toc.ticket_ref.clone(),
+            memory_binding: toc.memory_binding.clone(),
+            merkle_root: toc.merkle_root,
+            toc_checksum: [0u8; 32],
         }
+    }
+}
 
-        // Try V2 format (with memories_track/logic_mesh, without replay_manifest)
-        match decode_from_slice::<LegacyTocV2, _>(bytes, canonical_config()) {
-            Ok((legacy, bytes_read)) => {
-                if bytes_read != bytes.len() {
-                    return Err(MemvidError::InvalidToc {
-                        reason: "unexpected trailing bytes in V2 format".into(),
-                    });
-                }
-                tracing::debug!("Decoded TOC V2 format (pre-replay_manifest)");
-                return Ok(legacy.into());
-            }
-            Err(_) => {}
+impl LegacyTocV2 {
+    /// V2 view of `toc` with a zeroed checksum.
+    fn from_toc(toc: &Toc) -> Self {
+        Self {
+            toc_version: toc.toc_version,
+            segments: toc.segments.clone(),
+            frames: toc.frames.clone(),
+            indexes: (&toc.indexes).into(),
+            time_index: toc.time_index.clone(),
+            temporal_track: toc.temporal_track.clone(),
+            memories_track: toc.memories_track.clone(),
+            logic_mesh: toc.logic_mesh.clone(),
+            segment_catalog: toc.segment_catalog.clone(),
+            ticket_ref: toc.ticket_ref.clone(),
+            memory_binding: toc.memory_binding.clone(),
+            merkle_root: toc.merkle_root,
+            toc_checksum: [0u8; 32],
         }
+    }
+}
 
-        // Try V1 format (without memories_track/logic_mesh/replay_manifest)
-        match decode_from_slice::<LegacyTocV1, _>(bytes, canonical_config()) {
-            Ok((legacy, bytes_read)) => {
-                if bytes_read != bytes.len() {
-                    return Err(MemvidError::InvalidToc {
-                        reason: "unexpected trailing bytes in V1 format".into(),
-                    });
-                }
-                tracing::debug!("Decoded TOC V1 format (pre-memories_track)");
-                return Ok(legacy.into());
-            }
-            Err(e) => Err(e.into()),
+impl LegacyTocV3 {
+    /// V3 view of `toc` with a zeroed checksum.
+    fn from_toc(toc: &Toc) -> Self {
+        Self {
+            toc_version: toc.toc_version,
+            segments: toc.segments.clone(),
+            frames: toc.frames.clone(),
+            indexes: (&toc.indexes).into(),
+            time_index: toc.time_index.clone(),
+            temporal_track: toc.temporal_track.clone(),
+            memories_track: toc.memories_track.clone(),
+            logic_mesh: toc.logic_mesh.clone(),
+            sketch_track: toc.sketch_track.clone(),
+            segment_catalog: toc.segment_catalog.clone(),
+            ticket_ref: toc.ticket_ref.clone(),
+            memory_binding: toc.memory_binding.clone(),
+            replay_manifest: toc.replay_manifest.clone(),
+            enrichment_queue: toc.enrichment_queue.clone(),
+            merkle_root: toc.merkle_root,
+            toc_checksum: [0u8; 32],
         }
     }
+}
 
-    /// Deserialises bytes into a TOC, allowing trailing data (for recovery).
-    /// Supports current format and legacy formats (pre-replay_manifest, pre-memories_track).
-    pub fn decode_lenient(bytes: &[u8]) -> Result<Self> {
-        // Try current format first (with replay_manifest)
-        if let Ok((toc, _)) = decode_from_slice::<Toc, _>(bytes, canonical_config()) {
-            return Ok(toc);
-        }
-        // Try V2 format (with memories_track/logic_mesh, without replay_manifest)
-        if let Ok((legacy, _)) = decode_from_slice::<LegacyTocV2, _>(bytes, canonical_config()) {
-            tracing::debug!("Decoded TOC V2 format (pre-replay_manifest) in lenient mode");
-            return Ok(legacy.into());
-        }
-        // Try V1 format (without memories_track/logic_mesh/replay_manifest)
-        match decode_from_slice::<LegacyTocV1, _>(bytes, canonical_config()) {
-            Ok((legacy, _)) => {
-                tracing::debug!("Decoded TOC V1 format (pre-memories_track) in lenient mode");
-                Ok(legacy.into())
+/// TOC layouts this build reads, newest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TocLayout {
+    Current,
+    /// Before named vector spaces, the lex analyzer, signing and the change log.
+    V3,
+    /// Before `replay_manifest`.
+    V2,
+    /// Before `memories_track`.
+    V1,
+}
+
+impl TocLayout {
+    const ALL: [Self; 4] = [Self::Current, Self::V3, Self::V2, Self::V1];
+
+    /// Decode `bytes` in this layout, returning the TOC and the number of bytes read.
+    fn decode(self, bytes: &[u8]) -> Result<(Toc, usize)> {
+        let config = canonical_config();
+        Ok(match self {
+            Self::Current => decode_from_slice::<Toc, _>(bytes, config)?,
+            Self::V3 => {
+                let (legacy, read) = decode_from_slice::<LegacyTocV3, _>(bytes, config)?;
+                (legacy.into(), read)
             }
-            Err(e) => Err(e.into()),
-        }
+            Self::V2 => {
+                let (legacy, read) = decode_from_slice::<LegacyTocV2, _>(bytes, config)?;
+                (legacy.into(), read)
+            }
+            Self::V1 => {
+                let (legacy, read) = decode_from_slice::<LegacyTocV1, _>(bytes, config)?;
+                (legacy.into(), read)
+            }
+        })
     }
-}
 
-impl LegacyTocV1 {
-    /// Encode legacy TOC format for checksum verification.
-    fn encode(&self) -> Result<Vec<u8>> {
-        Ok(encode_to_vec(self, canonical_config())?)
+    /// Encode `toc` with a zeroed checksum, or `None` when it holds fields this layout
+    /// cannot have been written with.
+    fn encode_for_checksum(self, toc: &Toc) -> Result<Option<Vec<u8>>> {
+        let config = canonical_config();
+        let legacy = toc.indexes.vec_spaces.is_empty()
+            && toc.indexes.lex_analyzer().is_none()
+            && toc.signature.is_none()
+            && toc.change_log.is_empty();
+        let bytes = match self {
+            Self::Current => {
+                let mut clone = toc.clone();
+                clone.toc_checksum = [0u8; 32];
+                encode_to_vec(&clone, config)?
+            }
+            Self::V3 if legacy => encode_to_vec(LegacyTocV3::from_toc(toc), config)?,
+            Self::V2 if legacy && toc.replay_manifest.is_none() => {
+                encode_to_vec(LegacyTocV2::from_toc(toc), config)?
+            }
+            Self::V1 if legacy && toc.memories_track.is_none() && toc.replay_manifest.is_none() => {
+                encode_to_vec(LegacyTocV1::from_toc(toc), config)?
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(bytes))
     }
 }
 
-impl LegacyTocV2 {
-    /// Encode V2 TOC format for checksum verification.
-    fn encode(&self) -> Result<Vec<u8>> {
+impl Toc {
+    /// Serialises the TOC using the canonical bincode configuration.
+    ///
+    /// # Errors
+    ///
+    /// Fails when a field cannot be encoded.
+    pub fn encode(&self) -> Result<Vec<u8>> {
         Ok(encode_to_vec(self, canonical_config())?)
     }
+
+    /// Decodes the first layout `bytes` parse as, newest first.
+    fn decode_any(bytes: &[u8]) -> Result<(Self, usize)> {
+        let mut last_err = None;
+        for layout in TocLayout::ALL {
+            match layout.decode(bytes) {
+                Ok(decoded) => {
+                    if layout != TocLayout::Current {
+                        tracing::debug!(?layout, "Decoded legacy TOC format");
+                    }
+                    return Ok(decoded);
+                }
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap_or_else(|| MemvidError::InvalidToc {
+            reason: "no TOC layout to decode".into(),
+        }))
+    }
+
+    /// Deserialises bytes into a TOC, rejecting any trailing data.
+    /// Supports current format and legacy formats (pre-replay_manifest, pre-memories_track).
+    ///
+    /// # Errors
+    ///
+    /// Fails when no known layout decodes `bytes` or when bytes are left over.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let (toc, bytes_read) = Self::decode_any(bytes)?;
+        if bytes_read != bytes.len() {
+            return Err(MemvidError::InvalidToc {
+                reason: "unexpected trailing bytes".into(),
+            });
+        }
+        Ok(toc)
+    }
+
+    /// Deserialises bytes into a TOC, allowing trailing data (for recovery).
+    /// Supports current format and legacy formats (pre-replay_manifest, pre-memories_track).
+    ///
+    /// # Errors
+    ///
+    /// Fails when no known layout decodes `bytes`.
+    pub fn decode_lenient(bytes: &[u8]) -> Result<Self> {
+        Self::decode_any(bytes).map(|(toc, _)| toc)
+    }
 }
 
 impl Toc {
@@ -199,70 +415,310 @@ impl Toc {
 
     /// Verifies that the stored TOC checksum matches the deterministic encoding.
     /// Supports current format and legacy format checksums for backwards compatibility.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::ChecksumMismatch`] when no layout reproduces the checksum.
     pub fn verify_checksum(&self) -> Result<()> {
-        // Try current format first (with replay_manifest)
-        let mut clone = self.clone();
-        clone.toc_checksum = [0u8; 32];
-        let bytes = clone.encode()?;
-        let digest = Self::calculate_checksum(&bytes);
-        if digest == self.toc_checksum {
-            return Ok(());
-        }
-
-        // Try V2 format (with memories_track/logic_mesh, without replay_manifest)
-        // Only try if replay_manifest is None (indicates pre-replay origin)
-        if self.replay_manifest.is_none() {
-            let legacy_v2 = LegacyTocV2 {
-                toc_version: self.toc_version,
-                segments: self.segments.clone(),
-                frames: self.frames.clone(),
-                indexes: self.indexes.clone(),
-                time_index: self.time_index.clone(),
-                temporal_track: self.temporal_track.clone(),
-                memories_track: self.memories_track.clone(),
-                logic_mesh: self.logic_mesh.clone(),
-                segment_catalog: self.segment_catalog.clone(),
-                ticket_ref: self.ticket_ref.clone(),
-                memory_binding: self.memory_binding.clone(),
-                merkle_root: self.merkle_root,
-                toc_checksum: [0u8; 32],
+        for layout in TocLayout::ALL {
+            let Some(bytes) = layout.encode_for_checksum(self)? else {
+                continue;
             };
-            let v2_bytes = legacy_v2.encode()?;
-            let v2_digest = Self::calculate_checksum(&v2_bytes);
-            if v2_digest == self.toc_checksum {
-                tracing::debug!("TOC checksum verified using V2 format (pre-replay)");
+            if Self::calculate_checksum(&bytes) == self.toc_checksum {
+                if layout != TocLayout::Current {
+                    tracing::debug!(?layout, "TOC checksum verified using legacy format");
+                }
                 return Ok(());
             }
         }
+        Err(MemvidError::ChecksumMismatch { context: "toc" })
+    }
+}
 
-        // Try V1 format (without memories_track/logic_mesh/replay_manifest)
-        // Only try if memories_track is None (indicates V1 origin)
-        if self.memories_track.is_none() && self.replay_manifest.is_none() {
-            let legacy_v1 = LegacyTocV1 {
-                toc_version: self.toc_version,
-                segments: self.segments.clone(),
-                frames: self.frames.clone(),
-                indexes: self.indexes.clone(),
-                time_index: self.time_index.clone(),
-                temporal_track: self.temporal_track.clone(),
-                segment_catalog: self.segment_catalog.clone(),
-                ticket_ref: self.ticket_ref.clone(),
-                memory_binding: self.memory_binding.clone(),
-                merkle_root: self.merkle_root,
-                toc_checksum: [0u8; 32],
-            };
-            let v1_bytes = legacy_v1.encode()?;
-            let v1_digest = Self::calculate_checksum(&v1_bytes);
-            if v1_digest == self.toc_checksum {
-                tracing::debug!("TOC checksum verified using V1 format (pre-memories_track)");
-                return Ok(());
+impl Toc {
+    /// Computes the Merkle root over every frame record and index segment checksum.
+    ///
+    /// Leaves are one per frame (its canonical encoding, which carries the payload checksum)
+    /// followed by one per index artifact (its label and checksum), in TOC order. Inner nodes
+    /// hash their two children and an odd node is carried up unchanged; an empty TOC has an
+    /// all-zero root.
+    ///
+    /// # Errors
+    ///
+    /// Fails when a frame record cannot be encoded.
+    pub fn compute_merkle_root(&self) -> Result<[u8; 32]> {
+        let mut leaves = Vec::with_capacity(self.frames.len());
+        for frame in &self.frames {
+            let bytes = encode_to_vec(frame, canonical_config())?;
+            leaves.push(merkle_leaf("frame", &bytes));
+        }
+        for artifact in self.index_artifacts() {
+            leaves.push(merkle_leaf(&artifact.label, artifact.checksum));
+        }
+
+        while leaves.len() > 1 {
+            leaves = leaves
+                .chunks(2)
+                .map(|pair| match pair {
+                    [left, right] => {
+                        let mut hasher = Hasher::new();
+                        hasher.update(&[MERKLE_NODE_TAG]);
+                        hasher.update(left);
+                        hasher.update(right);
+                        *hasher.finalize().as_bytes()
+                    }
+                    [single] => *single,
+                    _ => unreachable!("chunks(2) yields one or two leaves"),
+                })
+                .collect();
+        }
+        Ok(leaves.pop().unwrap_or([0u8; 32]))
+    }
+
+    /// Every index artifact referenced by the TOC, labelled by kind, in Merkle leaf order.
+    pub(crate) fn index_artifacts(&self) -> Vec<IndexArtifactRef<'_>> {
+        let mut out = Vec::new();
+        let mut push = |label: String, bytes_offset: u64, bytes_length: u64, checksum| {
+            out.push(IndexArtifactRef {
+                label,
+                bytes_offset,
+                bytes_length,
+                checksum,
+            });
+        };
+        for segment in &self.segments {
+            push(
+                format!("segment:{}", segment.id),
+                segment.bytes_offset,
+                segment.bytes_length,
+                &segment.primary_checksum,
+            );
+        }
+        if let Some(lex) = &self.indexes.lex {
+            push(
+                "lex".to_string(),
+                lex.bytes_offset,
+                lex.bytes_length,
+                &lex.checksum,
+            );
+        }
+        for segment in &self.indexes.lex_segments {
+            push(
+                format!("lex_segment:{}", segment.path),
+                segment.bytes_offset,
+                segment.bytes_length,
+                &segment.checksum,
+            );
+        }
+        if let Some(vec) = &self.indexes.vec {
+            push(
+                "vec".to_string(),
+                vec.bytes_offset,
+                vec.bytes_length,
+                &vec.checksum,
+            );
+        }
+        if let Some(clip) = &self.indexes.clip {
+            push(
+                "clip".to_string(),
+                clip.bytes_offset,
+                clip.bytes_length,
+                &clip.checksum,
+            );
+        }
+        for space in &self.indexes.vec_spaces {
+            push(
+                format!("vec_space:{}", space.name),
+                space.index.bytes_offset,
+                space.index.bytes_length,
+                &space.index.checksum,
+            );
+        }
+        if let Some(time_index) = &self.time_index {
+            push(
+                "time_index".to_string(),
+                time_index.bytes_offset,
+                time_index.bytes_length,
+                &time_index.checksum,
+            );
+        }
+        if let Some(track) = &self.temporal_track {
+            push(
+                "temporal_track".to_string(),
+                track.bytes_offset,
+                track.bytes_length,
+                &track.checksum,
+            );
+        }
+        if let Some(track) = &self.memories_track {
+            push(
+                "memories_track".to_string(),
+                track.bytes_offset,
+                track.bytes_length,
+                &track.checksum,
+            );
+        }
+        if let Some(mesh) = &self.logic_mesh {
+            push(
+                "logic_mesh".to_string(),
+                mesh.bytes_offset,
+                mesh.bytes_length,
+                &mesh.checksum,
+            );
+        }
+        if let Some(track) = &self.sketch_track {
+            push(
+                "sketch_track".to_string(),
+                track.bytes_offset,
+                track.bytes_length,
+                &track.checksum,
+            );
+        }
+        out.extend(self.catalog_artifacts());
+        out
+    }
+
+    /// Segment catalog entries, labelled by segment kind and id.
+    fn catalog_artifacts(&self) -> impl Iterator<Item = IndexArtifactRef<'_>> {
+        let catalog = &self.segment_catalog;
+        catalog
+            .lex_segments
+            .iter()
+            .map(|s| ("catalog_lex", &s.common))
+            .chain(
+                catalog
+                    .vec_segments
+                    .iter()
+                    .map(|s| ("catalog_vec", &s.common)),
+            )
+            .chain(
+                catalog
+                    .time_segments
+                    .iter()
+                    .map(|s| ("catalog_time", &s.common)),
+            )
+            .chain(
+                catalog
+                    .temporal_segments
+                    .iter()
+                    .map(|s| ("catalog_temporal", &s.common)),
+            )
+            .chain(
+                catalog
+                    .tantivy_segments
+                    .iter()
+                    .map(|s| ("catalog_tantivy", &s.common)),
+            )
+            .chain(
+                catalog
+                    .index_segments
+                    .iter()
+                    .map(|s| ("catalog_index", &s.common)),
+            )
+            .map(|(kind, common)| IndexArtifactRef {
+                label: format!("{kind}:{}", common.segment_id),
+                bytes_offset: common.bytes_offset,
+                bytes_length: common.bytes_length,
+                checksum: &common.checksum,
+            })
+    }
+}
+
+/// An index artifact referenced by the TOC: its stored byte range and checksum.
+pub(crate) struct IndexArtifactRef<'a> {
+    pub label: String,
+    pub bytes_offset: u64,
+    pub bytes_length: u64,
+    pub checksum: &'a [u8; 32],
+}
+
+impl Toc {
+    /// Parts whose content differs between `self` and `other`, in [`TocPart`] order.
+    pub(crate) fn changed_parts(&self, other: &Toc) -> Result<Vec<TocPart>> {
+        let mut changed = Vec::new();
+        for part in TOC_PARTS {
+            if self.part_digest(part)? != other.part_digest(part)? {
+                changed.push(part);
             }
         }
+        Ok(changed)
+    }
 
-        Err(MemvidError::ChecksumMismatch { context: "toc" })
+    fn part_digest(&self, part: TocPart) -> Result<[u8; 32]> {
+        let catalog = &self.segment_catalog;
+        let bytes = match part {
+            TocPart::Frames => encode_to_vec((&self.segments, &self.frames), canonical_config()),
+            TocPart::LexIndex => encode_to_vec(
+                (
+                    &self.indexes.lex,
+                    &self.indexes.lex_segments,
+                    catalog.lex_enabled,
+                    &catalog.lex_segments,
+                    &catalog.tantivy_segments,
+                ),
+                canonical_config(),
+            ),
+            TocPart::VecIndex => encode_to_vec(
+                (&self.indexes.vec, &catalog.vec_segments),
+                canonical_config(),
+            ),
+            TocPart::VecSpaces => encode_to_vec(&self.indexes.vec_spaces, canonical_config()),
+            TocPart::ClipIndex => encode_to_vec(&self.indexes.clip, canonical_config()),
+            TocPart::TimeIndex => encode_to_vec(
+                (&self.time_index, &catalog.time_segments),
+                canonical_config(),
+            ),
+            TocPart::TemporalTrack => encode_to_vec(
+                (&self.temporal_track, &catalog.temporal_segments),
+                canonical_config(),
+            ),
+            TocPart::MemoriesTrack => encode_to_vec(&self.memories_track, canonical_config()),
+            TocPart::LogicMesh => encode_to_vec(&self.logic_mesh, canonical_config()),
+            TocPart::SketchTrack => encode_to_vec(&self.sketch_track, canonical_config()),
+            TocPart::Ticket => {
+                encode_to_vec((&self.ticket_ref, &self.memory_binding), canonical_config())
+            }
+            TocPart::Replay => encode_to_vec(&self.replay_manifest, canonical_config()),
+            TocPart::EnrichmentQueue => encode_to_vec(&self.enrichment_queue, canonical_config()),
+            TocPart::Signature => {
+                encode_to_vec((&self.merkle_root, &self.signature), canonical_config())
+            }
+            TocPart::ChangeLog => encode_to_vec(&self.change_log, canonical_config()),
+        }?;
+        Ok(Self::calculate_checksum(&bytes))
     }
 }
 
+const TOC_PARTS: [TocPart; 15] = [
+    TocPart::Frames,
+    TocPart::LexIndex,
+    TocPart::VecIndex,
+    TocPart::VecSpaces,
+    TocPart::ClipIndex,
+    TocPart::TimeIndex,
+    TocPart::TemporalTrack,
+    TocPart::MemoriesTrack,
+    TocPart::LogicMesh,
+    TocPart::SketchTrack,
+    TocPart::Ticket,
+    TocPart::Replay,
+    TocPart::EnrichmentQueue,
+    TocPart::Signature,
+    TocPart::ChangeLog,
+];
+
+const MERKLE_LEAF_TAG: u8 = 0x00;
+const MERKLE_NODE_TAG: u8 = 0x01;
+
+fn merkle_leaf(label: &str, data: &[u8]) -> [u8; 32] {
+    let mut hasher = Hasher::new();
+    hasher.update(&[MERKLE_LEAF_TAG]);
+    hasher.update(&u32::try_from(label.len()).unwrap_or(u32::MAX).to_le_bytes());
+    hasher.update(label.as_bytes());
+    hasher.update(data);
+    *hasher.finalize().as_bytes()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -371,6 +827,8 @@ mod tests {
             memory_binding: None,
             replay_manifest: None,
             enrichment_queue: Default::default(),
+            signature: None,
+            change_log: ChangeLog::default(),
             merkle_root: [0x55; 32],
             toc_checksum: [0u8; 32],
         }
@@ -401,6 +859,52 @@ mod tests {
         matches!(err, MemvidError::ChecksumMismatch { .. });
     }
 
+    #[test]
+    fn decode_v3_toc_without_spaces_analyzer_signature_or_change_log() {
+        let mut toc = sample_toc();
+        toc.indexes.lex = Some(LexIndexManifest::analyzer_placeholder(Default::default()));
+        toc.indexes.lex.as_mut().expect("lex").analyzer = None;
+        let mut legacy = LegacyTocV3::from_toc(&toc);
+        let encode =
+            |legacy: &LegacyTocV3| encode_to_vec(legacy, canonical_config()).expect("encode v3");
+        legacy.toc_checksum = Toc::calculate_checksum(&encode(&legacy));
+        let decoded = Toc::decode(&encode(&legacy)).expect("decode v3 toc");
+        assert!(decoded.indexes.lex.is_some());
+        assert!(decoded.indexes.lex_analyzer().is_none());
+        assert!(decoded.indexes.vec_spaces.is_empty());
+        assert!(decoded.signature.is_none());
+        assert!(decoded.change_log.is_empty());
+        decoded.verify_checksum().expect("v3 checksum matches");
+    }
+
+    #[test]
+    fn changed_parts_reports_only_modified_sections() {
+        let toc = sample_toc();
+        assert!(toc.changed_parts(&toc.clone()).expect("diff").is_empty());
+
+        let mut other = toc.clone();
+        other.frames[0].title = Some("Renamed".into());
+        other.merkle_root = [0x33; 32];
+        assert_eq!(
+            toc.changed_parts(&other).expect("diff"),
+            vec![TocPart::Frames, TocPart::Signature]
+        );
+    }
+
+    #[test]
+    fn merkle_root_tracks_frames_and_indexes() {
+        let mut toc = sample_toc();
+        let root = toc.compute_merkle_root().expect("root");
+        assert_eq!(root, toc.compute_merkle_root().expect("root"));
+
+        toc.frames[0].checksum[0] ^= 0x01;
+        let frame_root = toc.compute_merkle_root().expect("root");
+        assert_ne!(frame_root, root);
+
+        toc.time_index.as_mut().expect("time index").checksum[0] ^= 0x01;
+        assert_ne!(toc.compute_merkle_root().expect("root"), frame_root);
+    }
+
     #[test]
     fn reject_trailing_bytes() {
         let toc = stamp_checksum(sample_toc());
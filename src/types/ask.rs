@@ -2,9 +2,10 @@ use serde::{Deserialize, Serialize};
 
 use super::adaptive::AdaptiveConfig;
 use super::common::FrameId;
+use super::reranker::RerankerHook;
 #[cfg(feature = "temporal_track")]
 use super::search::SearchHitTemporal;
-use super::search::SearchResponse;
+use super::search::{MetadataFilter, SearchResponse};
 #[cfg(feature = "temporal_track")]
 use super::temporal::TemporalFilter;
 use crate::Result;
@@ -74,6 +75,15 @@ pub struct AskRequest {
     /// Adaptive retrieval configuration. When set, dynamically determines how many
     /// results to retrieve based on relevancy score distribution.
     pub adaptive: Option<AdaptiveConfig>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Structured metadata filter applied to lexical, vector and fallback retrieval.
+    pub filter: Option<MetadataFilter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Named vector space used for vector retrieval and semantic re-ranking.
+    pub vec_space: Option<String>,
+    #[serde(skip)]
+    /// Second-stage reranker applied to the retrieved hits before synthesis. Not serialized.
+    pub reranker: Option<RerankerHook>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
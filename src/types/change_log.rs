@@ -0,0 +1,118 @@
+//! Change feed recorded in the TOC.
+//!
+//! Every commit appends the mutations it published to [`Toc::change_log`](super::Toc), so a
+//! consumer that remembers the last generation it processed can ask for everything after it,
+//! even across reopens. The oldest generations are pruned once the log outgrows the retention
+//! of the handle that commits.
+
+use serde::{Deserialize, Serialize};
+
+use super::{EntityKind, FrameId, LinkType, MemoryCardId};
+
+/// Change feed kept in the TOC.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeLog {
+    /// Retained changes, oldest first.
+    pub events: Vec<ChangeEvent>,
+    /// Newest generation whose changes were pruned, or `0` if none were.
+    pub pruned_through: u64,
+}
+
+impl ChangeLog {
+    /// Whether no change was ever published.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty() && self.pruned_through == 0
+    }
+
+    /// Drop the oldest generations until at most `max_events` changes remain.
+    ///
+    /// Generations are dropped whole, and the newest one is always kept, so it may exceed
+    /// `max_events` on its own.
+    pub(crate) fn prune(&mut self, max_events: usize) {
+        let excess = self.events.len().saturating_sub(max_events);
+        let Some(newest) = self.events.last().map(|event| event.generation) else {
+            return;
+        };
+        if excess == 0 {
+            return;
+        }
+        let through = self.events[excess - 1].generation;
+        let cut = self
+            .events
+            .partition_point(|event| event.generation <= through && event.generation < newest);
+        if cut == 0 {
+            return;
+        }
+        self.pruned_through = self.events[cut - 1].generation;
+        self.events.drain(..cut);
+    }
+}
+
+/// One mutation published by a commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChangeEvent {
+    /// Commit generation that published the change.
+    pub generation: u64,
+    /// WAL sequence of the record that carried the change. Changes applied straight to the
+    /// in-memory tracks use the last sequence written before them.
+    pub sequence: u64,
+    pub kind: ChangeKind,
+}
+
+/// What changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// A new frame was appended.
+    FramePut {
+        frame_id: FrameId,
+    },
+    /// A new frame replaced `previous`, which is reported as superseded as well.
+    FrameUpdated {
+        frame_id: FrameId,
+        previous: FrameId,
+    },
+    /// A frame was tombstoned.
+    FrameDeleted {
+        frame_id: FrameId,
+    },
+    /// A frame was replaced by `superseded_by`.
+    FrameSuperseded {
+        frame_id: FrameId,
+        superseded_by: FrameId,
+    },
+    MemoryCardAdded {
+        card_id: MemoryCardId,
+    },
+    /// A Logic-Mesh node was added or merged into an existing one.
+    MeshNodeMerged {
+        node_id: u64,
+        canonical_name: String,
+        kind: EntityKind,
+    },
+    /// A Logic-Mesh edge was added; duplicates of an existing edge are reported too.
+    MeshEdgeMerged {
+        from_node: u64,
+        to_node: u64,
+        link: LinkType,
+    },
+    TicketApplied {
+        issuer: String,
+        seq_no: i64,
+    },
+}
+
+impl ChangeKind {
+    /// Frame the change refers to, if any.
+    #[must_use]
+    pub fn frame_id(&self) -> Option<FrameId> {
+        match self {
+            Self::FramePut { frame_id }
+            | Self::FrameUpdated { frame_id, .. }
+            | Self::FrameDeleted { frame_id }
+            | Self::FrameSuperseded { frame_id, .. } => Some(*frame_id),
+            _ => None,
+        }
+    }
+}
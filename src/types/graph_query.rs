@@ -2,9 +2,12 @@
 //!
 //! Enables combining graph traversal with vector similarity for relational queries.
 
+use std::cmp::Ordering;
+
 use serde::{Deserialize, Serialize};
 
 use super::common::FrameId;
+use crate::error::{MemvidError, Result};
 
 /// A triple pattern for graph matching.
 /// Variables start with `?`, literals are exact matches.
@@ -91,11 +94,121 @@ impl PatternTerm {
     }
 }
 
+/// Comparison operator of a [`GraphFilter`].
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl FilterOp {
+    /// Operator as written in query text.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Eq => "=",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+        }
+    }
+
+    fn from_symbol(symbol: &str) -> Option<Self> {
+        Some(match symbol {
+            "=" | "==" => Self::Eq,
+            "!=" => Self::Ne,
+            "<" => Self::Lt,
+            "<=" => Self::Le,
+            ">" => Self::Gt,
+            ">=" => Self::Ge,
+            _ => return None,
+        })
+    }
+
+    /// Whether two values ordered as `ordering` satisfy the operator.
+    #[must_use]
+    pub fn holds(self, ordering: Ordering) -> bool {
+        match self {
+            Self::Eq => ordering == Ordering::Equal,
+            Self::Ne => ordering != Ordering::Equal,
+            Self::Lt => ordering == Ordering::Less,
+            Self::Le => ordering != Ordering::Greater,
+            Self::Gt => ordering == Ordering::Greater,
+            Self::Ge => ordering != Ordering::Less,
+        }
+    }
+}
+
+/// FILTER constraint comparing a variable with a literal or another variable.
+///
+/// Values compare with the [`ValueType`](super::ValueType) of the predicate that bound the
+/// variable, so `?age > 30` is numeric when `age` is declared as a number.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GraphFilter {
+    /// Variable being tested (without the `?`).
+    pub variable: String,
+    pub op: FilterOp,
+    /// Literal to compare with, or another variable.
+    pub value: PatternTerm,
+}
+
+impl GraphFilter {
+    /// Create a filter comparing `?variable` with `value`.
+    #[must_use]
+    pub fn new(variable: &str, op: FilterOp, value: PatternTerm) -> Self {
+        Self {
+            variable: variable.to_string(),
+            op,
+            value,
+        }
+    }
+}
+
+/// ORDER BY key.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OrderKey {
+    /// Variable to sort on (without the `?`).
+    pub variable: String,
+    pub descending: bool,
+}
+
 /// A graph pattern for filtering - conjunction of triple patterns.
+///
+/// Patterns are joined on shared variables. Each `OPTIONAL` group extends a solution when all
+/// of its patterns match and leaves it as is otherwise; filters, ordering and the limit then
+/// apply to the joined solutions.
+///
+/// Patterns can be written as text and parsed with [`GraphPattern::parse`]:
+///
+/// ```text
+/// ?p employer acme . ?p location ?city
+/// OPTIONAL { ?p age ?age }
+/// FILTER (?age >= 30)
+/// ORDER BY DESC(?age) LIMIT 10
+/// ```
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct GraphPattern {
     /// Triple patterns to match (all must match - AND semantics)
     pub triples: Vec<TriplePattern>,
+    /// OPTIONAL groups, applied in order after the required triples.
+    #[serde(default)]
+    pub optional: Vec<Vec<TriplePattern>>,
+    /// FILTER constraints (all must hold).
+    #[serde(default)]
+    pub filters: Vec<GraphFilter>,
+    /// ORDER BY keys, most significant first.
+    #[serde(default)]
+    pub order_by: Vec<OrderKey>,
+    /// Maximum number of solutions.
+    #[serde(default)]
+    pub limit: Option<usize>,
 }
 
 impl GraphPattern {
@@ -105,16 +218,54 @@ impl GraphPattern {
         Self::default()
     }
 
+    /// Parse the text syntax described on [`GraphPattern`].
+    ///
+    /// Terms are `?variables`, bare words or `"quoted literals"`; triples are separated by `.`.
+    /// Subject and predicate literals are lowercased to match card entities and slots.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::InvalidQuery`] when `text` is not a valid pattern.
+    pub fn parse(text: &str) -> Result<Self> {
+        PatternParser::new(text)?.parse()
+    }
+
     /// Add a triple pattern.
     pub fn add(&mut self, pattern: TriplePattern) {
         self.triples.push(pattern);
     }
 
+    /// Add an OPTIONAL group.
+    pub fn add_optional(&mut self, patterns: Vec<TriplePattern>) {
+        self.optional.push(patterns);
+    }
+
+    /// Add a FILTER constraint.
+    pub fn add_filter(&mut self, filter: GraphFilter) {
+        self.filters.push(filter);
+    }
+
+    /// Sort solutions on `?variable`, after any keys already added.
+    pub fn add_order(&mut self, variable: &str, descending: bool) {
+        self.order_by.push(OrderKey {
+            variable: variable.to_string(),
+            descending,
+        });
+    }
+
+    /// Limit the number of solutions.
+    #[must_use]
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
     /// Create from a single triple pattern.
     #[must_use]
     pub fn single(pattern: TriplePattern) -> Self {
         Self {
             triples: vec![pattern],
+            ..Self::default()
         }
     }
 
@@ -128,7 +279,7 @@ impl GraphPattern {
     #[must_use]
     pub fn variables(&self) -> Vec<&str> {
         let mut vars = Vec::new();
-        for triple in &self.triples {
+        for triple in self.triples.iter().chain(self.optional.iter().flatten()) {
             if let Some(v) = triple.subject.variable_name() {
                 if !vars.contains(&v) {
                     vars.push(v);
@@ -149,6 +300,248 @@ impl GraphPattern {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Variable(String),
+    Word(String),
+    Quoted(String),
+    Operator(String),
+    Dot,
+    Open(char),
+    Close(char),
+}
+
+/// Recursive-descent parser for the [`GraphPattern`] text syntax.
+struct PatternParser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl PatternParser {
+    fn new(text: &str) -> Result<Self> {
+        Ok(Self {
+            tokens: tokenize(text)?,
+            pos: 0,
+        })
+    }
+
+    fn parse(mut self) -> Result<GraphPattern> {
+        let mut pattern = GraphPattern::new();
+        while self.peek().is_some() {
+            if self.eat_keyword("optional") {
+                self.expect(&Token::Open('{'))?;
+                let mut group = Vec::new();
+                while self.peek() != Some(&Token::Close('}')) {
+                    group.push(self.triple()?);
+                    self.eat(&Token::Dot);
+                }
+                self.expect(&Token::Close('}'))?;
+                if group.is_empty() {
+                    return Err(invalid("OPTIONAL group is empty"));
+                }
+                pattern.add_optional(group);
+            } else if self.eat_keyword("filter") {
+                self.expect(&Token::Open('('))?;
+                let variable = self.variable()?;
+                let op = match self.next() {
+                    Some(Token::Operator(symbol)) => FilterOp::from_symbol(&symbol)
+                        .ok_or_else(|| invalid(format!("unknown operator `{symbol}`")))?,
+                    _ => return Err(invalid("expected a comparison operator in FILTER")),
+                };
+                let value = self.term(false)?;
+                self.expect(&Token::Close(')'))?;
+                pattern.add_filter(GraphFilter::new(&variable, op, value));
+            } else if self.eat_keyword("order") {
+                if !self.eat_keyword("by") {
+                    return Err(invalid("expected BY after ORDER"));
+                }
+                loop {
+                    let descending = if self.eat_keyword("desc") {
+                        true
+                    } else {
+                        self.eat_keyword("asc");
+                        false
+                    };
+                    let wrapped = self.eat(&Token::Open('('));
+                    let variable = self.variable()?;
+                    if wrapped {
+                        self.expect(&Token::Close(')'))?;
+                    }
+                    pattern.add_order(&variable, descending);
+                    if !matches!(self.peek(), Some(Token::Variable(_)))
+                        && !self.at_keyword("asc")
+                        && !self.at_keyword("desc")
+                    {
+                        break;
+                    }
+                }
+            } else if self.eat_keyword("limit") {
+                let limit = match self.next() {
+                    Some(Token::Word(word)) => word.parse::<usize>().ok(),
+                    _ => None,
+                };
+                pattern.limit = Some(limit.ok_or_else(|| invalid("LIMIT expects a number"))?);
+            } else {
+                let triple = self.triple()?;
+                pattern.add(triple);
+                self.eat(&Token::Dot);
+            }
+        }
+        if pattern.triples.is_empty() {
+            return Err(invalid("query has no triple patterns"));
+        }
+        Ok(pattern)
+    }
+
+    fn triple(&mut self) -> Result<TriplePattern> {
+        Ok(TriplePattern::new(
+            self.term(true)?,
+            self.term(true)?,
+            self.term(false)?,
+        ))
+    }
+
+    fn term(&mut self, lowercase: bool) -> Result<PatternTerm> {
+        match self.next() {
+            Some(Token::Variable(name)) => Ok(PatternTerm::Variable(name)),
+            Some(Token::Word(value) | Token::Quoted(value)) if lowercase => {
+                Ok(PatternTerm::Literal(value.to_lowercase()))
+            }
+            Some(Token::Word(value) | Token::Quoted(value)) => Ok(PatternTerm::Literal(value)),
+            Some(token) => Err(invalid(format!("unexpected {}", describe(&token)))),
+            None => Err(invalid("query ends in the middle of a triple")),
+        }
+    }
+
+    fn variable(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Variable(name)) => Ok(name),
+            _ => Err(invalid("expected a ?variable")),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek() == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            Err(invalid(format!("expected {}", describe(token))))
+        }
+    }
+
+    fn at_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let found = self.at_keyword(keyword);
+        if found {
+            self.pos += 1;
+        }
+        found
+    }
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '{' | '(' => {
+                chars.next();
+                tokens.push(Token::Open(c));
+            }
+            '}' | ')' => {
+                chars.next();
+                tokens.push(Token::Close(c));
+            }
+            '"' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => value.extend(chars.next()),
+                        Some(c) => value.push(c),
+                        None => return Err(invalid("unterminated quoted literal")),
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            '<' | '>' | '=' | '!' => {
+                let mut symbol = String::new();
+                while let Some(&c) = chars.peek().filter(|c| matches!(c, '<' | '>' | '=' | '!')) {
+                    symbol.push(c);
+                    chars.next();
+                }
+                tokens.push(Token::Operator(symbol));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek().filter(|c| {
+                    !c.is_whitespace()
+                        && !matches!(c, '{' | '}' | '(' | ')' | '"' | '<' | '>' | '=' | '!')
+                }) {
+                    word.push(c);
+                    chars.next();
+                }
+                // A trailing dot ends the triple unless the word is the dot itself.
+                let dot = word.len() > 1 && word.ends_with('.');
+                if dot {
+                    word.pop();
+                }
+                tokens.push(match word.strip_prefix('?') {
+                    Some("") => return Err(invalid("`?` must be followed by a variable name")),
+                    Some(name) => Token::Variable(name.to_string()),
+                    None if word == "." => Token::Dot,
+                    None => Token::Word(word),
+                });
+                if dot {
+                    tokens.push(Token::Dot);
+                }
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Variable(name) => format!("variable `?{name}`"),
+        Token::Word(word) | Token::Quoted(word) => format!("`{word}`"),
+        Token::Operator(symbol) => format!("operator `{symbol}`"),
+        Token::Dot => "`.`".to_string(),
+        Token::Open(c) | Token::Close(c) => format!("`{c}`"),
+    }
+}
+
+fn invalid(reason: impl Into<String>) -> MemvidError {
+    MemvidError::InvalidQuery {
+        reason: reason.into(),
+    }
+}
+
 /// Query plan for graph-aware retrieval.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum QueryPlan {
@@ -315,6 +708,65 @@ mod tests {
         assert!(vars.contains(&"company"));
     }
 
+    #[test]
+    fn test_parse_joined_pattern() {
+        let pattern = GraphPattern::parse(
+            r#"?p employer Acme . ?p location ?city.
+            OPTIONAL { ?p age ?age } FILTER (?age >= 30) FILTER(?city != "San Francisco")
+            ORDER BY DESC(?age) ?city LIMIT 5"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            pattern.triples,
+            vec![
+                TriplePattern::any_slot_value("p", "employer", "Acme"),
+                TriplePattern::new(
+                    PatternTerm::Variable("p".into()),
+                    PatternTerm::Literal("location".into()),
+                    PatternTerm::Variable("city".into()),
+                ),
+            ]
+        );
+        assert_eq!(pattern.optional.len(), 1);
+        assert_eq!(
+            pattern.filters,
+            vec![
+                GraphFilter::new("age", FilterOp::Ge, PatternTerm::Literal("30".into())),
+                GraphFilter::new(
+                    "city",
+                    FilterOp::Ne,
+                    PatternTerm::Literal("San Francisco".into())
+                ),
+            ]
+        );
+        assert_eq!(pattern.order_by.len(), 2);
+        assert!(pattern.order_by[0].descending);
+        assert!(!pattern.order_by[1].descending);
+        assert_eq!(pattern.limit, Some(5));
+        assert_eq!(pattern.variables(), vec!["p", "city", "age"]);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_queries() {
+        for query in [
+            "",
+            "?p employer",
+            "?p age ?age FILTER (?age ~ 3)",
+            "OPTIONAL { ?p age ?age }",
+            "?p employer acme LIMIT many",
+            "?p employer \"acme",
+        ] {
+            assert!(
+                matches!(
+                    GraphPattern::parse(query),
+                    Err(MemvidError::InvalidQuery { .. })
+                ),
+                "{query}"
+            );
+        }
+    }
+
     #[test]
     fn test_query_plan_types() {
         let vector_plan = QueryPlan::vector_only(Some("test".into()), None, 10);
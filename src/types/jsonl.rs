@@ -0,0 +1,121 @@
+//! Record types for the portable JSONL dump produced by `Memvid::export_jsonl`.
+//!
+//! Every line is one self-describing JSON object tagged by `"type"`. The first line is always a
+//! [`JsonlHeader`]; the remaining records may appear in any order, although the exporter writes
+//! schemas, vector spaces, frames (in frame ID order), memory cards, Logic-Mesh nodes, edges and
+//! replay sessions.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use super::embedding_identity::EmbeddingIdentity;
+use super::frame::Frame;
+use super::logic_mesh::{MeshEdge, MeshNode};
+use super::manifest::VectorCompression;
+use super::memory_card::MemoryCard;
+use super::schema::PredicateSchema;
+use crate::replay::ReplaySession;
+
+/// Value of [`JsonlHeader::format`] identifying a memvid JSONL dump.
+pub const JSONL_FORMAT: &str = "memvid-jsonl";
+/// Current JSONL dump version. Importers reject dumps with a newer version.
+pub const JSONL_FORMAT_VERSION: u32 = 2;
+
+/// One line of a JSONL dump.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum JsonlRecord {
+    Header(JsonlHeader),
+    Schema(PredicateSchema),
+    VecSpace(JsonlVecSpace),
+    Frame(Box<JsonlFrame>),
+    MemoryCard(MemoryCard),
+    MeshNode(MeshNode),
+    MeshEdge(MeshEdge),
+    ReplaySession(Box<ReplaySession>),
+}
+
+/// Leading record describing the exported memory.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonlHeader {
+    /// Always [`JSONL_FORMAT`].
+    pub format: String,
+    /// Dump version, see [`JSONL_FORMAT_VERSION`].
+    pub version: u32,
+    /// Version of memvid-core that produced the dump.
+    #[serde(default)]
+    pub producer: String,
+    /// Number of frame records that follow.
+    pub frame_count: u64,
+    #[serde(default)]
+    pub lex_enabled: bool,
+    #[serde(default)]
+    pub vec_enabled: bool,
+    #[serde(default)]
+    pub clip_enabled: bool,
+    /// Whether strict schema validation was enabled on the source handle.
+    #[serde(default)]
+    pub schema_strict: bool,
+}
+
+/// A frame with its decoded payload and any embeddings attached to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonlFrame {
+    /// Frame metadata exactly as stored in the TOC. Storage offsets are informational only.
+    pub frame: Frame,
+    /// Canonical (decoded) payload bytes; absent when the bytes were reclaimed by vacuum.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payload: Option<JsonlPayload>,
+    /// Text embedding from the vector index.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding: Option<Vec<f32>>,
+    /// Visual embeddings from the CLIP index, one per page.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub clip_embeddings: Vec<JsonlClipEmbedding>,
+    /// Embeddings in named vector spaces, keyed by space name.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub space_embeddings: BTreeMap<String, Vec<f32>>,
+}
+
+/// Declaration of a named vector space; its embeddings travel with the frame records.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonlVecSpace {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity: Option<EmbeddingIdentity>,
+    #[serde(default)]
+    pub compression: VectorCompression,
+}
+
+/// Payload bytes, inlined as text when they are valid UTF-8.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonlPayload {
+    Text(String),
+    Base64(String),
+}
+
+/// A CLIP embedding attached to a frame.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonlClipEmbedding {
+    #[serde(default)]
+    pub page: Option<u32>,
+    pub embedding: Vec<f32>,
+}
+
+/// Record counts for an export or import.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct JsonlReport {
+    pub frames: u64,
+    pub embeddings: u64,
+    pub clip_embeddings: u64,
+    pub vec_spaces: u64,
+    pub space_embeddings: u64,
+    pub memory_cards: u64,
+    pub mesh_nodes: u64,
+    pub mesh_edges: u64,
+    pub replay_sessions: u64,
+    pub schemas: u64,
+}
@@ -0,0 +1,171 @@
+//! Analyzer configuration for the lexical (Tantivy) index.
+//!
+//! The configuration is persisted in [`LexIndexManifest`](super::LexIndexManifest) so the same
+//! analysis runs when frames are indexed and when queries are parsed, even after the memory is
+//! reopened on another machine.
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{MemvidError, Result};
+
+/// Largest n-gram length accepted by [`LexTokenizer::Ngram`].
+pub const MAX_LEX_NGRAM: u8 = 16;
+
+/// How text is split and normalised before it enters the lexical index.
+///
+/// Filters run in a fixed order: identifier splitting, lowercasing, stopword removal, ASCII
+/// folding, stemming and finally n-gram expansion. The default matches the analyzer used
+/// before this configuration existed (word tokens, lowercased, English stemming).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LexAnalyzerConfig {
+    /// Tokenizer that splits text into terms.
+    #[serde(default)]
+    pub tokenizer: LexTokenizer,
+    /// Stemmer language; `None` disables stemming.
+    #[serde(default)]
+    pub stemmer: Option<LexLanguage>,
+    /// Built-in stopword list to drop; `None` keeps every word.
+    #[serde(default)]
+    pub stopwords: Option<LexLanguage>,
+    /// Additional words to drop, matched after lowercasing and before folding.
+    #[serde(default)]
+    pub custom_stopwords: Vec<String>,
+    /// Fold accented Latin characters to their ASCII equivalents ("Straße" matches "strasse").
+    #[serde(default)]
+    pub ascii_folding: bool,
+    /// Split code identifiers at `camelCase` boundaries ("parseHttpHeader" becomes
+    /// "parse", "http", "header"). `snake_case` is always split on the underscore.
+    #[serde(default)]
+    pub split_identifiers: bool,
+}
+
+impl Default for LexAnalyzerConfig {
+    fn default() -> Self {
+        Self {
+            tokenizer: LexTokenizer::Words,
+            stemmer: Some(LexLanguage::English),
+            stopwords: None,
+            custom_stopwords: Vec::new(),
+            ascii_folding: false,
+            split_identifiers: false,
+        }
+    }
+}
+
+impl LexAnalyzerConfig {
+    /// Analyzer for `language`: its stemmer and stopword list with ASCII folding.
+    #[must_use]
+    pub fn for_language(language: LexLanguage) -> Self {
+        Self {
+            stemmer: Some(language),
+            stopwords: language.has_stopwords().then_some(language),
+            ascii_folding: true,
+            ..Self::default()
+        }
+    }
+
+    /// Analyzer for Chinese, Japanese and Korean text: overlapping character bigrams,
+    /// without stemming.
+    #[must_use]
+    pub fn cjk() -> Self {
+        Self {
+            tokenizer: LexTokenizer::CjkBigram,
+            stemmer: None,
+            ..Self::default()
+        }
+    }
+
+    /// Analyzer for source code: identifiers split into their words, without stemming.
+    #[must_use]
+    pub fn code() -> Self {
+        Self {
+            stemmer: None,
+            split_identifiers: true,
+            ..Self::default()
+        }
+    }
+
+    /// Whether consecutive terms occupy consecutive positions, so phrase queries apply.
+    /// N-grams of one word share its position and are matched as a conjunction instead.
+    #[must_use]
+    pub fn supports_phrases(&self) -> bool {
+        !matches!(self.tokenizer, LexTokenizer::Ngram { .. })
+    }
+
+    /// Reject configurations the index cannot apply.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::InvalidLexAnalyzer`] for an n-gram range outside
+    /// `1 <= min <= max <= MAX_LEX_NGRAM` or a language without a stopword list.
+    pub fn validate(&self) -> Result<()> {
+        if let LexTokenizer::Ngram { min_gram, max_gram } = self.tokenizer {
+            if min_gram == 0 || min_gram > max_gram || max_gram > MAX_LEX_NGRAM {
+                return Err(MemvidError::InvalidLexAnalyzer {
+                    reason: format!(
+                        "n-gram range {min_gram}..={max_gram} must satisfy 1 <= min <= max <= {MAX_LEX_NGRAM}"
+                    )
+                    .into(),
+                });
+            }
+        }
+        if let Some(language) = self.stopwords {
+            if !language.has_stopwords() {
+                return Err(MemvidError::InvalidLexAnalyzer {
+                    reason: format!("no built-in stopword list for {language:?}").into(),
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Tokenizer used by the lexical index.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LexTokenizer {
+    /// Runs of letters and digits; everything else separates words.
+    #[default]
+    Words,
+    /// Words expanded into character n-grams of `min_gram..=max_gram` characters, for
+    /// substring matching. Words shorter than `min_gram` are kept whole.
+    Ngram { min_gram: u8, max_gram: u8 },
+    /// Words for alphabetic scripts and overlapping character bigrams for runs of Han,
+    /// Hiragana, Katakana and Hangul characters.
+    CjkBigram,
+}
+
+/// Language for stemming and built-in stopword lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LexLanguage {
+    Arabic,
+    Danish,
+    Dutch,
+    English,
+    Finnish,
+    French,
+    German,
+    Greek,
+    Hungarian,
+    Italian,
+    Norwegian,
+    Portuguese,
+    Romanian,
+    Russian,
+    Spanish,
+    Swedish,
+    Tamil,
+    Turkish,
+}
+
+impl LexLanguage {
+    /// Whether a built-in stopword list exists for this language.
+    #[must_use]
+    pub fn has_stopwords(self) -> bool {
+        !matches!(
+            self,
+            Self::Arabic | Self::Greek | Self::Romanian | Self::Tamil | Self::Turkish
+        )
+    }
+}
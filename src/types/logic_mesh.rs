@@ -4,7 +4,8 @@
 //! during ingestion, allowing Memvid to follow facts instead of guessing with vectors.
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::hash::{Hash, Hasher};
 
 use super::common::FrameId;
@@ -22,6 +23,9 @@ pub const MAX_MESH_NODES: usize = 1_000_000;
 /// Maximum edges allowed (DoS prevention).
 pub const MAX_MESH_EDGES: usize = 5_000_000;
 
+/// Number of nodes reported in [`LogicMeshStats::top_central_nodes`].
+const TOP_CENTRAL_NODES: usize = 10;
+
 /// A node in the logic mesh representing an entity.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct MeshNode {
@@ -246,6 +250,89 @@ pub struct FollowResult {
     pub path_length: usize,
 }
 
+/// Result from `shortest_path()`: the most confident chain of relationships between two nodes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeshPath {
+    /// Node IDs along the path, from the start node to the target.
+    pub nodes: Vec<u64>,
+    /// Edges between consecutive nodes. An edge may be walked against its direction.
+    pub edges: Vec<MeshEdge>,
+    /// Product of the edge confidences (0.0-1.0).
+    pub confidence: f32,
+}
+
+impl MeshPath {
+    /// Number of edges walked.
+    #[must_use]
+    pub fn hops(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Frames the path's relationships were detected in, in path order.
+    #[must_use]
+    pub fn frame_ids(&self) -> Vec<FrameId> {
+        let mut frame_ids = Vec::new();
+        for edge in &self.edges {
+            if !frame_ids.contains(&edge.frame_id) {
+                frame_ids.push(edge.frame_id);
+            }
+        }
+        frame_ids
+    }
+}
+
+/// A node reached by `neighborhood()`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubgraphNode {
+    /// The entity, with the frames and byte spans it is mentioned in.
+    pub node: MeshNode,
+    /// Fewest hops from the center node.
+    pub hops: usize,
+    /// Best product of edge confidences over the paths within the hop limit (1.0 for the center).
+    pub weight: f32,
+}
+
+/// Result from `neighborhood()`: the nodes within k hops of a center node and the edges between them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeshSubgraph {
+    /// ID of the center node.
+    pub center: u64,
+    /// Nodes reached, strongest weight first.
+    pub nodes: Vec<SubgraphNode>,
+    /// Edges whose endpoints were both reached.
+    pub edges: Vec<MeshEdge>,
+}
+
+impl MeshSubgraph {
+    /// Frames supporting the subgraph: where its entities are mentioned or its edges were
+    /// detected, sorted.
+    #[must_use]
+    pub fn frame_ids(&self) -> Vec<FrameId> {
+        let mut frame_ids: Vec<FrameId> = self
+            .nodes
+            .iter()
+            .flat_map(|entry| entry.node.frame_ids.iter().copied())
+            .chain(self.edges.iter().map(|edge| edge.frame_id))
+            .collect();
+        frame_ids.sort_unstable();
+        frame_ids.dedup();
+        frame_ids
+    }
+}
+
+/// Degree centrality of a node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeCentrality {
+    /// Node ID.
+    pub node_id: u64,
+    /// Display name.
+    pub name: String,
+    /// Edges touching the node, in either direction.
+    pub degree: usize,
+    /// Degree divided by the number of other nodes.
+    pub centrality: f32,
+}
+
 /// Statistics about the logic mesh.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct LogicMeshStats {
@@ -257,6 +344,52 @@ pub struct LogicMeshStats {
     pub entity_kinds: HashMap<String, usize>,
     /// Count by link type.
     pub link_types: HashMap<String, usize>,
+    /// Connected components, ignoring edge direction.
+    pub component_count: usize,
+    /// Node count of the largest component.
+    pub largest_component: usize,
+    /// Nodes without edges.
+    pub isolated_nodes: usize,
+    /// Mean number of edges per node.
+    pub average_degree: f32,
+    /// Nodes with the highest degree centrality, most central first.
+    pub top_central_nodes: Vec<NodeCentrality>,
+}
+
+/// Dijkstra queue entry; ordered so the cheapest, then shortest, path pops first.
+#[derive(Debug, Clone, Copy)]
+struct PathCandidate {
+    cost: f64,
+    hops: usize,
+    node: u64,
+}
+
+impl PathCandidate {
+    fn rank(&self, other: &Self) -> Ordering {
+        self.cost
+            .total_cmp(&other.cost)
+            .then(self.hops.cmp(&other.hops))
+    }
+}
+
+impl PartialEq for PathCandidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PathCandidate {}
+
+impl PartialOrd for PathCandidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PathCandidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.rank(self).then(other.node.cmp(&self.node))
+    }
 }
 
 /// Complete Logic-Mesh graph structure.
@@ -286,7 +419,9 @@ impl LogicMesh {
     }
 
     /// Get statistics about the mesh.
+    // Node and degree counts stay far below where `f32` loses integer precision.
     #[must_use]
+    #[allow(clippy::cast_precision_loss)]
     pub fn stats(&self) -> LogicMeshStats {
         let mut entity_kinds = HashMap::new();
         for node in &self.nodes {
@@ -302,11 +437,69 @@ impl LogicMesh {
                 .or_insert(0) += 1;
         }
 
+        let index: HashMap<u64, usize> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| (node.id, idx))
+            .collect();
+        let mut degree = vec![0usize; self.nodes.len()];
+        let mut parent: Vec<usize> = (0..self.nodes.len()).collect();
+        for edge in &self.edges {
+            let (Some(&from), Some(&to)) = (index.get(&edge.from_node), index.get(&edge.to_node))
+            else {
+                continue;
+            };
+            degree[from] += 1;
+            degree[to] += 1;
+            let (from_root, to_root) = (find_root(&mut parent, from), find_root(&mut parent, to));
+            if from_root != to_root {
+                parent[from_root] = to_root;
+            }
+        }
+
+        let mut component_sizes: HashMap<usize, usize> = HashMap::new();
+        for idx in 0..self.nodes.len() {
+            *component_sizes
+                .entry(find_root(&mut parent, idx))
+                .or_insert(0) += 1;
+        }
+
+        let others = self.nodes.len().saturating_sub(1).max(1) as f32;
+        let mut central: Vec<usize> = (0..self.nodes.len())
+            .filter(|&idx| degree[idx] > 0)
+            .collect();
+        central.sort_by(|&a, &b| {
+            degree[b]
+                .cmp(&degree[a])
+                .then(self.nodes[a].id.cmp(&self.nodes[b].id))
+        });
+        let top_central_nodes = central
+            .into_iter()
+            .take(TOP_CENTRAL_NODES)
+            .map(|idx| NodeCentrality {
+                node_id: self.nodes[idx].id,
+                name: self.nodes[idx].display_name.clone(),
+                degree: degree[idx],
+                centrality: degree[idx] as f32 / others,
+            })
+            .collect();
+
+        let total_degree: usize = degree.iter().sum();
         LogicMeshStats {
             node_count: self.nodes.len(),
             edge_count: self.edges.len(),
             entity_kinds,
             link_types,
+            component_count: component_sizes.len(),
+            largest_component: component_sizes.values().copied().max().unwrap_or(0),
+            isolated_nodes: degree.iter().filter(|&&d| d == 0).count(),
+            average_degree: if self.nodes.is_empty() {
+                0.0
+            } else {
+                total_degree as f32 / self.nodes.len() as f32
+            },
+            top_central_nodes,
         }
     }
 
@@ -502,6 +695,186 @@ impl LogicMesh {
         results
     }
 
+    /// Find the most confident path between two nodes.
+    ///
+    /// Edges are walked in either direction and weighted by confidence, so the path returned
+    /// maximizes the product of its edge confidences; ties go to the path with fewer hops.
+    /// Only edges whose link is in `allowed_links` are walked (all edges if it is empty), and
+    /// edges with zero confidence are never walked.
+    #[must_use]
+    pub fn shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        allowed_links: &[LinkType],
+    ) -> Option<MeshPath> {
+        let start = self.find_node(from)?.id;
+        let target = self.find_node(to)?.id;
+
+        let origin = PathCandidate {
+            cost: 0.0,
+            hops: 0,
+            node: start,
+        };
+        let mut best = HashMap::from([(start, origin)]);
+        let mut previous: HashMap<u64, (u64, usize)> = HashMap::new();
+        let mut queue = BinaryHeap::from([origin]);
+
+        while let Some(current) = queue.pop() {
+            if current.node == target {
+                break;
+            }
+            if best
+                .get(&current.node)
+                .is_some_and(|known| known.rank(&current) == Ordering::Less)
+            {
+                continue;
+            }
+            for (edge_idx, next) in self.neighbors(current.node, allowed_links) {
+                let confidence = self.edges[edge_idx].confidence_f32();
+                if confidence <= 0.0 {
+                    continue;
+                }
+                let candidate = PathCandidate {
+                    cost: current.cost - f64::from(confidence).ln(),
+                    hops: current.hops + 1,
+                    node: next,
+                };
+                if best
+                    .get(&next)
+                    .is_none_or(|known| candidate.rank(known) == Ordering::Less)
+                {
+                    best.insert(next, candidate);
+                    previous.insert(next, (current.node, edge_idx));
+                    queue.push(candidate);
+                }
+            }
+        }
+
+        if start != target && !previous.contains_key(&target) {
+            return None;
+        }
+
+        let mut nodes = vec![target];
+        let mut edges = Vec::new();
+        let mut node = target;
+        while let Some(&(prev, edge_idx)) = previous.get(&node) {
+            nodes.push(prev);
+            edges.push(self.edges[edge_idx].clone());
+            node = prev;
+        }
+        nodes.reverse();
+        edges.reverse();
+        let confidence = edges.iter().map(MeshEdge::confidence_f32).product();
+
+        Some(MeshPath {
+            nodes,
+            edges,
+            confidence,
+        })
+    }
+
+    /// Extract the subgraph within `hops` edges of a node.
+    ///
+    /// Edges are walked in either direction, restricted to `allowed_links` unless it is empty.
+    /// Each node reached is weighted by its most confident path from the center.
+    #[must_use]
+    pub fn neighborhood(
+        &self,
+        start: &str,
+        hops: usize,
+        allowed_links: &[LinkType],
+    ) -> Option<MeshSubgraph> {
+        let center = self.find_node(start)?.id;
+
+        // node_id -> (fewest hops, best weight)
+        let mut reached: HashMap<u64, (usize, f32)> = HashMap::from([(center, (0, 1.0))]);
+        let mut frontier = vec![(center, 1.0f32)];
+        for depth in 1..=hops {
+            let mut improved: HashMap<u64, f32> = HashMap::new();
+            for &(node_id, weight) in &frontier {
+                for (edge_idx, next) in self.neighbors(node_id, allowed_links) {
+                    let candidate = weight * self.edges[edge_idx].confidence_f32();
+                    let beats_known = reached
+                        .get(&next)
+                        .is_none_or(|&(_, known)| candidate > known);
+                    if beats_known && improved.get(&next).is_none_or(|&found| candidate > found) {
+                        improved.insert(next, candidate);
+                    }
+                }
+            }
+            if improved.is_empty() {
+                break;
+            }
+            for (&node_id, &weight) in &improved {
+                reached
+                    .entry(node_id)
+                    .and_modify(|entry| entry.1 = weight)
+                    .or_insert((depth, weight));
+            }
+            frontier = improved.into_iter().collect();
+        }
+
+        let mut nodes: Vec<SubgraphNode> = self
+            .nodes
+            .iter()
+            .filter_map(|node| {
+                let &(hops, weight) = reached.get(&node.id)?;
+                Some(SubgraphNode {
+                    node: node.clone(),
+                    hops,
+                    weight,
+                })
+            })
+            .collect();
+        nodes.sort_by(|a, b| {
+            b.weight
+                .total_cmp(&a.weight)
+                .then(a.hops.cmp(&b.hops))
+                .then(a.node.id.cmp(&b.node.id))
+        });
+
+        let edges = self
+            .edges
+            .iter()
+            .filter(|edge| {
+                link_allowed(allowed_links, &edge.link)
+                    && reached.contains_key(&edge.from_node)
+                    && reached.contains_key(&edge.to_node)
+            })
+            .cloned()
+            .collect();
+
+        Some(MeshSubgraph {
+            center,
+            nodes,
+            edges,
+        })
+    }
+
+    /// Edges touching `node_id` with an allowed link, paired with the node at their other end.
+    fn neighbors<'a>(
+        &'a self,
+        node_id: u64,
+        allowed_links: &'a [LinkType],
+    ) -> impl Iterator<Item = (usize, u64)> + 'a {
+        self.adjacency
+            .get(&node_id)
+            .into_iter()
+            .flatten()
+            .filter_map(move |&(edge_idx, direction)| {
+                let edge = &self.edges[edge_idx];
+                if !link_allowed(allowed_links, &edge.link) {
+                    return None;
+                }
+                let other = match direction {
+                    EdgeDirection::Outgoing => edge.to_node,
+                    EdgeDirection::Incoming => edge.from_node,
+                };
+                Some((edge_idx, other))
+            })
+    }
+
     /// Merge a node into the mesh, deduplicating by canonical name + kind.
     pub fn merge_node(&mut self, node: MeshNode) {
         // Find existing node by canonical name and kind
@@ -533,6 +906,15 @@ impl LogicMesh {
                 && e.to_node == edge.to_node
                 && e.link.as_str() == edge.link.as_str()
         }) {
+            let idx = self.edges.len();
+            self.adjacency
+                .entry(edge.from_node)
+                .or_default()
+                .push((idx, EdgeDirection::Outgoing));
+            self.adjacency
+                .entry(edge.to_node)
+                .or_default()
+                .push((idx, EdgeDirection::Incoming));
             self.edges.push(edge);
         }
     }
@@ -560,6 +942,23 @@ pub fn compute_node_id(canonical_name: &str, kind: EntityKind) -> u64 {
     hasher.finish()
 }
 
+/// Whether `link` passes an `allowed_links` filter; an empty filter allows every link.
+fn link_allowed(allowed_links: &[LinkType], link: &LinkType) -> bool {
+    allowed_links.is_empty()
+        || allowed_links
+            .iter()
+            .any(|allowed| allowed.as_str() == link.as_str())
+}
+
+/// Union-find root lookup with path halving.
+fn find_root(parent: &mut [usize], mut idx: usize) -> usize {
+    while parent[idx] != idx {
+        parent[idx] = parent[parent[idx]];
+        idx = parent[idx];
+    }
+    idx
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -702,4 +1101,118 @@ mod tests {
 
         assert_eq!(bytes1, bytes2, "Serialization must be deterministic");
     }
+
+    fn person(name: &str, frame_id: FrameId) -> MeshNode {
+        MeshNode::new(
+            name.to_lowercase(),
+            name.to_string(),
+            EntityKind::Person,
+            0.9,
+            frame_id,
+            0,
+            name.len() as u16,
+        )
+    }
+
+    /// Alice -manager-> Bob -member-> Dana and Alice -related-> Carol -related-> Dana, with
+    /// Erin on her own.
+    fn team_mesh() -> LogicMesh {
+        let mut mesh = LogicMesh::new();
+        for (idx, name) in ["Alice", "Bob", "Carol", "Dana", "Erin"].iter().enumerate() {
+            mesh.merge_node(person(name, idx as FrameId));
+        }
+        let id = |name: &str| compute_node_id(name, EntityKind::Person);
+        mesh.merge_edge(MeshEdge::new(
+            id("alice"),
+            id("bob"),
+            LinkType::Manager,
+            0.9,
+            10,
+        ));
+        mesh.merge_edge(MeshEdge::new(
+            id("bob"),
+            id("dana"),
+            LinkType::Member,
+            0.8,
+            11,
+        ));
+        mesh.merge_edge(MeshEdge::new(
+            id("alice"),
+            id("carol"),
+            LinkType::Related,
+            0.5,
+            12,
+        ));
+        mesh.merge_edge(MeshEdge::new(
+            id("dana"),
+            id("carol"),
+            LinkType::Related,
+            0.5,
+            13,
+        ));
+        mesh.finalize();
+        mesh
+    }
+
+    #[test]
+    fn test_shortest_path_prefers_confident_edges() {
+        let mesh = team_mesh();
+        let id = |name: &str| compute_node_id(name, EntityKind::Person);
+
+        let path = mesh.shortest_path("Alice", "Dana", &[]).expect("path");
+        assert_eq!(path.nodes, vec![id("alice"), id("bob"), id("dana")]);
+        assert_eq!(path.frame_ids(), vec![10, 11]);
+        assert!((path.confidence - 0.72).abs() < 1e-6);
+
+        // Walks the related edges backwards from Dana when only they are allowed.
+        let path = mesh
+            .shortest_path("Dana", "Alice", &[LinkType::Related])
+            .expect("related path");
+        assert_eq!(path.nodes, vec![id("dana"), id("carol"), id("alice")]);
+        assert_eq!(path.hops(), 2);
+
+        assert!(mesh.shortest_path("Alice", "Erin", &[]).is_none());
+        assert!(
+            mesh.shortest_path("Alice", "Dana", &[LinkType::Manager])
+                .is_none()
+        );
+        assert_eq!(mesh.shortest_path("Alice", "alice", &[]).unwrap().hops(), 0);
+    }
+
+    #[test]
+    fn test_neighborhood_weights_nodes() {
+        let mesh = team_mesh();
+        let subgraph = mesh.neighborhood("Alice", 1, &[]).expect("subgraph");
+        let names: Vec<_> = subgraph
+            .nodes
+            .iter()
+            .map(|entry| entry.node.display_name.as_str())
+            .collect();
+        assert_eq!(names, vec!["Alice", "Bob", "Carol"]);
+        assert_eq!(subgraph.edges.len(), 2);
+        assert_eq!(subgraph.frame_ids(), vec![0, 1, 2, 10, 12]);
+
+        // Dana is two hops away either way; the manager chain is the stronger route.
+        let subgraph = mesh.neighborhood("Alice", 2, &[]).expect("subgraph");
+        let dana = subgraph
+            .nodes
+            .iter()
+            .find(|entry| entry.node.display_name == "Dana")
+            .expect("dana reached");
+        assert_eq!(dana.hops, 2);
+        assert!((dana.weight - 0.72).abs() < 1e-6);
+        assert_eq!(subgraph.edges.len(), 4);
+    }
+
+    #[test]
+    fn test_stats_components_and_centrality() {
+        let stats = team_mesh().stats();
+        assert_eq!(stats.component_count, 2);
+        assert_eq!(stats.largest_component, 4);
+        assert_eq!(stats.isolated_nodes, 1);
+        assert!((stats.average_degree - 1.6).abs() < 1e-6);
+        assert_eq!(stats.top_central_nodes.len(), 4);
+        assert_eq!(stats.top_central_nodes[0].degree, 2);
+        assert!((stats.top_central_nodes[0].centrality - 0.5).abs() < 1e-6);
+    }
 }
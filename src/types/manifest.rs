@@ -6,7 +6,10 @@ use serde::{
     ser::SerializeStruct,
 };
 
-use super::{common::FrameId, frame::Frame, ticket::TicketRef};
+use super::{
+    change_log::ChangeLog, common::FrameId, embedding_identity::EmbeddingIdentity, frame::Frame,
+    lex_analyzer::LexAnalyzerConfig, ticket::TicketRef,
+};
 
 use std::{fmt, marker::PhantomData};
 
@@ -687,6 +690,10 @@ pub struct IndexManifests {
     /// CLIP visual embeddings index (separate from text vec index due to different dimensions)
     #[serde(default)]
     pub clip: Option<crate::clip::ClipIndexManifest>,
+    /// Named vector spaces kept side by side with the default `vec` index
+    /// (e.g. one per embedding model during a migration).
+    #[serde(default)]
+    pub vec_spaces: Vec<VecSpaceManifest>,
 }
 
 impl Default for IndexManifests {
@@ -696,10 +703,56 @@ impl Default for IndexManifests {
             lex_segments: Vec::new(),
             vec: None,
             clip: None,
+            vec_spaces: Vec::new(),
         }
     }
 }
 
+impl IndexManifests {
+    /// Analyzer configured for the lexical index, if it differs from the default.
+    #[must_use]
+    pub fn lex_analyzer(&self) -> Option<&LexAnalyzerConfig> {
+        self.lex
+            .as_ref()
+            .and_then(|manifest| manifest.analyzer.as_ref())
+    }
+
+    /// Replace the lexical index manifest while keeping the configured analyzer.
+    ///
+    /// When `manifest` is `None` and an analyzer is configured, a placeholder manifest
+    /// keeps the analyzer so it survives index rebuilds.
+    pub(crate) fn replace_lex(&mut self, manifest: Option<LexIndexManifest>) {
+        let analyzer = self.lex.take().and_then(|previous| previous.analyzer);
+        self.lex = match (manifest, analyzer) {
+            (Some(mut manifest), analyzer) => {
+                if manifest.analyzer.is_none() {
+                    manifest.analyzer = analyzer;
+                }
+                Some(manifest)
+            }
+            (None, Some(analyzer)) => Some(LexIndexManifest::analyzer_placeholder(analyzer)),
+            (None, None) => None,
+        };
+    }
+
+    /// Look up a named vector space.
+    #[must_use]
+    pub fn vec_space(&self, name: &str) -> Option<&VecSpaceManifest> {
+        self.vec_spaces.iter().find(|space| space.name == name)
+    }
+}
+
+/// Manifest of a named vector space: its embedding model identity and index location.
+///
+/// `index.dimension` is fixed by the first vector stored in the space and
+/// `index.compression_mode` records the space's own `VectorCompression`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VecSpaceManifest {
+    pub name: String,
+    pub identity: Option<EmbeddingIdentity>,
+    pub index: VecIndexManifest,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LexIndexManifest {
     pub doc_count: u64,
@@ -708,6 +761,24 @@ pub struct LexIndexManifest {
     pub bytes_offset: u64,
     pub bytes_length: u64,
     pub checksum: [u8; 32],
+    /// Analyzer applied at index and query time; `None` means the default analyzer.
+    #[serde(default)]
+    pub analyzer: Option<LexAnalyzerConfig>,
+}
+
+impl LexIndexManifest {
+    /// Empty manifest that only records the analyzer (no embedded index bytes).
+    #[must_use]
+    pub fn analyzer_placeholder(analyzer: LexAnalyzerConfig) -> Self {
+        Self {
+            doc_count: 0,
+            generation: 0,
+            bytes_offset: 0,
+            bytes_length: 0,
+            checksum: [0u8; 32],
+            analyzer: Some(analyzer),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -812,10 +883,52 @@ pub struct Toc {
     /// Tracks frames needing background Phase 2 work (full extraction + embeddings).
     #[serde(default)]
     pub enrichment_queue: EnrichmentQueueManifest,
+    /// Ed25519 signature over `merkle_root`, set by `Memvid::sign`.
+    #[serde(default)]
+    pub signature: Option<MemorySignature>,
+    /// Mutations published by recent commits, oldest first.
+    #[serde(default)]
+    pub change_log: ChangeLog,
     pub merkle_root: [u8; 32],
     pub toc_checksum: [u8; 32],
 }
 
+/// Independently loaded section of a [`Toc`].
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum TocPart {
+    /// Frame records and payload segments.
+    Frames,
+    /// Lexical index, including its Tantivy segments and analyzer.
+    LexIndex,
+    /// Default vector index and its segments.
+    VecIndex,
+    /// Named vector spaces.
+    VecSpaces,
+    ClipIndex,
+    TimeIndex,
+    TemporalTrack,
+    MemoriesTrack,
+    LogicMesh,
+    SketchTrack,
+    /// Ticket and dashboard binding.
+    Ticket,
+    Replay,
+    EnrichmentQueue,
+    /// Merkle root and signature.
+    Signature,
+    ChangeLog,
+}
+
+/// Signature of a memory's Merkle root and the key that produced it.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MemorySignature {
+    /// Ed25519 public key of the signer.
+    pub public_key: [u8; 32],
+    /// Ed25519 signature (64 bytes).
+    pub signature: Vec<u8>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TimeIndexManifest {
     pub bytes_offset: u64,
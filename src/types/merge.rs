@@ -0,0 +1,86 @@
+//! Options and reports for combining memories with `Memvid::merge_from`.
+
+use serde::{Deserialize, Serialize};
+
+/// How to resolve an incoming document whose URI already exists in the target memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum UriConflictPolicy {
+    /// Keep the existing frame and drop the incoming document.
+    #[default]
+    Skip,
+    /// Insert the incoming document and mark the existing frame as superseded by it.
+    Replace,
+    /// Insert the incoming document alongside the existing frame.
+    KeepBoth,
+    /// Abort the merge before anything is written.
+    Fail,
+}
+
+/// Controls what `Memvid::merge_from` carries over from the source memory.
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct MergeOptions {
+    /// Skip incoming documents whose payload checksums match an active document in the target.
+    #[serde(default = "default_true")]
+    pub dedup_by_checksum: bool,
+    /// Policy applied when an incoming document URI is already present in the target.
+    #[serde(default)]
+    pub uri_conflict: UriConflictPolicy,
+    /// Copy memory cards whose source frames were carried over.
+    #[serde(default = "default_true")]
+    pub include_memory_cards: bool,
+    /// Merge Logic-Mesh nodes and edges that reference carried-over frames.
+    #[serde(default = "default_true")]
+    pub include_logic_mesh: bool,
+    /// Copy text embeddings from the source vector index.
+    #[serde(default = "default_true")]
+    pub include_embeddings: bool,
+    /// Copy visual embeddings from the source CLIP index.
+    #[serde(default = "default_true")]
+    pub include_clip: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl Default for MergeOptions {
+    fn default() -> Self {
+        Self {
+            dedup_by_checksum: true,
+            uri_conflict: UriConflictPolicy::default(),
+            include_memory_cards: true,
+            include_logic_mesh: true,
+            include_embeddings: true,
+            include_clip: true,
+        }
+    }
+}
+
+/// Summary returned by `Memvid::merge_from`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MergeReport {
+    /// Frames (documents and their chunks) copied into the target.
+    pub frames_copied: u64,
+    /// Documents skipped because identical content already existed.
+    pub documents_deduplicated: u64,
+    /// Documents skipped because of a URI conflict under `UriConflictPolicy::Skip`.
+    pub documents_skipped: u64,
+    /// Existing frames superseded under `UriConflictPolicy::Replace`.
+    pub frames_replaced: u64,
+    /// Text embeddings copied into the vector index.
+    pub embeddings_copied: u64,
+    /// Embeddings copied into named vector spaces.
+    pub space_embeddings_copied: u64,
+    /// Visual embeddings copied into the CLIP index.
+    pub clip_embeddings_copied: u64,
+    /// Memory cards added to the target memories track.
+    pub cards_copied: u64,
+    /// Memory cards dropped because their source frame was not carried over.
+    pub cards_skipped: u64,
+    /// Logic-Mesh nodes merged into the target.
+    pub mesh_nodes_merged: u64,
+    /// Logic-Mesh edges merged into the target.
+    pub mesh_edges_merged: u64,
+}
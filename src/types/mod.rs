@@ -4,15 +4,19 @@ pub mod adaptive;
 pub mod ask;
 pub mod audit;
 pub mod binding;
+pub mod change_log;
 pub mod common;
 pub mod embedding;
 pub mod embedding_identity;
 pub mod frame;
 pub mod graph_query;
+pub mod jsonl;
+pub mod lex_analyzer;
 pub mod logic_mesh;
 pub mod manifest;
 pub mod memories_track;
 pub mod memory_card;
+pub mod merge;
 pub mod metadata;
 pub mod options;
 pub mod reranker;
@@ -31,6 +35,7 @@ pub use ask::{
 };
 pub use audit::{AuditOptions, AuditReport, SourceSpan};
 pub use binding::{FileInfo, MemoryBinding};
+pub use change_log::{ChangeEvent, ChangeKind, ChangeLog};
 pub use common::{
     CanonicalEncoding, EnrichmentState, EnrichmentTask, FrameId, FrameRole, FrameStatus,
     MemvidHandle, Open, Sealed, Tier,
@@ -39,28 +44,37 @@ pub use common::{
 pub use frame::AnchorSource;
 pub use frame::{Frame, Stats, TimelineEntry, TimelineQuery, TimelineQueryBuilder};
 // Serialized manifest types - always exported for binary compatibility
+pub use lex_analyzer::{LexAnalyzerConfig, LexLanguage, LexTokenizer, MAX_LEX_NGRAM};
 pub use manifest::TemporalSegmentDescriptor;
 pub use manifest::TemporalTrackManifest;
 pub use manifest::{
     EnrichmentQueueManifest, Header, IndexManifests, IndexSegmentRef, LexIndexManifest,
     LexSegmentDescriptor, LexSegmentManifest, LogicMeshManifest, MemoriesTrackManifest,
-    SegmentCatalog, SegmentCommon, SegmentCompression, SegmentKind, SegmentMeta, SegmentSpan,
-    SegmentStats, SketchTrackManifest, TantivySegmentDescriptor, TimeIndexManifest,
-    TimeSegmentDescriptor, Toc, VecIndexManifest, VecSegmentDescriptor, VectorCompression,
+    MemorySignature, SegmentCatalog, SegmentCommon, SegmentCompression, SegmentKind, SegmentMeta,
+    SegmentSpan, SegmentStats, SketchTrackManifest, TantivySegmentDescriptor, TimeIndexManifest,
+    TimeSegmentDescriptor, Toc, TocPart, VecIndexManifest, VecSegmentDescriptor, VecSpaceManifest,
+    VectorCompression,
 };
 // Logic-Mesh types for entity-relationship graph traversal
+pub use jsonl::{
+    JSONL_FORMAT, JSONL_FORMAT_VERSION, JsonlClipEmbedding, JsonlFrame, JsonlHeader, JsonlPayload,
+    JsonlRecord, JsonlReport, JsonlVecSpace,
+};
 pub use logic_mesh::{
     EdgeDirection, EntityKind, FollowResult, LOGIC_MESH_MAGIC, LOGIC_MESH_VERSION, LinkType,
-    LogicMesh, LogicMeshStats, MeshEdge, MeshNode,
+    LogicMesh, LogicMeshStats, MeshEdge, MeshNode, MeshPath, MeshSubgraph, NodeCentrality,
+    SubgraphNode,
 };
+pub use merge::{MergeOptions, MergeReport, UriConflictPolicy};
 pub use metadata::{
     AudioSegmentMetadata, DocAudioMetadata, DocExifMetadata, DocGpsMetadata, DocMetadata,
     MediaManifest, TextChunkManifest, TextChunkRange,
 };
 pub use options::{PutManyOpts, PutOptions, PutOptionsBuilder, PutRequest};
 pub use search::{
-    SearchEngineKind, SearchHit, SearchHitEntity, SearchHitMetadata, SearchParams, SearchRequest,
-    SearchResponse,
+    FacetCounts, FacetField, FacetValueCount, FusionStrategy, HitHighlights, HitScoreBreakdown,
+    HybridSearch, MetadataFilter, SearchEngineKind, SearchHit, SearchHitEntity, SearchHitMetadata,
+    SearchParams, SearchRequest, SearchResponse, SnippetFragment, SnippetFragments,
 };
 #[cfg(feature = "temporal_track")]
 pub use search::{SearchHitTemporal, SearchHitTemporalAnchor, SearchHitTemporalMention};
@@ -75,8 +89,8 @@ pub use verification::{
     DOCTOR_PLAN_VERSION, DoctorActionDetail, DoctorActionKind, DoctorActionPlan,
     DoctorActionReport, DoctorActionStatus, DoctorFinding, DoctorFindingCode, DoctorMetrics,
     DoctorOptions, DoctorPhaseDuration, DoctorPhaseKind, DoctorPhasePlan, DoctorPhaseReport,
-    DoctorPhaseStatus, DoctorPlan, DoctorReport, DoctorSeverity, DoctorStatus, VerificationCheck,
-    VerificationReport, VerificationStatus,
+    DoctorPhaseStatus, DoctorPlan, DoctorReport, DoctorSeverity, DoctorStatus, VacuumReport,
+    VerificationCheck, VerificationReport, VerificationStatus,
 };
 // Memory card types for structured memory extraction
 pub use memories_track::{
@@ -110,7 +124,8 @@ pub use adaptive::{
 };
 // Graph-aware query types for hybrid retrieval
 pub use graph_query::{
-    GraphMatchResult, GraphPattern, HybridSearchHit, PatternTerm, QueryPlan, TriplePattern,
+    FilterOp, GraphFilter, GraphMatchResult, GraphPattern, HybridSearchHit, OrderKey, PatternTerm,
+    QueryPlan, TriplePattern,
 };
 // Schema types for predicate validation
 pub use schema::{
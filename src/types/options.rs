@@ -71,6 +71,10 @@ pub struct PutOptions {
     /// Default: 350ms (optimized for sub-second total ingestion).
     #[serde(default = "default_extraction_budget_ms")]
     pub extraction_budget_ms: u64,
+    /// Named vector space receiving the supplied embeddings.
+    /// `None` stores them in the default vector index.
+    #[serde(default)]
+    pub vector_space: Option<String>,
 }
 
 fn default_extraction_budget_ms() -> u64 {
@@ -101,6 +105,7 @@ impl Default for PutOptions {
             dedup: false,
             instant_index: true, // Instant searchability by default
             extraction_budget_ms: default_extraction_budget_ms(),
+            vector_space: None,
         }
     }
 }
@@ -248,6 +253,13 @@ impl PutOptionsBuilder {
         self
     }
 
+    /// Store the supplied embeddings in the named vector space `space`.
+    #[must_use]
+    pub fn vector_space<S: Into<String>>(mut self, space: S) -> Self {
+        self.inner.vector_space = Some(space.into());
+        self
+    }
+
     pub fn build(self) -> PutOptions {
         self.inner
     }
@@ -321,13 +333,13 @@ pub struct PutManyOpts {
     /// - true: Fast mode - trades crash-safety for speed
     pub skip_sync: bool,
 
-    /// Enable embedding generation (slower, not yet implemented)
+    /// Queue documents without a precomputed embedding for background embedding (slower)
     pub enable_embedding: bool,
 
-    /// Enable auto-tagging (slower, not yet implemented)
+    /// Enable auto-tagging (slower)
     pub auto_tag: bool,
 
-    /// Extract dates from text (slower, not yet implemented)
+    /// Extract dates from text (slower)
     pub extract_dates: bool,
 
     /// Don't store raw binary content (default: true).
@@ -23,7 +23,11 @@
 //! }
 //! ```
 
-use crate::error::Result;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::error::{MemvidError, Result};
 
 /// A document candidate for reranking.
 #[derive(Debug, Clone)]
@@ -228,6 +232,178 @@ impl std::str::FromStr for RerankerKind {
     }
 }
 
+/// Deterministic BM25 rescoring over the candidate set, for [`RerankerKind::Bm25`].
+///
+/// Document frequencies are computed over the candidates themselves, so scores only compare
+/// documents within one call. Scores are divided by the best score to land in `0.0..=1.0`;
+/// ties keep the original candidate order.
+#[derive(Debug, Clone, Copy)]
+pub struct Bm25Reranker {
+    /// Term-frequency saturation.
+    pub k1: f32,
+    /// Document-length normalisation.
+    pub b: f32,
+}
+
+impl Default for Bm25Reranker {
+    fn default() -> Self {
+        Self { k1: 1.2, b: 0.75 }
+    }
+}
+
+impl Bm25Reranker {
+    fn tokenize(text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|token| !token.is_empty())
+            .map(str::to_lowercase)
+            .collect()
+    }
+}
+
+impl Reranker for Bm25Reranker {
+    fn kind(&self) -> &'static str {
+        "bm25"
+    }
+
+    // Document and term counts stay far below where `f32` loses integer precision.
+    #[allow(clippy::cast_precision_loss)]
+    fn rerank(
+        &self,
+        query: &str,
+        documents: &[RerankerDocument],
+        top_k: usize,
+    ) -> Result<Vec<RerankerResult>> {
+        let mut query_terms = Self::tokenize(query);
+        query_terms.sort();
+        query_terms.dedup();
+
+        let docs: Vec<Vec<String>> = documents
+            .iter()
+            .map(|doc| match &doc.metadata {
+                Some(metadata) => Self::tokenize(&format!("{metadata} {}", doc.text)),
+                None => Self::tokenize(&doc.text),
+            })
+            .collect();
+        let doc_count = docs.len() as f32;
+        let avg_len =
+            (docs.iter().map(Vec::len).sum::<usize>() as f32 / doc_count.max(1.0)).max(1.0);
+
+        let mut doc_freq: HashMap<&str, f32> = HashMap::new();
+        for tokens in &docs {
+            for term in &query_terms {
+                if tokens.contains(term) {
+                    *doc_freq.entry(term.as_str()).or_default() += 1.0;
+                }
+            }
+        }
+
+        let scores: Vec<f32> = docs
+            .iter()
+            .map(|tokens| {
+                let len_norm = 1.0 - self.b + self.b * tokens.len() as f32 / avg_len;
+                query_terms
+                    .iter()
+                    .map(|term| {
+                        let tf = tokens.iter().filter(|token| *token == term).count() as f32;
+                        if tf == 0.0 {
+                            return 0.0;
+                        }
+                        let df = doc_freq.get(term.as_str()).copied().unwrap_or(0.0);
+                        let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+                        idf * tf * (self.k1 + 1.0) / (tf + self.k1 * len_norm)
+                    })
+                    .sum()
+            })
+            .collect();
+        let max_score = scores.iter().copied().fold(0.0f32, f32::max);
+
+        let mut results: Vec<RerankerResult> = documents
+            .iter()
+            .zip(scores)
+            .enumerate()
+            .map(|(idx, (doc, score))| RerankerResult {
+                id: doc.id,
+                score: if max_score > 0.0 {
+                    score / max_score
+                } else {
+                    0.0
+                },
+                original_rank: idx + 1,
+                new_rank: 0,
+            })
+            .collect();
+        results.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        results.truncate(top_k);
+        for (idx, result) in results.iter_mut().enumerate() {
+            result.new_rank = idx + 1;
+        }
+        Ok(results)
+    }
+}
+
+/// A reranker plus its configuration, attached to a `SearchRequest` or `AskRequest`.
+///
+/// The top `config.max_candidates` hits are passed to the reranker, which reorders them and
+/// keeps at most `config.top_k` scoring at least `config.min_score`. Hooks are not serialized.
+#[derive(Clone)]
+pub struct RerankerHook {
+    reranker: Arc<dyn Reranker>,
+    pub config: RerankerConfig,
+}
+
+impl RerankerHook {
+    #[must_use]
+    pub fn new(reranker: impl Reranker + 'static, config: RerankerConfig) -> Self {
+        Self::from_arc(Arc::new(reranker), config)
+    }
+
+    #[must_use]
+    pub fn from_arc(reranker: Arc<dyn Reranker>, config: RerankerConfig) -> Self {
+        Self { reranker, config }
+    }
+
+    /// Hook using the built-in [`Bm25Reranker`].
+    #[must_use]
+    pub fn bm25(config: RerankerConfig) -> Self {
+        Self::new(Bm25Reranker::default(), config)
+    }
+
+    /// Hook for a built-in reranker kind. `RerankerKind::None` yields `None`; kinds that need
+    /// an external model must be supplied with [`RerankerHook::new`].
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::RerankFailed`] for kinds that have no built-in implementation.
+    pub fn from_kind(kind: RerankerKind, config: RerankerConfig) -> Result<Option<Self>> {
+        match kind {
+            RerankerKind::None => Ok(None),
+            RerankerKind::Bm25 => Ok(Some(Self::bm25(config))),
+            other => Err(MemvidError::RerankFailed {
+                reason: format!("no built-in {other} reranker; supply one via RerankerHook::new")
+                    .into(),
+            }),
+        }
+    }
+
+    #[must_use]
+    pub fn reranker(&self) -> &dyn Reranker {
+        self.reranker.as_ref()
+    }
+}
+
+impl fmt::Debug for RerankerHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RerankerHook")
+            .field("kind", &self.reranker.kind())
+            .field("config", &self.config)
+            .finish()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -299,6 +475,24 @@ mod tests {
         assert_eq!("llm".parse::<RerankerKind>().unwrap(), RerankerKind::Llm);
     }
 
+    #[test]
+    fn test_bm25_reranker_prefers_term_matches() {
+        let reranker = Bm25Reranker::default();
+        let docs = vec![
+            RerankerDocument::new(0, "weather report for tuesday"),
+            RerankerDocument::new(1, "rust compiler release notes for the rust toolchain"),
+            RerankerDocument::new(2, "notes about gardening"),
+        ];
+
+        let results = reranker.rerank("rust release", &docs, 2).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, 1);
+        assert!((results[0].score - 1.0).abs() < f32::EPSILON);
+        assert_eq!(results[0].original_rank, 2);
+        assert_eq!(results[1].new_rank, 2);
+        assert_eq!(results[1].score, 0.0);
+    }
+
     #[test]
     fn test_config_defaults() {
         let default = RerankerConfig::default();
@@ -7,6 +7,7 @@
 //! - Inverse relationship tracking
 
 use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use super::logic_mesh::EntityKind;
@@ -65,6 +66,35 @@ impl ValueType {
         }
     }
 
+    /// Order two values of this type.
+    ///
+    /// Numbers and dates compare by magnitude and return `None` when either side does not
+    /// parse. Other types compare case-insensitively as strings, unless both sides parse as
+    /// numbers or as dates.
+    #[must_use]
+    pub fn compare(&self, left: &str, right: &str) -> Option<Ordering> {
+        let numbers = || {
+            left.trim()
+                .parse::<f64>()
+                .ok()
+                .zip(right.trim().parse::<f64>().ok())
+        };
+        let dates = || parse_timestamp(left).zip(parse_timestamp(right));
+        match self {
+            Self::Number => numbers().and_then(|(l, r)| l.partial_cmp(&r)),
+            Self::DateTime => dates().map(|(l, r)| l.cmp(&r)),
+            _ => {
+                if let Some((l, r)) = numbers() {
+                    l.partial_cmp(&r)
+                } else if let Some((l, r)) = dates() {
+                    Some(l.cmp(&r))
+                } else {
+                    Some(left.to_lowercase().cmp(&right.to_lowercase()))
+                }
+            }
+        }
+    }
+
     /// Get a human-readable description of this type.
     pub fn description(&self) -> String {
         match self {
@@ -79,6 +109,21 @@ impl ValueType {
     }
 }
 
+/// Parse a Unix timestamp, RFC 3339 datetime or `YYYY-MM-DD` date into seconds.
+fn parse_timestamp(value: &str) -> Option<i64> {
+    let value = value.trim();
+    if let Ok(ts) = value.parse::<i64>() {
+        return Some(ts);
+    }
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
+        return Some(dt.timestamp());
+    }
+    chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc().timestamp())
+}
+
 /// Cardinality of a predicate (single or multiple values allowed).
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "snake_case")]
@@ -526,6 +571,21 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_value_type_compare() {
+        assert_eq!(ValueType::Number.compare("9", "10"), Some(Ordering::Less));
+        assert_eq!(ValueType::Number.compare("9", "ten"), None);
+        assert_eq!(
+            ValueType::DateTime.compare("2024-03-01", "2024-02-28T12:00:00Z"),
+            Some(Ordering::Greater)
+        );
+        assert_eq!(ValueType::String.compare("9", "10"), Some(Ordering::Less));
+        assert_eq!(
+            ValueType::String.compare("Acme", "acme"),
+            Some(Ordering::Equal)
+        );
+    }
+
     #[test]
     fn test_predicate_schema_validation() {
         let schema = PredicateSchema::new("age", "Age")
@@ -1,10 +1,14 @@
 //! Public search request/response types exposed by the core library.
 
+use std::cmp::Ordering;
+
 use serde::{Deserialize, Serialize};
 
-use super::common::FrameId;
+use super::common::{FrameId, FrameRole};
 #[cfg(feature = "temporal_track")]
 use super::frame::AnchorSource;
+use super::frame::Frame;
+use super::reranker::RerankerHook;
 #[cfg(feature = "temporal_track")]
 use super::temporal::{TemporalFilter, TemporalMentionFlags, TemporalMentionKind};
 
@@ -65,6 +69,235 @@ pub struct SearchRequest {
     #[serde(default)]
     /// Disable sketch pre-filtering for this query.
     pub no_sketch: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Structured metadata filter applied to every retrieval path.
+    pub filter: Option<MetadataFilter>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Facets to count over the full matched set. Empty disables facet counting.
+    pub facets: Vec<FacetField>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Fuse lexical results with vector results for this query embedding.
+    pub hybrid: Option<HybridSearch>,
+    #[serde(skip)]
+    /// Second-stage reranker applied to the leading candidates. Not serialized.
+    pub reranker: Option<RerankerHook>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    /// Return one hit per matching chunk with its best fragments instead of one hit per passage.
+    pub fragments: Option<SnippetFragments>,
+}
+
+/// Multi-fragment snippet mode for lexical search.
+///
+/// Each matching chunk yields a single hit whose `text` is its best fragment; up to
+/// `max_fragments` fragments of about `snippet_chars` each are listed in
+/// [`HitHighlights::fragments`], ranked by the number of matched terms they contain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetFragments {
+    pub max_fragments: usize,
+}
+
+/// Vector half of a hybrid search, fused with the lexical results.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HybridSearch {
+    /// Embedding of the query text, produced by the same model as the stored embeddings.
+    pub query_embedding: Vec<f32>,
+    #[serde(default)]
+    pub fusion: FusionStrategy,
+    /// Candidates fetched from each retriever before fusion. Defaults to `max(top_k * 3, 20)`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub candidates: Option<usize>,
+}
+
+impl HybridSearch {
+    #[must_use]
+    pub fn new(query_embedding: Vec<f32>) -> Self {
+        Self {
+            query_embedding,
+            fusion: FusionStrategy::default(),
+            candidates: None,
+        }
+    }
+
+    #[must_use]
+    pub fn fusion(mut self, fusion: FusionStrategy) -> Self {
+        self.fusion = fusion;
+        self
+    }
+}
+
+/// How lexical and vector rankings are combined.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "strategy", rename_all = "snake_case")]
+pub enum FusionStrategy {
+    /// Reciprocal Rank Fusion: `sum(1 / (k + rank))` over the retrievers that returned a frame.
+    Rrf { k: f32 },
+    /// Linear combination of min-max normalised scores.
+    Weighted { lexical: f32, vector: f32 },
+}
+
+impl Default for FusionStrategy {
+    fn default() -> Self {
+        Self::Rrf { k: 60.0 }
+    }
+}
+
+/// Positions of matched terms in a lexical hit, found with the index's analyzer so stemmed,
+/// folded and fuzzy matches are marked where they occur in the original text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HitHighlights {
+    /// Byte ranges of matched terms within `SearchHit::text`.
+    #[serde(default)]
+    pub text: Vec<(usize, usize)>,
+    /// Byte ranges of matched terms within `SearchHit::chunk_text`.
+    #[serde(default)]
+    pub chunk: Vec<(usize, usize)>,
+    /// Best-first fragments, populated when the request sets
+    /// [`SearchRequest::fragments`].
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fragments: Vec<SnippetFragment>,
+}
+
+/// One highlighted fragment of a matching chunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnippetFragment {
+    /// Byte range within the frame text, like `SearchHit::range`.
+    pub range: (usize, usize),
+    pub text: String,
+    /// Byte ranges of matched terms within `text`.
+    #[serde(default)]
+    pub highlights: Vec<(usize, usize)>,
+}
+
+/// Per-retriever scores behind a fused hybrid hit.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HitScoreBreakdown {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lexical_rank: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lexical_score: Option<f32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector_rank: Option<usize>,
+    /// Vector hit score: `1 - d`, where `d` is the L2 distance between the frame and query
+    /// embeddings. Higher is better; it is not bounded below by zero.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vector_score: Option<f32>,
+    pub fused_score: f32,
+}
+
+/// Metadata dimension that can be counted across a result set.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FacetField {
+    Tag,
+    Label,
+    Track,
+    Kind,
+    /// Frame creation month in UTC, formatted as `YYYY-MM`.
+    Month,
+}
+
+/// Number of matched frames carrying a facet value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FacetValueCount {
+    pub value: String,
+    pub count: usize,
+}
+
+/// Counts for one requested facet, ordered by descending count then value.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FacetCounts {
+    pub field: FacetField,
+    pub values: Vec<FacetValueCount>,
+}
+
+/// Typed filter over frame metadata, evaluated before ranking.
+///
+/// Tag, label, kind and track comparisons ignore ASCII case, matching the `tag:`/`label:`/
+/// `track:` query fields. Metadata ranges compare numerically when both sides parse as numbers
+/// and lexicographically otherwise (so ISO-8601 dates order correctly).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetadataFilter {
+    /// All children must match (an empty list matches everything).
+    And(Vec<MetadataFilter>),
+    /// At least one child must match (an empty list matches nothing).
+    Or(Vec<MetadataFilter>),
+    Not(Box<MetadataFilter>),
+    Tag(String),
+    Label(String),
+    Kind(String),
+    Track(String),
+    Role(FrameRole),
+    ParentId(FrameId),
+    /// `extra_metadata[key] == value`.
+    MetadataEq {
+        key: String,
+        value: String,
+    },
+    /// Inclusive range over `extra_metadata[key]`; frames without the key never match.
+    MetadataRange {
+        key: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        min: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        max: Option<String>,
+    },
+    /// Inclusive range over the frame timestamp (seconds since the Unix epoch).
+    Timestamp {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        start: Option<i64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        end: Option<i64>,
+    },
+}
+
+impl MetadataFilter {
+    /// Returns true when `frame` satisfies the filter.
+    #[must_use]
+    pub fn matches(&self, frame: &Frame) -> bool {
+        let eq = |value: Option<&str>, expected: &str| {
+            value.is_some_and(|value| value.eq_ignore_ascii_case(expected))
+        };
+        match self {
+            Self::And(children) => children.iter().all(|child| child.matches(frame)),
+            Self::Or(children) => children.iter().any(|child| child.matches(frame)),
+            Self::Not(child) => !child.matches(frame),
+            Self::Tag(tag) => frame
+                .tags
+                .iter()
+                .any(|value| value.eq_ignore_ascii_case(tag)),
+            Self::Label(label) => frame
+                .labels
+                .iter()
+                .any(|value| value.eq_ignore_ascii_case(label)),
+            Self::Kind(kind) => eq(frame.kind.as_deref(), kind),
+            Self::Track(track) => eq(frame.track.as_deref(), track),
+            Self::Role(role) => frame.role == *role,
+            Self::ParentId(parent) => frame.parent_id == Some(*parent),
+            Self::MetadataEq { key, value } => frame.extra_metadata.get(key) == Some(value),
+            Self::MetadataRange { key, min, max } => {
+                let Some(value) = frame.extra_metadata.get(key) else {
+                    return false;
+                };
+                min.as_deref()
+                    .is_none_or(|min| compare_metadata_values(value, min) != Ordering::Less)
+                    && max
+                        .as_deref()
+                        .is_none_or(|max| compare_metadata_values(value, max) != Ordering::Greater)
+            }
+            Self::Timestamp { start, end } => {
+                start.is_none_or(|start| frame.timestamp >= start)
+                    && end.is_none_or(|end| frame.timestamp <= end)
+            }
+        }
+    }
+}
+
+fn compare_metadata_values(value: &str, bound: &str) -> Ordering {
+    match (value.trim().parse::<f64>(), bound.trim().parse::<f64>()) {
+        (Ok(value), Ok(bound)) => value.partial_cmp(&bound).unwrap_or(Ordering::Equal),
+        _ => value.cmp(bound),
+    }
 }
 
 /// A single ranked hit with snippet metadata.
@@ -86,6 +319,12 @@ pub struct SearchHit {
     pub score: Option<f32>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub metadata: Option<SearchHitMetadata>,
+    /// Per-retriever scores, populated by hybrid search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub score_breakdown: Option<HitScoreBreakdown>,
+    /// Matched term positions, populated by lexical search.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub highlights: Option<HitHighlights>,
 }
 
 /// Entity reference in search hit metadata.
@@ -166,7 +405,8 @@ pub struct SearchResponse {
     pub query: String,
     /// Milliseconds spent satisfying the request.
     pub elapsed_ms: u128,
-    /// Total hits found (without pagination applied).
+    /// Total hits found (without pagination applied). With a reranker, the candidates that
+    /// scored at least its `min_score`.
     pub total_hits: usize,
     /// Parameters used for this request, including cursors.
     pub params: SearchParams,
@@ -180,4 +420,7 @@ pub struct SearchResponse {
     #[serde(default)]
     /// Engine responsible for the results.
     pub engine: SearchEngineKind,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    /// Facet counts over every matched frame, present when requested.
+    pub facets: Vec<FacetCounts>,
 }
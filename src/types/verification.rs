@@ -22,6 +22,21 @@ pub struct DoctorOptions {
     pub quiet: bool,
 }
 
+/// Outcome of a `Memvid::vacuum` compaction pass.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VacuumReport {
+    /// File length before compaction.
+    pub bytes_before: u64,
+    /// File length after the compacted image was swapped in.
+    pub bytes_after: u64,
+    /// Bytes returned to the filesystem (`bytes_before - bytes_after`).
+    pub bytes_reclaimed: u64,
+    /// Active frames whose payloads were carried over.
+    pub active_frames: u64,
+    /// Deleted or superseded frames whose payload bytes were dropped.
+    pub released_frames: u64,
+}
+
 /// Version identifier embedded in `DoctorPlan` for compatibility checks.
 pub const DOCTOR_PLAN_VERSION: u32 = 1;
 
@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use blake3::hash;
 use serde::{Deserialize, Serialize};
 
@@ -9,7 +11,7 @@ fn vec_config() -> impl bincode::config::Config {
         .with_little_endian()
 }
 
-const VEC_DECODE_LIMIT: usize = crate::MAX_INDEX_BYTES as usize;
+pub(crate) const VEC_DECODE_LIMIT: usize = crate::MAX_INDEX_BYTES as usize;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VecDocument {
@@ -77,6 +79,7 @@ pub struct VecIndexArtifact {
 pub enum VecIndex {
     Uncompressed { documents: Vec<VecDocument> },
     Compressed(crate::vec_pq::QuantizedVecIndex),
+    Hnsw(crate::vec_hnsw::HnswIndex),
 }
 
 impl VecIndex {
@@ -86,6 +89,28 @@ impl VecIndex {
         Self::decode_with_compression(bytes, crate::VectorCompression::None)
     }
 
+    /// Decode the index whose (head) segment `bytes` is stored at `offset`.
+    ///
+    /// HNSW segment chains are followed back to their base through `fetch(offset, length)`;
+    /// every other format is self-contained.
+    ///
+    /// # Errors
+    /// Returns an error if a segment cannot be decoded or fetched.
+    pub fn decode_chain<F>(
+        bytes: &[u8],
+        offset: u64,
+        compression: crate::VectorCompression,
+        fetch: F,
+    ) -> Result<Self>
+    where
+        F: FnMut(u64, u64) -> Result<Vec<u8>>,
+    {
+        if bytes.starts_with(crate::vec_hnsw::HNSW_CHAIN_MAGIC) {
+            return crate::vec_hnsw::HnswIndex::decode_chain(bytes, offset, fetch).map(Self::Hnsw);
+        }
+        Self::decode_with_compression(bytes, compression)
+    }
+
     /// Decode vector index with compression mode from manifest
     ///
     /// ALWAYS tries uncompressed format first, regardless of compression flag.
@@ -96,6 +121,19 @@ impl VecIndex {
         bytes: &[u8],
         _compression: crate::VectorCompression,
     ) -> Result<Self> {
+        if bytes.starts_with(crate::vec_hnsw::HNSW_MAGIC) {
+            return crate::vec_hnsw::HnswIndex::decode(bytes).map(Self::Hnsw);
+        }
+        if bytes.starts_with(crate::vec_hnsw::HNSW_CHAIN_MAGIC) {
+            // Only a base segment stands on its own; deltas need `decode_chain`.
+            return crate::vec_hnsw::HnswIndex::decode_chain(bytes, 0, |_, _| {
+                Err(MemvidError::InvalidToc {
+                    reason: "HNSW delta segment decoded without its chain".into(),
+                })
+            })
+            .map(Self::Hnsw);
+        }
+
         // Try uncompressed format first, regardless of compression flag.
         // This is necessary because MIN_VECTORS_FOR_PQ threshold (100 vectors)
         // causes most segments to be stored as uncompressed even when Pq96 is requested.
@@ -155,25 +193,78 @@ impl VecIndex {
         }
         match self {
             VecIndex::Uncompressed { documents } => {
-                let mut hits: Vec<VecSearchHit> = documents
-                    .iter()
-                    .map(|doc| {
-                        let distance = l2_distance(query, &doc.embedding);
-                        VecSearchHit {
-                            frame_id: doc.frame_id,
-                            distance,
-                        }
-                    })
-                    .collect();
-                hits.sort_by(|a, b| {
-                    a.distance
-                        .partial_cmp(&b.distance)
-                        .unwrap_or(std::cmp::Ordering::Equal)
-                });
-                hits.truncate(limit);
-                hits
+                rank_documents(documents, query, limit, |_| true)
             }
             VecIndex::Compressed(quantized) => quantized.search(query, limit),
+            VecIndex::Hnsw(graph) => graph.search(query, limit),
+        }
+    }
+
+    /// Search only the vectors of frames in `allowed`, so filtered queries rank their
+    /// candidates instead of over-fetching from the whole index.
+    #[must_use]
+    pub fn search_within(
+        &self,
+        query: &[f32],
+        limit: usize,
+        allowed: &HashSet<FrameId>,
+    ) -> Vec<VecSearchHit> {
+        if query.is_empty() {
+            return Vec::new();
+        }
+        match self {
+            VecIndex::Uncompressed { documents } => {
+                rank_documents(documents, query, limit, |frame_id| {
+                    allowed.contains(&frame_id)
+                })
+            }
+            VecIndex::Compressed(quantized) => quantized.search_within(query, limit, allowed),
+            VecIndex::Hnsw(graph) => graph.search_within(query, limit, allowed),
+        }
+    }
+
+    /// Encode the whole index: an HNSW graph as one snapshot, a flat index as its documents
+    /// and a compressed index as its codebooks and codes.
+    ///
+    /// # Errors
+    /// Returns an error if the index cannot be serialized.
+    pub fn encode(&self) -> Result<VecIndexArtifact> {
+        match self {
+            VecIndex::Uncompressed { documents } => {
+                let mut builder = VecIndexBuilder::new();
+                for doc in documents {
+                    builder.add_document(doc.frame_id, doc.embedding.clone());
+                }
+                builder.finish()
+            }
+            VecIndex::Compressed(quantized) => {
+                let artifact = quantized.encode()?;
+                #[cfg(feature = "parallel_segments")]
+                let bytes_uncompressed = artifact.vector_count
+                    * u64::from(artifact.dimension)
+                    * std::mem::size_of::<f32>() as u64;
+                Ok(VecIndexArtifact {
+                    bytes: artifact.bytes,
+                    vector_count: artifact.vector_count,
+                    dimension: artifact.dimension,
+                    checksum: artifact.checksum,
+                    #[cfg(feature = "parallel_segments")]
+                    bytes_uncompressed,
+                })
+            }
+            VecIndex::Hnsw(graph) => graph.encode(),
+        }
+    }
+
+    /// Whether the index holds a vector for `frame_id`.
+    #[must_use]
+    pub fn contains(&self, frame_id: FrameId) -> bool {
+        match self {
+            VecIndex::Uncompressed { documents } => {
+                documents.iter().any(|doc| doc.frame_id == frame_id)
+            }
+            VecIndex::Compressed(quantized) => quantized.contains(frame_id),
+            VecIndex::Hnsw(graph) => graph.embedding_for(frame_id).is_some(),
         }
     }
 
@@ -188,6 +279,7 @@ impl VecIndex {
                 // Compressed vectors don't have direct f32 access
                 Box::new(std::iter::empty())
             }
+            VecIndex::Hnsw(graph) => Box::new(graph.entries()),
         }
     }
 
@@ -201,6 +293,29 @@ impl VecIndex {
                 // Compressed vectors don't have direct f32 access
                 None
             }
+            VecIndex::Hnsw(graph) => graph.embedding_for(frame_id),
+        }
+    }
+
+    /// Insert or replace the embedding of `frame_id`.
+    ///
+    /// Compressed indexes quantize the vector with their trained codebooks.
+    ///
+    /// # Errors
+    ///
+    /// Fails when a compressed or HNSW index rejects the embedding dimension.
+    pub fn insert(&mut self, frame_id: FrameId, embedding: Vec<f32>) -> Result<()> {
+        match self {
+            VecIndex::Uncompressed { documents } => {
+                documents.retain(|doc| doc.frame_id != frame_id);
+                documents.push(VecDocument {
+                    frame_id,
+                    embedding,
+                });
+                Ok(())
+            }
+            VecIndex::Compressed(quantized) => quantized.insert(frame_id, &embedding),
+            VecIndex::Hnsw(graph) => graph.insert(frame_id, embedding),
         }
     }
 
@@ -212,6 +327,7 @@ impl VecIndex {
             VecIndex::Compressed(_quantized) => {
                 // Compressed indices are immutable
             }
+            VecIndex::Hnsw(graph) => graph.remove(frame_id),
         }
     }
 }
@@ -222,7 +338,31 @@ pub struct VecSearchHit {
     pub distance: f32,
 }
 
-fn l2_distance(a: &[f32], b: &[f32]) -> f32 {
+/// Exact ranking of the flat-index documents that `keep` admits.
+fn rank_documents(
+    documents: &[VecDocument],
+    query: &[f32],
+    limit: usize,
+    keep: impl Fn(FrameId) -> bool,
+) -> Vec<VecSearchHit> {
+    let mut hits: Vec<VecSearchHit> = documents
+        .iter()
+        .filter(|doc| keep(doc.frame_id))
+        .map(|doc| VecSearchHit {
+            frame_id: doc.frame_id,
+            distance: l2_distance(query, &doc.embedding),
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        a.distance
+            .partial_cmp(&b.distance)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+    hits.truncate(limit);
+    hits
+}
+
+pub(crate) fn l2_distance(a: &[f32], b: &[f32]) -> f32 {
     a.iter()
         .zip(b.iter())
         .map(|(x, y)| (x - y).powi(2))
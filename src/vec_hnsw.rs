@@ -0,0 +1,1044 @@
+//! Hierarchical Navigable Small World (HNSW) graph for approximate vector search
+//!
+//! Vectors are linked into a layered proximity graph so a query visits roughly
+//! `ef_search * M` vectors instead of scanning the whole index.
+//!
+//! **Incremental updates**:
+//! 1. Nodes are append-only: an insert links one new node into the existing layers
+//! 2. Deletes only set a tombstone; tombstoned nodes still route queries but are never returned
+//! 3. Once tombstones outnumber live nodes the graph is compacted (rebuilt from live nodes)
+//!
+//! **Segment chains**: a graph is persisted as a base segment followed by delta segments.
+//! Each delta holds the nodes added since the previous segment plus the persisted nodes
+//! whose links or tombstone changed, and points back at its predecessor by relative offset
+//! and checksum. Loading walks the chain back to the base and replays the deltas; a new
+//! base is only written after compaction or once the chain reaches [`MAX_HNSW_CHAIN_DEPTH`].
+//!
+//! Node levels are derived from the frame id, so building the same inputs always yields
+//! the same graph.
+
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, BinaryHeap, HashMap, HashSet};
+
+use blake3::hash;
+use serde::{Deserialize, Serialize};
+
+use crate::vec::{VEC_DECODE_LIMIT, VecIndexArtifact, VecSearchHit, l2_distance};
+use crate::{MemvidError, Result, types::FrameId};
+
+fn vec_config() -> impl bincode::config::Config {
+    bincode::config::standard()
+        .with_fixed_int_encoding()
+        .with_little_endian()
+}
+
+/// Leading bytes of an encoded HNSW vector index.
+pub const HNSW_MAGIC: &[u8; 8] = b"MV2HNSW1";
+
+/// Leading bytes of an HNSW chain segment (a base or a delta over its predecessor).
+pub const HNSW_CHAIN_MAGIC: &[u8; 8] = b"MV2HNSWC";
+
+/// Delta segments kept behind a base before the chain is folded into a new base.
+pub const MAX_HNSW_CHAIN_DEPTH: u32 = 32;
+
+/// Default vector count at which a flat index is promoted to an HNSW graph on commit.
+///
+/// Below this size a brute-force scan is exact and cheaper than graph traversal.
+pub const MIN_VECTORS_FOR_HNSW: u32 = 1024;
+
+/// Construction and search parameters of an HNSW graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HnswConfig {
+    /// Maximum neighbours per node on upper layers (layer 0 keeps `2 * m`).
+    pub m: u32,
+    /// Candidate list size used while linking a new node.
+    pub ef_construction: u32,
+    /// Candidate list size used at query time (raised to `limit` when smaller).
+    pub ef_search: u32,
+    /// Vector count at which a flat index is promoted to a graph.
+    pub min_vectors: u32,
+}
+
+impl Default for HnswConfig {
+    fn default() -> Self {
+        Self {
+            m: 16,
+            ef_construction: 200,
+            ef_search: 64,
+            min_vectors: MIN_VECTORS_FOR_HNSW,
+        }
+    }
+}
+
+impl HnswConfig {
+    fn max_neighbors(&self, layer: usize) -> usize {
+        let m = self.m.max(2) as usize;
+        if layer == 0 { m * 2 } else { m }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HnswNode {
+    frame_id: FrameId,
+    embedding: Vec<f32>,
+    /// Neighbour node indices per layer; `neighbors.len() - 1` is the node level.
+    neighbors: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HnswSnapshot {
+    config: HnswConfig,
+    dimension: u32,
+    entry_point: Option<u32>,
+    nodes: Vec<HnswNode>,
+}
+
+/// Location of the previous segment of a chain, as seen from the segment referencing it.
+///
+/// The offset is stored relative to the referencing segment so the chain survives the data
+/// region being shifted as a whole (e.g. when the WAL grows).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HnswSegmentLink {
+    /// Bytes between the start of the previous segment and the referencing segment.
+    pub back: u64,
+    pub length: u64,
+    pub checksum: [u8; 32],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HnswNodeUpdate {
+    node: u32,
+    neighbors: Vec<Vec<u32>>,
+    deleted: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct HnswChainSegment {
+    previous: Option<HnswSegmentLink>,
+    /// Number of segments before this one; 0 for a base.
+    depth: u32,
+    config: HnswConfig,
+    dimension: u32,
+    entry_point: Option<u32>,
+    /// Node id of `added[0]`, i.e. the node count of the chain up to the previous segment.
+    first_added: u32,
+    updated: Vec<HnswNodeUpdate>,
+    added: Vec<HnswNode>,
+}
+
+#[derive(Debug, Clone)]
+pub struct HnswIndex {
+    config: HnswConfig,
+    dimension: u32,
+    entry_point: Option<u32>,
+    nodes: Vec<HnswNode>,
+    /// Live node per frame id.
+    by_frame: HashMap<FrameId, u32>,
+    /// Depth of the chain segment last written or loaded, `None` when the graph is not
+    /// backed by a segment chain.
+    chain_depth: Option<u32>,
+    /// Nodes `..persisted` are stored in the chain.
+    persisted: usize,
+    /// Persisted nodes whose links or tombstone changed since the last segment.
+    touched: BTreeSet<u32>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Candidate {
+    distance: f32,
+    node: u32,
+}
+
+impl Eq for Candidate {}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.distance
+            .total_cmp(&other.distance)
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl HnswIndex {
+    #[must_use]
+    pub fn new(config: HnswConfig) -> Self {
+        Self {
+            config,
+            dimension: 0,
+            entry_point: None,
+            nodes: Vec::new(),
+            by_frame: HashMap::new(),
+            chain_depth: None,
+            persisted: 0,
+            touched: BTreeSet::new(),
+        }
+    }
+
+    /// Build a graph from scratch by inserting every document in order.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the documents do not all share one embedding dimension.
+    pub fn build<I>(config: HnswConfig, documents: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (FrameId, Vec<f32>)>,
+    {
+        let mut index = Self::new(config);
+        for (frame_id, embedding) in documents {
+            index.insert(frame_id, embedding)?;
+        }
+        Ok(index)
+    }
+
+    #[must_use]
+    pub fn config(&self) -> HnswConfig {
+        self.config
+    }
+
+    /// Update the query-time candidate list size.
+    pub fn set_ef_search(&mut self, ef_search: u32) {
+        self.config.ef_search = ef_search;
+    }
+
+    #[must_use]
+    pub fn dimension(&self) -> u32 {
+        self.dimension
+    }
+
+    /// Number of live (non-tombstoned) vectors.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.by_frame.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.by_frame.is_empty()
+    }
+
+    /// Number of tombstoned nodes still kept for routing.
+    #[must_use]
+    pub fn tombstone_count(&self) -> usize {
+        self.nodes.len() - self.by_frame.len()
+    }
+
+    /// Whether tombstones dominate the graph and it should be compacted.
+    #[must_use]
+    pub fn needs_compaction(&self) -> bool {
+        self.tombstone_count() > self.len()
+    }
+
+    /// Rebuild the graph from its live nodes with `config`, dropping every tombstone.
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`HnswIndex::build`].
+    pub fn compacted(&self, config: HnswConfig) -> Result<Self> {
+        Self::build(
+            config,
+            self.entries()
+                .map(|(frame_id, embedding)| (frame_id, embedding.to_vec())),
+        )
+    }
+
+    /// Insert a vector, replacing (tombstoning) any live vector for the same frame.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::VecDimensionMismatch`] when `embedding` does not match the
+    /// dimension of the vectors already in the graph.
+    pub fn insert(&mut self, frame_id: FrameId, embedding: Vec<f32>) -> Result<()> {
+        if self.dimension == 0 {
+            self.dimension =
+                u32::try_from(embedding.len()).map_err(|_| MemvidError::EmbeddingFailed {
+                    reason: "embedding dimension exceeds the vector index limit".into(),
+                })?;
+        } else if embedding.len() != self.dimension as usize {
+            return Err(MemvidError::VecDimensionMismatch {
+                expected: self.dimension,
+                actual: embedding.len(),
+            });
+        }
+        self.remove(frame_id);
+
+        let node_id =
+            u32::try_from(self.nodes.len()).map_err(|_| MemvidError::EmbeddingFailed {
+                reason: "HNSW graph holds the maximum number of nodes".into(),
+            })?;
+        let level = node_level(frame_id, self.config.m);
+        self.nodes.push(HnswNode {
+            frame_id,
+            embedding,
+            neighbors: vec![Vec::new(); level + 1],
+            deleted: false,
+        });
+        self.by_frame.insert(frame_id, node_id);
+
+        let Some(entry_point) = self.entry_point else {
+            self.entry_point = Some(node_id);
+            return Ok(());
+        };
+        let top_level = self.level(entry_point);
+        let query = self.nodes[node_id as usize].embedding.clone();
+
+        let mut nearest = entry_point;
+        for layer in (level + 1..=top_level).rev() {
+            nearest = self.greedy_closest(&query, nearest, layer);
+        }
+
+        let ef_construction = self.config.ef_construction.max(1) as usize;
+        for layer in (0..=level.min(top_level)).rev() {
+            let candidates = self.search_layer(&query, &[nearest], ef_construction, layer);
+            let max_neighbors = self.config.max_neighbors(layer);
+            let selected = self.select_neighbors(&candidates, max_neighbors);
+            for &neighbor in &selected {
+                self.link(neighbor, node_id, layer);
+            }
+            self.nodes[node_id as usize].neighbors[layer] = selected;
+            if let Some(closest) = candidates.first() {
+                nearest = closest.node;
+            }
+        }
+
+        if level > top_level {
+            self.entry_point = Some(node_id);
+        }
+        Ok(())
+    }
+
+    /// Tombstone the live vector of `frame_id`, if any.
+    pub fn remove(&mut self, frame_id: FrameId) {
+        if let Some(node_id) = self.by_frame.remove(&frame_id) {
+            self.nodes[node_id as usize].deleted = true;
+            self.touch(node_id);
+        }
+    }
+
+    #[must_use]
+    pub fn search(&self, query: &[f32], limit: usize) -> Vec<VecSearchHit> {
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        // Widen the beam by the tombstone ratio so deleted nodes don't starve the result set.
+        let ef = (self.config.ef_search as usize)
+            .max(limit)
+            .saturating_mul(self.nodes.len().max(1))
+            / self.len().max(1);
+        self.live_candidates(query, ef).take(limit).collect()
+    }
+
+    /// Nearest live vectors among the frames in `allowed`.
+    ///
+    /// When no more of `allowed` is indexed than one beam would visit, those vectors are
+    /// ranked directly. Otherwise the beam is widened by the share of live vectors outside
+    /// `allowed`, so the cost grows with the filter's selectivity instead of the index size.
+    #[must_use]
+    pub fn search_within(
+        &self,
+        query: &[f32],
+        limit: usize,
+        allowed: &HashSet<FrameId>,
+    ) -> Vec<VecSearchHit> {
+        if query.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+        let beam = (self.config.ef_search as usize).max(limit);
+        let matching: Vec<(FrameId, u32)> = allowed
+            .iter()
+            .filter_map(|frame_id| self.by_frame.get(frame_id).map(|&node| (*frame_id, node)))
+            .collect();
+        if matching.len() <= beam {
+            let mut hits: Vec<VecSearchHit> = matching
+                .into_iter()
+                .map(|(frame_id, node)| VecSearchHit {
+                    frame_id,
+                    distance: self.distance(query, node),
+                })
+                .collect();
+            hits.sort_by(|a, b| {
+                a.distance
+                    .total_cmp(&b.distance)
+                    .then(a.frame_id.cmp(&b.frame_id))
+            });
+            hits.truncate(limit);
+            return hits;
+        }
+        let ef = beam.saturating_mul(self.nodes.len()) / matching.len();
+        self.live_candidates(query, ef)
+            .filter(|hit| allowed.contains(&hit.frame_id))
+            .take(limit)
+            .collect()
+    }
+
+    /// Descend to layer 0 and return the live vectors among its `ef` nearest candidates,
+    /// nearest first.
+    fn live_candidates(&self, query: &[f32], ef: usize) -> impl Iterator<Item = VecSearchHit> + '_ {
+        let candidates = self.entry_point.map_or_else(Vec::new, |entry_point| {
+            let mut nearest = entry_point;
+            for layer in (1..=self.level(entry_point)).rev() {
+                nearest = self.greedy_closest(query, nearest, layer);
+            }
+            self.search_layer(query, &[nearest], ef, 0)
+        });
+        candidates
+            .into_iter()
+            .filter(|candidate| !self.nodes[candidate.node as usize].deleted)
+            .map(|candidate| VecSearchHit {
+                frame_id: self.nodes[candidate.node as usize].frame_id,
+                distance: candidate.distance,
+            })
+    }
+
+    /// Live vectors in insertion order.
+    pub fn entries(&self) -> impl Iterator<Item = (FrameId, &[f32])> + '_ {
+        self.nodes
+            .iter()
+            .filter(|node| !node.deleted)
+            .map(|node| (node.frame_id, node.embedding.as_slice()))
+    }
+
+    #[must_use]
+    pub fn embedding_for(&self, frame_id: FrameId) -> Option<&[f32]> {
+        self.by_frame
+            .get(&frame_id)
+            .map(|&node_id| self.nodes[node_id as usize].embedding.as_slice())
+    }
+
+    /// Encode the whole graph as a single self-contained artifact.
+    ///
+    /// # Errors
+    ///
+    /// Fails when the snapshot cannot be serialized.
+    pub fn encode(&self) -> Result<VecIndexArtifact> {
+        let snapshot = HnswSnapshot {
+            config: self.config,
+            dimension: self.dimension,
+            entry_point: self.entry_point,
+            nodes: self.nodes.clone(),
+        };
+        let mut bytes = HNSW_MAGIC.to_vec();
+        bytes.extend(bincode::serde::encode_to_vec(&snapshot, vec_config())?);
+        Ok(self.artifact(bytes))
+    }
+
+    /// Decode an artifact written by [`HnswIndex::encode`].
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`MemvidError::InvalidToc`] when the bytes are not a valid HNSW index.
+    pub fn decode(bytes: &[u8]) -> Result<Self> {
+        let body = bytes
+            .strip_prefix(HNSW_MAGIC.as_slice())
+            .ok_or(MemvidError::InvalidToc {
+                reason: "missing HNSW vector index magic".into(),
+            })?;
+        let (snapshot, read): (HnswSnapshot, usize) = bincode::serde::decode_from_slice(
+            body,
+            bincode::config::standard()
+                .with_fixed_int_encoding()
+                .with_little_endian()
+                .with_limit::<VEC_DECODE_LIMIT>(),
+        )?;
+        if read != body.len() {
+            return Err(MemvidError::InvalidToc {
+                reason: "trailing bytes after HNSW vector index".into(),
+            });
+        }
+
+        Self::from_snapshot(snapshot)
+    }
+
+    /// Whether the graph is backed by a segment chain that further segments can extend.
+    #[must_use]
+    pub fn is_chained(&self) -> bool {
+        self.chain_depth.is_some()
+    }
+
+    /// Forget the backing segment chain, so the next segment written is a full base.
+    ///
+    /// Used when the graph is persisted into a file that does not hold its chain.
+    pub fn detach_chain(&mut self) {
+        self.chain_depth = None;
+        self.persisted = 0;
+        self.touched.clear();
+    }
+
+    /// Encode the changes since the last persisted segment as the segment following
+    /// `previous`, returning it with its chain depth.
+    ///
+    /// A delta is written when the graph is chained and `previous` locates the chain head;
+    /// otherwise, or once the chain reaches [`MAX_HNSW_CHAIN_DEPTH`], every node goes into
+    /// a new base. Returns `None` when the chain already holds the whole graph.
+    ///
+    /// # Errors
+    /// Returns an error if the segment cannot be serialized.
+    pub fn encode_segment(
+        &self,
+        previous: Option<HnswSegmentLink>,
+    ) -> Result<Option<(VecIndexArtifact, u32)>> {
+        let pending = self.persisted < self.nodes.len() || !self.touched.is_empty();
+        if self.is_chained() && !pending {
+            return Ok(None);
+        }
+        let first_added = u32::try_from(self.persisted).map_err(|_| MemvidError::InvalidToc {
+            reason: "HNSW graph exceeds the node id range".into(),
+        })?;
+        let delta = previous.zip(
+            self.chain_depth
+                .filter(|&depth| depth < MAX_HNSW_CHAIN_DEPTH),
+        );
+        let segment = match delta {
+            Some((link, depth)) => HnswChainSegment {
+                previous: Some(link),
+                depth: depth + 1,
+                config: self.config,
+                dimension: self.dimension,
+                entry_point: self.entry_point,
+                first_added,
+                updated: self
+                    .touched
+                    .iter()
+                    .map(|&node| HnswNodeUpdate {
+                        node,
+                        neighbors: self.nodes[node as usize].neighbors.clone(),
+                        deleted: self.nodes[node as usize].deleted,
+                    })
+                    .collect(),
+                added: self.nodes[self.persisted..].to_vec(),
+            },
+            None => HnswChainSegment {
+                previous: None,
+                depth: 0,
+                config: self.config,
+                dimension: self.dimension,
+                entry_point: self.entry_point,
+                first_added: 0,
+                updated: Vec::new(),
+                added: self.nodes.clone(),
+            },
+        };
+        let depth = segment.depth;
+        let mut bytes = HNSW_CHAIN_MAGIC.to_vec();
+        bytes.extend(bincode::serde::encode_to_vec(&segment, vec_config())?);
+        Ok(Some((self.artifact(bytes), depth)))
+    }
+
+    /// Record that the segment returned by [`HnswIndex::encode_segment`] was written.
+    pub fn mark_persisted(&mut self, depth: u32) {
+        self.chain_depth = Some(depth);
+        self.persisted = self.nodes.len();
+        self.touched.clear();
+    }
+
+    /// Decode the chain ending in the segment `head` stored at `head_offset`.
+    ///
+    /// `fetch(offset, length)` reads earlier segments; each one is checked against the
+    /// checksum recorded by its successor before its delta is replayed.
+    ///
+    /// # Errors
+    /// Returns `InvalidToc` if a segment is malformed, fails its checksum or does not
+    /// continue its predecessor, and any error returned by `fetch`.
+    pub fn decode_chain<F>(head: &[u8], head_offset: u64, mut fetch: F) -> Result<Self>
+    where
+        F: FnMut(u64, u64) -> Result<Vec<u8>>,
+    {
+        let mut segments = vec![decode_chain_segment(head)?];
+        let mut offset = head_offset;
+        while let Some(link) = segments.last().and_then(|segment| segment.previous) {
+            if segments.len() > MAX_HNSW_CHAIN_DEPTH as usize {
+                return Err(MemvidError::InvalidToc {
+                    reason: "HNSW segment chain exceeds its maximum depth".into(),
+                });
+            }
+            offset = offset
+                .checked_sub(link.back)
+                .filter(|_| link.back != 0)
+                .ok_or(MemvidError::InvalidToc {
+                    reason: "HNSW segment link points outside the data region".into(),
+                })?;
+            let bytes = fetch(offset, link.length)?;
+            if *hash(&bytes).as_bytes() != link.checksum {
+                return Err(MemvidError::InvalidToc {
+                    reason: "HNSW segment checksum mismatch".into(),
+                });
+            }
+            segments.push(decode_chain_segment(&bytes)?);
+        }
+
+        let depth = segments[0].depth;
+        if depth as usize + 1 != segments.len() {
+            return Err(MemvidError::InvalidToc {
+                reason: "HNSW segment chain depth mismatch".into(),
+            });
+        }
+        let mut snapshot = HnswSnapshot {
+            config: HnswConfig::default(),
+            dimension: 0,
+            entry_point: None,
+            nodes: Vec::new(),
+        };
+        for segment in segments.into_iter().rev() {
+            if segment.first_added as usize != snapshot.nodes.len() {
+                return Err(MemvidError::InvalidToc {
+                    reason: "HNSW segment does not continue its predecessor".into(),
+                });
+            }
+            for update in segment.updated {
+                let node = (update.node < segment.first_added)
+                    .then(|| snapshot.nodes.get_mut(update.node as usize))
+                    .flatten()
+                    .ok_or(MemvidError::InvalidToc {
+                        reason: "HNSW segment updates a missing node".into(),
+                    })?;
+                node.neighbors = update.neighbors;
+                node.deleted = update.deleted;
+            }
+            snapshot.nodes.extend(segment.added);
+            snapshot.config = segment.config;
+            snapshot.dimension = segment.dimension;
+            snapshot.entry_point = segment.entry_point;
+        }
+
+        let mut index = Self::from_snapshot(snapshot)?;
+        index.mark_persisted(depth);
+        Ok(index)
+    }
+
+    fn from_snapshot(snapshot: HnswSnapshot) -> Result<Self> {
+        let node_count = snapshot.nodes.len();
+        if u32::try_from(node_count).is_err() {
+            return Err(MemvidError::InvalidToc {
+                reason: "HNSW vector index has more nodes than it can address".into(),
+            });
+        }
+        let links_valid = snapshot
+            .nodes
+            .iter()
+            .flat_map(|node| node.neighbors.iter().flatten())
+            .all(|&neighbor| (neighbor as usize) < node_count);
+        let entry_valid = snapshot
+            .entry_point
+            .map_or(node_count == 0, |entry| (entry as usize) < node_count);
+        if !links_valid || !entry_valid {
+            return Err(MemvidError::InvalidToc {
+                reason: "HNSW vector index references missing nodes".into(),
+            });
+        }
+
+        let by_frame = (0..=u32::MAX)
+            .zip(&snapshot.nodes)
+            .filter(|(_, node)| !node.deleted)
+            .map(|(node_id, node)| (node.frame_id, node_id))
+            .collect();
+        Ok(Self {
+            config: snapshot.config,
+            dimension: snapshot.dimension,
+            entry_point: snapshot.entry_point,
+            nodes: snapshot.nodes,
+            by_frame,
+            chain_depth: None,
+            persisted: 0,
+            touched: BTreeSet::new(),
+        })
+    }
+
+    fn artifact(&self, bytes: Vec<u8>) -> VecIndexArtifact {
+        let checksum = *hash(&bytes).as_bytes();
+        #[cfg(feature = "parallel_segments")]
+        let bytes_uncompressed =
+            (self.len() * self.dimension as usize * std::mem::size_of::<f32>()) as u64;
+        VecIndexArtifact {
+            bytes,
+            vector_count: self.len() as u64,
+            dimension: self.dimension,
+            checksum,
+            #[cfg(feature = "parallel_segments")]
+            bytes_uncompressed,
+        }
+    }
+
+    /// Note a change to an already persisted node.
+    fn touch(&mut self, node_id: u32) {
+        if (node_id as usize) < self.persisted {
+            self.touched.insert(node_id);
+        }
+    }
+
+    fn level(&self, node_id: u32) -> usize {
+        self.nodes[node_id as usize].neighbors.len() - 1
+    }
+
+    fn distance(&self, query: &[f32], node_id: u32) -> f32 {
+        l2_distance(query, &self.nodes[node_id as usize].embedding)
+    }
+
+    /// Walk `layer` greedily towards `query`, starting at `start`.
+    fn greedy_closest(&self, query: &[f32], start: u32, layer: usize) -> u32 {
+        let mut best = start;
+        let mut best_distance = self.distance(query, start);
+        loop {
+            let mut improved = false;
+            for &neighbor in self.nodes[best as usize]
+                .neighbors
+                .get(layer)
+                .into_iter()
+                .flatten()
+            {
+                let distance = self.distance(query, neighbor);
+                if distance < best_distance {
+                    best = neighbor;
+                    best_distance = distance;
+                    improved = true;
+                }
+            }
+            if !improved {
+                return best;
+            }
+        }
+    }
+
+    /// Beam search over one layer; returns up to `ef` candidates sorted by distance.
+    fn search_layer(
+        &self,
+        query: &[f32],
+        entry: &[u32],
+        ef: usize,
+        layer: usize,
+    ) -> Vec<Candidate> {
+        let ef = ef.max(1);
+        let mut visited: HashSet<u32> = entry.iter().copied().collect();
+        // Min-heap of nodes to expand and max-heap of the current best results.
+        let mut frontier: BinaryHeap<std::cmp::Reverse<Candidate>> = BinaryHeap::new();
+        let mut results: BinaryHeap<Candidate> = BinaryHeap::new();
+        for &node in entry {
+            let candidate = Candidate {
+                distance: self.distance(query, node),
+                node,
+            };
+            frontier.push(std::cmp::Reverse(candidate));
+            results.push(candidate);
+        }
+
+        while let Some(std::cmp::Reverse(current)) = frontier.pop() {
+            if results
+                .peek()
+                .is_some_and(|worst| results.len() >= ef && current.distance > worst.distance)
+            {
+                break;
+            }
+            for &neighbor in self.nodes[current.node as usize]
+                .neighbors
+                .get(layer)
+                .into_iter()
+                .flatten()
+            {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let candidate = Candidate {
+                    distance: self.distance(query, neighbor),
+                    node: neighbor,
+                };
+                let admit = results.len() < ef
+                    || results
+                        .peek()
+                        .is_some_and(|worst| candidate.distance < worst.distance);
+                if admit {
+                    frontier.push(std::cmp::Reverse(candidate));
+                    results.push(candidate);
+                    if results.len() > ef {
+                        results.pop();
+                    }
+                }
+            }
+        }
+
+        results.into_sorted_vec()
+    }
+
+    /// Add a back-link `from -> to`, pruning `from` to its closest neighbours when full.
+    fn link(&mut self, from: u32, to: u32, layer: usize) {
+        let max_neighbors = self.config.max_neighbors(layer);
+        let Some(neighbors) = self.nodes[from as usize].neighbors.get(layer) else {
+            return;
+        };
+        let mut neighbors = neighbors.clone();
+        neighbors.push(to);
+        if neighbors.len() > max_neighbors {
+            let origin = self.nodes[from as usize].embedding.clone();
+            let mut ranked: Vec<Candidate> = neighbors
+                .into_iter()
+                .map(|node| Candidate {
+                    distance: self.distance(&origin, node),
+                    node,
+                })
+                .collect();
+            ranked.sort();
+            neighbors = self.select_neighbors(&ranked, max_neighbors);
+        }
+        self.nodes[from as usize].neighbors[layer] = neighbors;
+        self.touch(from);
+    }
+
+    /// Neighbour selection heuristic over candidates sorted by distance.
+    ///
+    /// A candidate is preferred when it is closer to the base node than to every neighbour
+    /// already selected, which keeps links spread across clusters; the remaining slots are
+    /// filled with the closest skipped candidates.
+    fn select_neighbors(&self, candidates: &[Candidate], max_neighbors: usize) -> Vec<u32> {
+        let mut selected: Vec<Candidate> = Vec::with_capacity(max_neighbors);
+        let mut skipped: Vec<Candidate> = Vec::new();
+        for &candidate in candidates {
+            if selected.len() >= max_neighbors {
+                break;
+            }
+            let embedding = &self.nodes[candidate.node as usize].embedding;
+            let diverse = selected
+                .iter()
+                .all(|chosen| self.distance(embedding, chosen.node) > candidate.distance);
+            if diverse {
+                selected.push(candidate);
+            } else {
+                skipped.push(candidate);
+            }
+        }
+        let open = max_neighbors.saturating_sub(selected.len());
+        selected.extend(skipped.into_iter().take(open));
+        selected
+            .into_iter()
+            .map(|candidate| candidate.node)
+            .collect()
+    }
+}
+
+fn decode_chain_segment(bytes: &[u8]) -> Result<HnswChainSegment> {
+    let body = bytes
+        .strip_prefix(HNSW_CHAIN_MAGIC.as_slice())
+        .ok_or(MemvidError::InvalidToc {
+            reason: "missing HNSW segment magic".into(),
+        })?;
+    let (segment, read): (HnswChainSegment, usize) = bincode::serde::decode_from_slice(
+        body,
+        bincode::config::standard()
+            .with_fixed_int_encoding()
+            .with_little_endian()
+            .with_limit::<VEC_DECODE_LIMIT>(),
+    )?;
+    if read != body.len() {
+        return Err(MemvidError::InvalidToc {
+            reason: "trailing bytes after HNSW segment".into(),
+        });
+    }
+    Ok(segment)
+}
+
+/// Deterministic level assignment: `floor(-ln(u) / ln(M))` with `u` hashed from the frame id.
+// `u` keeps 53 bits, which `f64` holds exactly, and the level is clamped to a small positive value.
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn node_level(frame_id: FrameId, m: u32) -> usize {
+    let z = splitmix64(frame_id);
+    let uniform = ((z >> 11) as f64 + 1.0) / (1u64 << 53) as f64;
+    let scale = 1.0 / f64::from(m.max(2)).ln();
+    ((-uniform.ln() * scale) as usize).min(16)
+}
+
+/// `SplitMix64` finaliser; spreads sequential inputs uniformly over `u64`.
+fn splitmix64(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn point(seed: u64, dimension: usize) -> Vec<f32> {
+        (0..dimension)
+            .map(|axis| {
+                let mixed = splitmix64(seed.wrapping_mul(64).wrapping_add(axis as u64));
+                (mixed % 1000) as f32 / 1000.0
+            })
+            .collect()
+    }
+
+    fn brute_force(points: &[(FrameId, Vec<f32>)], query: &[f32], limit: usize) -> Vec<FrameId> {
+        let mut ranked: Vec<(f32, FrameId)> = points
+            .iter()
+            .map(|(frame_id, embedding)| (l2_distance(query, embedding), *frame_id))
+            .collect();
+        ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+        ranked.into_iter().take(limit).map(|(_, id)| id).collect()
+    }
+
+    #[test]
+    fn search_recalls_nearest_neighbours() {
+        let points: Vec<(FrameId, Vec<f32>)> = (0..600).map(|id| (id, point(id, 8))).collect();
+        let index = HnswIndex::build(HnswConfig::default(), points.clone()).expect("build");
+        assert_eq!(index.len(), 600);
+
+        let mut found = 0;
+        for seed in 1000..1020 {
+            let query = point(seed, 8);
+            let expected = brute_force(&points, &query, 10);
+            let hits = index.search(&query, 10);
+            found += hits
+                .iter()
+                .filter(|hit| expected.contains(&hit.frame_id))
+                .count();
+        }
+        assert!(found >= 190, "recall too low: {found}/200");
+    }
+
+    #[test]
+    fn search_within_ranks_only_allowed_frames() {
+        let points: Vec<(FrameId, Vec<f32>)> = (0..600).map(|id| (id, point(id, 8))).collect();
+        let index = HnswIndex::build(HnswConfig::default(), points.clone()).expect("build");
+        let query = point(2000, 8);
+
+        let few: HashSet<FrameId> = (0..600).step_by(97).collect();
+        let allowed: Vec<(FrameId, Vec<f32>)> = points
+            .iter()
+            .filter(|(frame_id, _)| few.contains(frame_id))
+            .cloned()
+            .collect();
+        let hits: Vec<FrameId> = index
+            .search_within(&query, 3, &few)
+            .iter()
+            .map(|hit| hit.frame_id)
+            .collect();
+        assert_eq!(hits, brute_force(&allowed, &query, 3));
+
+        let half: HashSet<FrameId> = (0..600).filter(|id| id % 2 == 0).collect();
+        let hits = index.search_within(&query, 10, &half);
+        assert_eq!(hits.len(), 10);
+        assert!(hits.iter().all(|hit| half.contains(&hit.frame_id)));
+    }
+
+    #[test]
+    fn tombstones_hide_vectors_and_trigger_compaction() {
+        let points: Vec<(FrameId, Vec<f32>)> = (0..100).map(|id| (id, point(id, 4))).collect();
+        let mut index = HnswIndex::build(HnswConfig::default(), points.clone()).expect("build");
+
+        let target = points[42].1.clone();
+        assert_eq!(index.search(&target, 1)[0].frame_id, 42);
+        index.remove(42);
+        assert!(
+            index
+                .search(&target, 5)
+                .iter()
+                .all(|hit| hit.frame_id != 42)
+        );
+        assert!(index.embedding_for(42).is_none());
+        assert!(!index.needs_compaction());
+
+        for id in 0..60 {
+            index.remove(id);
+        }
+        assert!(index.needs_compaction());
+        let compacted = index.compacted(HnswConfig::default()).expect("compact");
+        assert_eq!(compacted.len(), 40);
+        assert_eq!(compacted.tombstone_count(), 0);
+    }
+
+    #[test]
+    fn encode_roundtrip_supports_further_inserts() {
+        let points: Vec<(FrameId, Vec<f32>)> = (0..50).map(|id| (id, point(id, 4))).collect();
+        let mut index = HnswIndex::build(HnswConfig::default(), points).expect("build");
+        index.remove(7);
+
+        let artifact = index.encode().expect("encode");
+        assert_eq!(artifact.vector_count, 49);
+        assert_eq!(artifact.dimension, 4);
+
+        let mut decoded = HnswIndex::decode(&artifact.bytes).expect("decode");
+        assert_eq!(decoded.len(), 49);
+        assert_eq!(decoded.tombstone_count(), 1);
+        decoded
+            .insert(500, vec![9.0, 9.0, 9.0, 9.0])
+            .expect("insert");
+        assert_eq!(decoded.search(&[9.0, 9.0, 9.0, 9.0], 1)[0].frame_id, 500);
+
+        let err = decoded.insert(501, vec![1.0]).unwrap_err();
+        assert!(matches!(err, MemvidError::VecDimensionMismatch { .. }));
+    }
+
+    #[test]
+    fn segment_chain_persists_only_changes() {
+        fn write(
+            index: &mut HnswIndex,
+            file: &mut Vec<u8>,
+            head: &mut Option<(u64, VecIndexArtifact)>,
+        ) -> Option<(usize, u32)> {
+            let offset = file.len() as u64;
+            let link = head
+                .as_ref()
+                .map(|(head_offset, artifact)| HnswSegmentLink {
+                    back: offset - head_offset,
+                    length: artifact.bytes.len() as u64,
+                    checksum: artifact.checksum,
+                });
+            let Some((artifact, depth)) = index.encode_segment(link).expect("encode") else {
+                return None;
+            };
+            file.extend_from_slice(&artifact.bytes);
+            index.mark_persisted(depth);
+            let len = artifact.bytes.len();
+            *head = Some((offset, artifact));
+            Some((len, depth))
+        }
+
+        let points: Vec<(FrameId, Vec<f32>)> = (0..200).map(|id| (id, point(id, 8))).collect();
+        let mut index = HnswIndex::build(HnswConfig::default(), points).expect("build");
+
+        // Segments are laid out back to back, as they are in the payload region.
+        let mut file = vec![0u8; 16];
+        let mut head: Option<(u64, VecIndexArtifact)> = None;
+
+        let (base_len, base_depth) = write(&mut index, &mut file, &mut head).expect("base");
+        assert_eq!(base_depth, 0);
+        assert!(write(&mut index, &mut file, &mut head).is_none());
+
+        index.remove(3);
+        index.insert(1000, vec![5.0; 8]).expect("insert");
+        let (delta_len, delta_depth) = write(&mut index, &mut file, &mut head).expect("delta");
+        assert_eq!(delta_depth, 1);
+        assert!(
+            delta_len * 4 < base_len,
+            "delta {delta_len} vs base {base_len}"
+        );
+
+        let (head_offset, artifact) = head.clone().expect("head");
+        let fetch = |offset: u64, length: u64| {
+            Ok(file[offset as usize..(offset + length) as usize].to_vec())
+        };
+        let decoded = HnswIndex::decode_chain(&artifact.bytes, head_offset, fetch).expect("chain");
+        assert_eq!(decoded.len(), index.len());
+        assert_eq!(decoded.tombstone_count(), 1);
+        assert!(decoded.embedding_for(3).is_none());
+        assert_eq!(decoded.search(&[5.0; 8], 1)[0].frame_id, 1000);
+        let query = point(77, 8);
+        assert_eq!(decoded.search(&query, 10), index.search(&query, 10));
+
+        // A delta cannot be decoded without the segments it builds on.
+        let mut corrupt = file.clone();
+        corrupt[20] ^= 0xff;
+        let err = HnswIndex::decode_chain(&artifact.bytes, head_offset, |offset, length| {
+            Ok(corrupt[offset as usize..(offset + length) as usize].to_vec())
+        })
+        .unwrap_err();
+        assert!(matches!(err, MemvidError::InvalidToc { .. }));
+    }
+}
@@ -9,6 +9,8 @@
 //! 3. Each vector is encoded as 96 bytes (one u8 index per subspace)
 //! 4. Search uses ADC (Asymmetric Distance Computation) with lookup tables
 
+use std::collections::HashSet;
+
 use blake3::hash;
 use serde::{Deserialize, Serialize};
 
@@ -29,6 +31,9 @@ const SUBSPACE_DIM: usize = 4; // Dimensions per subspace
 const NUM_CENTROIDS: usize = 256; // 2^8 centroids (encoded as u8)
 const TOTAL_DIM: usize = NUM_SUBSPACES * SUBSPACE_DIM; // 384
 
+/// Vector dimension product quantization supports (`TOTAL_DIM`).
+pub(crate) const PQ_DIMENSION: u32 = 384;
+
 /// Codebook for one subspace: 256 centroids, each with SUBSPACE_DIM dimensions
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SubspaceCodebook {
@@ -349,6 +354,26 @@ impl QuantizedVecIndex {
 
     /// Search using asymmetric distance computation
     pub fn search(&self, query: &[f32], limit: usize) -> Vec<VecSearchHit> {
+        self.rank(query, limit, |_| true)
+    }
+
+    /// Search only the vectors of frames in `allowed`.
+    #[must_use]
+    pub fn search_within(
+        &self,
+        query: &[f32],
+        limit: usize,
+        allowed: &HashSet<FrameId>,
+    ) -> Vec<VecSearchHit> {
+        self.rank(query, limit, |frame_id| allowed.contains(&frame_id))
+    }
+
+    fn rank(
+        &self,
+        query: &[f32],
+        limit: usize,
+        keep: impl Fn(FrameId) -> bool,
+    ) -> Vec<VecSearchHit> {
         if query.is_empty() {
             return Vec::new();
         }
@@ -356,6 +381,7 @@ impl QuantizedVecIndex {
         let mut hits: Vec<VecSearchHit> = self
             .documents
             .iter()
+            .filter(|doc| keep(doc.frame_id))
             .map(|doc| {
                 let distance = self.quantizer.asymmetric_distance(query, &doc.codes);
                 VecSearchHit {
@@ -379,6 +405,40 @@ impl QuantizedVecIndex {
         self.documents.retain(|doc| doc.frame_id != frame_id);
     }
 
+    /// Insert or replace the vector of `frame_id`, quantized with the trained codebooks.
+    ///
+    /// # Errors
+    /// Returns `InvalidQuery` if `embedding` does not match the quantizer dimension.
+    pub fn insert(&mut self, frame_id: FrameId, embedding: &[f32]) -> Result<()> {
+        let codes = self.quantizer.encode(embedding)?;
+        self.remove(frame_id);
+        self.documents
+            .push(QuantizedVecDocument { frame_id, codes });
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn contains(&self, frame_id: FrameId) -> bool {
+        self.documents.iter().any(|doc| doc.frame_id == frame_id)
+    }
+
+    /// Encode the index with its current codebooks, in the format `decode` reads.
+    ///
+    /// # Errors
+    /// Returns an error if the index cannot be serialized.
+    pub fn encode(&self) -> Result<QuantizedVecIndexArtifact> {
+        let bytes =
+            bincode::serde::encode_to_vec((&self.quantizer, &self.documents), vec_config())?;
+        let checksum = *hash(&bytes).as_bytes();
+        Ok(QuantizedVecIndexArtifact {
+            bytes,
+            vector_count: self.documents.len() as u64,
+            dimension: self.quantizer.dimension,
+            checksum,
+            compression_ratio: 16.0,
+        })
+    }
+
     /// Get compression statistics
     pub fn compression_stats(&self) -> CompressionStats {
         let original_bytes = self.documents.len() * TOTAL_DIM * std::mem::size_of::<f32>();
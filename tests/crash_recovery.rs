@@ -1,7 +1,11 @@
 use std::fs::OpenOptions;
 use std::io::{Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 
-use memvid_core::{DoctorOptions, DoctorStatus, Memvid, MemvidError};
+use memvid_core::{
+    DoctorOptions, DoctorStatus, FrameStatus, MemoryCard, MemoryCardBuilder, Memvid, MemvidError,
+    VectorCompression,
+};
 
 #[test]
 #[cfg(not(target_os = "windows"))] // Windows file locking prevents proper corruption simulation
@@ -60,3 +64,215 @@ fn doctor_recovers_from_corrupted_commit_footer_single_commit() {
 
     Memvid::open_read_only(&path).expect("open_read_only after doctor");
 }
+
+/// Copy the file of a live handle, as a crash would leave it on disk.
+fn crash_image(path: &Path) -> PathBuf {
+    let crashed = path.with_extension("crashed.mv2");
+    std::fs::copy(path, &crashed).expect("copy crash image");
+    crashed
+}
+
+fn employer_card(value: &str) -> MemoryCard {
+    MemoryCardBuilder::new()
+        .fact()
+        .entity("alice")
+        .slot("employer")
+        .value(value)
+        .source(0, None)
+        .engine("rules", "1.0.0")
+        .build(0)
+        .expect("card")
+}
+
+#[test]
+fn committed_transaction_is_replayed_after_crash() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let path = dir.path().join("tx-committed.mv2");
+
+    let old_id = {
+        let mut mem = Memvid::create(&path).expect("create");
+        let old_id = mem.next_frame_id();
+        mem.put_bytes(b"alice works at Initech").expect("put");
+        mem.commit().expect("commit");
+        old_id
+    };
+
+    // Crash right after the transaction, before the memory is committed.
+    let crashed = {
+        let mut mem = Memvid::open(&path).expect("open");
+        mem.transaction(|tx| {
+            tx.delete_frame(old_id)?;
+            tx.put_bytes(b"alice works at Acme")?;
+            tx.put_memory_card(employer_card("Acme"))
+        })
+        .expect("transaction");
+        crash_image(&path)
+    };
+
+    let mut mem = Memvid::open(&crashed).expect("reopen");
+    assert_eq!(mem.frame_count(), 2);
+    assert_eq!(
+        mem.frame_by_id(old_id).expect("old frame").status,
+        FrameStatus::Deleted
+    );
+    assert!(
+        mem.frame_text_by_id(old_id + 1)
+            .expect("new frame")
+            .starts_with("alice works at Acme")
+    );
+    assert_eq!(mem.memory_card_count(), 1);
+}
+
+#[test]
+fn torn_and_aborted_transactions_are_discarded() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let path = dir.path().join("tx-torn.mv2");
+
+    let kept_id = {
+        let mut mem = Memvid::create(&path).expect("create");
+        let kept_id = mem.next_frame_id();
+        mem.put_bytes(b"kept").expect("put");
+        mem.commit().expect("commit");
+        kept_id
+    };
+
+    let crashed = {
+        let mut mem = Memvid::open(&path).expect("open");
+
+        // Aborted: the closure fails after staging writes.
+        let err = mem
+            .transaction(|tx| {
+                tx.delete_frame(kept_id)?;
+                tx.put_bytes(b"aborted")?;
+                Err::<(), _>(MemvidError::Lock("simulated failure".into()))
+            })
+            .expect_err("transaction should abort");
+        assert!(matches!(err, MemvidError::Lock(_)));
+
+        // Torn: the process dies before the commit marker reaches the WAL.
+        let torn = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = mem.transaction::<(), _>(|tx| {
+                tx.put_bytes(b"torn")?;
+                tx.put_memory_card(employer_card("Torn"))?;
+                panic!("crash before commit marker");
+            });
+        }));
+        assert!(torn.is_err());
+        crash_image(&path)
+    };
+
+    let mem = Memvid::open(&crashed).expect("reopen");
+    assert_eq!(mem.frame_count(), 1);
+    assert_eq!(
+        mem.frame_by_id(kept_id).expect("kept frame").status,
+        FrameStatus::Active
+    );
+    assert_eq!(mem.memory_card_count(), 0);
+}
+
+#[test]
+fn writes_after_a_torn_transaction_are_replayed() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let path = dir.path().join("tx-after-torn.mv2");
+
+    let crashed = {
+        let mut mem = Memvid::create(&path).expect("create");
+        let torn = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            let _ = mem.transaction::<(), _>(|tx| {
+                tx.put_bytes(b"torn")?;
+                panic!("closure panics before the commit marker");
+            });
+        }));
+        assert!(torn.is_err());
+
+        // The handle outlives the panic; its next write must not land inside the torn
+        // transaction.
+        mem.put_bytes(b"after the panic").expect("put");
+        crash_image(&path)
+    };
+
+    let mut mem = Memvid::open(&crashed).expect("reopen");
+    assert_eq!(mem.frame_count(), 1);
+    assert!(
+        mem.frame_text_by_id(0)
+            .expect("frame")
+            .starts_with("after the panic")
+    );
+}
+
+#[test]
+fn wal_grows_for_entries_larger_than_its_region() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let path = dir.path().join("wal-growth.mv2");
+
+    // Barely compressible text, far larger than the 64 KiB WAL of a fresh memory.
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+    let mut state = 0x9E37_79B9_7F4A_7C15u64;
+    let large: Vec<u8> = (0..256 * 1024)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            ALPHABET[(state % ALPHABET.len() as u64) as usize]
+        })
+        .collect();
+
+    let (committed_id, large_id, crashed) = {
+        let mut mem = Memvid::create(&path).expect("create");
+        let committed_id = mem.next_frame_id();
+        mem.put_bytes(b"committed before growth").expect("put");
+        mem.commit().expect("commit");
+
+        let large_id = mem.next_frame_id();
+        mem.put_bytes(&large).expect("put large");
+        mem.put_bytes(b"after growth").expect("put small");
+        (committed_id, large_id, crash_image(&path))
+    };
+
+    // The grown WAL is recovered from the crash image along with the shifted data.
+    let mut mem = Memvid::open(&crashed).expect("reopen");
+    assert!(
+        mem.frame_text_by_id(committed_id)
+            .expect("committed frame")
+            .starts_with("committed before growth")
+    );
+    let payload = mem.frame_canonical_payload(large_id).expect("large frame");
+    assert!(
+        payload == large,
+        "large frame payload differs after recovery"
+    );
+}
+
+#[test]
+fn space_embeddings_are_replayed_after_crash() {
+    let dir = tempfile::tempdir().expect("tmp");
+    let path = dir.path().join("space-embeddings.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).expect("create");
+        mem.create_vec_space("narrow", None, VectorCompression::None)
+            .expect("create space");
+        mem.put_bytes(b"alpha").expect("put");
+        mem.put_bytes(b"beta").expect("put");
+        mem.commit().expect("commit");
+    }
+
+    // Crash after re-embedding, before the memory is committed.
+    let crashed = {
+        let mut mem = Memvid::open(&path).expect("open");
+        mem.add_embeddings_to_space("narrow", vec![(0, vec![1.0, 0.0]), (1, vec![0.0, 1.0])])
+            .expect("add embeddings");
+        crash_image(&path)
+    };
+
+    drop(Memvid::open(&crashed).expect("recover"));
+    let mut mem = Memvid::open_read_only(&crashed).expect("reopen");
+    let space = &mem.vec_spaces()[0];
+    assert_eq!(space.index.vector_count, 2);
+    assert_eq!(space.index.dimension, 2);
+    let hits = mem
+        .vec_search_in_space("narrow", "", &[0.0, 1.0], 1, 64, None)
+        .expect("search")
+        .hits;
+    assert_eq!(hits[0].frame_id, 1);
+}
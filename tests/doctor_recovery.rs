@@ -51,6 +51,11 @@ fn doctor_rebuilds_tantivy_index() {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
             })
             .unwrap();
 
@@ -97,6 +102,11 @@ fn doctor_rebuilds_tantivy_index() {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
             })
             .unwrap();
 
@@ -237,6 +247,11 @@ fn open_file_with_tantivy_segments_enables_lex() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         });
 
         assert!(
@@ -321,6 +336,11 @@ fn doctor_rebuild_produces_searchable_index() {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
             })
             .unwrap();
 
@@ -348,6 +368,11 @@ fn doctor_rebuild_produces_searchable_index() {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
             })
             .unwrap();
 
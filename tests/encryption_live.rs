@@ -0,0 +1,274 @@
+//! Live-encrypted memory tests (`Memvid::open_encrypted`).
+
+#[cfg(feature = "encryption")]
+use memvid_core::encryption::EncryptionError;
+#[cfg(feature = "encryption")]
+use memvid_core::{FrameStatus, Memvid, MemvidError, PutOptions, SearchRequest};
+
+#[cfg(feature = "encryption")]
+use std::fs::read;
+#[cfg(feature = "encryption")]
+use tempfile::TempDir;
+
+#[cfg(feature = "encryption")]
+const SECRET: &[u8] = b"the launch code is purple-otter-42";
+
+#[cfg(feature = "encryption")]
+fn search_request(query: &str) -> SearchRequest {
+    SearchRequest {
+        query: query.to_string(),
+        top_k: 10,
+        snippet_chars: 200,
+        uri: None,
+        scope: None,
+        cursor: None,
+        #[cfg(feature = "temporal_track")]
+        temporal: None,
+        as_of_frame: None,
+        as_of_ts: None,
+        no_sketch: false,
+        filter: None,
+        facets: Vec::new(),
+        hybrid: None,
+        reranker: None,
+        fragments: None,
+    }
+}
+
+#[cfg(feature = "encryption")]
+fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+    haystack
+        .windows(needle.len())
+        .any(|window| window == needle)
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn encrypted_memory_roundtrip_without_plaintext_on_disk() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+
+    let frame_id = {
+        let mut mem = Memvid::create_encrypted(&path, b"hunter2").expect("create");
+        assert!(mem.is_encrypted());
+        let frame_id = mem.next_frame_id();
+        mem.put_bytes_with_options(
+            SECRET,
+            PutOptions {
+                title: Some("launch".to_string()),
+                uri: Some("mv2://notes/launch".to_string()),
+                ..Default::default()
+            },
+        )
+        .expect("put");
+        mem.commit().expect("commit");
+        frame_id
+    };
+
+    let bytes = read(&path).expect("read file");
+    assert_eq!(&bytes[..4], b"MV2E");
+    assert!(
+        !contains(&bytes, b"otter"),
+        "payload or index stored in plaintext"
+    );
+    assert!(!contains(&bytes, b"mv2://notes"), "toc stored in plaintext");
+    let leaked = std::fs::read_dir(dir.path()).expect("list dir").count();
+    assert_eq!(leaked, 1, "no sidecar or plaintext copy should be written");
+
+    let mut mem = Memvid::open_encrypted(&path, b"hunter2").expect("open");
+    assert!(
+        mem.frame_text_by_id(frame_id)
+            .expect("frame")
+            .starts_with("the launch code")
+    );
+    let results = mem.search(search_request("otter")).expect("search");
+    assert_eq!(results.hits.len(), 1);
+    assert_eq!(results.hits[0].uri, "mv2://notes/launch");
+
+    // Writes after reopening stay encrypted too.
+    mem.put_bytes(b"second secret: magenta-heron").expect("put");
+    mem.commit().expect("commit");
+    drop(mem);
+    assert!(!contains(&read(&path).expect("read"), b"magenta-heron"));
+
+    let mut mem = Memvid::open_encrypted(&path, b"hunter2").expect("reopen");
+    assert_eq!(mem.frame_count(), 2);
+    assert_eq!(
+        mem.search(search_request("heron"))
+            .expect("search")
+            .hits
+            .len(),
+        1
+    );
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn encrypted_memory_rejects_wrong_password_and_plain_open() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+    {
+        let mut mem = Memvid::create_encrypted(&path, b"right").expect("create");
+        mem.put_bytes(SECRET).expect("put");
+        mem.commit().expect("commit");
+    }
+
+    let err = Memvid::open_encrypted(&path, b"wrong")
+        .err()
+        .expect("wrong password");
+    assert!(matches!(
+        err,
+        MemvidError::Encryption(EncryptionError::Decryption { .. })
+    ));
+
+    let err = Memvid::open(&path).err().expect("plain open");
+    assert!(matches!(err, MemvidError::EncryptedFile { .. }));
+
+    let plain = dir.path().join("plain.mv2");
+    Memvid::create(&plain).expect("create plain");
+    assert!(Memvid::open_encrypted(&plain, b"right").is_err());
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn rotating_the_password_rewraps_the_data_key() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+    {
+        let mut mem = Memvid::create_encrypted(&path, b"old-password").expect("create");
+        mem.put_bytes(SECRET).expect("put");
+        mem.commit().expect("commit");
+        mem.rotate_encryption_password(b"new-password")
+            .expect("rotate");
+    }
+
+    assert!(Memvid::open_encrypted(&path, b"old-password").is_err());
+    let mem = Memvid::open_encrypted(&path, b"new-password").expect("open");
+    assert_eq!(mem.frame_count(), 1);
+
+    let mut plain = Memvid::create(dir.path().join("plain.mv2")).expect("create plain");
+    assert!(plain.rotate_encryption_password(b"any").is_err());
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn encrypted_memory_survives_delete_and_vacuum() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+
+    let (dropped, kept) = {
+        let mut mem = Memvid::create_encrypted(&path, b"pw").expect("create");
+        let dropped = mem.next_frame_id();
+        mem.put_bytes(&b"obsolete ".repeat(2_000)).expect("put");
+        let kept = mem.next_frame_id();
+        mem.put_bytes(SECRET).expect("put");
+        mem.commit().expect("commit");
+        mem.delete_frame(dropped).expect("delete");
+        mem.commit().expect("commit");
+        let report = mem.vacuum().expect("vacuum");
+        assert!(report.bytes_reclaimed > 0);
+        (dropped, kept)
+    };
+
+    let bytes = read(&path).expect("read");
+    assert!(!contains(&bytes, b"purple-otter"));
+
+    let mut mem = Memvid::open_encrypted(&path, b"pw").expect("open");
+    assert_eq!(
+        mem.frame_by_id(dropped).expect("dropped frame").status,
+        FrameStatus::Deleted
+    );
+    assert!(
+        mem.frame_text_by_id(kept)
+            .expect("kept frame")
+            .starts_with("the launch code")
+    );
+}
+
+/// Key slots of the `.mv2e` version 2 envelope: offsets and size.
+#[cfg(feature = "encryption")]
+const KEY_SLOTS: [usize; 2] = [512, 1024];
+#[cfg(feature = "encryption")]
+const KEY_SLOT_SIZE: usize = 132;
+#[cfg(feature = "encryption")]
+const PAGE_SIZE: u64 = 4096;
+/// Envelope page, journal header page and 16 journal undo slots.
+#[cfg(feature = "encryption")]
+const DATA_OFFSET: u64 = 18 * PAGE_SIZE;
+
+#[test]
+#[cfg(feature = "encryption")]
+fn interrupted_rotation_opens_with_the_old_or_the_new_password() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+    {
+        let mut mem = Memvid::create_encrypted(&path, b"old-password").expect("create");
+        mem.put_bytes(SECRET).expect("put");
+        mem.commit().expect("commit");
+    }
+    let before = read(&path).expect("read");
+    Memvid::open_encrypted(&path, b"old-password")
+        .expect("open")
+        .rotate_encryption_password(b"new-password")
+        .expect("rotate");
+    let after = read(&path).expect("read");
+    let [old_slot, new_slot] = KEY_SLOTS.map(|offset| offset..offset + KEY_SLOT_SIZE);
+    assert_ne!(before[new_slot.clone()], after[new_slot.clone()]);
+
+    // Crash after the new slot is synced but before the old one is cleared.
+    let mut image = after.clone();
+    image[old_slot.clone()].copy_from_slice(&before[old_slot]);
+    let crashed = dir.path().join("crashed.mv2");
+    std::fs::write(&crashed, &image).expect("write");
+    assert!(Memvid::open_encrypted(&crashed, b"old-password").is_err());
+    assert_eq!(
+        Memvid::open_encrypted(&crashed, b"new-password")
+            .expect("open with the new password")
+            .frame_count(),
+        1
+    );
+
+    // Crash while the new slot is only half written.
+    image[new_slot.start + KEY_SLOT_SIZE / 2..new_slot.end].fill(0);
+    std::fs::write(&crashed, &image).expect("write");
+    assert!(Memvid::open_encrypted(&crashed, b"new-password").is_err());
+    assert_eq!(
+        Memvid::open_encrypted(&crashed, b"old-password")
+            .expect("open with the old password")
+            .frame_count(),
+        1
+    );
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn encrypted_memory_cut_at_a_page_boundary_fails_authentication() {
+    let dir = TempDir::new().expect("tmp");
+    let path = dir.path().join("secret.mv2");
+    {
+        let mut mem = Memvid::create_encrypted(&path, b"pw").expect("create");
+        for round in 0..3 {
+            mem.put_bytes(format!("{round}: {}", "filler ".repeat(2_000)).as_bytes())
+                .expect("put");
+            mem.commit().expect("commit");
+        }
+    }
+
+    let physical = std::fs::metadata(&path).expect("metadata").len();
+    let pages = (physical - DATA_OFFSET) / PAGE_SIZE;
+    let file = std::fs::OpenOptions::new()
+        .write(true)
+        .open(&path)
+        .expect("open");
+    file.set_len(DATA_OFFSET + (pages - 1) * PAGE_SIZE)
+        .expect("truncate");
+    drop(file);
+
+    let err = Memvid::open_encrypted(&path, b"pw")
+        .err()
+        .expect("truncated memory");
+    assert!(
+        matches!(&err, MemvidError::Io { source, .. } if source.kind() == std::io::ErrorKind::InvalidData),
+        "unexpected error: {err:?}"
+    );
+}
@@ -0,0 +1,133 @@
+//! Recipient capsule tests (.mv2e sealed to X25519 keys).
+
+#[cfg(feature = "encryption")]
+use ed25519_dalek::SigningKey;
+#[cfg(feature = "encryption")]
+use memvid_core::Memvid;
+#[cfg(feature = "encryption")]
+use memvid_core::encryption::{
+    EncryptionError, RecipientSecretKey, lock_file, lock_file_for_recipients, unlock_file,
+    unlock_file_for_recipient, verify_capsule,
+};
+
+#[cfg(feature = "encryption")]
+use std::fs::read;
+#[cfg(feature = "encryption")]
+use std::path::{Path, PathBuf};
+#[cfg(feature = "encryption")]
+use tempfile::TempDir;
+
+#[cfg(feature = "encryption")]
+fn sample_memory(dir: &Path) -> PathBuf {
+    let path = dir.join("team.mv2");
+    let mut mem = Memvid::create(&path).expect("create");
+    mem.put_bytes(b"shared roadmap notes").expect("put");
+    mem.commit().expect("commit");
+    path
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn every_recipient_can_unlock_and_others_cannot() {
+    let dir = TempDir::new().expect("tmp");
+    let mv2 = sample_memory(dir.path());
+    let capsule = dir.path().join("team.mv2e");
+
+    let alice = RecipientSecretKey::generate();
+    let bob = RecipientSecretKey::generate();
+    let mallory = RecipientSecretKey::generate();
+    lock_file_for_recipients(
+        &mv2,
+        Some(&capsule),
+        &[alice.public_key(), bob.public_key()],
+        None,
+    )
+    .expect("lock");
+
+    let info = verify_capsule(&capsule).expect("inspect");
+    assert_eq!(info.recipients, vec![alice.public_key(), bob.public_key()]);
+    assert!(info.signer.is_none());
+
+    let original = read(&mv2).expect("read");
+    for (name, key) in [("alice", &alice), ("bob", &bob)] {
+        let restored = dir.path().join(format!("{name}.mv2"));
+        unlock_file_for_recipient(&capsule, Some(&restored), key, None).expect("unlock");
+        assert_eq!(read(&restored).expect("read restored"), original);
+    }
+
+    let err = unlock_file_for_recipient(&capsule, Some(&dir.path().join("m.mv2")), &mallory, None)
+        .expect_err("not a recipient");
+    assert!(matches!(err, EncryptionError::NotARecipient));
+    assert!(!dir.path().join("m.mv2").exists());
+
+    let err = unlock_file(&capsule, None, b"password").expect_err("password unlock");
+    assert!(matches!(err, EncryptionError::RecipientCapsule));
+    let err = lock_file_for_recipients(&mv2, None, &[], None).expect_err("no recipients");
+    assert!(matches!(err, EncryptionError::NoRecipients));
+}
+
+#[test]
+#[cfg(feature = "encryption")]
+fn signed_capsule_verifies_and_rejects_tampering() {
+    let dir = TempDir::new().expect("tmp");
+    let mv2 = sample_memory(dir.path());
+    let capsule = dir.path().join("team.mv2e");
+
+    let recipient = RecipientSecretKey::generate();
+    let signer = SigningKey::from_bytes(&[7u8; 32]);
+    let impostor = SigningKey::from_bytes(&[9u8; 32]);
+    lock_file_for_recipients(
+        &mv2,
+        Some(&capsule),
+        &[recipient.public_key()],
+        Some(&signer),
+    )
+    .expect("lock");
+
+    let info = verify_capsule(&capsule).expect("verify");
+    assert_eq!(info.signer, Some(signer.verifying_key()));
+
+    let restored = dir.path().join("restored.mv2");
+    unlock_file_for_recipient(
+        &capsule,
+        Some(&restored),
+        &recipient,
+        Some(&signer.verifying_key()),
+    )
+    .expect("unlock signed");
+    assert_eq!(read(&restored).expect("read"), read(&mv2).expect("read"));
+
+    let err = unlock_file_for_recipient(
+        &capsule,
+        Some(&restored),
+        &recipient,
+        Some(&impostor.verifying_key()),
+    )
+    .expect_err("wrong signer");
+    assert!(matches!(err, EncryptionError::SignatureInvalid { .. }));
+
+    // Flip one ciphertext byte at the end of the capsule.
+    let mut bytes = read(&capsule).expect("read capsule");
+    let last = bytes.len() - 1;
+    bytes[last] ^= 0x01;
+    std::fs::write(&capsule, &bytes).expect("tamper");
+    let err = verify_capsule(&capsule).expect_err("tampered");
+    assert!(matches!(err, EncryptionError::SignatureInvalid { .. }));
+
+    // Password capsules carry no recipients or signature.
+    let password_capsule = dir.path().join("password.mv2e");
+    lock_file(&mv2, Some(&password_capsule), b"pw").expect("lock password");
+    let info = verify_capsule(&password_capsule).expect("inspect password capsule");
+    assert!(info.recipients.is_empty() && info.signer.is_none());
+    let err = unlock_file_for_recipient(&password_capsule, None, &recipient, None)
+        .expect_err("password capsule");
+    assert!(matches!(err, EncryptionError::PasswordCapsule));
+    let err = unlock_file_for_recipient(
+        &password_capsule,
+        None,
+        &recipient,
+        Some(&signer.verifying_key()),
+    )
+    .expect_err("password capsule with a trusted signer");
+    assert!(matches!(err, EncryptionError::PasswordCapsule));
+}
@@ -0,0 +1,267 @@
+//! Integration tests for portable JSONL export/import.
+
+use memvid_core::{
+    EmbeddingIdentity, EntityKind, FrameStatus, JsonlRecord, LinkType, MemoryCardBuilder, Memvid,
+    MemvidError, MeshEdge, MeshNode, PredicateSchema, PutOptions, VectorCompression,
+};
+use std::io::Cursor;
+use tempfile::TempDir;
+
+/// Test that export -> import rebuilds the same frames, embeddings, cards and mesh.
+#[test]
+fn jsonl_roundtrip_preserves_content() {
+    let dir = TempDir::new().unwrap();
+    let source_path = dir.path().join("source.mv2");
+    let target_path = dir.path().join("target.mv2");
+
+    let mut source = Memvid::create(&source_path).unwrap();
+    source.enable_vec().unwrap();
+    for (i, body) in ["alpha report", "bravo notes", "charlie log"]
+        .iter()
+        .enumerate()
+    {
+        let opts = PutOptions {
+            uri: Some(format!("mv2://doc{}", i)),
+            tags: vec![format!("tag{}", i)],
+            ..Default::default()
+        };
+        source
+            .put_with_embedding_and_options(body.as_bytes(), vec![i as f32, 1.0, 0.5], opts)
+            .unwrap();
+    }
+    source.put_bytes(&[0xff, 0x00, 0xfe]).unwrap();
+    source.commit().unwrap();
+
+    let deleted = source.frame_by_uri("mv2://doc1").unwrap().id;
+    source.delete_frame(deleted).unwrap();
+    source.commit().unwrap();
+
+    let card = MemoryCardBuilder::new()
+        .fact()
+        .entity("alice")
+        .slot("employer")
+        .value("Acme")
+        .source(0, Some("mv2://doc0".to_string()))
+        .engine("rules", "1.0.0")
+        .build(0)
+        .unwrap();
+    source.put_memory_card(card).unwrap();
+    let alice = MeshNode::new(
+        "alice".into(),
+        "Alice".into(),
+        EntityKind::Person,
+        0.9,
+        0,
+        0,
+        5,
+    );
+    let acme = MeshNode::new(
+        "acme".into(),
+        "Acme".into(),
+        EntityKind::Organization,
+        0.9,
+        0,
+        6,
+        4,
+    );
+    let edge = MeshEdge::new(alice.id, acme.id, LinkType::Employer, 0.8, 0);
+    source.add_mesh_nodes(vec![alice, acme]);
+    source.add_mesh_edge(edge);
+    source.commit().unwrap();
+
+    let mut dump = Vec::new();
+    let exported = source.export_jsonl(&mut dump).unwrap();
+    assert_eq!(exported.frames, 4);
+    assert_eq!(exported.memory_cards, 1);
+    assert_eq!(exported.mesh_nodes, 2);
+    assert_eq!(exported.mesh_edges, 1);
+
+    let first: JsonlRecord =
+        serde_json::from_slice(dump.split(|b| *b == b'\n').next().unwrap()).unwrap();
+    assert!(matches!(first, JsonlRecord::Header(_)));
+
+    let mut target = Memvid::create(&target_path).unwrap();
+    let imported = target.import_jsonl(Cursor::new(&dump)).unwrap();
+    assert_eq!(imported.frames, exported.frames);
+    assert_eq!(imported.embeddings, exported.embeddings);
+    drop(target);
+
+    let mut target = Memvid::open(&target_path).unwrap();
+    for id in 0..4 {
+        let original = source.frame_by_id(id).unwrap();
+        let restored = target.frame_by_id(id).unwrap();
+        assert_eq!(restored.uri, original.uri);
+        assert_eq!(restored.tags, original.tags);
+        assert_eq!(restored.status, original.status);
+        assert_eq!(restored.checksum, original.checksum);
+        assert_eq!(
+            target.frame_canonical_payload(id).unwrap(),
+            source.frame_canonical_payload(id).unwrap()
+        );
+    }
+    assert_eq!(
+        target.frame_by_id(deleted).unwrap().status,
+        FrameStatus::Deleted
+    );
+    assert_eq!(target.memories().cards().len(), 1);
+    assert_eq!(target.mesh_node_count(), 2);
+    assert_eq!(target.mesh_edge_count(), 1);
+    let hits = target.search_vec(&[2.0, 1.0, 0.5], 1).unwrap();
+    assert_eq!(hits[0].frame_id, 2);
+
+    // Importing into a memory that already has frames is rejected.
+    assert!(matches!(
+        target.import_jsonl(Cursor::new(&dump)),
+        Err(MemvidError::JsonlImport { .. })
+    ));
+}
+
+/// Test that a child keeps its parent link when a checkpoint separates it from the parent.
+#[test]
+fn jsonl_import_links_parents_across_checkpoints() {
+    let dir = TempDir::new().unwrap();
+    let source_path = dir.path().join("source.mv2");
+    let target_path = dir.path().join("target.mv2");
+
+    // Incompressible payloads below the chunking threshold; thirty of them overflow the 64 KiB
+    // WAL, so the import checkpoints part way through.
+    let mut state = 0x2545_f491_u64;
+    let mut payload = || -> Vec<u8> {
+        (0..2_000)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6_364_136_223_846_793_005)
+                    .wrapping_add(1);
+                (state >> 56) as u8
+            })
+            .collect()
+    };
+    let mut source = Memvid::create(&source_path).unwrap();
+    for _ in 0..30 {
+        source.put_bytes(&payload()).unwrap();
+    }
+    source.commit().unwrap();
+    let mut dump = Vec::new();
+    source.export_jsonl(&mut dump).unwrap();
+
+    // Point the last frame at the first, past the checkpoint the import takes once the WAL fills.
+    let mut lines: Vec<String> = String::from_utf8(dump)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect();
+    let last = lines
+        .iter()
+        .rposition(|line| {
+            matches!(
+                serde_json::from_str::<JsonlRecord>(line),
+                Ok(JsonlRecord::Frame(_))
+            )
+        })
+        .unwrap();
+    let JsonlRecord::Frame(mut record) = serde_json::from_str(&lines[last]).unwrap() else {
+        unreachable!()
+    };
+    assert_eq!(record.frame.id, 29);
+    record.frame.parent_id = Some(0);
+    lines[last] = serde_json::to_string(&JsonlRecord::Frame(record)).unwrap();
+
+    let mut target = Memvid::create(&target_path).unwrap();
+    target
+        .import_jsonl(Cursor::new(lines.join("\n").into_bytes()))
+        .unwrap();
+    drop(target);
+
+    let target = Memvid::open(&target_path).unwrap();
+    assert_eq!(target.frame_by_id(29).unwrap().parent_id, Some(0));
+    assert_eq!(target.frame_by_id(28).unwrap().parent_id, None);
+}
+
+/// Test that named vector spaces and their embeddings survive export -> import.
+#[test]
+fn jsonl_roundtrip_preserves_vec_spaces() {
+    let dir = TempDir::new().unwrap();
+    let source_path = dir.path().join("source.mv2");
+    let target_path = dir.path().join("target.mv2");
+
+    let mut source = Memvid::create(&source_path).unwrap();
+    let identity = EmbeddingIdentity {
+        provider: Some("test".into()),
+        model: Some("narrow".into()),
+        dimension: None,
+        normalized: None,
+    };
+    source
+        .create_vec_space("narrow", Some(identity), VectorCompression::None)
+        .unwrap();
+    source
+        .put_with_embedding_in_space(b"alpha", "narrow", vec![1.0, 0.0], PutOptions::default())
+        .unwrap();
+    source
+        .put_with_embedding_in_space(b"bravo", "narrow", vec![0.0, 1.0], PutOptions::default())
+        .unwrap();
+    source.commit().unwrap();
+
+    let mut dump = Vec::new();
+    let exported = source.export_jsonl(&mut dump).unwrap();
+    assert_eq!(exported.vec_spaces, 1);
+    assert_eq!(exported.space_embeddings, 2);
+
+    let mut target = Memvid::create(&target_path).unwrap();
+    let imported = target.import_jsonl(Cursor::new(&dump)).unwrap();
+    assert_eq!(imported.space_embeddings, 2);
+    drop(target);
+
+    let mut target = Memvid::open_read_only(&target_path).unwrap();
+    let spaces = target.vec_spaces();
+    assert_eq!(spaces.len(), 1);
+    assert_eq!(spaces[0].index.vector_count, 2);
+    assert_eq!(spaces[0].index.dimension, 2);
+    assert_eq!(
+        spaces[0]
+            .identity
+            .as_ref()
+            .and_then(|id| id.model.as_deref()),
+        Some("narrow")
+    );
+    let hits = target
+        .vec_search_in_space("narrow", "", &[0.0, 1.0], 1, 64, None)
+        .unwrap()
+        .hits;
+    assert_eq!(hits[0].frame_id, 1);
+}
+
+/// Test that imported schemas serve the importing handle but are not stored in the file.
+#[test]
+fn jsonl_imported_schemas_last_for_the_session() {
+    let dir = TempDir::new().unwrap();
+    let source_path = dir.path().join("source.mv2");
+    let target_path = dir.path().join("target.mv2");
+
+    let mut source = Memvid::create(&source_path).unwrap();
+    source.register_schema(PredicateSchema::new("favorite_tool", "Favorite Tool"));
+    source.put_bytes(b"alpha").unwrap();
+    source.commit().unwrap();
+
+    let mut dump = Vec::new();
+    source.export_jsonl(&mut dump).unwrap();
+
+    let mut target = Memvid::create(&target_path).unwrap();
+    target.import_jsonl(Cursor::new(&dump)).unwrap();
+    assert!(target.schema_registry().contains("favorite_tool"));
+    drop(target);
+
+    let mut target = Memvid::open(&target_path).unwrap();
+    assert_eq!(target.frame_count(), 1);
+    assert_eq!(target.frame_canonical_payload(0).unwrap(), b"alpha");
+    assert!(!target.schema_registry().contains("favorite_tool"));
+    assert!(target.schema_registry().contains("employer"));
+
+    // The dump still carries the schema for the caller to register again.
+    for line in dump.split(|b| *b == b'\n').filter(|line| !line.is_empty()) {
+        if let JsonlRecord::Schema(schema) = serde_json::from_slice(line).unwrap() {
+            target.register_schema(schema);
+        }
+    }
+    assert!(target.schema_registry().contains("favorite_tool"));
+}
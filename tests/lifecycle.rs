@@ -1,8 +1,13 @@
 //! Integration tests for Memvid lifecycle operations.
 //! Tests: create, open, open_read_only, commit, stats, verify
 
-use memvid_core::{Memvid, PutOptions, VerificationStatus};
+use memvid_core::{
+    ChangeKind, CommitMode, CommitOptions, EntityKind, LinkType, MemoryCardBuilder, Memvid,
+    MemvidError, MeshEdge, MeshNode, PutOptions, SearchRequest, TocPart, VerificationStatus,
+};
 use std::fs;
+use std::thread;
+use std::time::Duration;
 use tempfile::TempDir;
 
 /// Test basic create and open lifecycle.
@@ -266,3 +271,432 @@ fn commit_without_changes() {
         "Commit without changes should not significantly change file size"
     );
 }
+
+/// Test that a read-only handle picks up commits from another handle via refresh.
+#[test]
+fn refresh_loads_newer_generation() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    mem.enable_vec().unwrap();
+    mem.put_with_embedding(b"alpha release notes", vec![1.0, 0.0, 0.0])
+        .unwrap();
+    mem.commit().unwrap();
+
+    let mut reader = Memvid::open_read_only(&path).unwrap();
+    let opened_at = reader.generation();
+    assert!(!reader.changed_since(opened_at).unwrap());
+    assert!(reader.refresh().unwrap().is_none());
+
+    mem.put_with_embedding(b"beta migration guide", vec![0.0, 1.0, 0.0])
+        .unwrap();
+    mem.commit().unwrap();
+    assert!(reader.changed_since(opened_at).unwrap());
+    assert_eq!(reader.frame_count(), 1);
+
+    let report = reader.refresh().unwrap().expect("newer generation");
+    assert_eq!(report.previous_generation, opened_at);
+    assert_eq!(report.generation, reader.generation());
+    assert_eq!(report.frames_added, 1);
+    assert!(report.changed(TocPart::Frames));
+    assert!(report.changed(TocPart::LexIndex));
+    assert!(!report.changed(TocPart::LogicMesh));
+
+    assert_eq!(reader.frame_count(), 2);
+    assert!(
+        reader
+            .frame_text_by_id(1)
+            .unwrap()
+            .starts_with("beta migration guide")
+    );
+    let hits = reader.search_vec(&[0.0, 1.0, 0.0], 1).unwrap();
+    assert_eq!(hits[0].frame_id, 1);
+    #[cfg(feature = "lex")]
+    {
+        let response = reader
+            .search(SearchRequest {
+                query: "migration".to_string(),
+                top_k: 5,
+                snippet_chars: 120,
+                uri: None,
+                scope: None,
+                cursor: None,
+                #[cfg(feature = "temporal_track")]
+                temporal: None,
+                as_of_frame: None,
+                as_of_ts: None,
+                no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
+            })
+            .unwrap();
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].frame_id, 1);
+    }
+
+    assert!(!reader.changed_since(reader.generation()).unwrap());
+    assert!(reader.refresh().unwrap().is_none());
+    assert!(matches!(mem.refresh(), Err(MemvidError::RequiresReadOnly)));
+}
+
+/// Test that a background commit publishes a new generation while readers keep the old one.
+#[test]
+fn background_commit_publishes_new_generation() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    mem.enable_vec().unwrap();
+    mem.put_with_embedding(b"alpha release notes", vec![1.0, 0.0, 0.0])
+        .unwrap();
+    mem.commit().unwrap();
+    let reader = Memvid::open_read_only(&path).unwrap();
+
+    mem.put_with_embedding(b"beta migration guide", vec![0.0, 1.0, 0.0])
+        .unwrap();
+    let options = CommitOptions::new(CommitMode::Incremental).background(true);
+    let commit = mem.commit_in_background(options).unwrap();
+    while !commit.is_finished() {
+        thread::sleep(Duration::from_millis(5));
+    }
+
+    // The commit is staged but not published: the handle and readers serve the previous
+    // generation and the handle rejects writes.
+    assert_eq!(mem.frame_count(), 1);
+    assert_eq!(reader.frame_count(), 1);
+    assert_eq!(Memvid::open_read_only(&path).unwrap().frame_count(), 1);
+    assert!(matches!(
+        mem.put_bytes(b"too early"),
+        Err(MemvidError::CommitInProgress)
+    ));
+
+    commit.wait(&mut mem).unwrap();
+    assert_eq!(reader.frame_count(), 1);
+    assert_eq!(mem.frame_count(), 2);
+    let hits = mem.search_vec(&[0.0, 1.0, 0.0], 1).unwrap();
+    assert_eq!(hits[0].frame_id, 1);
+    #[cfg(feature = "lex")]
+    {
+        let response = mem
+            .search(SearchRequest {
+                query: "migration".to_string(),
+                top_k: 5,
+                snippet_chars: 120,
+                uri: None,
+                scope: None,
+                cursor: None,
+                #[cfg(feature = "temporal_track")]
+                temporal: None,
+                as_of_frame: None,
+                as_of_ts: None,
+                no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
+            })
+            .unwrap();
+        assert_eq!(response.hits.len(), 1);
+        assert_eq!(response.hits[0].frame_id, 1);
+    }
+
+    mem.put_bytes(b"gamma changelog").unwrap();
+    mem.commit().unwrap();
+    drop(mem);
+
+    let reopened = Memvid::open_read_only(&path).unwrap();
+    assert_eq!(reopened.frame_count(), 3);
+}
+
+/// Test that the change log lists each commit's mutations and survives reopening.
+#[test]
+fn change_feed_survives_reopen() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let options = |uri: &str| {
+        PutOptions::builder()
+            .uri(uri)
+            .extract_triplets(false)
+            .build()
+    };
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.put_bytes_with_options(b"alpha draft", options("mv2://alpha"))
+        .unwrap();
+    mem.commit().unwrap();
+    let first = mem.generation();
+    assert_eq!(
+        mem.changes_since(0)
+            .unwrap()
+            .map(|event| event.kind)
+            .collect::<Vec<_>>(),
+        vec![ChangeKind::FramePut { frame_id: 0 }]
+    );
+    assert_eq!(
+        mem.watch(first, Some(Duration::from_millis(100))).unwrap(),
+        None
+    );
+
+    let watch_path = path.clone();
+    let watcher = thread::spawn(move || {
+        let reader = Memvid::open_read_only(&watch_path).unwrap();
+        reader.watch(first, Some(Duration::from_secs(30))).unwrap()
+    });
+
+    mem.update_frame(
+        0,
+        Some(b"alpha final".to_vec()),
+        options("mv2://alpha"),
+        None,
+    )
+    .unwrap();
+    mem.put_bytes_with_options(b"beta draft", options("mv2://beta"))
+        .unwrap();
+    mem.commit().unwrap();
+    mem.delete_frame(2).unwrap();
+    let card = MemoryCardBuilder::new()
+        .fact()
+        .entity("alice")
+        .slot("employer")
+        .value("Acme")
+        .source(1, Some("mv2://alpha".to_string()))
+        .engine("rules", "1.0.0")
+        .build(0)
+        .unwrap();
+    let card_id = mem.put_memory_card(card).unwrap();
+    let alice = MeshNode::new(
+        "alice".into(),
+        "Alice".into(),
+        EntityKind::Person,
+        0.9,
+        1,
+        0,
+        5,
+    );
+    let edge = MeshEdge::new(alice.id, alice.id, LinkType::Related, 0.5, 1);
+    let alice_id = alice.id;
+    mem.add_mesh_node(alice);
+    mem.add_mesh_edge(edge);
+    mem.commit().unwrap();
+    let last = mem.generation();
+
+    let published = watcher.join().unwrap().expect("commit wakes the watcher");
+    assert_ne!(published, first);
+
+    let expected = vec![
+        ChangeKind::FrameUpdated {
+            frame_id: 1,
+            previous: 0,
+        },
+        ChangeKind::FrameSuperseded {
+            frame_id: 0,
+            superseded_by: 1,
+        },
+        ChangeKind::FramePut { frame_id: 2 },
+        ChangeKind::FrameDeleted { frame_id: 2 },
+        ChangeKind::MemoryCardAdded { card_id },
+        ChangeKind::MeshNodeMerged {
+            node_id: alice_id,
+            canonical_name: "alice".into(),
+            kind: EntityKind::Person,
+        },
+        ChangeKind::MeshEdgeMerged {
+            from_node: alice_id,
+            to_node: alice_id,
+            link: LinkType::Related,
+        },
+    ];
+    let events: Vec<_> = mem.changes_since(first).unwrap().collect();
+    assert_eq!(
+        events
+            .iter()
+            .map(|event| event.kind.clone())
+            .collect::<Vec<_>>(),
+        expected
+    );
+    assert!(events.windows(2).all(
+        |pair| (pair[0].generation, pair[0].sequence) <= (pair[1].generation, pair[1].sequence)
+    ));
+    assert_eq!(events.last().unwrap().generation, last);
+    assert_eq!(mem.changes_since(last).unwrap().count(), 0);
+    drop(mem);
+
+    let reopened = Memvid::open_read_only(&path).unwrap();
+    assert_eq!(
+        reopened.changes_since(first).unwrap().collect::<Vec<_>>(),
+        events
+    );
+    assert_eq!(
+        reopened.changes_since(0).unwrap().count(),
+        expected.len() + 1
+    );
+}
+
+/// Test that the change log drops its oldest commits beyond the retention.
+#[test]
+fn change_log_prunes_old_commits() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.set_change_log_retention(3);
+    let mut generations = Vec::new();
+    for round in 0..4 {
+        mem.put_bytes(format!("first {round}").as_bytes()).unwrap();
+        mem.put_bytes(format!("second {round}").as_bytes()).unwrap();
+        mem.commit().unwrap();
+        generations.push(mem.generation());
+    }
+    drop(mem);
+
+    let reopened = Memvid::open_read_only(&path).unwrap();
+    let err = reopened.changes_since(generations[1]).err().unwrap();
+    assert!(
+        matches!(err, MemvidError::ChangesPruned { requested, pruned_through }
+            if requested == generations[1] && pruned_through == generations[2]),
+        "unexpected error: {err:?}"
+    );
+    assert!(reopened.changes_since(0).is_err());
+    let kept: Vec<_> = reopened.changes_since(generations[2]).unwrap().collect();
+    assert_eq!(kept.len(), 2);
+    assert!(kept.iter().all(|event| event.generation == generations[3]));
+}
+
+/// Test signing a memory and verifying the signature after reopening.
+#[test]
+fn signed_memory_verifies() {
+    use ed25519_dalek::SigningKey;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    let other_key = SigningKey::from_bytes(&[9u8; 32]);
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.put_bytes(b"Signed release notes").unwrap();
+        mem.sign(&signing_key).unwrap();
+    }
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    assert_eq!(mem.signer(), Some(signing_key.verifying_key()));
+    mem.verify_signature(&signing_key.verifying_key()).unwrap();
+    assert!(mem.verify_signature(&other_key.verifying_key()).is_err());
+    drop(mem);
+
+    let report = Memvid::verify(&path, true).unwrap();
+    assert_eq!(report.overall_status, VerificationStatus::Passed);
+    let status = |name: &str| {
+        report
+            .checks
+            .iter()
+            .find(|check| check.name == name)
+            .map(|check| check.status)
+    };
+    assert_eq!(status("MerkleRoot"), Some(VerificationStatus::Passed));
+    assert_eq!(status("Signature"), Some(VerificationStatus::Passed));
+    assert_eq!(
+        status("FramePayloadChecksums"),
+        Some(VerificationStatus::Passed)
+    );
+    assert_eq!(
+        status("IndexPayloadChecksums"),
+        Some(VerificationStatus::Passed)
+    );
+
+    // Any later content change voids the signature.
+    let mut mem = Memvid::open(&path).unwrap();
+    mem.put_bytes(b"Unsigned addendum").unwrap();
+    mem.commit().unwrap();
+    assert!(mem.signer().is_none());
+    assert!(mem.verify_signature(&signing_key.verifying_key()).is_err());
+}
+
+/// Test that tampering with a signed payload is detected.
+#[test]
+fn signed_memory_detects_tampered_payload() {
+    use ed25519_dalek::SigningKey;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+
+    let payload_end = {
+        let mut mem = Memvid::create(&path).unwrap();
+        let frame_id = mem.next_frame_id();
+        mem.put_bytes(b"Signed release notes").unwrap();
+        mem.sign(&signing_key).unwrap();
+        let frame = mem.frame_by_id(frame_id).unwrap();
+        assert!(frame.payload_length > 0);
+        (frame.payload_offset + frame.payload_length) as usize
+    };
+
+    // Flip the last stored payload byte; the TOC is left untouched.
+    let mut bytes = fs::read(&path).unwrap();
+    bytes[payload_end - 1] ^= 0x01;
+    fs::write(&path, &bytes).unwrap();
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    assert!(mem.verify_signature(&signing_key.verifying_key()).is_err());
+    drop(mem);
+
+    let report = Memvid::verify(&path, true).unwrap();
+    assert_eq!(report.overall_status, VerificationStatus::Failed);
+}
+
+/// Test that tampering with a signed index segment is detected.
+#[test]
+fn signed_memory_detects_tampered_index() {
+    use ed25519_dalek::SigningKey;
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let signing_key = SigningKey::from_bytes(&[7u8; 32]);
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.put_bytes(b"Signed release notes").unwrap();
+        mem.sign(&signing_key).unwrap();
+    }
+
+    // Edit a setting in the stored Tantivy meta.json; frame payloads and the TOC are left
+    // untouched and the JSON stays valid.
+    let mut bytes = fs::read(&path).unwrap();
+    let needle = b"\"docstore_blocksize\": 16384";
+    let at = bytes
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .unwrap();
+    bytes[at + needle.len() - 1] = b'5';
+    fs::write(&path, &bytes).unwrap();
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let err = mem
+        .verify_signature(&signing_key.verifying_key())
+        .unwrap_err();
+    assert!(matches!(err, MemvidError::InvalidToc { .. }), "{err:?}");
+    drop(mem);
+
+    let report = Memvid::verify(&path, true).unwrap();
+    let status = |name: &str| {
+        report
+            .checks
+            .iter()
+            .find(|check| check.name == name)
+            .map(|check| check.status)
+    };
+    assert_eq!(
+        status("FramePayloadChecksums"),
+        Some(VerificationStatus::Passed)
+    );
+    assert_eq!(
+        status("IndexPayloadChecksums"),
+        Some(VerificationStatus::Failed)
+    );
+}
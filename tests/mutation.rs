@@ -1,10 +1,13 @@
 //! Integration tests for Memvid mutation operations.
-//! Tests: put, put_bytes_with_options, update, delete
+//! Tests: put, put_bytes_with_options, update, delete, vacuum, merge
 
 use memvid_core::{
-    EmbeddingIdentitySummary, MEMVID_EMBEDDING_MODEL_KEY, MEMVID_EMBEDDING_PROVIDER_KEY, Memvid,
-    MemvidError, PutOptions, TimelineQuery,
+    EmbeddingIdentity, EmbeddingIdentitySummary, EnrichmentEngine, HNSW_CHAIN_MAGIC, HnswConfig,
+    MEMVID_EMBEDDING_MODEL_KEY, MEMVID_EMBEDDING_PROVIDER_KEY, Memvid, MemvidError, MergeOptions,
+    PredicateSchema, PutManyOpts, PutOptions, PutRequest, RulesEngine, TimelineQuery,
+    UriConflictPolicy, ValueType, VectorCompression,
 };
+use std::collections::BTreeMap;
 use std::num::NonZeroU64;
 use tempfile::TempDir;
 
@@ -255,6 +258,502 @@ fn put_rejects_mixed_embedding_dimensions() {
     }
 }
 
+#[test]
+fn vec_index_promotes_to_hnsw_and_updates_incrementally() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let embedding = |i: usize| vec![(i % 8) as f32, (i / 8) as f32, 1.0, 0.5];
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_vec().unwrap();
+        mem.set_hnsw_config(HnswConfig {
+            min_vectors: 64,
+            ..HnswConfig::default()
+        });
+        for i in 0..64 {
+            mem.put_with_embedding(format!("doc {i}").as_bytes(), embedding(i))
+                .unwrap();
+        }
+        mem.commit().unwrap();
+    }
+    let chain_segments = |path: &std::path::Path| {
+        std::fs::read(path)
+            .unwrap()
+            .windows(HNSW_CHAIN_MAGIC.len())
+            .filter(|window| *window == HNSW_CHAIN_MAGIC)
+            .count()
+    };
+    assert_eq!(
+        chain_segments(&path),
+        1,
+        "commit past the threshold should persist an HNSW graph"
+    );
+
+    let (deleted, newcomer) = {
+        let mut mem = Memvid::open(&path).unwrap();
+        let hits = mem
+            .vec_search_with_embedding("", &embedding(100), 1, 64, None)
+            .unwrap()
+            .hits;
+        let deleted = hits[0].frame_id;
+        mem.delete_frame(deleted).unwrap();
+        let newcomer = mem.next_frame_id();
+        mem.put_with_embedding(b"newcomer", vec![500.0, 500.0, 1.0, 0.5])
+            .unwrap();
+        mem.commit().unwrap();
+        (deleted, newcomer)
+    };
+    assert_eq!(
+        chain_segments(&path),
+        2,
+        "later commits should append a delta instead of re-encoding the graph"
+    );
+
+    // A commit with payloads after the delta must leave the chain intact.
+    {
+        let mut mem = Memvid::open(&path).unwrap();
+        mem.put_with_embedding(b"late", vec![-500.0, -500.0, 1.0, 0.5])
+            .unwrap();
+        mem.commit().unwrap();
+        mem.put_bytes(b"no embedding").unwrap();
+        mem.commit().unwrap();
+    }
+    assert_eq!(chain_segments(&path), 3);
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let hits = mem
+        .vec_search_with_embedding("", &[500.0, 500.0, 1.0, 0.5], 1, 64, None)
+        .unwrap()
+        .hits;
+    assert_eq!(hits[0].frame_id, newcomer);
+    let hits = mem
+        .vec_search_with_embedding("", &embedding(100), 5, 64, None)
+        .unwrap()
+        .hits;
+    assert!(!hits.is_empty());
+    assert!(hits.iter().all(|hit| hit.frame_id != deleted));
+    let hits = mem
+        .vec_search_with_embedding("", &[-500.0, -500.0, 1.0, 0.5], 1, 64, None)
+        .unwrap()
+        .hits;
+    assert!(hits[0].text.contains("late"));
+}
+
+#[test]
+fn named_vec_spaces_are_searched_independently() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let (alpha, beta) = {
+        let mut mem = Memvid::create(&path).unwrap();
+        let identity = EmbeddingIdentity {
+            provider: Some("test".into()),
+            model: Some("wide".into()),
+            dimension: Some(4),
+            normalized: None,
+        };
+        mem.create_vec_space("wide", Some(identity), VectorCompression::None)
+            .unwrap();
+        mem.create_vec_space("narrow", None, VectorCompression::None)
+            .unwrap();
+        assert!(matches!(
+            mem.create_vec_space("wide", None, VectorCompression::None),
+            Err(MemvidError::VecSpaceExists { .. })
+        ));
+
+        mem.put_with_embedding(b"default", vec![1.0, 0.0, 0.0])
+            .unwrap();
+        let alpha = mem.next_frame_id();
+        mem.put_with_embedding_in_space(
+            b"alpha",
+            "wide",
+            vec![1.0, 0.0, 0.0, 0.0],
+            PutOptions::default(),
+        )
+        .unwrap();
+        let beta = mem.next_frame_id();
+        mem.put_with_embedding_in_space(
+            b"beta",
+            "wide",
+            vec![0.0, 1.0, 0.0, 0.0],
+            PutOptions::default(),
+        )
+        .unwrap();
+        assert!(matches!(
+            mem.put_with_embedding_in_space(
+                b"gamma",
+                "wide",
+                vec![0.0, 1.0],
+                PutOptions::default()
+            ),
+            Err(MemvidError::VecDimensionMismatch { expected: 4, .. })
+        ));
+        mem.commit().unwrap();
+
+        // Re-embed existing frames into a second space without re-ingesting them.
+        mem.add_embeddings_to_space(
+            "narrow",
+            vec![(alpha, vec![0.0, 1.0]), (beta, vec![1.0, 0.0])],
+        )
+        .unwrap();
+        mem.commit().unwrap();
+        (alpha, beta)
+    };
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let spaces = mem.vec_spaces();
+    assert_eq!(spaces.len(), 2);
+    assert_eq!(spaces[0].index.vector_count, 2);
+    assert_eq!(spaces[1].index.dimension, 2);
+
+    let hits = mem
+        .vec_search_in_space("wide", "", &[0.0, 1.0, 0.0, 0.0], 2, 64, None)
+        .unwrap()
+        .hits;
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].frame_id, beta);
+    let hits = mem
+        .vec_search_in_space("narrow", "", &[0.0, 1.0], 1, 64, None)
+        .unwrap()
+        .hits;
+    assert_eq!(hits[0].frame_id, alpha);
+
+    // The default index only holds the default-space embedding.
+    let hits = mem
+        .vec_search_with_embedding("", &[1.0, 0.0, 0.0], 5, 64, None)
+        .unwrap()
+        .hits;
+    assert_eq!(hits.len(), 1);
+    assert!(matches!(
+        mem.vec_search_in_space("missing", "", &[1.0], 1, 64, None),
+        Err(MemvidError::VecSpaceNotFound { .. })
+    ));
+}
+
+/// Test an aborted transaction leaves named vector spaces as they were.
+#[test]
+fn aborted_transaction_leaves_vec_spaces_untouched() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.create_vec_space("narrow", None, VectorCompression::None)
+        .unwrap();
+    mem.put_bytes(b"existing").unwrap();
+    mem.commit().unwrap();
+
+    let err = mem
+        .transaction(|tx| {
+            let options = PutOptions {
+                vector_space: Some("narrow".into()),
+                ..Default::default()
+            };
+            tx.put_with_embedding_and_options(b"staged", vec![1.0, 0.0, 0.0], options)?;
+            tx.add_embeddings_to_space("narrow", vec![(0, vec![0.0, 1.0, 0.0])])?;
+            Err::<(), _>(MemvidError::Lock("simulated failure".into()))
+        })
+        .unwrap_err();
+    assert!(matches!(err, MemvidError::Lock(_)));
+    assert_eq!(mem.vec_spaces()[0].index.dimension, 0);
+
+    // The aborted embeddings neither fixed the dimension nor reach the index.
+    mem.add_embeddings_to_space("narrow", vec![(0, vec![0.0, 1.0])])
+        .unwrap();
+    mem.commit().unwrap();
+    let space = &mem.vec_spaces()[0];
+    assert_eq!(space.index.dimension, 2);
+    assert_eq!(space.index.vector_count, 1);
+    assert_eq!(mem.frame_count(), 1);
+}
+
+/// Test an aborted transaction drops the cards and mesh state its puts extracted.
+#[test]
+fn aborted_transaction_discards_extracted_triplets() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let text: &[u8] = b"Alice works at Acme Corp. Alice lives in Berlin.";
+
+    let mut mem = Memvid::create(&path).unwrap();
+    let err = mem
+        .transaction(|tx| {
+            tx.put_bytes(text)?;
+            Err::<(), _>(MemvidError::Lock("simulated failure".into()))
+        })
+        .unwrap_err();
+    assert!(matches!(err, MemvidError::Lock(_)));
+    assert_eq!(mem.memory_card_count(), 0);
+    assert_eq!(mem.enrichment_queue_len(), 0);
+    assert!(!mem.has_logic_mesh());
+
+    mem.commit().unwrap();
+    drop(mem);
+    let mut mem = Memvid::open(&path).unwrap();
+    assert_eq!(mem.frame_count(), 0);
+    assert_eq!(mem.memory_card_count(), 0);
+    assert!(!mem.has_logic_mesh());
+
+    // A committed transaction adds the cards once its WAL entries are applied.
+    mem.transaction(|tx| tx.put_bytes(text)).unwrap();
+    assert_eq!(mem.memory_card_count(), 0);
+    mem.commit().unwrap();
+    assert!(mem.memory_card_count() > 0);
+}
+
+#[test]
+fn vec_spaces_are_only_rewritten_when_changed_and_honour_compression() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let embedding = |seed: usize| -> Vec<f32> {
+        (0..384)
+            .map(|axis| ((seed * 7919 + axis * axis * 104_729) % 1009) as f32 / 1009.0)
+            .collect()
+    };
+
+    let quantized_len = {
+        let mut mem = Memvid::create(&path).unwrap();
+        assert!(matches!(
+            mem.put_with_embedding_in_space(b"x", "pq", vec![0.0; 4], PutOptions::default()),
+            Err(MemvidError::VecSpaceNotFound { .. })
+        ));
+        mem.create_vec_space("pq", None, VectorCompression::Pq96)
+            .unwrap();
+        mem.create_vec_space("plain", None, VectorCompression::None)
+            .unwrap();
+        assert!(matches!(
+            mem.put_with_embedding_in_space(b"x", "pq", vec![0.0; 4], PutOptions::default()),
+            Err(MemvidError::VecDimensionMismatch { expected: 384, .. })
+        ));
+        for i in 0..120 {
+            mem.put_with_embedding_in_space(
+                format!("doc {i}").as_bytes(),
+                "pq",
+                embedding(i),
+                PutOptions::default(),
+            )
+            .unwrap();
+        }
+        mem.put_with_embedding_in_space(b"plain", "plain", vec![1.0, 0.0], PutOptions::default())
+            .unwrap();
+        mem.commit().unwrap();
+
+        let pq = mem.vec_spaces()[0].index.clone();
+        assert_eq!(pq.vector_count, 120);
+        // Quantized: the 96 trained codebooks plus 96 one-byte codes per vector.
+        let codebooks = 96 * 256 * 4 * 4;
+        assert!(
+            (codebooks..codebooks + 120 * 128).contains(&pq.bytes_length),
+            "a Pq96 space should be stored quantized, got {} bytes",
+            pq.bytes_length
+        );
+
+        // Commits that don't touch a space leave its bytes where they are.
+        let plain = mem.vec_spaces()[1].index.clone();
+        mem.put_bytes(b"no embedding").unwrap();
+        mem.commit().unwrap();
+        let location = |manifest: &memvid_core::VecIndexManifest| {
+            (
+                manifest.bytes_offset,
+                manifest.bytes_length,
+                manifest.checksum,
+            )
+        };
+        assert_eq!(location(&mem.vec_spaces()[0].index), location(&pq));
+        assert_eq!(location(&mem.vec_spaces()[1].index), location(&plain));
+        pq.bytes_length
+    };
+
+    // A reopened quantized space keeps its codebooks for new vectors.
+    {
+        let mut mem = Memvid::open(&path).unwrap();
+        mem.put_with_embedding_in_space(b"late", "pq", embedding(500), PutOptions::default())
+            .unwrap();
+        mem.commit().unwrap();
+        assert_eq!(mem.vec_spaces()[0].index.vector_count, 121);
+        assert!(mem.vec_spaces()[0].index.bytes_length < quantized_len + 1024);
+    }
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let hits = mem
+        .vec_search_in_space("pq", "", &embedding(500), 1, 64, None)
+        .unwrap()
+        .hits;
+    assert!(hits[0].text.contains("late"));
+    let hits = mem
+        .vec_search_in_space("plain", "", &[1.0, 0.0], 1, 64, None)
+        .unwrap()
+        .hits;
+    assert!(hits[0].text.contains("plain"));
+}
+
+#[test]
+fn put_many_reports_per_item_results() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let request = |title: &str, embedding: Vec<f32>| PutRequest {
+        title: title.to_string(),
+        label: "note".to_string(),
+        text: format!("{title} body text"),
+        uri: Some(format!("mv2://batch/{title}")),
+        metadata: [("source".to_string(), serde_json::json!(7))].into(),
+        tags: vec!["batch".to_string()],
+        labels: vec!["extra".to_string()],
+        embedding: Some(embedding),
+    };
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_vec().unwrap();
+    let opts = PutManyOpts {
+        compression_level: 0,
+        no_raw: false,
+        enable_enrichment: false,
+        ..PutManyOpts::default()
+    };
+    let results = mem
+        .put_many(
+            vec![
+                request("alpha", vec![1.0, 0.0]),
+                request("beta", vec![0.0, 1.0]),
+                request("gamma", vec![0.0, 1.0, 0.0]),
+            ],
+            &opts,
+        )
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    let alpha = *results[0].as_ref().unwrap();
+    let beta = *results[1].as_ref().unwrap();
+    assert!(matches!(
+        results[2],
+        Err(MemvidError::VecDimensionMismatch { .. })
+    ));
+    mem.commit().unwrap();
+    drop(mem);
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    assert_eq!(mem.frame_count(), 2);
+    let frame = mem.frame_by_id(beta).unwrap();
+    assert_eq!(frame.title.as_deref(), Some("beta"));
+    assert_eq!(frame.labels[..2], ["note".to_string(), "extra".to_string()]);
+    assert_eq!(
+        frame.extra_metadata.get("source").map(String::as_str),
+        Some("7")
+    );
+    assert!(
+        mem.frame_text_by_id(alpha)
+            .unwrap()
+            .starts_with("alpha body text")
+    );
+
+    let hits = mem
+        .vec_search_with_embedding("", &[0.0, 1.0], 1, 64, None)
+        .unwrap()
+        .hits;
+    assert_eq!(hits[0].frame_id, beta);
+}
+
+#[test]
+fn put_many_runs_rules_enrichment_and_recovers_as_one_batch() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let request = |title: &str, text: &str| PutRequest {
+        title: title.to_string(),
+        label: "note".to_string(),
+        text: text.to_string(),
+        uri: None,
+        metadata: BTreeMap::new(),
+        tags: Vec::new(),
+        labels: Vec::new(),
+        embedding: None,
+    };
+
+    let mut mem = Memvid::create(&path).unwrap();
+    let results = mem
+        .put_many(
+            vec![
+                request("job", "Hello! I work at Anthropic."),
+                request("weather", "The weather is nice today."),
+            ],
+            &PutManyOpts::default(),
+        )
+        .unwrap();
+    let job = *results[0].as_ref().unwrap();
+    let weather = *results[1].as_ref().unwrap();
+    mem.commit().unwrap();
+    // This batch is never committed; recovery replays it from the WAL.
+    let opts = PutManyOpts {
+        enable_enrichment: false,
+        ..PutManyOpts::default()
+    };
+    mem.put_many(vec![request("late", "Written after the commit.")], &opts)
+        .unwrap();
+    drop(mem);
+
+    let mut mem = Memvid::open(&path).unwrap();
+    assert_eq!(mem.frame_count(), 3);
+    assert_eq!(
+        mem.get_current_memory("user", "employer")
+            .map(|card| card.value.as_str()),
+        Some("Anthropic")
+    );
+    let engine = RulesEngine::new();
+    assert!(mem.is_frame_enriched(job, engine.kind(), engine.version()));
+    assert!(mem.is_frame_enriched(weather, engine.kind(), engine.version()));
+    // Only the frame of the batch that skipped enrichment is left for the engine.
+    assert_eq!(mem.run_enrichment(&engine).unwrap(), (1, 0));
+}
+
+#[test]
+fn put_many_skips_documents_whose_enrichment_is_rejected() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+    let request = |text: &str| PutRequest {
+        title: String::new(),
+        label: "note".to_string(),
+        text: text.to_string(),
+        uri: None,
+        metadata: BTreeMap::new(),
+        tags: Vec::new(),
+        labels: Vec::new(),
+        embedding: None,
+    };
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.set_schema_strict(true);
+    mem.register_schema(
+        PredicateSchema::new("employer", "Employer").with_range(ValueType::Enum {
+            values: vec!["Initech".to_string()],
+        }),
+    );
+    let results = mem
+        .put_many(
+            vec![
+                request("Hello! I work at Anthropic."),
+                request("The weather is nice today."),
+            ],
+            &PutManyOpts {
+                no_raw: false,
+                ..PutManyOpts::default()
+            },
+        )
+        .unwrap();
+    assert!(matches!(
+        results[0],
+        Err(MemvidError::SchemaValidation { .. })
+    ));
+    let weather = *results[1].as_ref().unwrap();
+    mem.commit().unwrap();
+    drop(mem);
+
+    let mut mem = Memvid::open(&path).unwrap();
+    assert_eq!(mem.frame_count(), 1);
+    assert!(
+        mem.frame_text_by_id(weather)
+            .unwrap()
+            .starts_with("The weather is nice today.")
+    );
+    assert_eq!(mem.memory_card_count(), 0);
+}
+
 #[test]
 fn embedding_identity_summary_unknown_when_missing() {
     let dir = TempDir::new().unwrap();
@@ -433,3 +932,240 @@ fn timeline_iteration() {
 
     assert_eq!(entries.len(), 3, "Should have 3 timeline entries");
 }
+
+/// Test vacuum drops deleted payloads, shrinks the file and keeps live frames readable.
+#[test]
+fn vacuum_reclaims_deleted_payloads() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        for i in 0..6 {
+            let opts = PutOptions {
+                uri: Some(format!("mv2://doc{}", i)),
+                ..Default::default()
+            };
+            let body = format!("document {} {}", i, "payload ".repeat(200));
+            mem.put_bytes_with_options(body.as_bytes(), opts).unwrap();
+        }
+        mem.commit().unwrap();
+    }
+
+    let size_before = std::fs::metadata(&path).unwrap().len();
+    {
+        let mut mem = Memvid::open(&path).unwrap();
+        for i in 0..4 {
+            let frame_id = mem.frame_by_uri(&format!("mv2://doc{}", i)).unwrap().id;
+            mem.delete_frame(frame_id).unwrap();
+        }
+        mem.commit().unwrap();
+
+        let report = mem.vacuum().unwrap();
+        assert_eq!(report.active_frames, 2);
+        assert_eq!(report.released_frames, 4);
+        assert!(report.bytes_reclaimed > 0, "vacuum should reclaim bytes");
+        assert_eq!(
+            report.bytes_after,
+            std::fs::metadata(&path).unwrap().len(),
+            "report should match the on-disk length"
+        );
+    }
+
+    let size_after = std::fs::metadata(&path).unwrap().len();
+    assert!(
+        size_after < size_before,
+        "file should shrink: {} -> {}",
+        size_before,
+        size_after
+    );
+
+    let mut mem = Memvid::open(&path).unwrap();
+    for i in 4..6 {
+        let frame_id = mem.frame_by_uri(&format!("mv2://doc{}", i)).unwrap().id;
+        let text = mem.frame_text_by_id(frame_id).unwrap();
+        assert!(text.starts_with(&format!("document {}", i)));
+    }
+
+    // The compacted memory stays writable.
+    mem.put_bytes(b"after vacuum").unwrap();
+    mem.commit().unwrap();
+}
+
+fn put_docs(path: &std::path::Path, docs: &[(&str, &str)]) {
+    let mut mem = Memvid::create(path).unwrap();
+    for (uri, body) in docs {
+        let opts = PutOptions {
+            uri: Some((*uri).to_string()),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(body.as_bytes(), opts).unwrap();
+    }
+    mem.commit().unwrap();
+}
+
+/// Test merging another memory with checksum dedup and the default URI skip policy.
+#[test]
+fn merge_from_dedups_and_skips_conflicts() {
+    let dir = TempDir::new().unwrap();
+    let target = dir.path().join("target.mv2");
+    let source = dir.path().join("source.mv2");
+
+    put_docs(
+        &target,
+        &[
+            ("mv2://a", "alpha shared text"),
+            ("mv2://b", "bravo original"),
+        ],
+    );
+    put_docs(
+        &source,
+        &[
+            ("mv2://a-copy", "alpha shared text"),
+            ("mv2://b", "bravo replacement"),
+            ("mv2://c", "charlie brand new"),
+        ],
+    );
+
+    let mut mem = Memvid::open(&target).unwrap();
+    let report = mem.merge_from(&source, MergeOptions::default()).unwrap();
+    assert_eq!(report.documents_deduplicated, 1);
+    assert_eq!(report.documents_skipped, 1);
+    assert_eq!(report.frames_copied, 1);
+
+    let c = mem.frame_by_uri("mv2://c").unwrap().id;
+    assert!(
+        mem.frame_text_by_id(c)
+            .unwrap()
+            .starts_with("charlie brand new")
+    );
+    let b = mem.frame_by_uri("mv2://b").unwrap().id;
+    assert!(
+        mem.frame_text_by_id(b)
+            .unwrap()
+            .starts_with("bravo original")
+    );
+    assert!(mem.frame_by_uri("mv2://a-copy").is_err());
+
+    // Merging into itself is rejected.
+    assert!(matches!(
+        mem.merge_from(&target, MergeOptions::default()),
+        Err(MemvidError::MergeFailed { .. })
+    ));
+}
+
+/// Test that named vector spaces and their embeddings follow the merged frames.
+#[test]
+fn merge_from_carries_named_vec_spaces() {
+    let dir = TempDir::new().unwrap();
+    let target = dir.path().join("target.mv2");
+    let source = dir.path().join("source.mv2");
+
+    put_docs(&target, &[("mv2://a", "alpha original")]);
+    {
+        let mut mem = Memvid::create(&source).unwrap();
+        let identity = EmbeddingIdentity {
+            provider: Some("test".into()),
+            model: Some("wide".into()),
+            dimension: None,
+            normalized: None,
+        };
+        mem.create_vec_space("wide", Some(identity), VectorCompression::None)
+            .unwrap();
+        let options = |uri: &str| PutOptions {
+            uri: Some(uri.to_string()),
+            ..Default::default()
+        };
+        mem.put_with_embedding_in_space(b"bravo", "wide", vec![1.0, 0.0], options("mv2://b"))
+            .unwrap();
+        mem.put_with_embedding_in_space(b"charlie", "wide", vec![0.0, 1.0], options("mv2://c"))
+            .unwrap();
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open(&target).unwrap();
+    let report = mem.merge_from(&source, MergeOptions::default()).unwrap();
+    assert_eq!(report.space_embeddings_copied, 2);
+    drop(mem);
+
+    let mut mem = Memvid::open_read_only(&target).unwrap();
+    let spaces = mem.vec_spaces();
+    assert_eq!(spaces.len(), 1);
+    assert_eq!(spaces[0].index.vector_count, 2);
+    assert_eq!(spaces[0].index.dimension, 2);
+    assert_eq!(
+        spaces[0]
+            .identity
+            .as_ref()
+            .and_then(|id| id.model.as_deref()),
+        Some("wide")
+    );
+    let charlie = mem.frame_by_uri("mv2://c").unwrap().id;
+    let hits = mem
+        .vec_search_in_space("wide", "", &[0.0, 1.0], 1, 64, None)
+        .unwrap()
+        .hits;
+    assert_eq!(hits[0].frame_id, charlie);
+    drop(mem);
+
+    // A same-named space with another dimension is rejected before anything is copied.
+    let other = dir.path().join("other.mv2");
+    {
+        let mut mem = Memvid::create(&other).unwrap();
+        mem.create_vec_space("wide", None, VectorCompression::None)
+            .unwrap();
+        mem.put_with_embedding_in_space(
+            b"delta",
+            "wide",
+            vec![1.0, 0.0, 0.0],
+            PutOptions::default(),
+        )
+        .unwrap();
+        mem.commit().unwrap();
+    }
+    let mut mem = Memvid::open(&target).unwrap();
+    let frames = mem.stats().unwrap().frame_count;
+    assert!(matches!(
+        mem.merge_from(&other, MergeOptions::default()),
+        Err(MemvidError::VecDimensionMismatch { expected: 2, .. })
+    ));
+    assert_eq!(mem.stats().unwrap().frame_count, frames);
+}
+
+/// Test URI conflict policies that write or abort.
+#[test]
+fn merge_from_replace_and_fail_policies() {
+    let dir = TempDir::new().unwrap();
+    let target = dir.path().join("target.mv2");
+    let source = dir.path().join("source.mv2");
+
+    put_docs(&target, &[("mv2://b", "bravo original")]);
+    put_docs(&source, &[("mv2://b", "bravo replacement")]);
+
+    let mut mem = Memvid::open(&target).unwrap();
+    let fail = MergeOptions {
+        uri_conflict: UriConflictPolicy::Fail,
+        ..Default::default()
+    };
+    assert!(matches!(
+        mem.merge_from(&source, fail),
+        Err(MemvidError::MergeFailed { .. })
+    ));
+    assert_eq!(mem.stats().unwrap().frame_count, 1);
+
+    let replace = MergeOptions {
+        uri_conflict: UriConflictPolicy::Replace,
+        ..Default::default()
+    };
+    let report = mem.merge_from(&source, replace).unwrap();
+    assert_eq!(report.frames_replaced, 1);
+    drop(mem);
+
+    let mut mem = Memvid::open(&target).unwrap();
+    let b = mem.frame_by_uri("mv2://b").unwrap().id;
+    assert!(
+        mem.frame_text_by_id(b)
+            .unwrap()
+            .starts_with("bravo replacement")
+    );
+}
@@ -44,6 +44,11 @@ fn replay_save_does_not_corrupt_lex_index() {
                 as_of_frame: None,
                 as_of_ts: None,
                 no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
             })
             .unwrap();
         mem.end_session().unwrap();
@@ -69,6 +74,11 @@ fn replay_save_does_not_corrupt_lex_index() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         })
         .unwrap();
 
@@ -1,7 +1,13 @@
 //! Integration tests for Memvid search operations.
 //! Tests: search (lex), timeline queries
 
-use memvid_core::{Memvid, PutOptions, SearchRequest, TimelineQuery};
+use memvid_core::types::{GraphPattern, PatternTerm, TriplePattern};
+use memvid_core::{
+    FacetField, FacetValueCount, FusionStrategy, GraphMatcher, HybridSearch, LexAnalyzerConfig,
+    LexLanguage, LexTokenizer, MemoryCardBuilder, Memvid, MemvidError, MemvidReader,
+    MetadataFilter, PutOptions, RerankerConfig, RerankerHook, RerankerKind, SearchEngineKind,
+    SearchHit, SearchRequest, SnippetFragments, TimelineQuery,
+};
 use std::num::NonZeroU64;
 use tempfile::TempDir;
 
@@ -76,6 +82,11 @@ fn search_basic_query() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         })
         .unwrap();
 
@@ -111,6 +122,11 @@ fn search_multiple_results() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         })
         .unwrap();
 
@@ -163,6 +179,11 @@ fn search_respects_top_k() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         })
         .unwrap();
 
@@ -194,6 +215,11 @@ fn search_with_scope() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         })
         .unwrap();
 
@@ -229,6 +255,11 @@ fn search_returns_snippets() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         })
         .unwrap();
 
@@ -262,6 +293,11 @@ fn search_no_results() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         })
         .unwrap();
 
@@ -295,6 +331,11 @@ fn search_empty_memory() {
             as_of_frame: None,
             as_of_ts: None,
             no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
         })
         .unwrap();
 
@@ -305,6 +346,701 @@ fn search_empty_memory() {
     );
 }
 
+/// Test typed metadata filters narrow lexical search results.
+#[test]
+#[cfg(feature = "lex")]
+fn search_with_metadata_filter() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_lex().unwrap();
+        let docs = [
+            (
+                "mv2://reports/2022",
+                "finance",
+                "2022",
+                "Annual revenue report for the year",
+            ),
+            (
+                "mv2://reports/2023",
+                "finance",
+                "2023",
+                "Annual revenue report with growth",
+            ),
+            (
+                "mv2://reports/2024",
+                "legal",
+                "2024",
+                "Annual revenue report reviewed by counsel",
+            ),
+        ];
+        for (uri, tag, year, content) in docs {
+            let mut opts = PutOptions {
+                uri: Some(uri.to_string()),
+                search_text: Some(content.to_string()),
+                tags: vec![tag.to_string()],
+                ..Default::default()
+            };
+            opts.extra_metadata
+                .insert("year".to_string(), year.to_string());
+            mem.put_bytes_with_options(content.as_bytes(), opts)
+                .unwrap();
+        }
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let mut search = |filter: MetadataFilter| {
+        let mut uris: Vec<String> = mem
+            .search(SearchRequest {
+                query: "revenue".to_string(),
+                top_k: 10,
+                snippet_chars: 200,
+                uri: None,
+                scope: None,
+                cursor: None,
+                #[cfg(feature = "temporal_track")]
+                temporal: None,
+                as_of_frame: None,
+                as_of_ts: None,
+                no_sketch: false,
+                filter: Some(filter),
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
+            })
+            .unwrap()
+            .hits
+            .into_iter()
+            .map(|hit| hit.uri)
+            .collect();
+        uris.sort();
+        uris
+    };
+
+    assert_eq!(
+        search(MetadataFilter::Tag("Finance".to_string())),
+        vec!["mv2://reports/2022", "mv2://reports/2023"]
+    );
+    assert_eq!(
+        search(MetadataFilter::And(vec![
+            MetadataFilter::MetadataRange {
+                key: "year".to_string(),
+                min: Some("2023".to_string()),
+                max: None,
+            },
+            MetadataFilter::Not(Box::new(MetadataFilter::Tag("legal".to_string()))),
+        ])),
+        vec!["mv2://reports/2023"]
+    );
+    assert!(search(MetadataFilter::Tag("missing".to_string())).is_empty());
+}
+
+/// Test facet counts cover the full matched set rather than the returned page.
+#[test]
+#[cfg(feature = "lex")]
+fn search_returns_facet_counts() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_lex().unwrap();
+        let docs = [
+            ("alpha", 1_704_067_200), // 2024-01-01
+            ("alpha", 1_704_153_600), // 2024-01-02
+            ("beta", 1_706_745_600),  // 2024-02-01
+        ];
+        for (index, (tag, timestamp)) in docs.into_iter().enumerate() {
+            let content = format!("shared keyword document {index}");
+            let opts = PutOptions {
+                uri: Some(format!("mv2://docs/{index}")),
+                search_text: Some(content.clone()),
+                tags: vec![tag.to_string()],
+                timestamp: Some(timestamp),
+                auto_tag: false,
+                ..Default::default()
+            };
+            mem.put_bytes_with_options(content.as_bytes(), opts)
+                .unwrap();
+        }
+        mem.put_bytes(b"unrelated text").unwrap();
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let response = mem
+        .search(SearchRequest {
+            query: "keyword".to_string(),
+            top_k: 1,
+            snippet_chars: 200,
+            uri: None,
+            scope: None,
+            cursor: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            no_sketch: false,
+            filter: None,
+            facets: vec![FacetField::Tag, FacetField::Month],
+            hybrid: None,
+            reranker: None,
+            fragments: None,
+        })
+        .unwrap();
+
+    assert_eq!(response.hits.len(), 1);
+    assert_eq!(response.facets.len(), 2);
+    let count = |value: &str, count: usize| FacetValueCount {
+        value: value.to_string(),
+        count,
+    };
+    assert_eq!(response.facets[0].field, FacetField::Tag);
+    assert_eq!(
+        response.facets[0].values,
+        vec![count("alpha", 2), count("beta", 1)]
+    );
+    assert_eq!(response.facets[1].field, FacetField::Month);
+    assert_eq!(
+        response.facets[1].values,
+        vec![count("2024-01", 2), count("2024-02", 1)]
+    );
+}
+
+/// Test hybrid search fuses lexical and vector rankings with a score breakdown.
+#[test]
+#[cfg(feature = "lex")]
+fn search_hybrid_fuses_lexical_and_vector() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_lex().unwrap();
+        mem.enable_vec().unwrap();
+        let docs = [
+            (
+                "mv2://docs/lexical",
+                "rust ownership rules",
+                [0.0, 1.0, 0.0],
+            ),
+            ("mv2://docs/both", "rust borrow checker", [1.0, 0.0, 0.0]),
+            (
+                "mv2://docs/vector",
+                "memory safety guarantees",
+                [0.9, 0.1, 0.0],
+            ),
+        ];
+        for (uri, content, embedding) in docs {
+            let opts = PutOptions {
+                uri: Some(uri.to_string()),
+                search_text: Some(content.to_string()),
+                ..Default::default()
+            };
+            mem.put_with_embedding_and_options(content.as_bytes(), embedding.to_vec(), opts)
+                .unwrap();
+        }
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let mut search = |fusion: FusionStrategy| {
+        mem.search(SearchRequest {
+            query: "rust".to_string(),
+            top_k: 3,
+            snippet_chars: 200,
+            uri: None,
+            scope: None,
+            cursor: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            no_sketch: true,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: Some(HybridSearch::new(vec![1.0, 0.0, 0.0]).fusion(fusion)),
+            reranker: None,
+            fragments: None,
+        })
+        .unwrap()
+    };
+
+    let response = search(FusionStrategy::default());
+    assert_eq!(response.engine, SearchEngineKind::Hybrid);
+    assert_eq!(response.hits[0].uri, "mv2://docs/both");
+    let breakdown = response.hits[0].score_breakdown.as_ref().unwrap();
+    assert!(breakdown.lexical_rank.is_some());
+    assert_eq!(breakdown.vector_rank, Some(1));
+    assert!(
+        response
+            .hits
+            .iter()
+            .any(|hit| hit.uri == "mv2://docs/vector"
+                && hit.score_breakdown.as_ref().unwrap().lexical_rank.is_none())
+    );
+
+    let response = search(FusionStrategy::Weighted {
+        lexical: 0.0,
+        vector: 1.0,
+    });
+    let uris: Vec<&str> = response.hits.iter().map(|hit| hit.uri.as_str()).collect();
+    assert_eq!(
+        uris,
+        vec!["mv2://docs/both", "mv2://docs/vector", "mv2://docs/lexical"]
+    );
+}
+
+/// Test hybrid search restricts both retrievers to the exact `uri`, not URIs it prefixes.
+#[test]
+#[cfg(feature = "lex")]
+fn search_hybrid_uri_is_exact_for_vector_hits() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_lex().unwrap();
+        mem.enable_vec().unwrap();
+        let docs = [
+            ("mv2://docs/a", "rust ownership rules", [0.0, 1.0, 0.0]),
+            ("mv2://docs/ab", "memory safety guarantees", [1.0, 0.0, 0.0]),
+        ];
+        for (uri, content, embedding) in docs {
+            let opts = PutOptions {
+                uri: Some(uri.to_string()),
+                search_text: Some(content.to_string()),
+                ..Default::default()
+            };
+            mem.put_with_embedding_and_options(content.as_bytes(), embedding.to_vec(), opts)
+                .unwrap();
+        }
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let response = mem
+        .search(SearchRequest {
+            query: "rust".to_string(),
+            top_k: 5,
+            snippet_chars: 200,
+            uri: Some("mv2://docs/a".to_string()),
+            scope: None,
+            cursor: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            no_sketch: true,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: Some(HybridSearch::new(vec![1.0, 0.0, 0.0])),
+            reranker: None,
+            fragments: None,
+        })
+        .unwrap();
+    let uris: Vec<&str> = response.hits.iter().map(|hit| hit.uri.as_str()).collect();
+    assert_eq!(uris, vec!["mv2://docs/a"]);
+    assert!(
+        response.hits[0]
+            .score_breakdown
+            .as_ref()
+            .unwrap()
+            .vector_rank
+            .is_some()
+    );
+}
+
+/// Test a BM25 reranker hook reorders and truncates lexical hits.
+#[test]
+#[cfg(feature = "lex")]
+fn search_with_bm25_reranker() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_lex().unwrap();
+        let docs = [
+            ("mv2://notes/a", "release checklist for the team"),
+            (
+                "mv2://notes/b",
+                "release notes: compiler release, toolchain release",
+            ),
+            (
+                "mv2://notes/c",
+                "gardening notes mention a release of seeds",
+            ),
+        ];
+        for (uri, content) in docs {
+            let opts = PutOptions {
+                uri: Some(uri.to_string()),
+                search_text: Some(content.to_string()),
+                ..Default::default()
+            };
+            mem.put_bytes_with_options(content.as_bytes(), opts)
+                .unwrap();
+        }
+        mem.commit().unwrap();
+    }
+
+    let config = RerankerConfig {
+        max_candidates: 10,
+        top_k: 2,
+        min_score: 0.0,
+        use_metadata: false,
+    };
+    let hook = RerankerHook::from_kind(RerankerKind::Bm25, config)
+        .unwrap()
+        .unwrap();
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    let response = mem
+        .search(SearchRequest {
+            query: "release".to_string(),
+            top_k: 3,
+            snippet_chars: 200,
+            uri: None,
+            scope: None,
+            cursor: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            no_sketch: true,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: Some(hook),
+            fragments: None,
+        })
+        .unwrap();
+
+    assert_eq!(response.hits.len(), 2, "reranker top_k caps the page");
+    assert_eq!(response.total_hits, 3, "every candidate passes min_score");
+    assert_eq!(response.hits[0].uri, "mv2://notes/b");
+    assert_eq!(response.hits[0].score, Some(1.0));
+    assert_eq!(response.hits[1].rank, 2);
+    assert!(
+        RerankerHook::from_kind(RerankerKind::CrossEncoder, RerankerConfig::default()).is_err()
+    );
+
+    // Cursors page through the reranked list one hit at a time.
+    let mut pages = Vec::new();
+    let mut cursor = None;
+    loop {
+        let config = RerankerConfig {
+            max_candidates: 10,
+            top_k: 3,
+            min_score: 0.0,
+            use_metadata: false,
+        };
+        let page = mem
+            .search(SearchRequest {
+                query: "release".to_string(),
+                top_k: 1,
+                snippet_chars: 200,
+                uri: None,
+                scope: None,
+                cursor: cursor.take(),
+                #[cfg(feature = "temporal_track")]
+                temporal: None,
+                as_of_frame: None,
+                as_of_ts: None,
+                no_sketch: true,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: RerankerHook::from_kind(RerankerKind::Bm25, config).unwrap(),
+                fragments: None,
+            })
+            .unwrap();
+        assert_eq!(page.hits.len(), 1);
+        pages.push(page.hits[0].uri.clone());
+        match page.next_cursor {
+            Some(next) => cursor = Some(next),
+            None => break,
+        }
+    }
+    assert_eq!(pages.len(), 3);
+    assert_eq!(pages[0], "mv2://notes/b");
+    pages.sort();
+    pages.dedup();
+    assert_eq!(pages.len(), 3, "pages do not repeat hits");
+}
+
+/// Test a configured lex analyzer is persisted and applied to existing and new frames.
+#[test]
+#[cfg(feature = "lex")]
+fn search_uses_configured_lex_analyzer() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    fn search_hits(mem: &mut Memvid, query: &str) -> Vec<SearchHit> {
+        mem.search(SearchRequest {
+            query: query.to_string(),
+            top_k: 10,
+            snippet_chars: 200,
+            uri: None,
+            scope: None,
+            cursor: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
+        })
+        .unwrap()
+        .hits
+    }
+
+    fn hit_uris(mem: &mut Memvid, query: &str) -> Vec<String> {
+        search_hits(mem, query)
+            .into_iter()
+            .map(|hit| hit.uri)
+            .collect()
+    }
+
+    let put = |mem: &mut Memvid, uri: &str, text: &str| {
+        let opts = PutOptions {
+            uri: Some(uri.to_string()),
+            search_text: Some(text.to_string()),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(text.as_bytes(), opts).unwrap();
+    };
+
+    let analyzer = LexAnalyzerConfig {
+        split_identifiers: true,
+        ..LexAnalyzerConfig::for_language(LexLanguage::German)
+    };
+    {
+        let mut mem = Memvid::create(&path).unwrap();
+        mem.enable_lex().unwrap();
+        put(&mut mem, "mv2://de/haeuser", "Die alten Häuser am Fluss");
+        put(
+            &mut mem,
+            "mv2://code/parser",
+            "fn parseHttpHeader(line: &str)",
+        );
+        mem.commit().unwrap();
+        assert!(hit_uris(&mut mem, "haus").is_empty());
+
+        let invalid = LexAnalyzerConfig {
+            tokenizer: LexTokenizer::Ngram {
+                min_gram: 4,
+                max_gram: 2,
+            },
+            ..LexAnalyzerConfig::default()
+        };
+        assert!(matches!(
+            mem.set_lex_analyzer(invalid),
+            Err(MemvidError::InvalidLexAnalyzer { .. })
+        ));
+
+        mem.set_lex_analyzer(analyzer.clone()).unwrap();
+        put(&mut mem, "mv2://de/strasse", "Eine Straße in der Stadt");
+        mem.commit().unwrap();
+    }
+
+    let mut mem = Memvid::open_read_only(&path).unwrap();
+    assert_eq!(mem.lex_analyzer(), analyzer);
+    assert_eq!(hit_uris(&mut mem, "Haus"), ["mv2://de/haeuser"]);
+    assert_eq!(hit_uris(&mut mem, "strasse"), ["mv2://de/strasse"]);
+    assert_eq!(hit_uris(&mut mem, "HÄUS*"), ["mv2://de/haeuser"]);
+    let hit = &search_hits(&mut mem, "HÄUS*")[0];
+    let (start, end) = hit.highlights.as_ref().unwrap().text[0];
+    assert_eq!(&hit.text[start..end], "Häuser");
+    assert_eq!(hit_uris(&mut mem, "http header"), ["mv2://code/parser"]);
+}
+
+/// Test fuzzy, prefix, proximity and boosted query terms.
+#[test]
+#[cfg(feature = "lex")]
+fn search_supports_fuzzy_prefix_proximity_and_boosts() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    for (uri, text) in [
+        (
+            "mv2://notes/parcel",
+            "Please receive the parcel at reception",
+        ),
+        (
+            "mv2://notes/fox",
+            "The quick brown fox jumps over the lazy dog",
+        ),
+        (
+            "mv2://notes/machines",
+            "Machinery maintenance schedule for the plant",
+        ),
+        ("mv2://notes/desk", "A standing desk for the office"),
+    ] {
+        let opts = PutOptions {
+            uri: Some(uri.to_string()),
+            search_text: Some(text.to_string()),
+            ..Default::default()
+        };
+        mem.put_bytes_with_options(text.as_bytes(), opts).unwrap();
+    }
+    mem.commit().unwrap();
+
+    let mut hit_uris = |query: &str| -> Vec<String> {
+        let response = mem
+            .search(SearchRequest {
+                query: query.to_string(),
+                top_k: 10,
+                snippet_chars: 200,
+                uri: None,
+                scope: None,
+                cursor: None,
+                #[cfg(feature = "temporal_track")]
+                temporal: None,
+                as_of_frame: None,
+                as_of_ts: None,
+                no_sketch: false,
+                filter: None,
+                facets: Vec::new(),
+                hybrid: None,
+                reranker: None,
+                fragments: None,
+            })
+            .unwrap();
+        let mut uris: Vec<String> = response.hits.into_iter().map(|hit| hit.uri).collect();
+        uris.dedup();
+        uris
+    };
+
+    assert!(hit_uris("recieve").is_empty());
+    assert_eq!(hit_uris("recieve~1"), ["mv2://notes/parcel"]);
+    assert_eq!(hit_uris("machin*"), ["mv2://notes/machines"]);
+    assert!(hit_uris("\"quick fox\"").is_empty());
+    assert_eq!(hit_uris("\"quick fox\"~1"), ["mv2://notes/fox"]);
+
+    assert_eq!(
+        hit_uris("parcel OR desk^5"),
+        ["mv2://notes/desk", "mv2://notes/parcel"]
+    );
+    assert_eq!(
+        hit_uris("parcel^5 OR desk"),
+        ["mv2://notes/parcel", "mv2://notes/desk"]
+    );
+    assert!(matches!(
+        mem.search(SearchRequest {
+            query: "parcel~7".to_string(),
+            top_k: 10,
+            snippet_chars: 200,
+            uri: None,
+            scope: None,
+            cursor: None,
+            #[cfg(feature = "temporal_track")]
+            temporal: None,
+            as_of_frame: None,
+            as_of_ts: None,
+            no_sketch: false,
+            filter: None,
+            facets: Vec::new(),
+            hybrid: None,
+            reranker: None,
+            fragments: None,
+        }),
+        Err(MemvidError::InvalidQuery { .. })
+    ));
+}
+
+/// Test hits carry analyzer-based highlights and the multi-fragment snippet mode.
+#[test]
+#[cfg(feature = "lex")]
+fn search_returns_highlights_and_fragments() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    let filler = "Nothing of note happens in this sentence at all. ".repeat(12);
+    let text = format!(
+        "The engine runs smoothly after tuning. {filler}Later the engine was running hot again."
+    );
+    let mut mem = Memvid::create(&path).unwrap();
+    mem.enable_lex().unwrap();
+    let opts = PutOptions {
+        uri: Some("mv2://notes/engine".to_string()),
+        search_text: Some(text.clone()),
+        ..Default::default()
+    };
+    mem.put_bytes_with_options(text.as_bytes(), opts).unwrap();
+    mem.commit().unwrap();
+
+    let request = |fragments: Option<SnippetFragments>| SearchRequest {
+        query: "engine running".to_string(),
+        top_k: 10,
+        snippet_chars: 80,
+        uri: None,
+        scope: None,
+        cursor: None,
+        #[cfg(feature = "temporal_track")]
+        temporal: None,
+        as_of_frame: None,
+        as_of_ts: None,
+        no_sketch: false,
+        filter: None,
+        facets: Vec::new(),
+        hybrid: None,
+        reranker: None,
+        fragments,
+    };
+    let is_query_word =
+        |word: &str| matches!(word.to_lowercase().as_str(), "engine" | "runs" | "running");
+    let marked = |text: &str, spans: &[(usize, usize)]| -> Vec<String> {
+        spans
+            .iter()
+            .map(|(start, end)| text[*start..*end].to_string())
+            .collect()
+    };
+
+    let response = mem.search(request(None)).unwrap();
+    assert_eq!(response.engine, SearchEngineKind::Tantivy);
+    assert!(response.hits.len() >= 2);
+    let first = &response.hits[0];
+    let highlights = first.highlights.as_ref().expect("highlights");
+    assert_eq!(marked(&first.text, &highlights.text), ["engine", "runs"]);
+    let chunk_text = first.chunk_text.as_deref().unwrap();
+    let chunk_words = marked(chunk_text, &highlights.chunk);
+    assert_eq!(chunk_words[..4], ["engine", "runs", "engine", "running"]);
+    assert!(chunk_words.iter().all(|word| is_query_word(word)));
+    assert!(highlights.fragments.is_empty());
+
+    let response = mem
+        .search(request(Some(SnippetFragments { max_fragments: 2 })))
+        .unwrap();
+    assert_eq!(response.hits.len(), 1);
+    let hit = &response.hits[0];
+    let fragments = &hit.highlights.as_ref().unwrap().fragments;
+    assert_eq!(fragments.len(), 2);
+    assert_eq!(hit.text, fragments[0].text);
+    assert_eq!(hit.range, fragments[0].range);
+    let chunk_text = hit.chunk_text.as_deref().unwrap();
+    let chunk_start = hit.chunk_range.unwrap().0;
+    for fragment in fragments {
+        let (start, end) = fragment.range;
+        assert_eq!(
+            &chunk_text[start - chunk_start..end - chunk_start],
+            fragment.text
+        );
+        let words = marked(&fragment.text, &fragment.highlights);
+        assert!(!words.is_empty());
+        assert!(words.iter().all(|word| is_query_word(word)), "{words:?}");
+    }
+}
+
 /// Test timeline query returns ordered results.
 #[test]
 fn timeline_returns_ordered() {
@@ -468,3 +1204,170 @@ fn timeline_respects_limit() {
         "Timeline should return exactly limit entries"
     );
 }
+
+/// Test that one reader serves searches and frame reads from many threads at once.
+#[test]
+#[cfg(feature = "lex")]
+fn reader_serves_concurrent_queries() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<MemvidReader>();
+
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("test.mv2");
+
+    create_searchable_memory(&path);
+
+    let mut mem = Memvid::open(&path).unwrap();
+    assert_eq!(mem.reader().unwrap().handles(), 4);
+    let reader = mem.reader_with_handles(3).unwrap();
+    assert_eq!(reader.handles(), 3);
+    assert_eq!(reader.frame_count(), 5);
+
+    // Commits after the reader was created are not visible to it.
+    mem.put_bytes(b"Quantum entanglement links particle states")
+        .unwrap();
+    mem.commit().unwrap();
+    drop(mem);
+
+    let queries = ["quantum", "cells", "molecules", "calculus"];
+    std::thread::scope(|scope| {
+        for worker in 0..8 {
+            let reader = reader.clone();
+            let query = queries[worker % queries.len()];
+            scope.spawn(move || {
+                for _ in 0..5 {
+                    let response = reader
+                        .search(SearchRequest {
+                            query: query.to_string(),
+                            top_k: 10,
+                            snippet_chars: 200,
+                            uri: None,
+                            scope: None,
+                            cursor: None,
+                            #[cfg(feature = "temporal_track")]
+                            temporal: None,
+                            as_of_frame: None,
+                            as_of_ts: None,
+                            no_sketch: false,
+                            filter: None,
+                            facets: Vec::new(),
+                            hybrid: None,
+                            reranker: None,
+                            fragments: None,
+                        })
+                        .unwrap();
+                    assert_eq!(response.hits.len(), 1, "query {query}");
+                    let hit = &response.hits[0];
+                    let text = reader.frame_text_by_id(hit.frame_id).unwrap();
+                    assert!(text.to_lowercase().contains(query), "{text}");
+                    assert_eq!(
+                        reader.frame_by_id(hit.frame_id).unwrap().uri,
+                        Some(hit.uri.clone())
+                    );
+                }
+            });
+        }
+    });
+
+    assert_eq!(reader.frame_count(), 5);
+    assert_eq!(Memvid::open_read_only(&path).unwrap().frame_count(), 6);
+}
+
+/// Test multi-pattern graph queries joining memory cards on shared variables.
+#[test]
+fn graph_pattern_joins_memory_cards() {
+    let dir = TempDir::new().unwrap();
+    let mut mem = Memvid::create(dir.path().join("test.mv2")).unwrap();
+
+    let facts = [
+        ("alice", "employer", "Acme", 100),
+        ("alice", "location", "Berlin", 100),
+        ("alice", "location", "Munich", 200),
+        ("alice", "age", "34", 100),
+        ("bob", "employer", "Acme", 100),
+        ("bob", "location", "Paris", 100),
+        ("bob", "age", "9", 100),
+        ("carol", "employer", "Globex", 100),
+        ("carol", "location", "Rome", 100),
+        ("carol", "age", "41", 100),
+        ("dave", "employer", "Acme Corp", 100),
+        ("acme", "founded", "1999-05-01", 100),
+    ];
+    let cards = facts
+        .iter()
+        .enumerate()
+        .map(|(frame, &(entity, slot, value, date))| {
+            MemoryCardBuilder::new()
+                .fact()
+                .entity(entity)
+                .slot(slot)
+                .value(value)
+                .source(frame as u64, None)
+                .document_date(date)
+                .engine("rules", "1.0.0")
+                .build(0)
+                .unwrap()
+        })
+        .collect();
+    mem.put_memory_cards(cards).unwrap();
+
+    let matcher = GraphMatcher::new(&mem);
+    let run = |query: &str| matcher.execute(&GraphPattern::parse(query).unwrap());
+    let column = |query: &str, variable: &str| -> Vec<Option<String>> {
+        run(query)
+            .into_iter()
+            .map(|hit| hit.bindings.get(variable).cloned())
+            .collect()
+    };
+
+    // Joined on ?p; only the current location of single-valued slots matches.
+    let hits = run("?p employer acme . ?p location ?city ORDER BY ?p");
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0].entity, "alice");
+    assert_eq!(hits[0].bindings["city"], "Munich");
+    assert_eq!(hits[0].frame_ids, vec![0, 2]);
+    assert_eq!(hits[1].bindings["city"], "Paris");
+
+    assert_eq!(
+        column(
+            "?p employer acme OPTIONAL { ?p location ?city } ORDER BY ?p",
+            "city"
+        ),
+        vec![Some("Munich".into()), Some("Paris".into()), None]
+    );
+
+    // `age` is declared numeric, so "9" sorts before "34".
+    assert_eq!(
+        column("?p age ?age ORDER BY ?age", "p"),
+        vec![
+            Some("bob".into()),
+            Some("alice".into()),
+            Some("carol".into())
+        ]
+    );
+    assert_eq!(
+        column(
+            "?p age ?age FILTER (?age > 30) ORDER BY DESC(?age) LIMIT 1",
+            "p"
+        ),
+        vec![Some("carol".into())]
+    );
+    assert_eq!(
+        column(
+            "?c founded ?since . ?p employer ?c FILTER (?since < 2000-01-01) ORDER BY ?p",
+            "p"
+        ),
+        vec![Some("alice".into()), Some("bob".into())]
+    );
+    assert!(run("?p employer globex . ?p location berlin").is_empty());
+
+    // Hand-built patterns match slots regardless of case, like the parsed syntax.
+    let employees = matcher.execute(&GraphPattern::single(TriplePattern::new(
+        PatternTerm::Variable("p".into()),
+        PatternTerm::Literal("Employer".into()),
+        PatternTerm::Literal("Acme".into()),
+    )));
+    let mut names: Vec<_> = employees.iter().map(|hit| hit.entity.as_str()).collect();
+    names.sort_unstable();
+    assert_eq!(names, ["alice", "bob", "dave"]);
+}